@@ -23,37 +23,116 @@ Register-ArgumentCompleter -Native -CommandName 'hanko' -ScriptBlock {
         'hanko' {
             [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
             [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
             [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
             [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
             [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
-            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help')
-            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
             [CompletionResult]::new('-V', '-V ', [CompletionResultType]::ParameterName, 'Print version')
             [CompletionResult]::new('--version', '--version', [CompletionResultType]::ParameterName, 'Print version')
             [CompletionResult]::new('update', 'update', [CompletionResultType]::ParameterValue, 'Update the allowed signers file')
             [CompletionResult]::new('signer', 'signer', [CompletionResultType]::ParameterValue, 'Manage allowed signers')
+            [CompletionResult]::new('source', 'source', [CompletionResultType]::ParameterValue, 'Manage sources')
+            [CompletionResult]::new('config', 'config', [CompletionResultType]::ParameterValue, 'Inspect the configuration')
+            [CompletionResult]::new('report', 'report', [CompletionResultType]::ParameterValue, 'Report the keys currently configured for each signer on each of it''s sources, without writing the allowed signers file')
+            [CompletionResult]::new('export', 'export', [CompletionResultType]::ParameterValue, 'Print all entries of the allowed signers file as a structured document for other tools, without writing the allowed signers file')
+            [CompletionResult]::new('fetch', 'fetch', [CompletionResultType]::ParameterValue, 'Print the allowed signers file entries of a configured signer, retrieving it''s keys only from the given configured source instead of the sources of the signer')
+            [CompletionResult]::new('apply', 'apply', [CompletionResultType]::ParameterValue, 'Apply a plan previously written using `hanko update --plan-out`')
+            [CompletionResult]::new('verify-file', 'verify-file', [CompletionResultType]::ParameterValue, 'Verify the detached signature of the allowed signers file')
+            [CompletionResult]::new('prune-config', 'prune-config', [CompletionResultType]::ParameterValue, 'Remove signers that no longer exist on any of their sources from the configuration, only printing them unless `--yes` is given')
+            [CompletionResult]::new('install-hook', 'install-hook', [CompletionResultType]::ParameterValue, 'Install git hooks into the repository in the current directory, updating the allowed signers file after checking out or merging commits')
+            [CompletionResult]::new('uninstall-hook', 'uninstall-hook', [CompletionResultType]::ParameterValue, 'Remove the git hooks installed by hanko from the repository in the current directory')
+            [CompletionResult]::new('doctor', 'doctor', [CompletionResultType]::ParameterValue, 'Check the git configuration in the current directory for the settings required to sign and verify commits and tags using the allowed signers file, suggesting fixes for misconfigured settings. Requires the `detect-allowed-signers` feature')
+            [CompletionResult]::new('env', 'env', [CompletionResultType]::ParameterValue, 'List the environment variables used by hanko')
+            [CompletionResult]::new('__complete', '__complete', [CompletionResultType]::ParameterValue, 'Print the configured names of the given kind for dynamic shell completion')
             [CompletionResult]::new('help', 'help', [CompletionResultType]::ParameterValue, 'Print this message or the help of the given subcommand(s)')
             break
         }
         'hanko;update' {
+            [CompletionResult]::new('--output-format', '--output-format', [CompletionResultType]::ParameterName, 'The format the allowed signers file is written in')
+            [CompletionResult]::new('--only', '--only', [CompletionResultType]::ParameterName, 'Only update the signer(s) with the given name, dropping all other signers from the file')
+            [CompletionResult]::new('--principal-filter', '--principal-filter', [CompletionResultType]::ParameterName, 'Only write entries with principals matching the given glob pattern, e.g. `*@acme.corp`, where `*` matches any number of characters and `?` matches exactly one. Entries of multiple principals are written if any of them matches')
+            [CompletionResult]::new('--exclude-principal', '--exclude-principal', [CompletionResultType]::ParameterName, 'Remove principals matching the given glob pattern from all entries, in addition to the configured `exclude_principals`, dropping entries none of whose principals remain')
+            [CompletionResult]::new('--retries', '--retries', [CompletionResultType]::ParameterName, 'Re-run the whole update up to this many times if retrieving the keys of any signer failed, e.g. during a provider outage affecting many signers at once')
+            [CompletionResult]::new('--retry-delay', '--retry-delay', [CompletionResultType]::ParameterName, 'The duration to wait before re-running a failed update, defaults to 10s')
+            [CompletionResult]::new('--plan-out', '--plan-out', [CompletionResultType]::ParameterName, 'Write a plan of the changes to the given JSON file instead of updating the allowed signers file, which can be applied later using `hanko apply`')
+            [CompletionResult]::new('--validate-keys', '--validate-keys', [CompletionResultType]::ParameterName, 'How keys that are not well-formed SSH public keys are handled, overriding the `validate_keys` output option')
+            [CompletionResult]::new('--pipe-to', '--pipe-to', [CompletionResultType]::ParameterName, 'Pipe the allowed signers file to the stdin of the given command in addition to writing it, e.g. `pbcopy`. The command is run using the system shell')
+            [CompletionResult]::new('--each-config', '--each-config', [CompletionResultType]::ParameterName, 'Update each of the given configurations independently instead of the configuration file, writing the allowed signers file configured by their `allowed_signers_file` option and printing a summary of all updates. Directories update every configuration file they contain')
+            [CompletionResult]::new('--jobs', '--jobs', [CompletionResultType]::ParameterName, 'The number of configurations given using `--each-config` updated concurrently')
             [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
             [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
             [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-rate-limit-check', '--skip-rate-limit-check', [CompletionResultType]::ParameterName, 'Don''t check whether the rate limit budget of authenticated sources suffices before updating')
+            [CompletionResult]::new('--always-run-hooks', '--always-run-hooks', [CompletionResultType]::ParameterName, 'Run hooks even if the allowed signers file did not change')
+            [CompletionResult]::new('--principal-filter-all', '--principal-filter-all', [CompletionResultType]::ParameterName, 'Only write entries of multiple principals if all of them match the principal filter')
+            [CompletionResult]::new('--report-shared-keys', '--report-shared-keys', [CompletionResultType]::ParameterName, 'Warn about keys written for multiple distinct principals, like a shared bot key or a misattributed key, which undermine attributing signatures to a single signer')
+            [CompletionResult]::new('--only-valid', '--only-valid', [CompletionResultType]::ParameterName, 'Only write entries valid right now, dropping entries not yet valid or already expired according to their validity period')
+            [CompletionResult]::new('--include-expired', '--include-expired', [CompletionResultType]::ParameterName, 'Keep expired entries when only writing valid entries')
+            [CompletionResult]::new('--write-lock', '--write-lock', [CompletionResultType]::ParameterName, 'Record the fingerprints of the keys resolved for each signer in `hanko.lock` next to the configuration file, locking them for updates using `--locked`')
+            [CompletionResult]::new('--locked', '--locked', [CompletionResultType]::ParameterName, 'Refuse to update if the keys resolved for any signer differ from those recorded in `hanko.lock` next to the configuration file, which is regenerated using `--write-lock`')
+            [CompletionResult]::new('--first-run-safe', '--first-run-safe', [CompletionResultType]::ParameterName, 'Refuse to overwrite an existing allowed signers file that was not written by hanko')
+            [CompletionResult]::new('--force', '--force', [CompletionResultType]::ParameterName, 'Overwrite the allowed signers file even if it was not written by hanko')
+            [CompletionResult]::new('--tolerate-connection-errors', '--tolerate-connection-errors', [CompletionResultType]::ParameterName, 'Skip signers whose sources could not be connected to with a warning, keeping the entries of all other signers, instead of failing the update. Connection errors remain fatal if keys are validated strictly')
+            [CompletionResult]::new('--dry-run', '--dry-run', [CompletionResultType]::ParameterName, 'Print an estimate of the requests the update would make, without making any requests or writing the allowed signers file')
+            [CompletionResult]::new('--no-partial', '--no-partial', [CompletionResultType]::ParameterName, 'Abort without writing the allowed signers file when interrupted, instead of writing the entries of signers retrieved so far')
+            [CompletionResult]::new('--status-line', '--status-line', [CompletionResultType]::ParameterName, 'Only print a single line summarizing the update, e.g. for shell prompts or status bars, instead of the regular output')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
             [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
             [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
-            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help')
-            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
             break
         }
         'hanko;signer' {
             [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
             [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
             [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
             [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
             [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
-            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help')
-            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
             [CompletionResult]::new('add', 'add', [CompletionResultType]::ParameterValue, 'Add an allowed signer')
+            [CompletionResult]::new('enable', 'enable', [CompletionResultType]::ParameterValue, 'Enable a disabled signer, using it again when updating')
+            [CompletionResult]::new('disable', 'disable', [CompletionResultType]::ParameterValue, 'Disable a signer, skipping it when updating while retaining it''s configuration')
             [CompletionResult]::new('help', 'help', [CompletionResultType]::ParameterValue, 'Print this message or the help of the given subcommand(s)')
             break
         }
@@ -62,28 +141,520 @@ Register-ArgumentCompleter -Native -CommandName 'hanko' -ScriptBlock {
             [CompletionResult]::new('--source', '--source', [CompletionResultType]::ParameterName, 'The source(s) of the signer to add')
             [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
             [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
             [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
             [CompletionResult]::new('--no-update', '--no-update', [CompletionResultType]::ParameterName, 'Don''t update the allowed signers file with the added signer(s)')
+            [CompletionResult]::new('--no-verify', '--no-verify', [CompletionResultType]::ParameterName, 'Don''t verify that the signer exists and has signing keys on it''s source(s)')
+            [CompletionResult]::new('--strict', '--strict', [CompletionResultType]::ParameterName, 'Don''t add the signer if it does not exist or has no signing keys on it''s source(s)')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
             [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
             [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
-            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help')
-            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            break
+        }
+        'hanko;signer;enable' {
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            break
+        }
+        'hanko;signer;disable' {
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
             break
         }
         'hanko;signer;help' {
             [CompletionResult]::new('add', 'add', [CompletionResultType]::ParameterValue, 'Add an allowed signer')
+            [CompletionResult]::new('enable', 'enable', [CompletionResultType]::ParameterValue, 'Enable a disabled signer, using it again when updating')
+            [CompletionResult]::new('disable', 'disable', [CompletionResultType]::ParameterValue, 'Disable a signer, skipping it when updating while retaining it''s configuration')
             [CompletionResult]::new('help', 'help', [CompletionResultType]::ParameterValue, 'Print this message or the help of the given subcommand(s)')
             break
         }
         'hanko;signer;help;add' {
             break
         }
+        'hanko;signer;help;enable' {
+            break
+        }
+        'hanko;signer;help;disable' {
+            break
+        }
         'hanko;signer;help;help' {
             break
         }
+        'hanko;source' {
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('add', 'add', [CompletionResultType]::ParameterValue, 'Add a source')
+            [CompletionResult]::new('providers', 'providers', [CompletionResultType]::ParameterValue, 'List the supported source providers')
+            [CompletionResult]::new('help', 'help', [CompletionResultType]::ParameterValue, 'Print this message or the help of the given subcommand(s)')
+            break
+        }
+        'hanko;source;add' {
+            [CompletionResult]::new('--url', '--url', [CompletionResultType]::ParameterName, 'The URL of the source''s API endpoint, defaults to the canonical public instance of the provider')
+            [CompletionResult]::new('--provider', '--provider', [CompletionResultType]::ParameterName, 'The provider of the source, detected by the host of the URL if not specified')
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            break
+        }
+        'hanko;source;providers' {
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            break
+        }
+        'hanko;source;help' {
+            [CompletionResult]::new('add', 'add', [CompletionResultType]::ParameterValue, 'Add a source')
+            [CompletionResult]::new('providers', 'providers', [CompletionResultType]::ParameterValue, 'List the supported source providers')
+            [CompletionResult]::new('help', 'help', [CompletionResultType]::ParameterValue, 'Print this message or the help of the given subcommand(s)')
+            break
+        }
+        'hanko;source;help;add' {
+            break
+        }
+        'hanko;source;help;providers' {
+            break
+        }
+        'hanko;source;help;help' {
+            break
+        }
+        'hanko;config' {
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('test', 'test', [CompletionResultType]::ParameterValue, 'Check that saving the configuration file, as done when editing it using the `signer` and `source` commands, preserves it''s formatting, printing the lines that would change')
+            [CompletionResult]::new('help', 'help', [CompletionResultType]::ParameterValue, 'Print this message or the help of the given subcommand(s)')
+            break
+        }
+        'hanko;config;test' {
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            break
+        }
+        'hanko;config;help' {
+            [CompletionResult]::new('test', 'test', [CompletionResultType]::ParameterValue, 'Check that saving the configuration file, as done when editing it using the `signer` and `source` commands, preserves it''s formatting, printing the lines that would change')
+            [CompletionResult]::new('help', 'help', [CompletionResultType]::ParameterValue, 'Print this message or the help of the given subcommand(s)')
+            break
+        }
+        'hanko;config;help;test' {
+            break
+        }
+        'hanko;config;help;help' {
+            break
+        }
+        'hanko;report' {
+            [CompletionResult]::new('--format', '--format', [CompletionResultType]::ParameterName, 'The format of the report')
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            break
+        }
+        'hanko;export' {
+            [CompletionResult]::new('--format', '--format', [CompletionResultType]::ParameterName, 'The format of the export')
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            break
+        }
+        'hanko;fetch' {
+            [CompletionResult]::new('--signer', '--signer', [CompletionResultType]::ParameterName, 'The configured signer to fetch the keys of')
+            [CompletionResult]::new('--source', '--source', [CompletionResultType]::ParameterName, 'The configured source to exclusively fetch keys from')
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            break
+        }
+        'hanko;apply' {
+            [CompletionResult]::new('--plan', '--plan', [CompletionResultType]::ParameterName, 'The plan to apply')
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            break
+        }
+        'hanko;verify-file' {
+            [CompletionResult]::new('--public-key', '--public-key', [CompletionResultType]::ParameterName, 'The public key of the key the allowed signers file was signed with')
+            [CompletionResult]::new('--signature', '--signature', [CompletionResultType]::ParameterName, 'The detached signature, defaults to the allowed signers file with a `.sig` extension appended')
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            break
+        }
+        'hanko;prune-config' {
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--yes', '--yes', [CompletionResultType]::ParameterName, 'Remove the signers instead of only printing them')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            break
+        }
+        'hanko;install-hook' {
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--force', '--force', [CompletionResultType]::ParameterName, 'Overwrite existing hooks that were not installed by hanko')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            break
+        }
+        'hanko;uninstall-hook' {
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            break
+        }
+        'hanko;doctor' {
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            break
+        }
+        'hanko;env' {
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            break
+        }
+        'hanko;__complete' {
+            [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'The configuration file')
+            [CompletionResult]::new('--config-dir', '--config-dir', [CompletionResultType]::ParameterName, 'A directory of additional configuration files merged in lexical order')
+            [CompletionResult]::new('--file', '--file', [CompletionResultType]::ParameterName, 'The allowed signers file')
+            [CompletionResult]::new('--max-redirects', '--max-redirects', [CompletionResultType]::ParameterName, 'The maximum number of redirects followed per request to a source')
+            [CompletionResult]::new('--connect-timeout', '--connect-timeout', [CompletionResultType]::ParameterName, 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation')
+            [CompletionResult]::new('--request-timeout', '--request-timeout', [CompletionResultType]::ParameterName, 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation')
+            [CompletionResult]::new('--lock-timeout', '--lock-timeout', [CompletionResultType]::ParameterName, 'How long to wait for another run writing the allowed signers file, failing immediately if not given')
+            [CompletionResult]::new('--now', '--now', [CompletionResultType]::ParameterName, 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future')
+            [CompletionResult]::new('--trace-file', '--trace-file', [CompletionResultType]::ParameterName, 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured')
+            [CompletionResult]::new('--trace-file-format', '--trace-file-format', [CompletionResultType]::ParameterName, 'The format of traces written to the trace file')
+            [CompletionResult]::new('--error-format', '--error-format', [CompletionResultType]::ParameterName, 'The format errors are printed to stderr in')
+            [CompletionResult]::new('--suppress-warning', '--suppress-warning', [CompletionResultType]::ParameterName, 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times')
+            [CompletionResult]::new('--skip-invalid-configs', '--skip-invalid-configs', [CompletionResultType]::ParameterName, 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped')
+            [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Use verbose output')
+            [CompletionResult]::new('--dump-requests', '--dump-requests', [CompletionResultType]::ParameterName, 'Dump requests made to sources and their responses to stderr, with secrets redacted')
+            [CompletionResult]::new('--no-env-token', '--no-env-token', [CompletionResultType]::ParameterName, 'Don''t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens')
+            [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
+            break
+        }
         'hanko;help' {
             [CompletionResult]::new('update', 'update', [CompletionResultType]::ParameterValue, 'Update the allowed signers file')
             [CompletionResult]::new('signer', 'signer', [CompletionResultType]::ParameterValue, 'Manage allowed signers')
+            [CompletionResult]::new('source', 'source', [CompletionResultType]::ParameterValue, 'Manage sources')
+            [CompletionResult]::new('config', 'config', [CompletionResultType]::ParameterValue, 'Inspect the configuration')
+            [CompletionResult]::new('report', 'report', [CompletionResultType]::ParameterValue, 'Report the keys currently configured for each signer on each of it''s sources, without writing the allowed signers file')
+            [CompletionResult]::new('export', 'export', [CompletionResultType]::ParameterValue, 'Print all entries of the allowed signers file as a structured document for other tools, without writing the allowed signers file')
+            [CompletionResult]::new('fetch', 'fetch', [CompletionResultType]::ParameterValue, 'Print the allowed signers file entries of a configured signer, retrieving it''s keys only from the given configured source instead of the sources of the signer')
+            [CompletionResult]::new('apply', 'apply', [CompletionResultType]::ParameterValue, 'Apply a plan previously written using `hanko update --plan-out`')
+            [CompletionResult]::new('verify-file', 'verify-file', [CompletionResultType]::ParameterValue, 'Verify the detached signature of the allowed signers file')
+            [CompletionResult]::new('prune-config', 'prune-config', [CompletionResultType]::ParameterValue, 'Remove signers that no longer exist on any of their sources from the configuration, only printing them unless `--yes` is given')
+            [CompletionResult]::new('install-hook', 'install-hook', [CompletionResultType]::ParameterValue, 'Install git hooks into the repository in the current directory, updating the allowed signers file after checking out or merging commits')
+            [CompletionResult]::new('uninstall-hook', 'uninstall-hook', [CompletionResultType]::ParameterValue, 'Remove the git hooks installed by hanko from the repository in the current directory')
+            [CompletionResult]::new('doctor', 'doctor', [CompletionResultType]::ParameterValue, 'Check the git configuration in the current directory for the settings required to sign and verify commits and tags using the allowed signers file, suggesting fixes for misconfigured settings. Requires the `detect-allowed-signers` feature')
+            [CompletionResult]::new('env', 'env', [CompletionResultType]::ParameterValue, 'List the environment variables used by hanko')
+            [CompletionResult]::new('__complete', '__complete', [CompletionResultType]::ParameterValue, 'Print the configured names of the given kind for dynamic shell completion')
             [CompletionResult]::new('help', 'help', [CompletionResultType]::ParameterValue, 'Print this message or the help of the given subcommand(s)')
             break
         }
@@ -92,11 +663,70 @@ Register-ArgumentCompleter -Native -CommandName 'hanko' -ScriptBlock {
         }
         'hanko;help;signer' {
             [CompletionResult]::new('add', 'add', [CompletionResultType]::ParameterValue, 'Add an allowed signer')
+            [CompletionResult]::new('enable', 'enable', [CompletionResultType]::ParameterValue, 'Enable a disabled signer, using it again when updating')
+            [CompletionResult]::new('disable', 'disable', [CompletionResultType]::ParameterValue, 'Disable a signer, skipping it when updating while retaining it''s configuration')
             break
         }
         'hanko;help;signer;add' {
             break
         }
+        'hanko;help;signer;enable' {
+            break
+        }
+        'hanko;help;signer;disable' {
+            break
+        }
+        'hanko;help;source' {
+            [CompletionResult]::new('add', 'add', [CompletionResultType]::ParameterValue, 'Add a source')
+            [CompletionResult]::new('providers', 'providers', [CompletionResultType]::ParameterValue, 'List the supported source providers')
+            break
+        }
+        'hanko;help;source;add' {
+            break
+        }
+        'hanko;help;source;providers' {
+            break
+        }
+        'hanko;help;config' {
+            [CompletionResult]::new('test', 'test', [CompletionResultType]::ParameterValue, 'Check that saving the configuration file, as done when editing it using the `signer` and `source` commands, preserves it''s formatting, printing the lines that would change')
+            break
+        }
+        'hanko;help;config;test' {
+            break
+        }
+        'hanko;help;report' {
+            break
+        }
+        'hanko;help;export' {
+            break
+        }
+        'hanko;help;fetch' {
+            break
+        }
+        'hanko;help;apply' {
+            break
+        }
+        'hanko;help;verify-file' {
+            break
+        }
+        'hanko;help;prune-config' {
+            break
+        }
+        'hanko;help;install-hook' {
+            break
+        }
+        'hanko;help;uninstall-hook' {
+            break
+        }
+        'hanko;help;doctor' {
+            break
+        }
+        'hanko;help;env' {
+            break
+        }
+        'hanko;help;__complete' {
+            break
+        }
         'hanko;help;help' {
             break
         }
@@ -1,56 +1,556 @@
 # Print an optspec for argparse to handle cmd's options that are independent of any subcommand.
 function __fish_hanko_global_optspecs
-	string join \n c/config= file= v/verbose h/help V/version
+    string join \n c/config= config-dir= skip-invalid-configs file= v/verbose dump-requests no-env-token max-redirects= connect-timeout= request-timeout= lock-timeout= now= trace-file= trace-file-format= error-format= suppress-warning= h/help V/version
 end
 
 function __fish_hanko_needs_command
-	# Figure out if the current invocation already has a command.
-	set -l cmd (commandline -opc)
-	set -e cmd[1]
-	argparse -s (__fish_hanko_global_optspecs) -- $cmd 2>/dev/null
-	or return
-	if set -q argv[1]
-		# Also print the command, so this can be used to figure out what it is.
-		echo $argv[1]
-		return 1
-	end
-	return 0
+    # Figure out if the current invocation already has a command.
+    set -l cmd (commandline -opc)
+    set -e cmd[1]
+    argparse -s (__fish_hanko_global_optspecs) -- $cmd 2>/dev/null
+    or return
+    if set -q argv[1]
+        # Also print the command, so this can be used to figure out what it is.
+        echo $argv[1]
+        return 1
+    end
+    return 0
 end
 
 function __fish_hanko_using_subcommand
-	set -l cmd (__fish_hanko_needs_command)
-	test -z "$cmd"
-	and return 1
-	contains -- $cmd[1] $argv
+    set -l cmd (__fish_hanko_needs_command)
+    test -z "$cmd"
+    and return 1
+    contains -- $cmd[1] $argv
 end
 
 complete -c hanko -n "__fish_hanko_needs_command" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_needs_command" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
 complete -c hanko -n "__fish_hanko_needs_command" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_needs_command" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_needs_command" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_needs_command" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_needs_command" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_needs_command" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_needs_command" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_needs_command" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_needs_command" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_needs_command" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_needs_command" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
 complete -c hanko -n "__fish_hanko_needs_command" -s v -l verbose -d 'Use verbose output'
-complete -c hanko -n "__fish_hanko_needs_command" -s h -l help -d 'Print help'
+complete -c hanko -n "__fish_hanko_needs_command" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_needs_command" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_needs_command" -s h -l help -d 'Print help (see more with \'--help\')'
 complete -c hanko -n "__fish_hanko_needs_command" -s V -l version -d 'Print version'
 complete -c hanko -n "__fish_hanko_needs_command" -f -a "update" -d 'Update the allowed signers file'
 complete -c hanko -n "__fish_hanko_needs_command" -f -a "signer" -d 'Manage allowed signers'
+complete -c hanko -n "__fish_hanko_needs_command" -f -a "source" -d 'Manage sources'
+complete -c hanko -n "__fish_hanko_needs_command" -f -a "config" -d 'Inspect the configuration'
+complete -c hanko -n "__fish_hanko_needs_command" -f -a "report" -d 'Report the keys currently configured for each signer on each of it\'s sources, without writing the allowed signers file'
+complete -c hanko -n "__fish_hanko_needs_command" -f -a "export" -d 'Print all entries of the allowed signers file as a structured document for other tools, without writing the allowed signers file'
+complete -c hanko -n "__fish_hanko_needs_command" -f -a "fetch" -d 'Print the allowed signers file entries of a configured signer, retrieving it\'s keys only from the given configured source instead of the sources of the signer'
+complete -c hanko -n "__fish_hanko_needs_command" -f -a "apply" -d 'Apply a plan previously written using `hanko update --plan-out`'
+complete -c hanko -n "__fish_hanko_needs_command" -f -a "verify-file" -d 'Verify the detached signature of the allowed signers file'
+complete -c hanko -n "__fish_hanko_needs_command" -f -a "prune-config" -d 'Remove signers that no longer exist on any of their sources from the configuration, only printing them unless `--yes` is given'
+complete -c hanko -n "__fish_hanko_needs_command" -f -a "install-hook" -d 'Install git hooks into the repository in the current directory, updating the allowed signers file after checking out or merging commits'
+complete -c hanko -n "__fish_hanko_needs_command" -f -a "uninstall-hook" -d 'Remove the git hooks installed by hanko from the repository in the current directory'
+complete -c hanko -n "__fish_hanko_needs_command" -f -a "doctor" -d 'Check the git configuration in the current directory for the settings required to sign and verify commits and tags using the allowed signers file, suggesting fixes for misconfigured settings. Requires the `detect-allowed-signers` feature'
+complete -c hanko -n "__fish_hanko_needs_command" -f -a "env" -d 'List the environment variables used by hanko'
+complete -c hanko -n "__fish_hanko_needs_command" -f -a "__complete" -d 'Print the configured names of the given kind for dynamic shell completion'
 complete -c hanko -n "__fish_hanko_needs_command" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l output-format -d 'The format the allowed signers file is written in' -r -f -a "allowed-signers\t'The OpenSSH allowed signers format'
+authorized-keys\t'The OpenSSH authorized keys format, containing only the deduplicated keys of all entries'"
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l only -d 'Only update the signer(s) with the given name, dropping all other signers from the file' -r -f -a "(__fish_complete_users)"
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l principal-filter -d 'Only write entries with principals matching the given glob pattern, e.g. `*@acme.corp`, where `*` matches any number of characters and `?` matches exactly one. Entries of multiple principals are written if any of them matches' -r
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l exclude-principal -d 'Remove principals matching the given glob pattern from all entries, in addition to the configured `exclude_principals`, dropping entries none of whose principals remain' -r
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l retries -d 'Re-run the whole update up to this many times if retrieving the keys of any signer failed, e.g. during a provider outage affecting many signers at once' -r
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l retry-delay -d 'The duration to wait before re-running a failed update, defaults to 10s' -r
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l plan-out -d 'Write a plan of the changes to the given JSON file instead of updating the allowed signers file, which can be applied later using `hanko apply`' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l validate-keys -d 'How keys that are not well-formed SSH public keys are handled, overriding the `validate_keys` output option' -r -f -a "off\t'Write keys without validating them'
+warn\t'Drop invalid keys with a warning'
+strict\t'Fail if any key is invalid'"
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l pipe-to -d 'Pipe the allowed signers file to the stdin of the given command in addition to writing it, e.g. `pbcopy`. The command is run using the system shell' -r
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l each-config -d 'Update each of the given configurations independently instead of the configuration file, writing the allowed signers file configured by their `allowed_signers_file` option and printing a summary of all updates. Directories update every configuration file they contain' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l jobs -d 'The number of configurations given using `--each-config` updated concurrently' -r
 complete -c hanko -n "__fish_hanko_using_subcommand update" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
 complete -c hanko -n "__fish_hanko_using_subcommand update" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l skip-rate-limit-check -d 'Don\'t check whether the rate limit budget of authenticated sources suffices before updating'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l always-run-hooks -d 'Run hooks even if the allowed signers file did not change'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l principal-filter-all -d 'Only write entries of multiple principals if all of them match the principal filter'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l report-shared-keys -d 'Warn about keys written for multiple distinct principals, like a shared bot key or a misattributed key, which undermine attributing signatures to a single signer'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l only-valid -d 'Only write entries valid right now, dropping entries not yet valid or already expired according to their validity period'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l include-expired -d 'Keep expired entries when only writing valid entries'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l write-lock -d 'Record the fingerprints of the keys resolved for each signer in `hanko.lock` next to the configuration file, locking them for updates using `--locked`'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l locked -d 'Refuse to update if the keys resolved for any signer differ from those recorded in `hanko.lock` next to the configuration file, which is regenerated using `--write-lock`'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l first-run-safe -d 'Refuse to overwrite an existing allowed signers file that was not written by hanko'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l force -d 'Overwrite the allowed signers file even if it was not written by hanko'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l tolerate-connection-errors -d 'Skip signers whose sources could not be connected to with a warning, keeping the entries of all other signers, instead of failing the update. Connection errors remain fatal if keys are validated strictly'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l dry-run -d 'Print an estimate of the requests the update would make, without making any requests or writing the allowed signers file'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l no-partial -d 'Abort without writing the allowed signers file when interrupted, instead of writing the entries of signers retrieved so far'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l status-line -d 'Only print a single line summarizing the update, e.g. for shell prompts or status bars, instead of the regular output'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
 complete -c hanko -n "__fish_hanko_using_subcommand update" -s v -l verbose -d 'Use verbose output'
-complete -c hanko -n "__fish_hanko_using_subcommand update" -s h -l help -d 'Print help'
-complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add help" -s c -l config -d 'The configuration file' -r -F
-complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add help" -l file -d 'The allowed signers file' -r -F
-complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add help" -s v -l verbose -d 'Use verbose output'
-complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add help" -s h -l help -d 'Print help'
-complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add help" -f -a "add" -d 'Add an allowed signer'
-complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand update" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -f -a "add" -d 'Add an allowed signer'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -f -a "enable" -d 'Enable a disabled signer, using it again when updating'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -f -a "disable" -d 'Disable a signer, skipping it when updating while retaining it\'s configuration'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and not __fish_seen_subcommand_from add enable disable help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
 complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -s s -l source -d 'The source(s) of the signer to add' -r
 complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
 complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
 complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l no-update -d 'Don\'t update the allowed signers file with the added signer(s)'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l no-verify -d 'Don\'t verify that the signer exists and has signing keys on it\'s source(s)'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l strict -d 'Don\'t add the signer if it does not exist or has no signing keys on it\'s source(s)'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
 complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -s v -l verbose -d 'Use verbose output'
-complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -s h -l help -d 'Print help'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -s h -l help -d 'Print help (see more with \'--help\')'
 complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from help" -f -a "add" -d 'Add an allowed signer'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from help" -f -a "enable" -d 'Enable a disabled signer, using it again when updating'
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from help" -f -a "disable" -d 'Disable a signer, skipping it when updating while retaining it\'s configuration'
 complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
-complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer help" -f -a "update" -d 'Update the allowed signers file'
-complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer help" -f -a "signer" -d 'Manage allowed signers'
-complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -f -a "add" -d 'Add a source'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -f -a "providers" -d 'List the supported source providers'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and not __fish_seen_subcommand_from add providers help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -l url -d 'The URL of the source\'s API endpoint, defaults to the canonical public instance of the provider' -r -f
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -l provider -d 'The provider of the source, detected by the host of the URL if not specified' -r -f -a "github\t''
+gitlab\t''
+command\t''
+url\t''"
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from add" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from providers" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from help" -f -a "add" -d 'Add a source'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from help" -f -a "providers" -d 'List the supported source providers'
+complete -c hanko -n "__fish_hanko_using_subcommand source; and __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -f -a "test" -d 'Check that saving the configuration file, as done when editing it using the `signer` and `source` commands, preserves it\'s formatting, printing the lines that would change'
+complete -c hanko -n "__fish_hanko_using_subcommand config; and not __fish_seen_subcommand_from test help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from test" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from help" -f -a "test" -d 'Check that saving the configuration file, as done when editing it using the `signer` and `source` commands, preserves it\'s formatting, printing the lines that would change'
+complete -c hanko -n "__fish_hanko_using_subcommand config; and __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c hanko -n "__fish_hanko_using_subcommand report" -l format -d 'The format of the report' -r -f -a "tree\t'A human readable tree of signers, sources and keys'
+json\t'A JSON array of signers'"
+complete -c hanko -n "__fish_hanko_using_subcommand report" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand report" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand report" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand report" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand report" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand report" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand report" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand report" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand report" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand report" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand report" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand report" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand report" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand report" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand report" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand report" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand report" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand export" -l format -d 'The format of the export' -r -f -a "json\t'A versioned JSON document containing an object per entry'"
+complete -c hanko -n "__fish_hanko_using_subcommand export" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand export" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand export" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand export" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand export" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand export" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand export" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand export" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand export" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand export" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand export" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand export" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand export" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand export" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand export" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand export" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand export" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l signer -d 'The configured signer to fetch the keys of' -r
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l source -d 'The configured source to exclusively fetch keys from' -r
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -l plan -d 'The plan to apply' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand apply" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -l public-key -d 'The public key of the key the allowed signers file was signed with' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -l signature -d 'The detached signature, defaults to the allowed signers file with a `.sig` extension appended' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand verify-file" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -l yes -d 'Remove the signers instead of only printing them'
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand prune-config" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -l force -d 'Overwrite existing hooks that were not installed by hanko'
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand install-hook" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand uninstall-hook" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand doctor" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand env" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand env" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand env" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand env" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand env" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand env" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand env" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand env" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand env" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand env" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand env" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand env" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand env" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand env" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand env" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand env" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand env" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -s c -l config -d 'The configuration file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -l config-dir -d 'A directory of additional configuration files merged in lexical order' -r -f -a "(__fish_complete_directories)"
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -l file -d 'The allowed signers file' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -l max-redirects -d 'The maximum number of redirects followed per request to a source' -r
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -l connect-timeout -d 'How long connecting to a source may take, overriding the built-in default of 2s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -l request-timeout -d 'How long a request to a source may take in total, overriding the built-in default of 10s for this invocation' -r
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -l lock-timeout -d 'How long to wait for another run writing the allowed signers file, failing immediately if not given' -r
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -l now -d 'Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the current time, e.g. to debug entries expiring in the future' -r
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -l trace-file -d 'Additionally write traces to the given file, at least at the info level, e.g. for long-running deployments whose output is not captured' -r -F
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -l trace-file-format -d 'The format of traces written to the trace file' -r -f -a "full\t'Every trace on a single line, including the spans it was recorded in'
+compact\t'Every trace on a single line, omitting the names of spans'"
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -l error-format -d 'The format errors are printed to stderr in' -r -f -a "human\t'A human readable description of the error and it\'s causes'
+json\t'A JSON object containing the kind of error, it\'s message and causes, as well as the affected signer if any'"
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -l suppress-warning -d 'Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple times' -r
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -l skip-invalid-configs -d 'Skip files within the configuration directory that fail to parse with a warning, instead of failing. The configuration file itself is never skipped'
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -s v -l verbose -d 'Use verbose output'
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -l dump-requests -d 'Dump requests made to sources and their responses to stderr, with secrets redacted'
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -l no-env-token -d 'Don\'t authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN` and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens'
+complete -c hanko -n "__fish_hanko_using_subcommand __complete" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer source config report export fetch apply verify-file prune-config install-hook uninstall-hook doctor env __complete help" -f -a "update" -d 'Update the allowed signers file'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer source config report export fetch apply verify-file prune-config install-hook uninstall-hook doctor env __complete help" -f -a "signer" -d 'Manage allowed signers'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer source config report export fetch apply verify-file prune-config install-hook uninstall-hook doctor env __complete help" -f -a "source" -d 'Manage sources'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer source config report export fetch apply verify-file prune-config install-hook uninstall-hook doctor env __complete help" -f -a "config" -d 'Inspect the configuration'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer source config report export fetch apply verify-file prune-config install-hook uninstall-hook doctor env __complete help" -f -a "report" -d 'Report the keys currently configured for each signer on each of it\'s sources, without writing the allowed signers file'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer source config report export fetch apply verify-file prune-config install-hook uninstall-hook doctor env __complete help" -f -a "export" -d 'Print all entries of the allowed signers file as a structured document for other tools, without writing the allowed signers file'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer source config report export fetch apply verify-file prune-config install-hook uninstall-hook doctor env __complete help" -f -a "fetch" -d 'Print the allowed signers file entries of a configured signer, retrieving it\'s keys only from the given configured source instead of the sources of the signer'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer source config report export fetch apply verify-file prune-config install-hook uninstall-hook doctor env __complete help" -f -a "apply" -d 'Apply a plan previously written using `hanko update --plan-out`'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer source config report export fetch apply verify-file prune-config install-hook uninstall-hook doctor env __complete help" -f -a "verify-file" -d 'Verify the detached signature of the allowed signers file'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer source config report export fetch apply verify-file prune-config install-hook uninstall-hook doctor env __complete help" -f -a "prune-config" -d 'Remove signers that no longer exist on any of their sources from the configuration, only printing them unless `--yes` is given'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer source config report export fetch apply verify-file prune-config install-hook uninstall-hook doctor env __complete help" -f -a "install-hook" -d 'Install git hooks into the repository in the current directory, updating the allowed signers file after checking out or merging commits'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer source config report export fetch apply verify-file prune-config install-hook uninstall-hook doctor env __complete help" -f -a "uninstall-hook" -d 'Remove the git hooks installed by hanko from the repository in the current directory'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer source config report export fetch apply verify-file prune-config install-hook uninstall-hook doctor env __complete help" -f -a "doctor" -d 'Check the git configuration in the current directory for the settings required to sign and verify commits and tags using the allowed signers file, suggesting fixes for misconfigured settings. Requires the `detect-allowed-signers` feature'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer source config report export fetch apply verify-file prune-config install-hook uninstall-hook doctor env __complete help" -f -a "env" -d 'List the environment variables used by hanko'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer source config report export fetch apply verify-file prune-config install-hook uninstall-hook doctor env __complete help" -f -a "__complete" -d 'Print the configured names of the given kind for dynamic shell completion'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and not __fish_seen_subcommand_from update signer source config report export fetch apply verify-file prune-config install-hook uninstall-hook doctor env __complete help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
 complete -c hanko -n "__fish_hanko_using_subcommand help; and __fish_seen_subcommand_from signer" -f -a "add" -d 'Add an allowed signer'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and __fish_seen_subcommand_from signer" -f -a "enable" -d 'Enable a disabled signer, using it again when updating'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and __fish_seen_subcommand_from signer" -f -a "disable" -d 'Disable a signer, skipping it when updating while retaining it\'s configuration'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and __fish_seen_subcommand_from source" -f -a "add" -d 'Add a source'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and __fish_seen_subcommand_from source" -f -a "providers" -d 'List the supported source providers'
+complete -c hanko -n "__fish_hanko_using_subcommand help; and __fish_seen_subcommand_from config" -f -a "test" -d 'Check that saving the configuration file, as done when editing it using the `signer` and `source` commands, preserves it\'s formatting, printing the lines that would change'
+
+# Complete the names of configured signers and sources using `hanko __complete`.
+complete -c hanko -n "__fish_hanko_using_subcommand update" -l only -f -a "(hanko __complete signers 2>/dev/null)"
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l signer -f -a "(hanko __complete signers 2>/dev/null)"
+complete -c hanko -n "__fish_hanko_using_subcommand fetch" -l source -f -a "(hanko __complete sources 2>/dev/null)"
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from add" -s s -l source -f -a "(hanko __complete sources 2>/dev/null)"
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from enable" -f -a "(hanko __complete signers 2>/dev/null)"
+complete -c hanko -n "__fish_hanko_using_subcommand signer; and __fish_seen_subcommand_from disable" -f -a "(hanko __complete signers 2>/dev/null)"
@@ -0,0 +1,42 @@
+use codspeed_criterion_compat::{criterion_group, criterion_main, Criterion};
+use hanko::allowed_signers::{Entry, File, OutputFormat};
+use std::path::PathBuf;
+
+/// The number of entries, every key is used by several of them.
+const N_ENTRIES: usize = 5000;
+const N_KEYS: usize = 500;
+const N_PRINCIPALS: usize = 50;
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let entries: Vec<Entry> = (0..N_ENTRIES)
+        .map(|i| {
+            Entry::new(
+                vec![format!("signer-{}@acme.corp", i % N_PRINCIPALS)],
+                None,
+                None,
+                format!(
+                    "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79Hydd{:08}",
+                    i % N_KEYS
+                )
+                .parse()
+                .unwrap(),
+            )
+        })
+        .collect();
+    // The content is rendered in memory, leaving no files behind.
+    let path = PathBuf::from("allowed_signers");
+
+    c.bench_function("deduplicate and render a large signers file", |b| {
+        b.iter(|| File::from_entries(path.clone(), entries.clone()).content());
+    });
+    c.bench_function("deduplicate and render a large authorized keys file", |b| {
+        b.iter(|| {
+            File::from_entries(path.clone(), entries.clone())
+                .with_format(OutputFormat::AuthorizedKeys)
+                .content()
+        });
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
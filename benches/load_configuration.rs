@@ -1,5 +1,5 @@
 use codspeed_criterion_compat::{criterion_group, criterion_main, Criterion};
-use hanko::config::Configuration;
+use hanko::config::{Configuration, LoadOptions};
 use indoc::indoc;
 use std::{io::Write, path::Path};
 
@@ -24,7 +24,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     let path: &Path = &file.into_temp_path();
 
     c.bench_function("load the example configuration", |b| {
-        b.iter(|| Configuration::load(path).unwrap());
+        b.iter(|| Configuration::load(path, LoadOptions::default()).unwrap());
     });
 }
 
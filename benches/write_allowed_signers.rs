@@ -1,4 +1,4 @@
-use chrono::{Local, TimeZone};
+use chrono::{TimeZone, Utc};
 use codspeed_criterion_compat::{criterion_group, criterion_main, Criterion};
 use hanko::allowed_signers::{Entry, File};
 
@@ -19,7 +19,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             ),
             Entry::new(
                 vec!["ian.malcom@acme.corp".to_string()],
-                Some(Local.with_ymd_and_hms(2024, 4, 11, 22, 00, 00).unwrap()),
+                Some(Utc.with_ymd_and_hms(2024, 4, 11, 22, 00, 00).unwrap()),
                 None,
                 "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILWtK6WxXw7NVhbn6fTQ0dECF8y98fahSIsqKMh+sSo9"
                     .parse()
@@ -28,7 +28,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             Entry::new(
                 vec!["cwoods@universal.exports".to_string()],
                 None,
-                Some(Local.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()),
+                Some(Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()),
                 "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIJHDGMF+tZQL3dcr1arPst+YP8v33Is0kAJVvyTKrxMw"
                     .parse()
                     .unwrap(),
@@ -2,46 +2,175 @@
 //!
 //! [File Format Documentation](https://man.openbsd.org/ssh-keygen.1#ALLOWED_SIGNERS)
 use std::{
+    collections::HashMap,
+    ffi::OsString,
     fmt, fs,
+    future::{self, Future},
     io::{self, Write},
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
 use anyhow::Context;
-use chrono::{DateTime, Local};
-use tracing::trace;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, trace};
 
 use super::{
-    signer::{get_entries, Signer},
-    ssh::PublicKey,
+    signer::{get_entries_until, glob_matches, KeyValidation, Signer},
+    ssh::{is_key_type, Fingerprint, PublicKey},
 };
+use crate::{
+    clock,
+    diagnostic::{warning, Warning},
+    duration::Duration,
+};
+
+/// The comment marking files written by hanko, written as the first line of the file.
+pub const MANAGED_MARKER: &str = "# Managed by hanko, manual changes will be overwritten.";
+
+/// The time signers still being retrieved are given to finish once generating the allowed signers
+/// file is interrupted.
+const INTERRUPT_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_secs(2);
 
 /// The allowed signers file.
 #[derive(Debug)]
 pub struct File {
     pub path: PathBuf,
     pub entries: Vec<Entry>, // TODO: Use HashSet
+    pub format: OutputFormat,
+    /// Group entries by the source their key was retrieved from, writing a header comment above
+    /// each group.
+    pub group_by_source: bool,
+    /// The order entries are written in.
+    pub sort: SortOrder,
+}
+
+/// The order entries of the allowed signers file are written in.
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortOrder {
+    /// Sorted by principals, then validity and key, writing the same file regardless of the order
+    /// keys were retrieved in.
+    #[default]
+    Principal,
+    /// Sorted by key type, then like [`SortOrder::Principal`].
+    KeyType,
+    /// Deduplicated like the sorted orders, but in the order of the configured signers and the
+    /// keys returned by their sources, with duplicates written at their first occurrence.
+    Insertion,
+    /// Written exactly as retrieved in the order of [`SortOrder::Insertion`], without
+    /// deduplicating entries.
+    None,
+}
+
+/// The format the allowed signers file is written in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// The OpenSSH allowed signers format.
+    #[default]
+    AllowedSigners,
+    /// The OpenSSH authorized keys format, containing only the deduplicated keys of all entries.
+    AuthorizedKeys,
 }
 
 impl File {
     /// Write the file to disk.
+    /// Regular files are replaced atomically, such that readers never observe a partially written
+    /// file. Special files like FIFOs are written to without being truncated, allowing the output
+    /// to be consumed by another process.
     #[tracing::instrument(skip(self), fields(path = %self.path.display()), level = "trace")]
     pub fn write(&self) -> io::Result<()> {
-        trace!("Opening allowed signers file for writing");
-        let file = fs::File::create(&self.path)?;
-        let mut file_buf = io::BufWriter::new(file);
-
-        let sorted_entries = {
-            let mut entries = self.entries.iter().collect::<Vec<_>>();
-            entries.sort();
-            entries
-        };
+        if !is_special_file(&self.path) {
+            trace!("Writing allowed signers file atomically");
+            return write_atomic(&self.path, self.content().as_bytes());
+        }
+
+        trace!("Allowed signers file is a special file and will not be truncated");
+        let file = fs::OpenOptions::new().write(true).open(&self.path)?;
+
         trace!("Writing to allowed signers file");
-        for entry in sorted_entries {
-            writeln!(file_buf, "{entry}")?;
+        self.write_to(io::BufWriter::new(file))
+    }
+
+    /// Write the content of the file to the given writer, e.g. the stdin of another process.
+    ///
+    /// # Errors
+    ///
+    /// When writing to the writer fails.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.content().as_bytes())?;
+        writer.flush()
+    }
+
+    /// Write the file to disk unless it's content would not change, returning whether it was
+    /// written. Special files are always written since their content cannot be compared.
+    ///
+    /// # Errors
+    ///
+    /// When the file cannot be written.
+    pub fn write_if_changed(&self) -> io::Result<bool> {
+        if !is_special_file(&self.path)
+            && fs::read_to_string(&self.path).is_ok_and(|existing| existing == self.content())
+        {
+            trace!("Allowed signers file is up to date and will not be written");
+            return Ok(false);
         }
-        writeln!(file_buf)?;
-        Ok(())
+        self.write()?;
+        Ok(true)
+    }
+
+    /// The content of the file according to it's format, starting with the [`MANAGED_MARKER`].
+    /// In the allowed signers format, this consists of the sorted [`canonical_entries`] followed by
+    /// a blank line, with the comment of entries written on a line above the first entry of their
+    /// principals. If grouped by source, entries of unknown source are written first, followed by
+    /// the entries of each source below a header comment, ordered by source name. In the authorized
+    /// keys format, it consists of the sorted and deduplicated keys, preferring keys without a
+    /// comment among keys differing only in their comment.
+    /// Entries are sorted and deduplicated afterwards, which is considerably faster than collecting
+    /// large sets of entries into an ordered set.
+    #[must_use]
+    pub fn content(&self) -> String {
+        let mut content = String::from(MANAGED_MARKER);
+        content.push('\n');
+        match self.format {
+            OutputFormat::AllowedSigners => {
+                let mut entries = self.written_entries();
+                if self.group_by_source {
+                    entries.sort_by(|a, b| a.key.source().cmp(&b.key.source()));
+                    for group in entries.chunk_by(|a, b| a.key.source() == b.key.source()) {
+                        if let Some(source) = group[0].key.source() {
+                            content.push_str("# --- ");
+                            content.push_str(source);
+                            content.push_str(" ---\n");
+                        }
+                        push_entries(&mut content, group);
+                    }
+                } else {
+                    push_entries(&mut content, &entries);
+                }
+                content.push('\n');
+            }
+            OutputFormat::AuthorizedKeys => {
+                let mut keys = self.entries.iter().map(|e| &e.key).collect::<Vec<_>>();
+                keys.sort_unstable_by(|a, b| {
+                    (a.material(), a.has_comment(), a).cmp(&(b.material(), b.has_comment(), b))
+                });
+                keys.dedup_by(|a, b| a.material() == b.material());
+                for key in keys {
+                    content.push_str(&key.to_string());
+                    content.push('\n');
+                }
+            }
+        }
+        content
+    }
+
+    /// The entries as written in the allowed signers format, deduplicated and ordered according to
+    /// the sort order of the file, but not grouped by source.
+    #[must_use]
+    pub fn written_entries(&self) -> Vec<Entry> {
+        ordered_entries(&self.entries, self.sort)
     }
 
     /// Create an instance from a collection of entries.
@@ -52,17 +181,280 @@ impl File {
         Self {
             path,
             entries: entries.into_iter().collect(),
+            format: OutputFormat::default(),
+            group_by_source: false,
+            sort: SortOrder::default(),
+        }
+    }
+
+    /// Keep only entries with principals matching the given glob pattern, where `*` matches any
+    /// number of characters and `?` matches exactly one. Entries of multiple principals are kept
+    /// if any of them matches, or only if all of them match if `all` is set.
+    pub fn retain_principals(&mut self, pattern: &str, all: bool) {
+        let before = self.entries.len();
+        self.entries.retain(|entry| {
+            let mut principals = entry.principals.iter();
+            if all {
+                principals.all(|principal| glob_matches(pattern, principal))
+            } else {
+                principals.any(|principal| glob_matches(pattern, principal))
+            }
+        });
+        debug!(
+            pattern,
+            dropped = before - self.entries.len(),
+            "Filtered entries by principal"
+        );
+    }
+
+    /// Remove principals matching any of the given glob patterns from all entries, dropping
+    /// entries none of whose principals remain.
+    pub fn exclude_principals(&mut self, patterns: &[String]) {
+        if patterns.is_empty() {
+            return;
+        }
+        let before = self.entries.len();
+        for entry in &mut self.entries {
+            entry
+                .principals
+                .retain(|principal| !patterns.iter().any(|p| glob_matches(p, principal)));
+        }
+        self.entries.retain(|entry| !entry.principals.is_empty());
+        debug!(
+            ?patterns,
+            dropped = before - self.entries.len(),
+            "Excluded principals"
+        );
+    }
+
+    /// Keep only entries valid at the given point in time, dropping entries not yet valid as well
+    /// as expired entries, unless `include_expired` is set.
+    pub fn retain_valid(&mut self, now: DateTime<Utc>, include_expired: bool) {
+        let before = self.entries.len();
+        self.entries.retain(|entry| {
+            let started = entry
+                .valid_after
+                .is_none_or(|valid_after| valid_after <= now);
+            let expired = entry
+                .valid_before
+                .is_some_and(|valid_before| valid_before <= now);
+            started && (include_expired || !expired)
+        });
+        debug!(
+            dropped = before - self.entries.len(),
+            "Filtered entries by validity"
+        );
+    }
+
+    /// Keys written for multiple distinct principals, e.g. a shared bot key or a misattributed key,
+    /// by fingerprint along with the principals of each of their entries. Keys that are not
+    /// well-formed cannot be fingerprinted and are never reported.
+    #[must_use]
+    pub fn shared_keys(&self) -> Vec<(Fingerprint, Vec<&[String]>)> {
+        let mut principals: HashMap<Fingerprint, Vec<&[String]>> = HashMap::new();
+        for entry in &self.entries {
+            let Some(fingerprint) = entry.key.fingerprint() else {
+                continue;
+            };
+            let seen = principals.entry(fingerprint).or_default();
+            if !seen.contains(&entry.principals.as_slice()) {
+                seen.push(&entry.principals);
+            }
         }
+        let mut shared: Vec<_> = principals
+            .into_iter()
+            .filter(|(_, principals)| principals.len() > 1)
+            .collect();
+        shared.sort_by(|(_, a), (_, b)| a.cmp(b));
+        shared
     }
+
+    /// Use the given format when writing the file.
+    #[must_use]
+    pub fn with_format(self, format: OutputFormat) -> Self {
+        Self { format, ..self }
+    }
+
+    /// Set whether entries are grouped by the source their key was retrieved from.
+    #[must_use]
+    pub fn with_group_by_source(self, group_by_source: bool) -> Self {
+        Self {
+            group_by_source,
+            ..self
+        }
+    }
+
+    /// Use the given order when writing entries.
+    #[must_use]
+    pub fn with_sort(self, sort: SortOrder) -> Self {
+        Self { sort, ..self }
+    }
+}
+
+/// Append the given entries to the content of the file, with the comment of entries written on a
+/// line above the first entry of their principals.
+fn push_entries(content: &mut String, entries: &[Entry]) {
+    let mut previous: Option<&Entry> = None;
+    for entry in entries {
+        if let Some(comment) = &entry.comment {
+            if previous
+                .is_none_or(|p| p.principals != entry.principals || p.comment != entry.comment)
+            {
+                content.push_str("# ");
+                content.push_str(&comment.replace(['\n', '\r'], " "));
+                content.push('\n');
+            }
+        }
+        previous = Some(entry);
+        content.push_str(&entry.to_string());
+        content.push('\n');
+    }
+}
+
+/// The given entries in the given order, deduplicated using [`canonical_entries`] unless the order
+/// is [`SortOrder::None`].
+fn ordered_entries(entries: &[Entry], sort: SortOrder) -> Vec<Entry> {
+    match sort {
+        SortOrder::Principal => canonical_entries(entries),
+        SortOrder::KeyType => {
+            let mut canonical = canonical_entries(entries);
+            canonical.sort_by(|a, b| a.key.material().0.cmp(b.key.material().0));
+            canonical
+        }
+        SortOrder::Insertion => {
+            let mut first = HashMap::new();
+            for (i, entry) in entries.iter().enumerate() {
+                first
+                    .entry((&entry.principals, entry.key.material()))
+                    .or_insert(i);
+            }
+            let mut canonical = canonical_entries(entries);
+            canonical.sort_by_cached_key(|entry| first[&(&entry.principals, entry.key.material())]);
+            canonical
+        }
+        SortOrder::None => entries.to_vec(),
+    }
+}
+
+/// The given entries in sorted order, with duplicates collapsed into a single canonical entry.
+/// Entries of the same principals are duplicates if their keys have the same key material, even
+/// if they differ in comment, e.g. since GitLab keeps the comment of keys while GitHub doesn't.
+/// The canonical entry uses the key without a comment if any and the most restrictive validity of
+/// all duplicates, so that the same file is written regardless of which source returned a key.
+/// Likewise, the source of the canonical entry is the first of all duplicates by name.
+fn canonical_entries(entries: &[Entry]) -> Vec<Entry> {
+    let mut entries = entries.to_vec();
+    entries.sort_unstable_by(|a, b| {
+        (&a.principals, a.key.material())
+            .cmp(&(&b.principals, b.key.material()))
+            .then_with(|| a.cmp(b))
+    });
+    entries.dedup_by(|duplicate, canonical| {
+        if duplicate.principals != canonical.principals
+            || duplicate.key.material() != canonical.key.material()
+        {
+            return false;
+        }
+        canonical.valid_after = canonical.valid_after.max(duplicate.valid_after);
+        canonical.valid_before = match (canonical.valid_before, duplicate.valid_before) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        let source = match (canonical.key.source(), duplicate.key.source()) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+        .map(ToString::to_string);
+        if canonical.key.has_comment() && !duplicate.key.has_comment() {
+            canonical.key = duplicate.key.clone();
+        }
+        if canonical.key.source() != source.as_deref() {
+            canonical.key = canonical.key.clone().with_source(source);
+        }
+        if canonical.comment.is_none() {
+            canonical.comment = duplicate.comment.take();
+        }
+        true
+    });
+    entries.sort_unstable();
+    entries
+}
+
+/// Whether the given path refers to an existing, non-empty regular file that does not contain the
+/// [`MANAGED_MARKER`], e.g. a hand-written allowed signers file that predates using hanko.
+#[must_use]
+pub fn is_unmanaged(path: &Path) -> bool {
+    if !fs::metadata(path).is_ok_and(|metadata| metadata.is_file()) {
+        return false;
+    }
+    fs::read_to_string(path).is_ok_and(|content| {
+        !content.trim().is_empty() && !content.lines().any(|line| line == MANAGED_MARKER)
+    })
+}
+
+/// Write the given content to a temporary file next to the given path and rename it onto the
+/// path, atomically replacing any existing file. Symbolic links are followed, replacing the file
+/// they point to, and the permissions of an existing file are kept.
+pub(crate) fn write_atomic(path: &Path, content: &[u8]) -> io::Result<()> {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let Some(file_name) = path.file_name() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a file", path.display()),
+        ));
+    };
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let mut prefix = OsString::from(".");
+    prefix.push(file_name);
+    prefix.push(".");
+
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(&prefix).suffix(".tmp");
+    // New files get the same permissions as when created using `File::create`, instead of only
+    // being readable by their owner.
+    #[cfg(unix)]
+    builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o666));
+    // The temporary file is removed when dropped, unless it was persisted.
+    let mut file = builder.tempfile_in(dir)?;
+    file.write_all(content)?;
+    if let Ok(metadata) = fs::metadata(&path) {
+        file.as_file().set_permissions(metadata.permissions())?;
+    }
+    file.as_file().sync_all()?;
+    file.persist(&path).map_err(|err| err.error)?;
+    Ok(())
+}
+
+/// Whether the given path refers to an existing special file, like a FIFO or character device,
+/// that cannot be truncated like a regular file.
+#[cfg(unix)]
+fn is_special_file(path: &Path) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+
+    fs::metadata(path).is_ok_and(|metadata| {
+        let file_type = metadata.file_type();
+        file_type.is_fifo() || file_type.is_char_device()
+    })
+}
+
+#[cfg(not(unix))]
+fn is_special_file(_path: &Path) -> bool {
+    false
 }
 
 /// An entry in the allowed signers file.
-#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Entry {
     principals: Vec<String>,
-    valid_after: Option<DateTime<Local>>,
-    valid_before: Option<DateTime<Local>>,
+    valid_after: Option<DateTime<Utc>>,
+    valid_before: Option<DateTime<Utc>>,
     key: PublicKey,
+    /// A comment describing the signer of the entry, written on a separate line since OpenSSH
+    /// only supports full-line comments.
+    comment: Option<String>,
 }
 
 impl Entry {
@@ -73,8 +465,8 @@ impl Entry {
     /// If the provided principals are empty.
     pub fn new(
         principals: Vec<String>,
-        valid_after: Option<DateTime<Local>>,
-        valid_before: Option<DateTime<Local>>,
+        valid_after: Option<DateTime<Utc>>,
+        valid_before: Option<DateTime<Utc>>,
         key: PublicKey,
     ) -> Self {
         assert!(
@@ -86,61 +478,378 @@ impl Entry {
             valid_after,
             valid_before,
             key,
+            comment: None,
         }
     }
+
+    /// The principals allowed to sign using the key of the entry.
+    #[must_use]
+    pub fn principals(&self) -> &[String] {
+        &self.principals
+    }
+
+    /// The point in time from which on the key of the entry is valid, if limited.
+    #[must_use]
+    pub fn valid_after(&self) -> Option<DateTime<Utc>> {
+        self.valid_after
+    }
+
+    /// The point in time until which the key of the entry is valid, if limited.
+    #[must_use]
+    pub fn valid_before(&self) -> Option<DateTime<Utc>> {
+        self.valid_before
+    }
+
+    /// The key of the entry.
+    #[must_use]
+    pub fn key(&self) -> &PublicKey {
+        &self.key
+    }
+
+    /// Use the given comment describing the signer of the entry.
+    #[must_use]
+    pub fn with_comment(self, comment: Option<String>) -> Self {
+        Self { comment, ..self }
+    }
+
+    /// The entry with it's principals sorted and deduplicated, such that entries of the same
+    /// principals are written the same regardless of the order they are configured in.
+    #[must_use]
+    pub fn with_sorted_principals(mut self) -> Self {
+        self.principals.sort_unstable();
+        self.principals.dedup();
+        self
+    }
 }
 
 impl fmt::Display for Entry {
     /// Display the entry in the format expected by the allowed signers file.
+    /// Timestamps are displayed in UTC using a `Z` suffix, so that the file has the same meaning
+    /// regardless of the timezone of the machine it was generated on.
     ///
     /// # Examples
     /// ```
     /// # use hanko::allowed_signers::Entry;
-    /// # use chrono::{TimeZone, Local};
-    /// let signer = Entry {
-    ///     principals: vec!["cwoods@universal.exports".to_string()],
-    ///     valid_after: None,
-    ///     valid_before: Some(Local.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()),
-    ///     key: "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIJHDGMF+tZQL3dcr1arPst+YP8v33Is0kAJVvyTKrxMw"
+    /// # use chrono::{TimeZone, Utc};
+    /// let signer = Entry::new(
+    ///     vec!["cwoods@universal.exports".to_string()],
+    ///     None,
+    ///     Some(Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()),
+    ///     "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIJHDGMF+tZQL3dcr1arPst+YP8v33Is0kAJVvyTKrxMw"
     ///         .parse()
     ///         .unwrap(),
-    /// };
-    /// assert_eq!(signer.to_string(), "cwoods@universal.exports valid-before=20300101000000 ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIJHDGMF+tZQL3dcr1arPst+YP8v33Is0kAJVvyTKrxMw");
+    /// );
+    /// assert_eq!(signer.to_string(), "cwoods@universal.exports valid-before=20300101000000Z ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIJHDGMF+tZQL3dcr1arPst+YP8v33Is0kAJVvyTKrxMw");
     /// ```
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        const TIMESTAMP_FMT: &str = "%Y%m%d%H%M%S";
+        const TIMESTAMP_FMT: &str = "%Y%m%d%H%M%SZ";
 
         write!(f, "{}", self.principals.join(","))?;
 
-        if let Some(valid_after) = self.valid_after {
-            write!(f, " valid-after={}", valid_after.format(TIMESTAMP_FMT))?;
-        };
-        if let Some(valid_before) = self.valid_before {
-            write!(f, " valid-before={}", valid_before.format(TIMESTAMP_FMT))?;
-        };
+        // Options are separated by commas, as whitespace separates the options from the key.
+        let options: Vec<String> = [
+            ("valid-after", self.valid_after),
+            ("valid-before", self.valid_before),
+        ]
+        .into_iter()
+        .filter_map(|(name, timestamp)| {
+            timestamp.map(|t| format!("{name}={}", t.format(TIMESTAMP_FMT)))
+        })
+        .collect();
+        if !options.is_empty() {
+            write!(f, " {}", options.join(","))?;
+        }
 
         write!(f, " {}", self.key)
     }
 }
 
-/// Update the allowed signers file.
-pub async fn update<S>(path: &Path, signers: S) -> anyhow::Result<()>
+/// An error that can occur when parsing an allowed signers file entry.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum ParseEntryError {
+    #[error("entry is missing principals")]
+    MissingPrincipals,
+    #[error("entry is missing a public key")]
+    MissingKey,
+    #[error("invalid timestamp `{0}`")]
+    InvalidTimestamp(String),
+    #[error("unsupported option `{0}`")]
+    UnsupportedOption(String),
+}
+
+impl FromStr for Entry {
+    type Err = ParseEntryError;
+
+    /// Parse an entry in the format of the allowed signers file.
+    ///
+    /// Timestamps of the `valid-after` and `valid-before` options are accepted in the same forms
+    /// as `ssh-keygen`, being `YYYYMMDD`, `YYYYMMDDHHMM` or `YYYYMMDDHHMMSS`, interpreted as local
+    /// time unless followed by a `Z` suffix for UTC or an explicit `+HHMM`/`-HHMM` offset.
+    ///
+    /// # Examples
+    /// ```
+    /// # use hanko::allowed_signers::Entry;
+    /// let entry: Entry = "cwoods@universal.exports valid-before=20300101000000Z ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIJHDGMF+tZQL3dcr1arPst+YP8v33Is0kAJVvyTKrxMw"
+    ///     .parse()
+    ///     .unwrap();
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (principals, rest) = s.split_once(char::is_whitespace).unwrap_or((s, ""));
+        let principals: Vec<String> = principals
+            .trim_matches('"')
+            .split(',')
+            .filter(|p| !p.is_empty())
+            .map(ToString::to_string)
+            .collect();
+        if principals.is_empty() {
+            return Err(ParseEntryError::MissingPrincipals);
+        }
+
+        let mut rest = rest.trim_start();
+        let mut valid_after = None;
+        let mut valid_before = None;
+        let (first, remainder) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        if !first.is_empty() && !is_key_type(first) {
+            for option in first.split(',') {
+                match option.split_once('=') {
+                    Some(("valid-after", value)) => {
+                        valid_after = Some(parse_timestamp(value.trim_matches('"'))?);
+                    }
+                    Some(("valid-before", value)) => {
+                        valid_before = Some(parse_timestamp(value.trim_matches('"'))?);
+                    }
+                    _ => return Err(ParseEntryError::UnsupportedOption(option.to_string())),
+                }
+            }
+            rest = remainder.trim_start();
+        }
+        if rest.is_empty() {
+            return Err(ParseEntryError::MissingKey);
+        }
+
+        Ok(Entry {
+            principals,
+            valid_after,
+            valid_before,
+            key: rest.parse().expect("parsing a public key is infallible"),
+            comment: None,
+        })
+    }
+}
+
+/// Parse a timestamp as accepted by `ssh-keygen` for the `valid-after` and `valid-before` options.
+/// Like `ssh-keygen`, timestamps without a `Z` suffix or offset are interpreted in local time.
+fn parse_timestamp(s: &str) -> Result<DateTime<Utc>, ParseEntryError> {
+    let invalid = || ParseEntryError::InvalidTimestamp(s.to_string());
+
+    let (datetime, offset) = if let Some(datetime) = s.strip_suffix(['Z', 'z']) {
+        (datetime, Some(FixedOffset::east_opt(0).unwrap()))
+    } else if let Some(pos) = s.rfind(['+', '-']) {
+        let (datetime, offset) = s.split_at(pos);
+        let offset = parse_offset(offset).ok_or_else(invalid)?;
+        (datetime, Some(offset))
+    } else {
+        (s, None)
+    };
+    if !datetime.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    let naive = match datetime.len() {
+        8 => NaiveDate::parse_from_str(datetime, "%Y%m%d")
+            .ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0)),
+        12 => NaiveDateTime::parse_from_str(datetime, "%Y%m%d%H%M").ok(),
+        14 => NaiveDateTime::parse_from_str(datetime, "%Y%m%d%H%M%S").ok(),
+        _ => None,
+    }
+    .ok_or_else(invalid)?;
+
+    match offset {
+        Some(offset) => offset
+            .from_local_datetime(&naive)
+            .single()
+            .map(|dt| dt.with_timezone(&Utc)),
+        None => Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc)),
+    }
+    .ok_or_else(invalid)
+}
+
+/// Parse a timezone offset in the form of `+HHMM` or `-HHMM`.
+fn parse_offset(s: &str) -> Option<FixedOffset> {
+    let (sign, digits) = s.split_at(1);
+    if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits[2..].parse().ok()?;
+    let seconds = (hours * 60 + minutes) * 60;
+    match sign {
+        "+" => FixedOffset::east_opt(seconds),
+        _ => FixedOffset::west_opt(seconds),
+    }
+}
+
+/// Options affecting the content of the written allowed signers file.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct OutputOptions {
+    /// Strip comments, like the ones appended by GitLab, from keys.
+    pub strip_key_comments: bool,
+    /// Drop keys created more recently than this.
+    pub min_key_age: Option<Duration>,
+    /// Drop keys created longer ago than this.
+    pub max_key_age: Option<Duration>,
+    /// Drop keys without a known creation date if a key age is configured, instead of keeping them.
+    pub require_key_creation_date: bool,
+    /// How keys that are not well-formed SSH public keys are handled.
+    pub validate_keys: KeyValidation,
+    /// Group entries by the source their key was retrieved from, below a header comment.
+    pub group_by_source: bool,
+    /// Sort and deduplicate the principals of each entry instead of keeping their configured
+    /// order.
+    pub sort_principals: bool,
+    /// The order entries are written in.
+    pub sort: SortOrder,
+    /// Warn about keys expiring within this window.
+    pub warn_expiry_within: Option<Duration>,
+    /// Glob patterns of principals removed from all entries, dropping entries without remaining
+    /// principals.
+    pub exclude_principals: Vec<String>,
+}
+
+impl OutputOptions {
+    /// Whether the age of the given key at the given point in time complies with the configured
+    /// minimum and maximum key age. Keys without a known creation date comply unless
+    /// `require_key_creation_date` is set.
+    pub(crate) fn accepts_key_age(&self, key: &PublicKey, now: DateTime<Utc>) -> bool {
+        if self.min_key_age.is_none() && self.max_key_age.is_none() {
+            return true;
+        }
+        let Some(created_at) = key.created_at() else {
+            return !self.require_key_creation_date;
+        };
+        let age = now - created_at;
+        self.min_key_age
+            .is_none_or(|min| age >= min.as_time_delta())
+            && self
+                .max_key_age
+                .is_none_or(|max| age <= max.as_time_delta())
+    }
+}
+
+/// Update the allowed signers file using the given format, returning whether it's content changed.
+///
+/// # Errors
+///
+/// When retrieving keys from a source fails or the file cannot be written.
+pub async fn update<S>(
+    path: &Path,
+    signers: S,
+    options: &OutputOptions,
+    format: OutputFormat,
+) -> anyhow::Result<bool>
 where
     S: IntoIterator<Item = Signer>,
 {
-    let entries = get_entries(signers).await?;
-
-    let file = File::from_entries(path.to_path_buf(), entries);
-    file.write().context(format!(
+    let file = generate(path, signers, options, format).await?;
+    file.write_if_changed().context(format!(
         "Failed to write allowed signers file to {}",
         path.display()
     ))
 }
 
+/// Generate the allowed signers file using the given format from the keys of the given signers,
+/// without writing it.
+///
+/// # Errors
+///
+/// When retrieving keys from a source fails.
+pub async fn generate<S>(
+    path: &Path,
+    signers: S,
+    options: &OutputOptions,
+    format: OutputFormat,
+) -> anyhow::Result<File>
+where
+    S: IntoIterator<Item = Signer>,
+{
+    let (file, _) = generate_until(path, signers, options, format, future::pending()).await?;
+    Ok(file)
+}
+
+/// Generate the allowed signers file like [`generate`], but stop retrieving keys once the given
+/// future completes, e.g. when the user interrupts hanko. Signers still being retrieved are given
+/// a short grace period to finish before they are left out of the file. Returns the file and
+/// whether it is partial, i.e. any signer was left out.
+///
+/// # Errors
+///
+/// When retrieving keys from a source fails.
+pub async fn generate_until<S, F>(
+    path: &Path,
+    signers: S,
+    options: &OutputOptions,
+    format: OutputFormat,
+    interrupted: F,
+) -> anyhow::Result<(File, bool)>
+where
+    S: IntoIterator<Item = Signer>,
+    F: Future<Output = ()>,
+{
+    let (mut entries, partial) =
+        get_entries_until(signers, interrupted, INTERRUPT_GRACE_PERIOD).await?;
+    let now = clock::now();
+    entries.retain(|entry| {
+        let accepted = options.accepts_key_age(&entry.key, now);
+        if !accepted {
+            warning!(
+                Warning::KeyAgeRejected,
+                [principals = ?entry.principals],
+                "Dropping key {} since it does not comply with the configured key age", entry.key
+            );
+        }
+        accepted
+    });
+    if !options.exclude_principals.is_empty() {
+        let mut file = File::from_entries(path.to_path_buf(), entries);
+        file.exclude_principals(&options.exclude_principals);
+        entries = file.entries;
+    }
+    if options.sort_principals {
+        entries = entries
+            .into_iter()
+            .map(Entry::with_sorted_principals)
+            .collect();
+    }
+    if options.strip_key_comments {
+        for entry in &mut entries {
+            entry.key = entry.key.without_comment();
+        }
+    }
+
+    if format == OutputFormat::AuthorizedKeys {
+        debug!("Ignoring principals since they are not part of the authorized keys format");
+    }
+
+    Ok((
+        File::from_entries(path.to_path_buf(), entries)
+            .with_format(format)
+            .with_group_by_source(options.group_by_source)
+            .with_sort(options.sort),
+        partial,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::TimeZone as _;
+    use chrono::TimeDelta;
     use rstest::*;
     use std::fs;
 
@@ -153,6 +862,7 @@ mod tests {
             key: "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS"
                 .parse()
                 .unwrap(),
+            comment: None,
         }
     }
 
@@ -160,11 +870,12 @@ mod tests {
     fn entry_imalcom() -> Entry {
         Entry {
             principals: vec!["ian.malcom@acme.corp".to_string()],
-            valid_after: Some(Local.with_ymd_and_hms(2024, 4, 11, 22, 00, 00).unwrap()),
+            valid_after: Some(Utc.with_ymd_and_hms(2024, 4, 11, 22, 00, 00).unwrap()),
             valid_before: None,
             key: "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILWtK6WxXw7NVhbn6fTQ0dECF8y98fahSIsqKMh+sSo9"
                 .parse()
                 .unwrap(),
+            comment: None,
         }
     }
 
@@ -173,10 +884,11 @@ mod tests {
         Entry {
             principals: vec!["cwoods@universal.exports".to_string()],
             valid_after: None,
-            valid_before: Some(Local.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()),
+            valid_before: Some(Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()),
             key: "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIJHDGMF+tZQL3dcr1arPst+YP8v33Is0kAJVvyTKrxMw"
                 .parse()
                 .unwrap(),
+            comment: None,
         }
     }
 
@@ -192,6 +904,7 @@ mod tests {
             key: "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIDw32w3ciofX3/gFoyCtPWxSsWYmylwdKZ9Q/BmoBR/g"
                 .parse()
                 .unwrap(),
+            comment: None,
         }
     }
 
@@ -227,11 +940,11 @@ mod tests {
     )]
     #[case(
         entry_imalcom(),
-        "ian.malcom@acme.corp valid-after=20240411220000 ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILWtK6WxXw7NVhbn6fTQ0dECF8y98fahSIsqKMh+sSo9"
+        "ian.malcom@acme.corp valid-after=20240411220000Z ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILWtK6WxXw7NVhbn6fTQ0dECF8y98fahSIsqKMh+sSo9"
     )]
     #[case(
         entry_cwoods(),
-        "cwoods@universal.exports valid-before=20300101000000 ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIJHDGMF+tZQL3dcr1arPst+YP8v33Is0kAJVvyTKrxMw"
+        "cwoods@universal.exports valid-before=20300101000000Z ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIJHDGMF+tZQL3dcr1arPst+YP8v33Is0kAJVvyTKrxMw"
     )]
     #[case(
         entry_ebert(),
@@ -269,6 +982,496 @@ mod tests {
         assert!(content.ends_with("\n\n")); // Two newlines since the last entry already ends with one.
     }
 
+    /// Writing the allowed signers file only if changed skips writing identical content.
+    #[rstest]
+    fn writing_if_changed_skips_unchanged_content(
+        example_allowed_signers: (File, tempfile::TempPath),
+    ) {
+        let (mut file, _path) = example_allowed_signers;
+
+        assert!(file.write_if_changed().unwrap());
+        assert!(!file.write_if_changed().unwrap());
+
+        file.entries.pop();
+        assert!(file.write_if_changed().unwrap());
+    }
+
+    /// Writing to a FIFO delivers all entries to the reading process.
+    #[cfg(unix)]
+    #[rstest]
+    fn writing_to_fifo_delivers_entries(example_allowed_signers: (File, tempfile::TempPath)) {
+        let (mut file, _) = example_allowed_signers;
+        let dir = tempfile::TempDir::new().unwrap();
+        let fifo = dir.path().join("allowed_signers");
+        let status = std::process::Command::new("mkfifo")
+            .arg(&fifo)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        file.path.clone_from(&fifo);
+
+        let reader = std::thread::spawn(move || fs::read_to_string(fifo).unwrap());
+        file.write().unwrap();
+        let content = reader.join().unwrap();
+
+        for entry in &file.entries {
+            assert!(content.contains(&entry.to_string()));
+        }
+    }
+
+    /// Duplicate entries, e.g. returned by multiple sources of a signer, are only written once.
+    #[rstest]
+    fn duplicate_entries_written_once(example_allowed_signers: (File, tempfile::TempPath)) {
+        let (mut file, _path) = example_allowed_signers;
+        file.entries.push(entry_jsnow());
+
+        let content = file.content();
+
+        assert_eq!(content.matches(&entry_jsnow().to_string()).count(), 1);
+    }
+
+    /// Entries of the same principals and key material are collapsed into one using the key
+    /// without comment and the most restrictive validity.
+    #[rstest]
+    fn duplicate_keys_differing_in_comment_collapsed() {
+        let jsnow = entry_jsnow();
+        let with_comment = Entry {
+            key: format!("{} jsnow@laptop", jsnow.key).parse().unwrap(),
+            valid_after: entry_imalcom().valid_after,
+            ..jsnow.clone()
+        };
+        let with_validity = Entry {
+            valid_before: entry_cwoods().valid_before,
+            ..jsnow.clone()
+        };
+
+        let entries = canonical_entries(&[with_comment, jsnow.clone(), with_validity]);
+
+        assert_eq!(
+            entries,
+            vec![Entry {
+                valid_after: entry_imalcom().valid_after,
+                valid_before: entry_cwoods().valid_before,
+                ..jsnow
+            }]
+        );
+    }
+
+    /// The comment of entries is written once above the entries of their principals, staying
+    /// adjacent to them after sorting.
+    #[rstest]
+    fn comments_written_above_entries_of_principals() {
+        let comment = Some("Contractor until\n2025 Q4".to_string());
+        let file = File::from_entries(
+            PathBuf::from("allowed_signers"),
+            [
+                entry_jsnow().with_comment(comment.clone()),
+                entry_cwoods(),
+                Entry {
+                    key: entry_imalcom().key,
+                    ..entry_jsnow()
+                }
+                .with_comment(comment),
+            ],
+        );
+
+        let content = file.content();
+
+        assert_eq!(
+            content.lines().skip(1).collect::<Vec<_>>(),
+            vec![
+                entry_cwoods().to_string(),
+                "# Contractor until 2025 Q4".to_string(),
+                entry_jsnow().to_string(),
+                Entry {
+                    key: entry_imalcom().key,
+                    ..entry_jsnow()
+                }
+                .to_string(),
+                String::new(),
+            ]
+        );
+    }
+
+    /// Sorting principals sorts and deduplicates them.
+    #[rstest]
+    fn principals_sorted(entry_ebert: Entry) {
+        let entry = Entry {
+            principals: vec![
+                "ernie@muppets.com".to_string(),
+                "bert@muppets.com".to_string(),
+                "ernie@muppets.com".to_string(),
+            ],
+            ..entry_ebert
+        };
+
+        assert_eq!(
+            entry.with_sorted_principals().principals,
+            vec!["bert@muppets.com", "ernie@muppets.com"]
+        );
+    }
+
+    /// Entries are kept if any of their principals matches the pattern, or only if all of them
+    /// match if required.
+    #[rstest]
+    #[case("*@acme.corp", false, &["ian.malcom@acme.corp"])]
+    #[case("ernie@*", false, &["ernie@muppets.com"])]
+    #[case("ernie@*", true, &[])]
+    #[case("*@muppets.com", true, &["ernie@muppets.com"])]
+    #[case("*@*.com", false, &["j.snow@wall.com", "ernie@muppets.com"])]
+    fn entries_retained_by_principal(
+        example_allowed_signers: (File, tempfile::TempPath),
+        #[case] pattern: &str,
+        #[case] all: bool,
+        #[case] expected: &[&str],
+    ) {
+        let (mut file, _path) = example_allowed_signers;
+
+        file.retain_principals(pattern, all);
+
+        let first_principals: Vec<&str> = file
+            .entries
+            .iter()
+            .map(|entry| entry.principals[0].as_str())
+            .collect();
+        assert_eq!(first_principals, expected);
+    }
+
+    /// Excluded principals are removed from entries, dropping entries only once none of their
+    /// principals remain.
+    #[rstest]
+    #[case(&["ernie@muppets.com"], vec![vec!["j.snow@wall.com"], vec!["ian.malcom@acme.corp"], vec!["cwoods@universal.exports"], vec!["bert@muppets.com"]])]
+    #[case(&["*@muppets.com"], vec![vec!["j.snow@wall.com"], vec!["ian.malcom@acme.corp"], vec!["cwoods@universal.exports"]])]
+    #[case(&["j.snow@wall.com", "*@*.exports"], vec![vec!["ian.malcom@acme.corp"], vec!["ernie@muppets.com", "bert@muppets.com"]])]
+    #[case(&[], vec![vec!["j.snow@wall.com"], vec!["ian.malcom@acme.corp"], vec!["cwoods@universal.exports"], vec!["ernie@muppets.com", "bert@muppets.com"]])]
+    fn principals_excluded(
+        example_allowed_signers: (File, tempfile::TempPath),
+        #[case] patterns: &[&str],
+        #[case] expected: Vec<Vec<&str>>,
+    ) {
+        let (mut file, _path) = example_allowed_signers;
+        let patterns: Vec<String> = patterns.iter().map(ToString::to_string).collect();
+
+        file.exclude_principals(&patterns);
+
+        let principals: Vec<&[String]> = file.entries.iter().map(Entry::principals).collect();
+        assert_eq!(principals, expected);
+    }
+
+    /// Only entries valid at the given point in time are kept, along with expired entries if
+    /// requested.
+    #[rstest]
+    #[case(Some(-2), Some(-1), false, false)]
+    #[case(Some(-2), Some(-1), true, true)]
+    #[case(Some(-1), Some(1), false, true)]
+    #[case(None, None, false, true)]
+    #[case(Some(1), Some(2), false, false)]
+    #[case(Some(1), None, true, false)]
+    fn entries_retained_by_validity(
+        #[case] valid_after_days: Option<i64>,
+        #[case] valid_before_days: Option<i64>,
+        #[case] include_expired: bool,
+        #[case] retained: bool,
+    ) {
+        let now = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        let at = |days: Option<i64>| days.map(|days| now + TimeDelta::days(days));
+        let entry = entry_jsnow();
+        let mut file = File::from_entries(
+            PathBuf::from("allowed_signers"),
+            [Entry::new(
+                entry.principals.clone(),
+                at(valid_after_days),
+                at(valid_before_days),
+                entry.key,
+            )],
+        );
+
+        file.retain_valid(now, include_expired);
+
+        assert_eq!(file.entries.len(), usize::from(retained));
+    }
+
+    /// Keys of entries with different principals are reported as shared, while keys of a single
+    /// entry or of entries with the same principals are not.
+    #[rstest]
+    fn shared_keys_detected() {
+        let file = File::from_entries(
+            PathBuf::from("allowed_signers"),
+            [
+                entry_jsnow(),
+                Entry::new(
+                    vec!["ci-bot@wall.com".to_string()],
+                    None,
+                    None,
+                    entry_jsnow().key,
+                ),
+                entry_jsnow(),
+                entry_imalcom(),
+            ],
+        );
+
+        let shared = file.shared_keys();
+
+        assert_eq!(shared.len(), 1);
+        assert_eq!(shared[0].0, entry_jsnow().key.fingerprint().unwrap());
+        assert_eq!(
+            shared[0].1,
+            vec![
+                entry_jsnow().principals(),
+                &["ci-bot@wall.com".to_string()][..]
+            ]
+        );
+    }
+
+    /// Grouped by source, entries are written below a header comment of their source, sorted
+    /// within their group. Keys retrieved from multiple sources are written once, in the group of
+    /// the first source by name.
+    #[rstest]
+    fn entries_grouped_by_source() {
+        let from = |entry: Entry, source: &str| Entry {
+            key: entry.key.with_source(Some(source.to_string())),
+            ..entry
+        };
+        let file = File::from_entries(
+            PathBuf::from("allowed_signers"),
+            [
+                from(entry_jsnow(), "gitlab"),
+                from(entry_imalcom(), "gitlab"),
+                from(entry_cwoods(), "github"),
+                from(entry_jsnow(), "github"),
+                entry_ebert(),
+            ],
+        )
+        .with_group_by_source(true);
+
+        let content = file.content();
+
+        assert_eq!(
+            content.lines().skip(1).collect::<Vec<_>>(),
+            vec![
+                entry_ebert().to_string(),
+                "# --- github ---".to_string(),
+                entry_cwoods().to_string(),
+                entry_jsnow().to_string(),
+                "# --- gitlab ---".to_string(),
+                entry_imalcom().to_string(),
+                String::new(),
+            ]
+        );
+    }
+
+    /// Entries are written in the configured order, deduplicated unless the order is `none`.
+    #[rstest]
+    #[case(SortOrder::Principal, &["cwoods@universal.exports", "ernie@muppets.com", "j.snow@wall.com", "zed@acme.corp"])]
+    #[case(SortOrder::KeyType, &["zed@acme.corp", "cwoods@universal.exports", "ernie@muppets.com", "j.snow@wall.com"])]
+    #[case(SortOrder::Insertion, &["cwoods@universal.exports", "j.snow@wall.com", "zed@acme.corp", "ernie@muppets.com"])]
+    #[case(SortOrder::None, &["cwoods@universal.exports", "j.snow@wall.com", "zed@acme.corp", "j.snow@wall.com", "ernie@muppets.com"])]
+    fn entries_written_in_sort_order(#[case] sort: SortOrder, #[case] expected: &[&str]) {
+        let entry_zed = Entry {
+            principals: vec!["zed@acme.corp".to_string()],
+            key: "ecdsa-sha2-nistp256 AAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAAAIbmlzdHAyNTYAAABBBEmKSENjQEezOmxkZMy7opKgwFB9nkt5YRrYMjNuG5N87uRgg6CLrbo5wAdT/y6v0mKV0U2w0WZ2YB/++Tpockg="
+                .parse()
+                .unwrap(),
+            ..entry_jsnow()
+        };
+        let file = File::from_entries(
+            PathBuf::from("allowed_signers"),
+            [
+                entry_cwoods(),
+                entry_jsnow(),
+                entry_zed,
+                entry_jsnow(),
+                entry_ebert(),
+            ],
+        )
+        .with_sort(sort);
+
+        let content = file.content();
+
+        let first_principals: Vec<&str> = content
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.split([' ', ',']).next())
+            .filter(|principal| !principal.is_empty())
+            .collect();
+        assert_eq!(first_principals, expected);
+    }
+
+    /// In the authorized keys format, only the keys of entries are written, without duplicates.
+    #[rstest]
+    fn authorized_keys_format_contains_deduplicated_keys(
+        example_allowed_signers: (File, tempfile::TempPath),
+    ) {
+        let (mut file, _path) = example_allowed_signers;
+        file.entries.push(Entry {
+            principals: vec!["jon.snow@winterfell.com".to_string()],
+            ..entry_jsnow()
+        });
+        let file = file.with_format(OutputFormat::AuthorizedKeys);
+
+        let content = file.content();
+
+        assert_eq!(content.lines().count(), 5);
+        for entry in &file.entries {
+            assert!(content.contains(&entry.key.to_string()));
+            assert!(!content.contains(&entry.principals[0]));
+        }
+    }
+
+    /// Parsing a displayed entry results in the same entry.
+    #[rstest]
+    #[case(entry_jsnow())]
+    #[case(entry_imalcom())]
+    #[case(entry_cwoods())]
+    #[case(entry_ebert())]
+    fn parsing_displayed_entry_roundtrips(#[case] entry: Entry) {
+        let parsed: Entry = entry.to_string().parse().unwrap();
+
+        assert_eq!(parsed, entry);
+    }
+
+    /// Timestamps are accepted with and without a UTC suffix as well as with explicit offsets.
+    #[rstest]
+    #[case("20300101000000Z", Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap())]
+    #[case("20300101000000z", Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap())]
+    #[case("203001010000Z", Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap())]
+    #[case("20300101Z", Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap())]
+    #[case("20300101020000+0200", Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap())]
+    #[case("20291231193000-0430", Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap())]
+    #[case(
+        "20300101000000",
+        Local.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap().to_utc()
+    )]
+    #[case("20300101", Local.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap().to_utc())]
+    fn parsing_entry_accepts_timestamp_forms(
+        #[case] timestamp: &str,
+        #[case] expected: DateTime<Utc>,
+    ) {
+        let line = format!(
+            "cwoods@universal.exports valid-after={timestamp},valid-before=\"{timestamp}\" {}",
+            entry_cwoods().key
+        );
+
+        let entry: Entry = line.parse().unwrap();
+
+        assert_eq!(entry.valid_after.unwrap(), expected);
+        assert_eq!(entry.valid_before.unwrap(), expected);
+    }
+
+    /// Timestamps given in a non-UTC timezone are displayed normalized to UTC.
+    #[rstest]
+    #[case("20300101053000+0530", "20300101000000Z")]
+    #[case("20291231190000-0500", "20300101000000Z")]
+    #[case("20300101000000Z", "20300101000000Z")]
+    fn timestamp_displayed_in_utc(#[case] timestamp: &str, #[case] expected: &str) {
+        let line = format!(
+            "cwoods@universal.exports valid-before={timestamp} {}",
+            entry_cwoods().key
+        );
+
+        let entry: Entry = line.parse().unwrap();
+
+        assert_eq!(entry.to_string(), entry_cwoods().to_string());
+        assert!(entry
+            .to_string()
+            .contains(&format!("valid-before={expected} ")));
+    }
+
+    /// Entries restricted by both options are displayed with comma separated options and parsed
+    /// back into the same entry.
+    #[test]
+    fn entry_with_both_options_roundtrips() {
+        let entry = Entry::new(
+            vec!["cwoods@universal.exports".to_string()],
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+            Some(Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()),
+            entry_cwoods().key,
+        );
+
+        let line = entry.to_string();
+
+        assert_eq!(
+            line,
+            format!(
+                "cwoods@universal.exports valid-after=20240101000000Z,valid-before=20300101000000Z {}",
+                entry_cwoods().key
+            )
+        );
+        assert_eq!(line.parse::<Entry>().unwrap(), entry);
+    }
+
+    #[rstest]
+    #[case("", ParseEntryError::MissingPrincipals)]
+    #[case("j.snow@wall.com", ParseEntryError::MissingKey)]
+    #[case("j.snow@wall.com valid-after=20300101", ParseEntryError::MissingKey)]
+    #[case(
+        "j.snow@wall.com valid-after=2030 ssh-ed25519 AAAA",
+        ParseEntryError::InvalidTimestamp("2030".to_string())
+    )]
+    #[case(
+        "j.snow@wall.com valid-after=20301301 ssh-ed25519 AAAA",
+        ParseEntryError::InvalidTimestamp("20301301".to_string())
+    )]
+    #[case(
+        "j.snow@wall.com valid-after=20300101+25 ssh-ed25519 AAAA",
+        ParseEntryError::InvalidTimestamp("20300101+25".to_string())
+    )]
+    #[case(
+        "j.snow@wall.com cert-authority ssh-ed25519 AAAA",
+        ParseEntryError::UnsupportedOption("cert-authority".to_string())
+    )]
+    fn parsing_invalid_entry_returns_error(#[case] line: &str, #[case] expected: ParseEntryError) {
+        assert_eq!(line.parse::<Entry>().unwrap_err(), expected);
+    }
+
+    /// Keys are accepted if their age lies within the configured bounds, keys without a known
+    /// creation date only if it is not required.
+    #[rstest]
+    #[case(None, None, false, Some(400), true)]
+    #[case(None, Some("1w"), false, Some(6), true)]
+    #[case(None, Some("1w"), false, Some(8), false)]
+    #[case(Some("1w"), None, false, Some(6), false)]
+    #[case(Some("1w"), None, false, Some(8), true)]
+    #[case(None, Some("1w"), false, None, true)]
+    #[case(None, Some("1w"), true, None, false)]
+    #[case(None, None, true, None, true)]
+    fn key_age_compared_against_configured_bounds(
+        #[case] min_key_age: Option<&str>,
+        #[case] max_key_age: Option<&str>,
+        #[case] require_key_creation_date: bool,
+        #[case] age_days: Option<i64>,
+        #[case] expected: bool,
+    ) {
+        let now = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        let options = OutputOptions {
+            min_key_age: min_key_age.map(|s| s.parse().unwrap()),
+            max_key_age: max_key_age.map(|s| s.parse().unwrap()),
+            require_key_creation_date,
+            ..Default::default()
+        };
+        let key = entry_jsnow()
+            .key
+            .with_created_at(age_days.map(|days| now - TimeDelta::days(days)));
+
+        assert_eq!(options.accepts_key_age(&key, now), expected);
+    }
+
+    /// Only existing, non-empty files without the managed marker are considered unmanaged.
+    #[rstest]
+    #[case(None, false)]
+    #[case(Some(""), false)]
+    #[case(Some("j.snow@wall.com ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS\n"), true)]
+    #[case(Some(MANAGED_MARKER), false)]
+    fn unmanaged_file_detected(#[case] content: Option<&str>, #[case] expected: bool) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("allowed_signers");
+        if let Some(content) = content {
+            fs::write(&path, content).unwrap();
+        }
+
+        assert_eq!(is_unmanaged(&path), expected);
+    }
+
     #[rstest]
     fn writing_overrides_existing_content(example_allowed_signers: (File, tempfile::TempPath)) {
         let (file, path) = example_allowed_signers;
@@ -280,4 +1483,34 @@ mod tests {
         let content = fs::read_to_string(path).unwrap();
         assert!(!content.contains(existing_content));
     }
+
+    /// Files are replaced atomically through symbolic links, keeping the permissions of the
+    /// existing file and leaving no temporary files behind.
+    #[cfg(unix)]
+    #[rstest]
+    fn written_atomically_through_symlink(example_allowed_signers: (File, tempfile::TempPath)) {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (file, _path) = example_allowed_signers;
+        let dir = tempfile::TempDir::new().unwrap();
+        let target = dir.path().join("allowed_signers");
+        let link = dir.path().join("link");
+        fs::write(&target, "gathered dust").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o640)).unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        let file = File {
+            path: link.clone(),
+            ..file
+        };
+
+        file.write().unwrap();
+
+        assert!(fs::symlink_metadata(&link).unwrap().is_symlink());
+        assert_eq!(fs::read_to_string(&target).unwrap(), file.content());
+        assert_eq!(
+            fs::metadata(&target).unwrap().permissions().mode() & 0o777,
+            0o640
+        );
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
 }
@@ -1,5 +1,11 @@
-pub use file::{update, Entry, File};
-pub use signer::Signer;
+pub(crate) use file::write_atomic;
+pub use file::{
+    generate, generate_until, is_unmanaged, update, Entry, File, OutputFormat, OutputOptions,
+    ParseEntryError, SortOrder, MANAGED_MARKER,
+};
+pub use signer::{
+    check_rate_limit_budget, estimate_requests, KeyValidation, Signer, SignerError, SourceMode,
+};
 
 mod file;
 mod signer;
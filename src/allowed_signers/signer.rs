@@ -1,10 +1,27 @@
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
+    sync::Arc,
+    time::Duration,
+};
 
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
 use tokio::task::JoinSet;
-use tracing::{debug, error, warn};
+use tracing::{debug, error};
 
-use super::{file::Entry, ssh::PublicKey};
-use crate::{source::Source, Error};
+use super::{
+    file::Entry,
+    ssh::{Fingerprint, PublicKey},
+};
+use crate::{
+    cache::{CacheKey, CachedKeys, KeyCache},
+    clock,
+    diagnostic::{warning, Warning},
+    duration::Duration as ConfigDuration,
+    source::Source,
+    Error, Fetched,
+};
 
 /// An allowed signer.
 #[derive(Debug)]
@@ -12,44 +29,100 @@ pub struct Signer {
     pub name: String,
     pub principals: Vec<String>,
     pub sources: Vec<Arc<Box<dyn Source>>>,
+    /// The names of the sources in the same order, recorded on the keys retrieved from them.
+    pub source_names: Vec<String>,
+    pub source_mode: SourceMode,
+    /// Treat the name of the signer as an email address, resolving it to the username on each
+    /// source.
+    pub resolve_email: bool,
+    /// An organization the signers SAML identity is used as an additional principal of.
+    pub saml_organization: Option<String>,
+    /// The fingerprints of the only keys accepted for the signer, accepting all keys if empty.
+    pub allowed_fingerprints: Vec<Fingerprint>,
+    /// The fingerprints of keys never accepted for the signer, e.g. known compromised keys.
+    pub denied_fingerprints: Vec<Fingerprint>,
+    /// Fail instead of dropping keys not matching the allowed fingerprints or matching the denied
+    /// fingerprints, and instead of warning about an unexpected number of keys.
+    pub strict_fingerprints: bool,
+    /// The exact number of distinct keys expected to be retrieved for the signer, warning if it
+    /// differs, e.g. since keys were added to a compromised account. Keys of signers of the same
+    /// name are counted together.
+    pub expected_keys: Option<usize>,
+    /// A glob pattern the titles of keys need to match, dropping other keys including those
+    /// without a title. Supports `*` matching any number of characters and `?` matching one.
+    pub key_title_pattern: Option<String>,
+    /// A cache consulted to revalidate previously retrieved keys.
+    pub cache: Option<Arc<dyn KeyCache>>,
+    /// A comment describing the signer, written above it's entries in the allowed signers file.
+    pub comment: Option<String>,
+    /// How keys retrieved from sources that are not well-formed SSH public keys are handled.
+    pub key_validation: KeyValidation,
+    /// Skip the signer with a warning instead of failing if any of it's sources could not be
+    /// connected to, unless keys are validated strictly.
+    pub tolerate_connection_errors: bool,
+    /// Warn about keys expiring within this window, so that they are rotated before signatures
+    /// made using them fail to verify.
+    pub warn_expiry_within: Option<ConfigDuration>,
+}
+
+/// How the keys of a signer with multiple sources are determined.
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SourceMode {
+    /// Query all sources concurrently and merge their keys.
+    #[default]
+    Merge,
+    /// Query sources sequentially in configured order, using the keys of the first source that
+    /// returns any.
+    FirstMatch,
+}
+
+/// How keys that are not well-formed SSH public keys are handled, guarding against sources
+/// returning invalid keys that would break verification using the whole allowed signers file.
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeyValidation {
+    /// Write keys without validating them.
+    #[default]
+    Off,
+    /// Drop invalid keys with a warning.
+    Warn,
+    /// Fail if any key is invalid.
+    Strict,
 }
 
 impl Signer {
-    /// Get the signers public keys from all of it's sources.
+    /// Get the signers public keys from it's sources according to the configured [`SourceMode`].
     #[tracing::instrument(skip_all, fields(username=self.name), level = "debug")]
     async fn get_keys(&self) -> Result<Vec<PublicKey>, Error> {
+        match self.source_mode {
+            SourceMode::Merge => self.get_keys_merged().await,
+            SourceMode::FirstMatch => self.get_keys_first_match().await,
+        }
+    }
+
+    /// Get the signers public keys from all of it's sources concurrently.
+    async fn get_keys_merged(&self) -> Result<Vec<PublicKey>, Error> {
         let mut set: JoinSet<_> = self
             .sources
             .iter()
-            .map(|source| {
+            .enumerate()
+            .map(|(i, source)| {
                 let source = source.clone();
+                let source_name = self.source_names.get(i).cloned();
                 let username = self.name.clone();
+                let cache = self.cache.clone();
+                let validation = self.key_validation;
+                let resolve_email = self.resolve_email;
                 async move {
-                    debug!(
-                        ?source,
-                        "Requesting keys from source for signer {}", &username
-                    );
-                    match source.get_keys_by_username(&username).await {
-                        Ok(keys) => {
-                            if keys.is_empty() {
-                                warn!(
-                                    ?source,
-                                    "User {} does not have any signing keys configured on source",
-                                    &username
-                                );
-                            }
-                            Ok(keys)
-                        }
-                        Err(Error::UserNotFound) => {
-                            warn!(?source, "User {} does not exist on source", &username);
-                            Ok(vec![])
-                        }
-                        Err(Error::ConnectionError) => {
-                            error!(?source, "Failed to connect to source");
-                            Err(Error::ConnectionError)
-                        }
-                        Err(err) => Err(err),
-                    }
+                    let Some(username) =
+                        resolve_username(&source, &username, resolve_email).await?
+                    else {
+                        return Ok(vec![]);
+                    };
+                    let keys = get_keys_from_source(&source, &username, cache.as_deref()).await?;
+                    valid_keys(&source, &username, keys, validation)
+                        .map(|keys| with_source_name(keys, source_name.as_ref()))
                 }
             })
             .collect();
@@ -60,29 +133,1374 @@ impl Signer {
         Ok(keys)
     }
 
-    /// Get the allowed signers file entries corresponding to this signer.
-    pub(super) async fn get_entries(&self) -> Result<Vec<Entry>, Error> {
-        let keys = self.get_keys().await?;
+    /// Get the signers public keys from the first of it's sources that returns any.
+    async fn get_keys_first_match(&self) -> Result<Vec<PublicKey>, Error> {
+        for (i, source) in self.sources.iter().enumerate() {
+            let Some(username) = resolve_username(source, &self.name, self.resolve_email).await?
+            else {
+                continue;
+            };
+            let keys = get_keys_from_source(source, &username, self.cache.as_deref()).await?;
+            let keys = valid_keys(source, &username, keys, self.key_validation)?;
+            if !keys.is_empty() {
+                return Ok(with_source_name(keys, self.source_names.get(i)));
+            }
+        }
+        Ok(vec![])
+    }
+
+    /// Get the SAML identity of the signer within it's organization from the first source
+    /// that knows it. Failing to retrieve it is not considered an error, logging a warning instead.
+    async fn get_saml_principal(&self, organization: &str) -> Option<String> {
+        for source in &self.sources {
+            match source.saml_name_id(organization, &self.name).await {
+                Ok(Some(name_id)) => return Some(name_id),
+                Ok(None) => {}
+                Err(err) => warning!(
+                    Warning::SamlIdentityFailed,
+                    [?source],
+                    "Failed to get SAML identity of signer {} within organization {}: {err}",
+                    self.name,
+                    organization
+                ),
+            }
+        }
+        None
+    }
+
+    /// Whether the given key matches the allowed fingerprints of the signer, if any are configured,
+    /// and does not match any of it's denied fingerprints.
+    pub(crate) fn accepts_fingerprint(&self, key: &PublicKey) -> bool {
+        let fingerprint = key.fingerprint();
+        let allowed = self.allowed_fingerprints.is_empty()
+            || fingerprint
+                .as_ref()
+                .is_some_and(|f| self.allowed_fingerprints.contains(f));
+        let denied = fingerprint.is_some_and(|f| self.denied_fingerprints.contains(&f));
+        allowed && !denied
+    }
+
+    /// Filter the given keys, dropping the ones matching any of the denied fingerprints of the
+    /// signer.
+    fn undenied_keys(&self, keys: Vec<PublicKey>) -> Result<Vec<PublicKey>, Error> {
+        if self.denied_fingerprints.is_empty() {
+            return Ok(keys);
+        }
+        let mut undenied = Vec::with_capacity(keys.len());
+        for key in keys {
+            match key.fingerprint() {
+                Some(fingerprint) if self.denied_fingerprints.contains(&fingerprint) => {
+                    if self.strict_fingerprints {
+                        error!(
+                            "Key {key} of signer {} matches denied fingerprint {fingerprint}",
+                            self.name
+                        );
+                        return Err(Error::DeniedKey(fingerprint.to_string()));
+                    }
+                    warning!(
+                        Warning::DeniedKey,
+                        "Dropping denied key {key} with fingerprint {fingerprint} of signer {}, it should be removed from the signers account",
+                        self.name
+                    );
+                }
+                _ => undenied.push(key),
+            }
+        }
+        Ok(undenied)
+    }
+
+    /// Filter the given keys, keeping only the ones matching the allowed fingerprints of the
+    /// signer if any are configured.
+    fn pinned_keys(&self, keys: Vec<PublicKey>) -> Result<Vec<PublicKey>, Error> {
+        if self.allowed_fingerprints.is_empty() {
+            return Ok(keys);
+        }
+        let mut pinned = Vec::with_capacity(keys.len());
+        for key in keys {
+            match key.fingerprint() {
+                Some(fingerprint) if self.allowed_fingerprints.contains(&fingerprint) => {
+                    pinned.push(key);
+                }
+                fingerprint => {
+                    let fingerprint =
+                        fingerprint.map_or_else(|| "unknown".to_string(), |f| f.to_string());
+                    if self.strict_fingerprints {
+                        error!(
+                            "Key {key} of signer {} does not match any allowed fingerprint",
+                            self.name
+                        );
+                        return Err(Error::UnpinnedKey(fingerprint));
+                    }
+                    warning!(
+                        Warning::FingerprintNotAllowed,
+                        "Dropping key {key} with fingerprint {fingerprint} of signer {} since it does not match any allowed fingerprint",
+                        self.name
+                    );
+                }
+            }
+        }
+        Ok(pinned)
+    }
+
+    /// Filter the given keys, keeping only the ones whose title matches the key title pattern of
+    /// the signer if one is configured.
+    fn titled_keys(&self, keys: Vec<PublicKey>) -> Vec<PublicKey> {
+        let Some(pattern) = &self.key_title_pattern else {
+            return keys;
+        };
+        keys.into_iter()
+            .filter(|key| {
+                let matches = key
+                    .title()
+                    .is_some_and(|title| glob_matches(pattern, title));
+                if !matches {
+                    warning!(
+                        Warning::KeyTitleMismatch,
+                        "Dropping key {} of signer {} since it's title does not match {pattern}",
+                        key.title().unwrap_or("without title"),
+                        self.name
+                    );
+                }
+                matches
+            })
+            .collect()
+    }
+
+    /// Get the allowed signers file entries corresponding to this signer, along with the keys
+    /// retrieved from it's sources before any of them were dropped. No keys are returned if the
+    /// signer was skipped without retrieving them.
+    pub(super) async fn get_entries(&self) -> Result<(Vec<Entry>, Option<Vec<PublicKey>>), Error> {
+        let mut principals = self.principals.clone();
+        if let Some(organization) = &self.saml_organization {
+            match self.get_saml_principal(organization).await {
+                Some(principal) if !principals.contains(&principal) => principals.push(principal),
+                Some(_) => {}
+                None => debug!(
+                    "Signer {} has no SAML identity within organization {}",
+                    self.name, organization
+                ),
+            }
+        }
+        if principals.is_empty() {
+            warning!(
+                Warning::SignerWithoutPrincipals,
+                "Signer {} has no principals, skipping it's keys",
+                self.name
+            );
+            return Ok((vec![], None));
+        }
+
+        let keys = match self.get_keys().await {
+            Err(Error::ConnectionError)
+                if self.tolerate_connection_errors
+                    && self.key_validation != KeyValidation::Strict =>
+            {
+                warning!(
+                    Warning::ConnectionErrorTolerated,
+                    "Skipping signer {} since a source could not be connected to",
+                    self.name
+                );
+                return Ok((vec![], None));
+            }
+            keys => keys?,
+        };
+        let retrieved = keys.clone();
+        let keys = self.pinned_keys(self.undenied_keys(keys)?)?;
+        let keys = self.titled_keys(keys);
 
-        Ok(keys
+        let now = clock::now();
+        let entries = keys
             .into_iter()
-            .map(|key| Entry::new(self.principals.clone(), None, None, key))
-            .collect())
+            .map(|key| {
+                // Certificates are only valid within their own validity period, keys expiring on
+                // their source until they expire.
+                let validity = key.certificate_validity().unwrap_or_default();
+                let valid_before = match (validity.valid_before, key.expires_at()) {
+                    (Some(certificate), Some(expiry)) => Some(certificate.min(expiry)),
+                    (certificate, expiry) => certificate.or(expiry),
+                };
+                if let (Some(window), Some(valid_before)) = (self.warn_expiry_within, valid_before)
+                {
+                    if expires_within(valid_before, window.as_time_delta(), now) {
+                        warning!(
+                            Warning::KeyExpiringSoon,
+                            "Key {key} of signer {} expires at {valid_before}, it should be rotated before signatures fail to verify",
+                            self.name
+                        );
+                    }
+                }
+                let key = key.with_signer(Some(self.name.clone()));
+                Entry::new(principals.clone(), validity.valid_after, valid_before, key)
+                    .with_comment(self.comment.clone())
+            })
+            .collect();
+        Ok((entries, Some(retrieved)))
+    }
+}
+
+/// Check that the number of distinct keys retrieved for the signer of the given name matches the
+/// expected number of keys, failing in strict mode and warning otherwise.
+fn check_key_count(
+    name: &str,
+    expected: usize,
+    strict: bool,
+    retrieved: usize,
+) -> Result<(), Error> {
+    if retrieved == expected {
+        return Ok(());
+    }
+    if strict {
+        error!("Signer {name} has {retrieved} key(s) while {expected} are expected");
+        return Err(Error::UnexpectedKeyCount(expected, retrieved));
     }
+    warning!(
+        Warning::UnexpectedKeyCount,
+        "Signer {name} has {retrieved} key(s) while {expected} are expected, verify that the keys were added by the signer"
+    );
+    Ok(())
+}
+
+/// Whether something valid until the given point in time expires within the given window from
+/// now, without having expired already.
+fn expires_within(valid_before: DateTime<Utc>, window: TimeDelta, now: DateTime<Utc>) -> bool {
+    valid_before > now && valid_before - now <= window
 }
 
-/// Get entries for multiple given signers concurrently.
-pub(super) async fn get_entries<S>(signers: S) -> Result<Vec<Entry>, Error>
+/// Whether the given text matches the given glob pattern, where `*` matches any number of
+/// characters and `?` matches exactly one.
+pub(crate) fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // The position of the last `*` in the pattern and the text position it was tried at.
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` match one more character.
+                Some((star, start)) => {
+                    p = star + 1;
+                    t = start + 1;
+                    backtrack = Some((star, start + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Record the name of the source the given keys were retrieved from on each of them, if known.
+fn with_source_name(keys: Vec<PublicKey>, source_name: Option<&String>) -> Vec<PublicKey> {
+    match source_name {
+        Some(name) => keys
+            .into_iter()
+            .map(|key| key.with_source(Some(name.clone())))
+            .collect(),
+        None => keys,
+    }
+}
+
+/// The username of a signer with the given name on the given source, resolving the name as an
+/// email address if `resolve_email` is set. Email addresses not resolving to any user are not
+/// considered an error, returning `None` instead.
+async fn resolve_username(
+    source: &Arc<Box<dyn Source>>,
+    name: &str,
+    resolve_email: bool,
+) -> Result<Option<String>, Error> {
+    if !resolve_email {
+        return Ok(Some(name.to_string()));
+    }
+    let login = source.login_by_email(name).await?;
+    if let Some(login) = &login {
+        debug!(?source, "Resolved email address {name} to user {login}");
+    } else {
+        warning!(
+            Warning::EmailNotResolved,
+            [?source],
+            "Email address {name} does not resolve to any user on source"
+        );
+    }
+    Ok(login)
+}
+
+/// Get a users public keys from a single source, revalidating previously retrieved keys if a
+/// cache is given.
+/// A user not existing on the source is not considered an error, returning no keys instead.
+async fn get_keys_from_source(
+    source: &Arc<Box<dyn Source>>,
+    username: &str,
+    cache: Option<&dyn KeyCache>,
+) -> Result<Vec<PublicKey>, Error> {
+    debug!(
+        ?source,
+        "Requesting keys from source for signer {}", username
+    );
+    let cache = cache.zip(source.url()).map(|(cache, url)| {
+        let key = CacheKey {
+            source_url: url.to_string(),
+            username: username.to_string(),
+            auth_identity: source.auth_identity(),
+        };
+        (cache, key)
+    });
+    let cached = match &cache {
+        Some((cache, key)) => cache.get(key).await,
+        None => None,
+    };
+    let etag = cached.as_ref().map(|c| c.etag.as_str());
+
+    match source.get_keys_if_changed(username, etag).await {
+        Ok(Fetched::NotModified) => {
+            debug!(?source, "Keys of user {} did not change", username);
+            Ok(cached.map(|c| c.keys).unwrap_or_default())
+        }
+        Ok(Fetched::Keys { keys, etag }) => {
+            if keys.is_empty() {
+                warning!(
+                    Warning::NoSigningKeys,
+                    [?source],
+                    "User {} does not have any signing keys configured on source",
+                    username
+                );
+            }
+            if let (Some((cache, key)), Some(etag)) = (&cache, etag) {
+                let value = CachedKeys {
+                    keys: keys.clone(),
+                    etag,
+                };
+                cache.put(key, value).await;
+            }
+            Ok(keys)
+        }
+        Err(Error::UserNotFound) => {
+            warning!(
+                Warning::UserNotFound,
+                [?source],
+                "User {} does not exist on source",
+                username
+            );
+            Ok(vec![])
+        }
+        Err(Error::ConnectionError) => {
+            error!(?source, "Failed to connect to source");
+            Err(Error::ConnectionError)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Filter the given keys retrieved from the given source, handling keys that are not well-formed
+/// SSH public keys according to the given validation.
+fn valid_keys(
+    source: &Arc<Box<dyn Source>>,
+    username: &str,
+    keys: Vec<PublicKey>,
+    validation: KeyValidation,
+) -> Result<Vec<PublicKey>, Error> {
+    if validation == KeyValidation::Off {
+        return Ok(keys);
+    }
+    let mut valid = Vec::with_capacity(keys.len());
+    for key in keys {
+        let Err(reason) = key.validate() else {
+            valid.push(key);
+            continue;
+        };
+        let title = key.title().unwrap_or("without title");
+        if validation == KeyValidation::Strict {
+            error!(
+                ?source,
+                "Key {title} of signer {username} is invalid: {reason}"
+            );
+            return Err(Error::InvalidKey(format!("{title}: {reason}")));
+        }
+        warning!(
+            Warning::InvalidKey,
+            [?source],
+            "Dropping invalid key {title} of signer {username}: {reason}"
+        );
+    }
+    Ok(valid)
+}
+
+/// Estimate the number of requests made to each source used by the given signers, in order of
+/// their first use, without making any requests.
+/// Each source takes a single request per signer, since a page of keys fits more keys than users
+/// commonly have. Signers using the first source returning keys are assumed to query all of their
+/// sources, while looking up the SAML identity of a signer and resolving it's email address take
+/// another request per source each.
+#[must_use]
+pub fn estimate_requests(signers: &[Signer]) -> Vec<(&Arc<Box<dyn Source>>, usize)> {
+    let mut estimate: Vec<(&Arc<Box<dyn Source>>, usize)> = Vec::new();
+    for signer in signers {
+        let per_source =
+            1 + usize::from(signer.saml_organization.is_some()) + usize::from(signer.resolve_email);
+        for source in &signer.sources {
+            match estimate.iter_mut().find(|(s, _)| Arc::ptr_eq(s, source)) {
+                Some((_, n)) => *n += per_source,
+                None => estimate.push((source, per_source)),
+            }
+        }
+    }
+    estimate
+}
+
+/// Check whether the rate limit budget of all sources used by the given signers suffices to make
+/// the required requests, logging a warning for every source that would exceed it's rate limit.
+/// Sources that don't expose their rate limit are assumed to have a sufficient budget.
+pub async fn check_rate_limit_budget(signers: &[Signer]) -> bool {
+    let mut sufficient = true;
+    for (source, required) in estimate_requests(signers) {
+        match source.remaining_rate_limit().await {
+            Ok(Some(remaining)) if remaining < required => {
+                warning!(
+                    Warning::InsufficientRateLimit,
+                    [?source],
+                    "Source has {remaining} requests remaining until it's rate limit is exceeded, but up to {required} are required"
+                );
+                sufficient = false;
+            }
+            Ok(remaining) => debug!(?source, ?remaining, required, "Rate limit budget suffices"),
+            Err(err) => warning!(
+                Warning::UnknownRateLimit,
+                [?source],
+                "Failed to determine remaining rate limit: {err}"
+            ),
+        }
+    }
+    sufficient
+}
+
+/// An error that occurred getting the entries of a signer.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq, Serialize)]
+#[error("failed to get keys of signer {signer}")]
+pub struct SignerError {
+    /// The name of the affected signer.
+    pub signer: String,
+    #[source]
+    pub error: Error,
+}
+
+/// Get entries for multiple given signers concurrently until the given future completes.
+/// Once it completes, signers still being retrieved are given the grace period to finish before
+/// they are aborted. Returns the retrieved entries and whether any signer was aborted, in which
+/// case the entries are partial.
+/// The first signer error is returned immediately, aborting all signers still being retrieved.
+/// Once all signers were retrieved, the number of keys of each signer is checked against it's
+/// expected number of keys.
+pub(super) async fn get_entries_until<S, F>(
+    signers: S,
+    interrupted: F,
+    grace_period: Duration,
+) -> Result<(Vec<Entry>, bool), SignerError>
 where
     S: IntoIterator<Item = Signer>,
+    F: Future<Output = ()>,
 {
+    let signers: Vec<Signer> = signers.into_iter().collect();
+    let expected_keys: Vec<_> = signers
+        .iter()
+        .map(|signer| {
+            signer
+                .expected_keys
+                .map(|expected| (signer.name.clone(), expected, signer.strict_fingerprints))
+        })
+        .collect();
     let mut set: JoinSet<_> = signers
         .into_iter()
-        .map(|signer| async move { signer.get_entries().await })
+        .enumerate()
+        .map(|(i, signer)| async move {
+            signer
+                .get_entries()
+                .await
+                .map(|(entries, keys)| (i, entries, keys))
+                .map_err(|error| SignerError {
+                    signer: signer.name.clone(),
+                    error,
+                })
+        })
         .collect();
     let mut entries = Vec::new();
-    while let Some(output) = set.join_next().await {
-        entries.extend(output.unwrap()?);
+    if join_until(&mut set, &mut entries, interrupted).await? {
+        check_key_counts(&expected_keys, &entries)?;
+        return Ok((in_signer_order(entries), false));
+    }
+
+    debug!(
+        "Interrupted, waiting up to {grace_period:?} for {} signer(s) to be retrieved",
+        set.len()
+    );
+    if join_until(&mut set, &mut entries, tokio::time::sleep(grace_period)).await? {
+        check_key_counts(&expected_keys, &entries)?;
+        return Ok((in_signer_order(entries), false));
+    }
+    warning!(
+        Warning::RetrievalAborted,
+        "Aborting retrieval of {} signer(s)",
+        set.len()
+    );
+    set.abort_all();
+    Ok((in_signer_order(entries), true))
+}
+
+/// The entries and retrieved keys of a signer along with it's index.
+type Retrieved = (usize, Vec<Entry>, Option<Vec<PublicKey>>);
+
+/// Check the number of distinct keys retrieved for each signer given by index against the
+/// expected number of keys, if any. The keys of signers of the same name, whose principals are
+/// routed to different sources, are counted together, as are keys returned by multiple sources.
+/// Signers skipped without retrieving their keys are not checked.
+fn check_key_counts(
+    expected_keys: &[Option<(String, usize, bool)>],
+    retrieved: &[Retrieved],
+) -> Result<(), SignerError> {
+    // The distinct keys of each signer by it's name and expectation, which all signers of the
+    // same name share.
+    let mut keys: BTreeMap<_, BTreeSet<_>> = BTreeMap::new();
+    for (i, _, signer_keys) in retrieved {
+        if let (Some(expectation), Some(signer_keys)) = (&expected_keys[*i], signer_keys) {
+            keys.entry(expectation)
+                .or_default()
+                .extend(signer_keys.iter().map(PublicKey::material));
+        }
+    }
+    for ((name, expected, strict), keys) in keys {
+        check_key_count(name, *expected, *strict, keys.len()).map_err(|error| SignerError {
+            signer: name.clone(),
+            error,
+        })?;
+    }
+    Ok(())
+}
+
+/// The entries of signers, ordered like the signers they were retrieved for regardless of which
+/// signer was retrieved first.
+fn in_signer_order(mut entries: Vec<Retrieved>) -> Vec<Entry> {
+    entries.sort_unstable_by_key(|(i, _, _)| *i);
+    entries
+        .into_iter()
+        .flat_map(|(_, entries, _)| entries)
+        .collect()
+}
+
+/// Collect the entries of the given set of signers, along with the index of their signer, until
+/// the given future completes, returning whether the entries of all signers were collected.
+async fn join_until<F>(
+    set: &mut JoinSet<Result<Retrieved, SignerError>>,
+    entries: &mut Vec<Retrieved>,
+    until: F,
+) -> Result<bool, SignerError>
+where
+    F: Future<Output = ()>,
+{
+    tokio::pin!(until);
+    loop {
+        tokio::select! {
+            output = set.join_next() => match output {
+                Some(output) => entries.push(output.unwrap()?),
+                None => return Ok(true),
+            },
+            () = &mut until => return Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use reqwest::Url;
+    use rstest::*;
+    use std::{collections::HashMap, future, sync::Mutex};
+
+    /// A source returning a static result for any user.
+    #[derive(Debug)]
+    struct StaticSource {
+        keys: Result<Vec<&'static str>, Error>,
+        remaining_rate_limit: Option<usize>,
+        saml_name_id: Result<Option<&'static str>, Error>,
+    }
+
+    #[async_trait]
+    impl Source for StaticSource {
+        async fn get_keys_by_username(&self, _username: &str) -> Result<Vec<PublicKey>, Error> {
+            self.keys
+                .clone()
+                .map(|keys| keys.into_iter().map(|k| k.parse().unwrap()).collect())
+        }
+
+        async fn remaining_rate_limit(&self) -> Result<Option<usize>, Error> {
+            Ok(self.remaining_rate_limit)
+        }
+
+        async fn saml_name_id(
+            &self,
+            _organization: &str,
+            _username: &str,
+        ) -> Result<Option<String>, Error> {
+            self.saml_name_id
+                .clone()
+                .map(|name_id| name_id.map(ToString::to_string))
+        }
+    }
+
+    /// A source returning the given key, retaining it's metadata.
+    #[derive(Debug)]
+    struct ExpiringSource(PublicKey);
+
+    #[async_trait]
+    impl Source for ExpiringSource {
+        async fn get_keys_by_username(&self, _username: &str) -> Result<Vec<PublicKey>, Error> {
+            Ok(vec![self.0.clone()])
+        }
+    }
+
+    fn source(keys: Result<Vec<&'static str>, Error>) -> Arc<Box<dyn Source>> {
+        Arc::new(Box::new(StaticSource {
+            keys,
+            remaining_rate_limit: None,
+            saml_name_id: Ok(None),
+        }))
+    }
+
+    fn rate_limited_source(remaining: usize) -> Arc<Box<dyn Source>> {
+        Arc::new(Box::new(StaticSource {
+            keys: Ok(vec![]),
+            remaining_rate_limit: Some(remaining),
+            saml_name_id: Ok(None),
+        }))
+    }
+
+    fn saml_source(name_id: Result<Option<&'static str>, Error>) -> Arc<Box<dyn Source>> {
+        Arc::new(Box::new(StaticSource {
+            keys: Ok(vec![KEY_A]),
+            remaining_rate_limit: None,
+            saml_name_id: name_id,
+        }))
+    }
+
+    /// A source supporting conditional requests, returning a static key identified by an `ETag`.
+    #[derive(Debug)]
+    struct ConditionalSource {
+        url: Url,
+        key: &'static str,
+        etag: &'static str,
+    }
+
+    #[async_trait]
+    impl Source for ConditionalSource {
+        async fn get_keys_by_username(&self, _username: &str) -> Result<Vec<PublicKey>, Error> {
+            Ok(vec![self.key.parse().unwrap()])
+        }
+
+        async fn get_keys_if_changed(
+            &self,
+            _username: &str,
+            etag: Option<&str>,
+        ) -> Result<Fetched, Error> {
+            if etag == Some(self.etag) {
+                return Ok(Fetched::NotModified);
+            }
+            Ok(Fetched::Keys {
+                keys: vec![self.key.parse().unwrap()],
+                etag: Some(self.etag.to_string()),
+            })
+        }
+
+        fn url(&self) -> Option<&Url> {
+            Some(&self.url)
+        }
+    }
+
+    /// A cache keeping keys in memory.
+    #[derive(Debug, Default)]
+    struct MemoryCache(Mutex<HashMap<CacheKey, CachedKeys>>);
+
+    #[async_trait]
+    impl KeyCache for MemoryCache {
+        async fn get(&self, key: &CacheKey) -> Option<CachedKeys> {
+            self.0.lock().unwrap().get(key).cloned()
+        }
+
+        async fn put(&self, key: &CacheKey, value: CachedKeys) {
+            self.0.lock().unwrap().insert(key.clone(), value);
+        }
+    }
+
+    const KEY_A: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS";
+    const KEY_B: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILWtK6WxXw7NVhbn6fTQ0dECF8y98fahSIsqKMh+sSo9";
+    const KEY_C: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIJHDGMF+tZQL3dcr1arPst+YP8v33Is0kAJVvyTKrxMw";
+
+    fn signer(sources: Vec<Arc<Box<dyn Source>>>, source_mode: SourceMode) -> Signer {
+        Signer {
+            name: "octocat".to_string(),
+            principals: vec!["octocat@github.com".to_string()],
+            sources,
+            source_names: Vec::new(),
+            source_mode,
+            resolve_email: false,
+            saml_organization: None,
+            allowed_fingerprints: vec![],
+            denied_fingerprints: vec![],
+            strict_fingerprints: false,
+            expected_keys: None,
+            key_title_pattern: None,
+            cache: None,
+            comment: None,
+            key_validation: KeyValidation::default(),
+            tolerate_connection_errors: false,
+            warn_expiry_within: None,
+        }
+    }
+
+    /// The entries of the given signer.
+    async fn signer_entries(signer: &Signer) -> Result<Vec<Entry>, Error> {
+        signer.get_entries().await.map(|(entries, _)| entries)
+    }
+
+    /// Keys are cached along with their `ETag`, returning the cached keys if the source reports
+    /// them as not modified.
+    #[rstest]
+    #[tokio::test]
+    async fn cached_keys_returned_if_not_modified() {
+        let source: Arc<Box<dyn Source>> = Arc::new(Box::new(ConditionalSource {
+            url: "https://api.github.com".parse().unwrap(),
+            key: KEY_A,
+            etag: "a",
+        }));
+        let cache = Arc::new(MemoryCache::default());
+        let key = CacheKey {
+            source_url: "https://api.github.com/".to_string(),
+            username: "octocat".to_string(),
+            auth_identity: None,
+        };
+        let mut signer = signer(vec![source], SourceMode::Merge);
+        signer.cache = Some(cache.clone() as Arc<dyn KeyCache>);
+
+        assert_eq!(
+            signer.get_keys().await.unwrap(),
+            vec![KEY_A.parse().unwrap()]
+        );
+        assert_eq!(
+            cache.get(&key).await,
+            Some(CachedKeys {
+                keys: vec![KEY_A.parse().unwrap()],
+                etag: "a".to_string()
+            })
+        );
+
+        cache
+            .put(
+                &key,
+                CachedKeys {
+                    keys: vec![KEY_B.parse().unwrap()],
+                    etag: "a".to_string(),
+                },
+            )
+            .await;
+        assert_eq!(
+            signer.get_keys().await.unwrap(),
+            vec![KEY_B.parse().unwrap()]
+        );
+    }
+
+    const FINGERPRINT_A: &str = "SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM";
+
+    /// Keys not matching the allowed fingerprints of a signer are dropped, while no allowed
+    /// fingerprints accept all keys.
+    #[rstest]
+    #[case(vec![], vec![KEY_A, KEY_B])]
+    #[case(vec![FINGERPRINT_A], vec![KEY_A])]
+    #[tokio::test]
+    async fn keys_not_matching_allowed_fingerprints_dropped(
+        #[case] allowed_fingerprints: Vec<&str>,
+        #[case] expected: Vec<&str>,
+    ) {
+        let mut signer = signer(vec![source(Ok(vec![KEY_A, KEY_B]))], SourceMode::Merge);
+        signer.allowed_fingerprints = allowed_fingerprints
+            .into_iter()
+            .map(|f| f.parse().unwrap())
+            .collect();
+
+        let mut entries = signer_entries(&signer).await.unwrap();
+        entries.sort();
+
+        assert_eq!(
+            entries,
+            expected
+                .into_iter()
+                .map(|k| Entry::new(
+                    vec!["octocat@github.com".to_string()],
+                    None,
+                    None,
+                    k.parse().unwrap()
+                ))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    /// Only keys whose title matches the key title pattern of a signer are kept, dropping keys
+    /// without a title, while all keys are kept without a pattern.
+    #[rstest]
+    #[case(None, vec![KEY_A, KEY_B, KEY_C])]
+    #[case(Some("hanko-*"), vec![KEY_A])]
+    #[case(Some("*"), vec![KEY_A, KEY_B])]
+    #[case(Some("work"), vec![])]
+    fn keys_not_matching_title_pattern_dropped(
+        #[case] pattern: Option<&str>,
+        #[case] expected: Vec<&str>,
+    ) {
+        let mut signer = signer(vec![], SourceMode::Merge);
+        signer.key_title_pattern = pattern.map(ToString::to_string);
+        let keys = vec![
+            KEY_A
+                .parse::<PublicKey>()
+                .unwrap()
+                .with_title(Some("hanko-managed".to_string())),
+            KEY_B
+                .parse::<PublicKey>()
+                .unwrap()
+                .with_title(Some("personal".to_string())),
+            KEY_C.parse().unwrap(),
+        ];
+
+        let keys = signer.titled_keys(keys);
+
+        assert_eq!(
+            keys,
+            expected
+                .into_iter()
+                .map(|k| k.parse().unwrap())
+                .collect::<Vec<PublicKey>>()
+        );
+    }
+
+    /// Glob patterns match using `*` for any number of characters and `?` for a single one.
+    #[rstest]
+    #[case("hanko-managed", "hanko-managed", true)]
+    #[case("hanko-*", "hanko-managed", true)]
+    #[case("hanko-*", "hanko-", true)]
+    #[case("*-managed", "hanko-managed", true)]
+    #[case("*a*a*", "banana", true)]
+    #[case("key-?", "key-1", true)]
+    #[case("key-?", "key-10", false)]
+    #[case("hanko-*", "personal", false)]
+    #[case("*managed", "managed-by-hand", false)]
+    #[case("", "", true)]
+    #[case("", "key", false)]
+    fn glob_pattern_matched(#[case] pattern: &str, #[case] text: &str, #[case] expected: bool) {
+        assert_eq!(glob_matches(pattern, text), expected);
+    }
+
+    /// In strict mode, keys not matching the allowed fingerprints of a signer return an error.
+    #[rstest]
+    #[tokio::test]
+    async fn keys_not_matching_allowed_fingerprints_strict_returns_error() {
+        let mut signer = signer(vec![source(Ok(vec![KEY_A, KEY_B]))], SourceMode::Merge);
+        signer.allowed_fingerprints = vec![FINGERPRINT_A.parse().unwrap()];
+        signer.strict_fingerprints = true;
+
+        let err = signer_entries(&signer).await.unwrap_err();
+
+        assert_eq!(
+            err,
+            Error::UnpinnedKey("SHA256:AwKAWrobAVuK0DN/1EkXEddUEKNF/soOb+gzQZClxm8".to_string())
+        );
+    }
+
+    /// Keys expiring on their source are only valid until they expire, unless their certificate
+    /// expires earlier.
+    #[rstest]
+    #[tokio::test]
+    async fn expiring_keys_valid_until_expiry() {
+        let expires_at: DateTime<Utc> = "2030-01-01T00:00:00Z".parse().unwrap();
+        let key: PublicKey = KEY_A.parse().unwrap();
+        let signer = signer(
+            vec![Arc::new(Box::new(ExpiringSource(
+                key.clone().with_expires_at(Some(expires_at)),
+            )))],
+            SourceMode::Merge,
+        );
+
+        let entries = signer_entries(&signer).await.unwrap();
+
+        assert_eq!(entries[0].valid_before(), Some(expires_at));
+    }
+
+    /// Only keys that expire within the window and have not expired yet expire soon.
+    #[rstest]
+    #[case(3, 7, true)]
+    #[case(7, 7, true)]
+    #[case(8, 7, false)]
+    #[case(-1, 7, false)]
+    fn expiry_within_window_detected(
+        #[case] expires_in_days: i64,
+        #[case] window_days: i64,
+        #[case] expected: bool,
+    ) {
+        let now = clock::now();
+
+        assert_eq!(
+            expires_within(
+                now + TimeDelta::days(expires_in_days),
+                TimeDelta::days(window_days),
+                now
+            ),
+            expected
+        );
+    }
+
+    /// Connection errors only skip the signer if tolerated and keys are not validated strictly.
+    #[rstest]
+    #[case(false, KeyValidation::Warn, Err(Error::ConnectionError))]
+    #[case(true, KeyValidation::Warn, Ok(vec![]))]
+    #[case(true, KeyValidation::Strict, Err(Error::ConnectionError))]
+    #[tokio::test]
+    async fn connection_errors_tolerated_unless_strict(
+        #[case] tolerate: bool,
+        #[case] validation: KeyValidation,
+        #[case] expected: Result<Vec<Entry>, Error>,
+    ) {
+        let mut signer = signer(vec![source(Err(Error::ConnectionError))], SourceMode::Merge);
+        signer.tolerate_connection_errors = tolerate;
+        signer.key_validation = validation;
+
+        assert_eq!(signer_entries(&signer).await, expected);
+    }
+
+    /// An unexpected number of distinct keys is only rejected in strict mode, returning the keys
+    /// otherwise. Keys returned by multiple sources are counted once.
+    #[rstest]
+    #[case(Some(2), false, Ok(3))]
+    #[case(Some(1), false, Ok(3))]
+    #[case(Some(1), true, Err(Error::UnexpectedKeyCount(1, 2)))]
+    #[case(None, true, Ok(3))]
+    #[tokio::test]
+    async fn unexpected_key_count_rejected_in_strict_mode(
+        #[case] expected_keys: Option<usize>,
+        #[case] strict: bool,
+        #[case] expected: Result<usize, Error>,
+    ) {
+        let mut signer = signer(
+            vec![source(Ok(vec![KEY_A, KEY_B])), source(Ok(vec![KEY_A]))],
+            SourceMode::Merge,
+        );
+        signer.expected_keys = expected_keys;
+        signer.strict_fingerprints = strict;
+
+        let result = get_entries_until([signer], future::pending(), Duration::MAX)
+            .await
+            .map(|(entries, _)| entries.len())
+            .map_err(|err| err.error);
+
+        assert_eq!(result, expected);
+    }
+
+    /// The keys of a signer whose principals are routed to different sources are counted together
+    /// against it's expected number of keys.
+    #[rstest]
+    #[case(Some(2), Ok(2))]
+    #[case(Some(1), Err(Error::UnexpectedKeyCount(1, 2)))]
+    #[tokio::test]
+    async fn key_count_of_routed_signer_checked_together(
+        #[case] expected_keys: Option<usize>,
+        #[case] expected: Result<usize, Error>,
+    ) {
+        let routed = |key: &'static str, principal: &str| {
+            let mut signer = signer(vec![source(Ok(vec![key]))], SourceMode::Merge);
+            signer.principals = vec![principal.to_string()];
+            signer.expected_keys = expected_keys;
+            signer.strict_fingerprints = true;
+            signer
+        };
+        let signers = [
+            routed(KEY_A, "octocat@github.com"),
+            routed(KEY_B, "octocat@gitlab.com"),
+        ];
+
+        let result = get_entries_until(signers, future::pending(), Duration::MAX)
+            .await
+            .map(|(entries, _)| entries.len())
+            .map_err(|err| err.error);
+
+        assert_eq!(result, expected);
+    }
+
+    /// Keys matching the denied fingerprints of a signer are dropped, even if they match it's
+    /// allowed fingerprints.
+    #[rstest]
+    #[case(vec![], vec![KEY_B])]
+    #[case(vec![FINGERPRINT_A], vec![])]
+    #[tokio::test]
+    async fn keys_matching_denied_fingerprints_dropped(
+        #[case] allowed_fingerprints: Vec<&str>,
+        #[case] expected: Vec<&str>,
+    ) {
+        let mut signer = signer(vec![source(Ok(vec![KEY_A, KEY_B]))], SourceMode::Merge);
+        signer.allowed_fingerprints = allowed_fingerprints
+            .into_iter()
+            .map(|f| f.parse().unwrap())
+            .collect();
+        signer.denied_fingerprints = vec![FINGERPRINT_A.parse().unwrap()];
+
+        let entries = signer_entries(&signer).await.unwrap();
+
+        assert_eq!(
+            entries,
+            expected
+                .into_iter()
+                .map(|k| Entry::new(
+                    vec!["octocat@github.com".to_string()],
+                    None,
+                    None,
+                    k.parse().unwrap()
+                ))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    /// In strict mode, keys matching the denied fingerprints of a signer return an error.
+    #[rstest]
+    #[tokio::test]
+    async fn keys_matching_denied_fingerprints_strict_returns_error() {
+        let mut signer = signer(vec![source(Ok(vec![KEY_A, KEY_B]))], SourceMode::Merge);
+        signer.denied_fingerprints = vec![FINGERPRINT_A.parse().unwrap()];
+        signer.strict_fingerprints = true;
+
+        let err = signer_entries(&signer).await.unwrap_err();
+
+        assert_eq!(err, Error::DeniedKey(FINGERPRINT_A.to_string()));
+    }
+
+    /// The SAML identity of a signer is added to it's principals, while failing to retrieve it
+    /// falls back to the configured principals.
+    #[rstest]
+    #[case(Ok(Some("octocat@acme.corp")), vec!["octocat@github.com", "octocat@acme.corp"])]
+    #[case(Ok(Some("octocat@github.com")), vec!["octocat@github.com"])]
+    #[case(Ok(None), vec!["octocat@github.com"])]
+    #[case(Err(Error::SamlIdentityInaccessible("missing scope".to_string())), vec!["octocat@github.com"])]
+    #[tokio::test]
+    async fn saml_identity_added_to_principals(
+        #[case] name_id: Result<Option<&'static str>, Error>,
+        #[case] expected: Vec<&str>,
+    ) {
+        let mut signer = signer(vec![saml_source(name_id)], SourceMode::Merge);
+        signer.saml_organization = Some("acme".to_string());
+
+        let entries = signer_entries(&signer).await.unwrap();
+
+        assert_eq!(
+            entries,
+            vec![Entry::new(
+                expected.into_iter().map(ToString::to_string).collect(),
+                None,
+                None,
+                KEY_A.parse().unwrap()
+            )]
+        );
+    }
+
+    /// A signer without principals and SAML identity does not produce any entries.
+    #[rstest]
+    #[tokio::test]
+    async fn signer_without_any_principals_skipped() {
+        let mut signer = signer(vec![saml_source(Ok(None))], SourceMode::Merge);
+        signer.principals.clear();
+        signer.saml_organization = Some("acme".to_string());
+
+        let entries = signer_entries(&signer).await.unwrap();
+
+        assert!(entries.is_empty());
+    }
+
+    /// In merge mode, the keys of all sources are returned.
+    #[rstest]
+    #[tokio::test]
+    async fn merge_mode_returns_keys_of_all_sources() {
+        let signer = signer(
+            vec![source(Ok(vec![KEY_A])), source(Ok(vec![KEY_B]))],
+            SourceMode::Merge,
+        );
+
+        let mut keys = signer.get_keys().await.unwrap();
+        keys.sort();
+
+        assert_eq!(keys, vec![KEY_A.parse().unwrap(), KEY_B.parse().unwrap()]);
+    }
+
+    /// In first match mode, only the keys of the first source returning any keys are returned,
+    /// skipping sources the user does not exist on or has no keys configured.
+    #[rstest]
+    #[case(vec![source(Ok(vec![KEY_A])), source(Ok(vec![KEY_B]))], vec![KEY_A])]
+    #[case(vec![source(Err(Error::UserNotFound)), source(Ok(vec![KEY_B]))], vec![KEY_B])]
+    #[case(vec![source(Ok(vec![])), source(Ok(vec![KEY_B]))], vec![KEY_B])]
+    #[case(vec![source(Err(Error::UserNotFound)), source(Ok(vec![]))], vec![])]
+    #[tokio::test]
+    async fn first_match_mode_returns_keys_of_first_matching_source(
+        #[case] sources: Vec<Arc<Box<dyn Source>>>,
+        #[case] expected: Vec<&str>,
+    ) {
+        let signer = signer(sources, SourceMode::FirstMatch);
+
+        let keys = signer.get_keys().await.unwrap();
+
+        assert_eq!(
+            keys,
+            expected
+                .into_iter()
+                .map(|k| k.parse().unwrap())
+                .collect::<Vec<PublicKey>>()
+        );
+    }
+
+    /// In first match mode, errors other than a user not existing are returned early.
+    #[rstest]
+    #[tokio::test]
+    async fn first_match_mode_returns_connection_error() {
+        let signer = signer(
+            vec![source(Err(Error::ConnectionError)), source(Ok(vec![KEY_B]))],
+            SourceMode::FirstMatch,
+        );
+
+        let err = signer.get_keys().await.unwrap_err();
+
+        assert_eq!(err, Error::ConnectionError);
+    }
+
+    /// The rate limit budget suffices if every source has at least as many requests remaining as
+    /// signers using it.
+    #[rstest]
+    #[case(2, None, true)]
+    #[case(2, Some(2), true)]
+    #[case(2, Some(1), false)]
+    #[case(3, Some(0), false)]
+    #[tokio::test]
+    async fn rate_limit_budget_compared_against_required_requests(
+        #[case] n_signers: usize,
+        #[case] remaining: Option<usize>,
+        #[case] expected: bool,
+    ) {
+        let source = match remaining {
+            Some(remaining) => rate_limited_source(remaining),
+            None => source(Ok(vec![])),
+        };
+        let signers: Vec<Signer> = (0..n_signers)
+            .map(|_| signer(vec![source.clone()], SourceMode::Merge))
+            .collect();
+
+        assert_eq!(check_rate_limit_budget(&signers).await, expected);
+    }
+
+    /// Requests are estimated per source shared by signers, counting SAML identity lookups and
+    /// email address resolutions as additional requests.
+    #[rstest]
+    fn requests_estimated_per_source() {
+        let github = source(Ok(vec![]));
+        let gitlab = source(Ok(vec![]));
+        let mut saml_signer = signer(vec![github.clone()], SourceMode::Merge);
+        saml_signer.saml_organization = Some("acme".to_string());
+        let mut email_signer = signer(vec![gitlab.clone()], SourceMode::Merge);
+        email_signer.resolve_email = true;
+        let signers = vec![
+            signer(vec![github.clone(), gitlab.clone()], SourceMode::FirstMatch),
+            signer(vec![github.clone()], SourceMode::Merge),
+            saml_signer,
+            email_signer,
+        ];
+
+        let estimate = estimate_requests(&signers);
+
+        assert_eq!(estimate.len(), 2);
+        assert!(Arc::ptr_eq(estimate[0].0, &github));
+        assert_eq!(estimate[0].1, 4);
+        assert!(Arc::ptr_eq(estimate[1].0, &gitlab));
+        assert_eq!(estimate[1].1, 3);
+    }
+
+    /// A source resolving a single email address to a user, returning keys only for that user.
+    #[derive(Debug)]
+    struct ResolvingSource;
+
+    #[async_trait]
+    impl Source for ResolvingSource {
+        async fn get_keys_by_username(&self, username: &str) -> Result<Vec<PublicKey>, Error> {
+            match username {
+                "octocat" => Ok(vec![KEY_A.parse().unwrap()]),
+                _ => Err(Error::UserNotFound),
+            }
+        }
+
+        async fn login_by_email(&self, email: &str) -> Result<Option<String>, Error> {
+            Ok((email == "octocat@github.com").then(|| "octocat".to_string()))
+        }
+    }
+
+    /// Signers resolving their name as an email address use the keys of the user it resolves to,
+    /// while email addresses not resolving to any user have no keys.
+    #[rstest]
+    #[case("octocat@github.com", SourceMode::Merge, vec![KEY_A])]
+    #[case("octocat@github.com", SourceMode::FirstMatch, vec![KEY_A])]
+    #[case("octodog@github.com", SourceMode::Merge, vec![])]
+    #[case("octodog@github.com", SourceMode::FirstMatch, vec![])]
+    #[tokio::test]
+    async fn keys_of_user_resolved_by_email(
+        #[case] name: &str,
+        #[case] source_mode: SourceMode,
+        #[case] expected: Vec<&str>,
+    ) {
+        let mut signer = signer(vec![Arc::new(Box::new(ResolvingSource))], source_mode);
+        signer.name = name.to_string();
+        signer.resolve_email = true;
+
+        let keys = signer.get_keys().await.unwrap();
+
+        assert_eq!(
+            keys,
+            expected
+                .into_iter()
+                .map(|k| k.parse().unwrap())
+                .collect::<Vec<PublicKey>>()
+        );
+    }
+
+    const CERTIFICATE: &str = "ssh-ed25519-cert-v01@openssh.com AAAAIHNzaC1lZDI1NTE5LWNlcnQtdjAxQG9wZW5zc2guY29tAAAAIJrUVEPsce+Dav74tW2F5nuGyN0DRh0m+gIYNJ+2twexAAAAIOskftcyLDSS5Dg/wd7vHTXorY5B86LcOo7S7ZLyCq3fAAAAAAAAAAAAAAABAAAABWpzbm93AAAAEwAAAA9qLnNub3dAd2FsbC5jb20AAAAAZZIAgAAAAABw29iAAAAAAAAAAIIAAAAVcGVybWl0LVgxMS1mb3J3YXJkaW5nAAAAAAAAABdwZXJtaXQtYWdlbnQtZm9yd2FyZGluZwAAAAAAAAAWcGVybWl0LXBvcnQtZm9yd2FyZGluZwAAAAAAAAAKcGVybWl0LXB0eQAAAAAAAAAOcGVybWl0LXVzZXItcmMAAAAAAAAAAAAAADMAAAALc3NoLWVkMjU1MTkAAAAgdXdBjjTmvUNbxYqKFF3ygWoKRabgYUqfNEfjscehidMAAABTAAAAC3NzaC1lZDI1NTE5AAAAQGjf4NxxorkWkCSA+uJ9JFQFx8yuOZku4QioYPCLhE/N+mxJ9dqhLlmHWMzHCpYLGR+QeFPEuBxTgTH4K1QOiAQ=";
+
+    /// Entries of certificates are restricted to the validity period of the certificate, while
+    /// entries of plain keys are not restricted.
+    #[rstest]
+    #[tokio::test]
+    async fn certificate_entries_restricted_to_certificate_validity() {
+        let signer = signer(
+            vec![source(Ok(vec![KEY_A, CERTIFICATE]))],
+            SourceMode::Merge,
+        );
+
+        let entries: Vec<String> = signer_entries(&signer)
+            .await
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            entries,
+            vec![
+                format!("octocat@github.com {KEY_A}"),
+                format!("octocat@github.com valid-after=20240101000000Z,valid-before=20300101000000Z {CERTIFICATE}"),
+            ]
+        );
+    }
+
+    /// Retrieved keys record the name of the source they were retrieved from.
+    #[rstest]
+    #[case(SourceMode::Merge, vec![Some("github"), Some("gitlab")])]
+    #[case(SourceMode::FirstMatch, vec![Some("github")])]
+    #[tokio::test]
+    async fn keys_record_source_name(
+        #[case] source_mode: SourceMode,
+        #[case] expected: Vec<Option<&str>>,
+    ) {
+        let mut signer = signer(
+            vec![source(Ok(vec![KEY_A])), source(Ok(vec![KEY_B]))],
+            source_mode,
+        );
+        signer.source_names = vec!["github".to_string(), "gitlab".to_string()];
+
+        let mut keys = signer.get_keys().await.unwrap();
+        keys.sort();
+
+        assert_eq!(
+            keys.iter().map(PublicKey::source).collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    /// A source never returning any keys.
+    #[derive(Debug)]
+    struct PendingSource;
+
+    #[async_trait]
+    impl Source for PendingSource {
+        async fn get_keys_by_username(&self, _username: &str) -> Result<Vec<PublicKey>, Error> {
+            future::pending().await
+        }
+    }
+
+    /// Once interrupted, the entries of signers retrieved within the grace period are returned
+    /// as partial entries, aborting signers that are still being retrieved.
+    #[rstest]
+    #[tokio::test]
+    async fn entries_partial_if_interrupted() {
+        let retrieved = signer(vec![source(Ok(vec![KEY_A]))], SourceMode::Merge);
+        let mut pending = signer(vec![Arc::new(Box::new(PendingSource))], SourceMode::Merge);
+        pending.name = "pending".to_string();
+
+        let (entries, partial) = get_entries_until(
+            [retrieved, pending],
+            tokio::time::sleep(Duration::from_millis(10)),
+            Duration::from_millis(10),
+        )
+        .await
+        .unwrap();
+
+        assert!(partial);
+        assert_eq!(
+            entries,
+            vec![Entry::new(
+                vec!["octocat@github.com".to_string()],
+                None,
+                None,
+                KEY_A.parse().unwrap()
+            )]
+        );
+    }
+
+    /// Entries are complete if all signers are retrieved within the grace period.
+    #[rstest]
+    #[tokio::test]
+    async fn entries_complete_if_retrieved_within_grace_period() {
+        let signers = [signer(vec![source(Ok(vec![KEY_A]))], SourceMode::Merge)];
+
+        let (entries, partial) =
+            get_entries_until(signers, future::ready(()), Duration::from_secs(10))
+                .await
+                .unwrap();
+
+        assert!(!partial);
+        assert_eq!(entries.len(), 1);
+    }
+
+    /// The first signer error is returned without waiting for the remaining signers, which are
+    /// aborted.
+    #[rstest]
+    #[tokio::test]
+    async fn first_signer_error_returned_immediately() {
+        let mut failing = signer(
+            vec![source(Err(Error::ClientError(
+                reqwest::StatusCode::FORBIDDEN,
+            )))],
+            SourceMode::Merge,
+        );
+        failing.name = "failing".to_string();
+        let pending = signer(vec![Arc::new(Box::new(PendingSource))], SourceMode::Merge);
+
+        let error = get_entries_until([pending, failing], future::pending(), Duration::MAX)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.signer, "failing");
     }
-    Ok(entries)
 }
@@ -1,18 +1,431 @@
-use serde::{Deserialize, Serialize};
-use std::{fmt, str::FromStr};
+use base64::{
+    engine::general_purpose::{STANDARD, STANDARD_NO_PAD},
+    Engine,
+};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+use tracing::debug;
 
 /// An SSH public key.
-#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+///
+/// Keys are compared by their content only, metadata like the creation date is not taken into account.
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PublicKey {
+    #[serde(deserialize_with = "deserialize_key")]
     key: String,
-    // TODO: Add expiration field for GitLab keys.
+    /// When the key was added to the source it was retrieved from, if known.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_lenient_timestamp",
+        skip_serializing
+    )]
+    created_at: Option<DateTime<Utc>>,
+    /// The title the key was given on the source it was retrieved from, if known.
+    #[serde(default, skip_serializing)]
+    title: Option<String>,
+    /// The name of the configured source the key was retrieved from, if known.
+    #[serde(skip)]
+    source: Option<String>,
+    /// The name of the configured signer the key was retrieved for, if known.
+    #[serde(skip)]
+    signer: Option<String>,
+    /// When the key expires on the source it was retrieved from, if it does.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_lenient_timestamp",
+        skip_serializing
+    )]
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl PublicKey {
+    /// The key without it's trailing comment, consisting only of the key type and base64 encoded
+    /// key material.
+    #[must_use]
+    pub fn without_comment(&self) -> Self {
+        let key = self
+            .key
+            .split_whitespace()
+            .take(2)
+            .collect::<Vec<_>>()
+            .join(" ");
+        PublicKey {
+            key,
+            created_at: self.created_at,
+            title: self.title.clone(),
+            source: self.source.clone(),
+            signer: self.signer.clone(),
+            expires_at: self.expires_at,
+        }
+    }
+
+    /// The key type and base64 encoded key material, identifying the key regardless of it's
+    /// comment.
+    pub(crate) fn material(&self) -> (&str, &str) {
+        let mut parts = self.key.split_whitespace();
+        (
+            parts.next().unwrap_or_default(),
+            parts.next().unwrap_or_default(),
+        )
+    }
+
+    /// The decoded key material in the SSH wire format.
+    /// Returns `None` if the key material is not valid base64.
+    pub(crate) fn blob(&self) -> Option<Vec<u8>> {
+        STANDARD.decode(self.material().1).ok()
+    }
+
+    /// Whether the key has a trailing comment.
+    pub(crate) fn has_comment(&self) -> bool {
+        self.key.split_whitespace().nth(2).is_some()
+    }
+
+    /// When the key was added to the source it was retrieved from, if known.
+    #[must_use]
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    /// Set when the key was added to the source it was retrieved from.
+    #[must_use]
+    pub fn with_created_at(self, created_at: Option<DateTime<Utc>>) -> Self {
+        Self { created_at, ..self }
+    }
+
+    /// When the key expires on the source it was retrieved from, if it does.
+    #[must_use]
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    /// Set when the key expires on the source it was retrieved from.
+    #[must_use]
+    pub fn with_expires_at(self, expires_at: Option<DateTime<Utc>>) -> Self {
+        Self { expires_at, ..self }
+    }
+
+    /// The title the key was given on the source it was retrieved from, if known.
+    #[must_use]
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Set the title the key was given on the source it was retrieved from.
+    #[must_use]
+    pub fn with_title(self, title: Option<String>) -> Self {
+        Self { title, ..self }
+    }
+
+    /// The name of the configured source the key was retrieved from, if known.
+    #[must_use]
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Set the name of the configured source the key was retrieved from.
+    #[must_use]
+    pub fn with_source(self, source: Option<String>) -> Self {
+        Self { source, ..self }
+    }
+
+    /// The name of the configured signer the key was retrieved for, if known.
+    #[must_use]
+    pub fn signer(&self) -> Option<&str> {
+        self.signer.as_deref()
+    }
+
+    /// Set the name of the configured signer the key was retrieved for.
+    #[must_use]
+    pub fn with_signer(self, signer: Option<String>) -> Self {
+        Self { signer, ..self }
+    }
+
+    /// Check that the key is a well-formed SSH public key of a recognized type, consisting of
+    /// valid base64 encoded key material of the stated type.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason the key is invalid.
+    pub fn validate(&self) -> Result<(), String> {
+        let (key_type, material) = self.material();
+        let base_type = key_type
+            .strip_suffix("-cert-v01@openssh.com")
+            .unwrap_or(key_type);
+        let Some(key_fields) = key_fields(base_type) else {
+            return Err(format!("unrecognized key type `{key_type}`"));
+        };
+        if material.is_empty() {
+            return Err("missing key material".to_string());
+        }
+        let blob = STANDARD
+            .decode(material)
+            .map_err(|_| "key material is not valid base64".to_string())?;
+        let mut reader = Reader(&blob);
+        if reader.string() != Some(key_type.as_bytes()) {
+            return Err(format!("key material is not of type `{key_type}`"));
+        }
+        if base_type != key_type {
+            return self
+                .certificate_validity()
+                .map(|_| ())
+                .ok_or_else(|| "malformed certificate".to_string());
+        }
+        for _ in 0..key_fields {
+            reader.string().ok_or("truncated key material")?;
+        }
+        if !reader.0.is_empty() {
+            return Err("trailing data after key material".to_string());
+        }
+        Ok(())
+    }
+
+    /// The SHA256 fingerprint of the key, as displayed by `ssh-keygen -l`.
+    /// Returns `None` if the key material is not valid base64.
+    #[must_use]
+    pub fn fingerprint(&self) -> Option<Fingerprint> {
+        let material = self.key.split_whitespace().nth(1)?;
+        let blob = STANDARD.decode(material).ok()?;
+        let digest = Sha256::digest(blob);
+        Some(Fingerprint(STANDARD_NO_PAD.encode(digest)))
+    }
+
+    /// The validity period embedded in the key if it is an OpenSSH certificate.
+    /// Returns `None` for plain keys and certificates that cannot be parsed.
+    ///
+    /// [Certificate Format](https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.certkeys?rev=HEAD)
+    #[must_use]
+    pub fn certificate_validity(&self) -> Option<CertificateValidity> {
+        let material = self.key.split_whitespace().nth(1)?;
+        let blob = STANDARD.decode(material).ok()?;
+        let mut reader = Reader(&blob);
+
+        let key_type = std::str::from_utf8(reader.string()?).ok()?;
+        let base_type = key_type.strip_suffix("-cert-v01@openssh.com")?;
+        let key_fields = key_fields(base_type)?;
+        // Skip the nonce and public key.
+        for _ in 0..=key_fields {
+            reader.string()?;
+        }
+        let _serial = reader.u64()?;
+        let _type = reader.u32()?;
+        let _key_id = reader.string()?;
+        let _valid_principals = reader.string()?;
+        let valid_after = reader.u64()?;
+        let valid_before = reader.u64()?;
+
+        let timestamp = |secs: u64| {
+            i64::try_from(secs)
+                .ok()
+                .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+        };
+        Some(CertificateValidity {
+            valid_after: (valid_after != 0).then(|| timestamp(valid_after)).flatten(),
+            valid_before: (valid_before != u64::MAX)
+                .then(|| timestamp(valid_before))
+                .flatten(),
+        })
+    }
+}
+
+/// The number of fields of a public key of the given type, each of which is encoded as a string or
+/// mpint. Returns `None` for unrecognized key types.
+fn key_fields(key_type: &str) -> Option<usize> {
+    match key_type {
+        "ssh-ed25519" => Some(1),
+        "ssh-rsa"
+        | "sk-ssh-ed25519@openssh.com"
+        | "ecdsa-sha2-nistp256"
+        | "ecdsa-sha2-nistp384"
+        | "ecdsa-sha2-nistp521" => Some(2),
+        "sk-ecdsa-sha2-nistp256@openssh.com" => Some(3),
+        "ssh-dss" => Some(4),
+        _ => None,
+    }
+}
+
+/// Whether the given string is an SSH public key type, as opposed to an option.
+pub(crate) fn is_key_type(s: &str) -> bool {
+    ["ssh-", "ecdsa-", "sk-"]
+        .iter()
+        .any(|prefix| s.starts_with(prefix))
+}
+
+/// Strip options preceding the key type from a key in the format of the `authorized_keys` file,
+/// e.g. `no-pty ssh-ed25519 AAAA...`, keeping only the key type, key material and comment.
+/// Options may contain whitespace within double quotes, e.g. `command="echo hi"`.
+/// Keys without options are returned unchanged.
+fn strip_options(s: &str) -> &str {
+    let mut quoted = false;
+    let Some(end) = s.find(|c: char| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        c.is_whitespace() && !quoted
+    }) else {
+        return s;
+    };
+    let (options, key) = (&s[..end], s[end..].trim_start());
+    if options.is_empty() || is_key_type(options) || !is_key_type(key) {
+        return s;
+    }
+    debug!(options, "Stripping options preceding key");
+    key
+}
+
+/// The validity period of an OpenSSH certificate, `None` bounds meaning the certificate is valid
+/// since or until forever.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CertificateValidity {
+    pub valid_after: Option<DateTime<Utc>>,
+    pub valid_before: Option<DateTime<Utc>>,
+}
+
+/// A reader of the big endian integers and length prefixed strings of the SSH wire format.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.0.len() < n {
+            return None;
+        }
+        let (bytes, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Some(bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_be_bytes(self.bytes(8)?.try_into().ok()?))
+    }
+
+    fn string(&mut self) -> Option<&'a [u8]> {
+        let len = usize::try_from(self.u32()?).ok()?;
+        self.bytes(len)
+    }
+}
+
+/// The SHA256 fingerprint of an SSH public key, in the form of `SHA256:<base64>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Fingerprint(String);
+
+impl FromStr for Fingerprint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid fingerprint `{s}`, expected `SHA256:<base64>`");
+
+        let digest = s.strip_prefix("SHA256:").ok_or_else(invalid)?;
+        let decoded = STANDARD_NO_PAD
+            .decode(digest.trim_end_matches('='))
+            .map_err(|_| invalid())?;
+        if decoded.len() != <Sha256 as Digest>::output_size() {
+            return Err(invalid());
+        }
+        Ok(Self(STANDARD_NO_PAD.encode(decoded)))
+    }
+}
+
+impl Fingerprint {
+    /// The raw SHA256 digest the fingerprint consists of.
+    pub(crate) fn digest(&self) -> Vec<u8> {
+        STANDARD_NO_PAD
+            .decode(&self.0)
+            .expect("fingerprint contains valid base64 since it is validated when parsed")
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SHA256:{}", self.0)
+    }
+}
+
+impl TryFrom<String> for Fingerprint {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Fingerprint> for String {
+    fn from(fingerprint: Fingerprint) -> Self {
+        fingerprint.to_string()
+    }
+}
+
+impl PartialEq for PublicKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for PublicKey {}
+
+impl Hash for PublicKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+impl PartialOrd for PublicKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PublicKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+/// Deserialize an optional RFC 3339 timestamp, ignoring timestamps that cannot be parsed.
+pub(crate) fn deserialize_lenient_timestamp<'de, D>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: Option<String> = Option::deserialize(deserializer)?;
+    Ok(s.and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+        .map(|dt| dt.with_timezone(&Utc)))
+}
+
+/// Deserialize a key, stripping any options preceding it.
+fn deserialize_key<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    Ok(strip_options(&s).to_string())
 }
 
 impl FromStr for PublicKey {
     type Err = ();
 
+    /// Parse a key, stripping any options preceding it as they may be present in lines of an
+    /// `authorized_keys` file.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(PublicKey { key: s.to_string() })
+        Ok(PublicKey {
+            key: strip_options(s).to_string(),
+            created_at: None,
+            title: None,
+            source: None,
+            signer: None,
+            expires_at: None,
+        })
     }
 }
 
@@ -21,3 +434,170 @@ impl fmt::Display for PublicKey {
         write!(f, "{}", self.key)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS John Doe (gitlab.com)",
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS"
+    )]
+    #[case(
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS",
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS"
+    )]
+    fn key_without_comment(#[case] key: &str, #[case] expected: &str) {
+        let key: PublicKey = key.parse().unwrap();
+
+        assert_eq!(key.without_comment().to_string(), expected);
+    }
+
+    /// The validity period of certificates is parsed, while plain keys have none.
+    #[rstest]
+    #[case(
+        "ssh-ed25519-cert-v01@openssh.com AAAAIHNzaC1lZDI1NTE5LWNlcnQtdjAxQG9wZW5zc2guY29tAAAAIJrUVEPsce+Dav74tW2F5nuGyN0DRh0m+gIYNJ+2twexAAAAIOskftcyLDSS5Dg/wd7vHTXorY5B86LcOo7S7ZLyCq3fAAAAAAAAAAAAAAABAAAABWpzbm93AAAAEwAAAA9qLnNub3dAd2FsbC5jb20AAAAAZZIAgAAAAABw29iAAAAAAAAAAIIAAAAVcGVybWl0LVgxMS1mb3J3YXJkaW5nAAAAAAAAABdwZXJtaXQtYWdlbnQtZm9yd2FyZGluZwAAAAAAAAAWcGVybWl0LXBvcnQtZm9yd2FyZGluZwAAAAAAAAAKcGVybWl0LXB0eQAAAAAAAAAOcGVybWl0LXVzZXItcmMAAAAAAAAAAAAAADMAAAALc3NoLWVkMjU1MTkAAAAgdXdBjjTmvUNbxYqKFF3ygWoKRabgYUqfNEfjscehidMAAABTAAAAC3NzaC1lZDI1NTE5AAAAQGjf4NxxorkWkCSA+uJ9JFQFx8yuOZku4QioYPCLhE/N+mxJ9dqhLlmHWMzHCpYLGR+QeFPEuBxTgTH4K1QOiAQ=",
+        Some(CertificateValidity {
+            valid_after: Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+            valid_before: Some(Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()),
+        })
+    )]
+    #[case(
+        "ssh-ed25519-cert-v01@openssh.com AAAAIHNzaC1lZDI1NTE5LWNlcnQtdjAxQG9wZW5zc2guY29tAAAAIOMhnqv725/FU35r/uFMYLQ1Y1/Yu+3SxYrKWb1l0O71AAAAIOskftcyLDSS5Dg/wd7vHTXorY5B86LcOo7S7ZLyCq3fAAAAAAAAAAAAAAABAAAABWpzbm93AAAAEwAAAA9qLnNub3dAd2FsbC5jb20AAAAAAAAAAP//////////AAAAAAAAAAAAAAAAAAAAMwAAAAtzc2gtZWQyNTUxOQAAACB1d0GONOa9Q1vFiooUXfKBagpFpuBhSp80R+Oxx6GJ0wAAAFMAAAALc3NoLWVkMjU1MTkAAABAdWN62vsD5shYi1MgEUzSQioOltic5iSgWpjE6Lh2RaoQNn1YQk2HvbPJj86Z9LLFGtpTNXgtJV5em2HnFtZbAA==",
+        Some(CertificateValidity::default())
+    )]
+    #[case(
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS",
+        None
+    )]
+    #[case("ssh-ed25519-cert-v01@openssh.com AAAA", None)]
+    fn certificate_validity_parsed(
+        #[case] key: &str,
+        #[case] expected: Option<CertificateValidity>,
+    ) {
+        let key: PublicKey = key.parse().unwrap();
+
+        assert_eq!(key.certificate_validity(), expected);
+    }
+
+    /// The creation date of a key is deserialized if present and parseable, ignored otherwise.
+    #[rstest]
+    #[case(
+        r#"{"key": "ssh-ed25519 AAAA", "created_at": "2023-05-23T09:35:15.638Z"}"#,
+        Some("2023-05-23T09:35:15.638Z")
+    )]
+    #[case(r#"{"key": "ssh-ed25519 AAAA", "created_at": "yesterday"}"#, None)]
+    #[case(r#"{"key": "ssh-ed25519 AAAA", "created_at": null}"#, None)]
+    #[case(r#"{"key": "ssh-ed25519 AAAA"}"#, None)]
+    fn created_at_deserialized_leniently(#[case] json: &str, #[case] expected: Option<&str>) {
+        let key: PublicKey = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            key.created_at(),
+            expected.map(|s| s.parse::<DateTime<Utc>>().unwrap())
+        );
+    }
+
+    /// The fingerprint of a key matches the one displayed by `ssh-keygen -l`, regardless of
+    /// it's comment.
+    #[rstest]
+    #[case("ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS")]
+    #[case(
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS John Doe (gitlab.com)"
+    )]
+    fn key_fingerprint(#[case] key: &str) {
+        let key: PublicKey = key.parse().unwrap();
+
+        assert_eq!(
+            key.fingerprint().unwrap().to_string(),
+            "SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM"
+        );
+    }
+
+    /// Keys are validated to consist of base64 encoded key material of a recognized type, matching
+    /// the stated type.
+    #[rstest]
+    #[case(
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS jsnow@wall",
+        Ok(())
+    )]
+    #[case(
+        "ssh-ed25519-cert-v01@openssh.com AAAAIHNzaC1lZDI1NTE5LWNlcnQtdjAxQG9wZW5zc2guY29tAAAAIOMhnqv725/FU35r/uFMYLQ1Y1/Yu+3SxYrKWb1l0O71AAAAIOskftcyLDSS5Dg/wd7vHTXorY5B86LcOo7S7ZLyCq3fAAAAAAAAAAAAAAABAAAABWpzbm93AAAAEwAAAA9qLnNub3dAd2FsbC5jb20AAAAAAAAAAP//////////AAAAAAAAAAAAAAAAAAAAMwAAAAtzc2gtZWQyNTUxOQAAACB1d0GONOa9Q1vFiooUXfKBagpFpuBhSp80R+Oxx6GJ0wAAAFMAAAALc3NoLWVkMjU1MTkAAABAdWN62vsD5shYi1MgEUzSQioOltic5iSgWpjE6Lh2RaoQNn1YQk2HvbPJj86Z9LLFGtpTNXgtJV5em2HnFtZbAA==",
+        Ok(())
+    )]
+    #[case(
+        "ssh-foo AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS",
+        Err("unrecognized key type `ssh-foo`")
+    )]
+    #[case("ssh-ed25519", Err("missing key material"))]
+    #[case("ssh-ed25519 not-base64!", Err("key material is not valid base64"))]
+    #[case(
+        "ssh-rsa AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS",
+        Err("key material is not of type `ssh-rsa`")
+    )]
+    #[case(
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQ",
+        Err("truncated key material")
+    )]
+    fn key_validated(#[case] key: &str, #[case] expected: Result<(), &str>) {
+        let key: PublicKey = key.parse().unwrap();
+
+        assert_eq!(key.validate(), expected.map_err(ToString::to_string));
+    }
+
+    /// Options preceding the key type are stripped, keeping keys without options unchanged.
+    #[rstest]
+    #[case(
+        "no-pty ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS jsnow@wall",
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS jsnow@wall"
+    )]
+    #[case(
+        r#"command="echo hi there",no-pty  ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS"#,
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS"
+    )]
+    #[case(
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS ssh-rsa comment",
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS ssh-rsa comment"
+    )]
+    #[case("not a key", "not a key")]
+    fn key_options_stripped(#[case] key: &str, #[case] expected: &str) {
+        let parsed: PublicKey = key.parse().unwrap();
+        let deserialized: PublicKey =
+            serde_json::from_value(serde_json::json!({ "key": key })).unwrap();
+
+        assert_eq!(parsed.to_string(), expected);
+        assert_eq!(deserialized.to_string(), expected);
+    }
+
+    /// Keys without valid base64 key material don't have a fingerprint.
+    #[rstest]
+    #[case("ssh-ed25519")]
+    #[case("ssh-ed25519 not-base64!")]
+    fn invalid_key_has_no_fingerprint(#[case] key: &str) {
+        let key: PublicKey = key.parse().unwrap();
+
+        assert_eq!(key.fingerprint(), None);
+    }
+
+    /// Fingerprints are parsed with or without padding, rejecting other hash algorithms and
+    /// digests of the wrong length.
+    #[rstest]
+    #[case("SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM", true)]
+    #[case("SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM=", true)]
+    #[case("/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM", false)]
+    #[case("MD5:1f:3b:9c:5a:6e:1d:2a:8b:9c:0d:4e:7f:8a:1b:2c:3d", false)]
+    #[case("SHA256:AAAA", false)]
+    fn fingerprint_parsed(#[case] s: &str, #[case] valid: bool) {
+        let fingerprint = s.parse::<Fingerprint>();
+
+        assert_eq!(fingerprint.is_ok(), valid);
+        if valid {
+            assert_eq!(
+                fingerprint.unwrap().to_string(),
+                "SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM"
+            );
+        }
+    }
+}
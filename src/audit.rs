@@ -0,0 +1,105 @@
+//! An append-only audit log recording changes to the allowed signers file.
+use crate::clock;
+use chrono::Local;
+use serde_json::json;
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+/// The lines of the allowed signers file at the given path, excluding blank lines and comments.
+/// Files that don't exist yet or are not regular files, like FIFOs that cannot be read without
+/// blocking, have no lines.
+pub(crate) fn snapshot(path: &Path) -> Vec<String> {
+    if !fs::metadata(path).is_ok_and(|metadata| metadata.is_file()) {
+        return Vec::new();
+    }
+    fs::read_to_string(path)
+        .map(|content| {
+            content
+                .lines()
+                .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+                .map(ToString::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The entries added and removed between two snapshots of the allowed signers file.
+pub(crate) fn changes<'a>(
+    before: &'a [String],
+    after: &'a [String],
+) -> (Vec<&'a String>, Vec<&'a String>) {
+    let added = after.iter().filter(|line| !before.contains(line)).collect();
+    let removed = before.iter().filter(|line| !after.contains(line)).collect();
+    (added, removed)
+}
+
+/// Append a record of the entries added and removed between two snapshots of the allowed signers
+/// file to the audit log as a line of JSON, creating the log if it doesn't exist.
+pub(crate) fn append_record(
+    log: &Path,
+    config: &Path,
+    before: &[String],
+    after: &[String],
+) -> io::Result<()> {
+    let (added, removed) = changes(before, after);
+    let record = json!({
+        "timestamp": clock::now().with_timezone(&Local).to_rfc3339(),
+        "config": config,
+        "added": added,
+        "removed": removed,
+    });
+
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(log)?;
+    writeln!(file, "{record}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value as JsonValue;
+
+    /// Records of added and removed entries are appended to the log without truncating it.
+    #[test]
+    fn records_appended_to_log() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let log = dir.path().join("audit.jsonl");
+        let config = dir.path().join("config.toml");
+        let line = |s: &str| s.to_string();
+
+        append_record(&log, &config, &[], &[line("a"), line("b")]).unwrap();
+        append_record(
+            &log,
+            &config,
+            &[line("a"), line("b")],
+            &[line("b"), line("c")],
+        )
+        .unwrap();
+
+        let records: Vec<JsonValue> = fs::read_to_string(&log)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["added"], json!(["a", "b"]));
+        assert_eq!(records[0]["removed"], json!([]));
+        assert_eq!(records[1]["added"], json!(["c"]));
+        assert_eq!(records[1]["removed"], json!(["a"]));
+        assert_eq!(records[1]["config"], json!(config));
+    }
+
+    /// Blank lines and comments are excluded from snapshots and missing files have no lines.
+    #[test]
+    fn snapshot_excludes_blank_lines_and_comments() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("allowed_signers");
+        assert!(snapshot(&path).is_empty());
+
+        fs::write(&path, "# comment\na\nb\n\n").unwrap();
+
+        assert_eq!(snapshot(&path), vec!["a", "b"]);
+    }
+}
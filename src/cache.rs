@@ -0,0 +1,216 @@
+//! Caching of public keys retrieved from sources, revalidated using conditional requests.
+use crate::{
+    allowed_signers::{ssh::PublicKey, write_atomic},
+    diagnostic::{warning, Warning},
+    digest::sha256_hex,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Debug, fs, io, path::PathBuf};
+use tracing::debug;
+
+/// Identifies the keys of a user on a source.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub source_url: String,
+    pub username: String,
+    /// A digest identifying the credentials the keys were retrieved with, if any. Since the same
+    /// user may have different keys depending on the credentials used, changing them, e.g. by
+    /// rotating a token, misses previously cached keys.
+    pub auth_identity: Option<String>,
+}
+
+/// The keys of a user along with the `ETag` of the response they were retrieved from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedKeys {
+    pub keys: Vec<PublicKey>,
+    pub etag: String,
+}
+
+/// A store for keys retrieved from sources, consulted before making requests to revalidate
+/// previously retrieved keys instead of retrieving them again.
+///
+/// Caches are best effort, failing to read or write cached keys should not fail an update.
+#[async_trait]
+pub trait KeyCache: Debug + Send + Sync {
+    /// Get the cached keys of a user on a source, if any.
+    async fn get(&self, key: &CacheKey) -> Option<CachedKeys>;
+
+    /// Store the keys of a user on a source.
+    async fn put(&self, key: &CacheKey, value: CachedKeys);
+}
+
+/// A cache storing keys as JSON files within a directory.
+#[derive(Debug)]
+pub struct FileCache {
+    dir: PathBuf,
+}
+
+impl FileCache {
+    #[must_use]
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// The path of the file containing the given cached keys.
+    fn path(&self, key: &CacheKey) -> PathBuf {
+        let mut input = format!("{}\n{}", key.source_url, key.username);
+        if let Some(identity) = &key.auth_identity {
+            input.push('\n');
+            input.push_str(identity);
+        }
+        self.dir.join(format!("{}.json", sha256_hex(input)))
+    }
+
+    fn read(&self, key: &CacheKey) -> io::Result<CachedKeys> {
+        let content = fs::read_to_string(self.path(key))?;
+        let file: CacheFile = serde_json::from_str(&content)?;
+        Ok(file.into())
+    }
+
+    fn write(&self, key: &CacheKey, value: &CachedKeys) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let content = serde_json::to_string(&CacheFile::from(value))?;
+        write_atomic(&self.path(key), content.as_bytes())
+    }
+}
+
+#[async_trait]
+impl KeyCache for FileCache {
+    async fn get(&self, key: &CacheKey) -> Option<CachedKeys> {
+        match self.read(key) {
+            Ok(value) => Some(value),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => {
+                warning!(
+                    Warning::CacheFailure,
+                    [?key],
+                    "Ignoring unreadable cached keys: {err}"
+                );
+                None
+            }
+        }
+    }
+
+    async fn put(&self, key: &CacheKey, value: CachedKeys) {
+        if let Err(err) = self.write(key, &value) {
+            warning!(Warning::CacheFailure, [?key], "Failed to cache keys: {err}");
+        } else {
+            debug!(?key, "Cached keys");
+        }
+    }
+}
+
+/// The JSON representation of cached keys, retaining their creation date.
+#[derive(Debug, Deserialize, Serialize)]
+struct CacheFile {
+    etag: String,
+    keys: Vec<CacheFileKey>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CacheFileKey {
+    key: String,
+    created_at: Option<String>,
+}
+
+impl From<&CachedKeys> for CacheFile {
+    fn from(value: &CachedKeys) -> Self {
+        Self {
+            etag: value.etag.clone(),
+            keys: value
+                .keys
+                .iter()
+                .map(|key| CacheFileKey {
+                    key: key.to_string(),
+                    created_at: key.created_at().map(|dt| dt.to_rfc3339()),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<CacheFile> for CachedKeys {
+    fn from(file: CacheFile) -> Self {
+        Self {
+            etag: file.etag,
+            keys: file
+                .keys
+                .into_iter()
+                .filter_map(|key| {
+                    let created_at = key
+                        .created_at
+                        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                        .map(|dt| dt.with_timezone(&Utc));
+                    let public_key: PublicKey = key.key.parse().ok()?;
+                    Some(public_key.with_created_at(created_at))
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache_key(username: &str) -> CacheKey {
+        CacheKey {
+            source_url: "https://api.github.com/".to_string(),
+            username: username.to_string(),
+            auth_identity: None,
+        }
+    }
+
+    /// Keys stored in the cache are returned including their creation date, while keys of
+    /// other users are not.
+    #[tokio::test]
+    async fn file_cache_returns_stored_keys() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = FileCache::new(dir.path().join("keys"));
+        let created_at = "2023-05-23T09:35:15.638Z".parse::<DateTime<Utc>>().unwrap();
+        let value = CachedKeys {
+            keys: vec![
+                "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS"
+                    .parse::<PublicKey>()
+                    .unwrap()
+                    .with_created_at(Some(created_at)),
+            ],
+            etag: r#"W/"6d82cbb050ddc7fa9cbb659014546e59""#.to_string(),
+        };
+
+        cache.put(&cache_key("octocat"), value.clone()).await;
+        let cached = cache.get(&cache_key("octocat")).await.unwrap();
+
+        assert_eq!(cached, value);
+        assert_eq!(cached.keys[0].created_at(), Some(created_at));
+        assert_eq!(cache.get(&cache_key("torvalds")).await, None);
+    }
+
+    /// Keys cached using different credentials, or none at all, are not returned.
+    #[tokio::test]
+    async fn file_cache_misses_keys_of_other_auth_identity() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = FileCache::new(dir.path().join("keys"));
+        let with_identity = |identity: Option<&str>| CacheKey {
+            auth_identity: identity.map(ToString::to_string),
+            ..cache_key("octocat")
+        };
+        let value = CachedKeys {
+            keys: vec![],
+            etag: "a".to_string(),
+        };
+
+        cache
+            .put(&with_identity(Some("token-a")), value.clone())
+            .await;
+
+        assert_eq!(
+            cache.get(&with_identity(Some("token-a"))).await,
+            Some(value)
+        );
+        assert_eq!(cache.get(&with_identity(Some("token-b"))).await, None);
+        assert_eq!(cache.get(&with_identity(None)).await, None);
+    }
+}
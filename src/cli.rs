@@ -1,21 +1,78 @@
 use crate::{
-    allowed_signers,
-    config::{default_user_source, Configuration},
+    allowed_signers::{self, KeyValidation, OutputFormat, Signer, SignerError},
+    audit,
+    cache::{FileCache, KeyCache},
+    clock::{self, Clock},
+    config::{Configuration, DropIns, LoadOptions, SourceType},
+    diagnostic::{self, warning, Warning},
+    duration::Duration,
+    export, git_hook,
+    lock::FileLock,
+    lockfile::Lockfile,
+    notify, paths,
+    plan::Plan,
+    report, revocation, signature, source, Source,
 };
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, Utc};
 use clap::{
     builder::{OsStr, Resettable},
-    Parser, Subcommand, ValueHint,
+    error::ErrorKind,
+    CommandFactory, Parser, Subcommand, ValueHint,
 };
+use reqwest::Url;
+use serde_json::json;
 use std::{
+    cell::RefCell,
+    collections::HashMap,
     env,
-    path::{Path, PathBuf},
+    fmt::Arguments,
+    fs, future, io,
+    path::{Component, Path, PathBuf},
+    process,
+    sync::{Arc, Mutex},
     time::Instant,
 };
-use tracing::Level;
+use tokio::{
+    sync::Semaphore,
+    task::{self, JoinSet},
+};
+use tracing::{info, Level};
+use tracing_subscriber::{
+    fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer, Registry,
+};
+
+/// The time waited before re-running a failed update unless configured using `--retry-delay`.
+const RUN_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(10);
+/// The exit code of an interrupted run, following the shell convention of 128 plus `SIGINT`.
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// An update was interrupted, leaving the allowed signers file with only the entries of the
+/// signers retrieved so far.
+#[derive(Debug, thiserror::Error)]
+#[error("Update was interrupted, allowed signers file {} only contains signers retrieved so far", .0.display())]
+struct Interrupted(PathBuf);
+
+tokio::task_local! {
+    /// The output of updating one of the configurations given using `--each-config`, printed at
+    /// once after the update finished so that the output of concurrent updates is not interleaved.
+    static OUTPUT: RefCell<String>;
+}
+
+/// Print a line of output like `println!`, buffering it within [`OUTPUT`] if set.
+macro_rules! outputln {
+    ($($arg:tt)*) => {
+        print_output(format_args!($($arg)*))
+    };
+}
 
 #[derive(Debug, Parser)]
-#[command(long_version=long_version(), about, long_about = None)]
+#[command(
+    long_version=long_version(),
+    about,
+    long_about = None,
+    after_help = "Run `hanko env` to list the environment variables used by hanko."
+)]
 pub struct Cli {
     #[command(subcommand)]
     command: Commands,
@@ -27,10 +84,110 @@ pub struct Cli {
 #[derive(Debug, Subcommand)]
 enum Commands {
     /// Update the allowed signers file.
-    Update,
+    Update(UpdateArgs),
     /// Manage allowed signers.
     #[command(subcommand)]
     Signer(ManageSigners),
+    /// Manage sources.
+    #[command(subcommand)]
+    Source(ManageSources),
+    /// Inspect the configuration.
+    #[command(subcommand)]
+    Config(ManageConfig),
+    /// Report the keys currently configured for each signer on each of it's sources, without
+    /// writing the allowed signers file.
+    Report {
+        /// The format of the report.
+        #[arg(long, value_enum, default_value_t)]
+        format: ReportFormat,
+    },
+    /// Print all entries of the allowed signers file as a structured document for other tools,
+    /// without writing the allowed signers file.
+    Export {
+        /// The format of the export.
+        #[arg(long, value_enum, default_value_t)]
+        format: ExportFormat,
+    },
+    /// Print the allowed signers file entries of a configured signer, retrieving it's keys only
+    /// from the given configured source instead of the sources of the signer.
+    Fetch {
+        /// The configured signer to fetch the keys of.
+        #[arg(long, value_name = "NAME")]
+        signer: String,
+        /// The configured source to exclusively fetch keys from.
+        #[arg(long, value_name = "NAME")]
+        source: String,
+    },
+    /// Apply a plan previously written using `hanko update --plan-out`.
+    Apply {
+        /// The plan to apply.
+        #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+        plan: PathBuf,
+    },
+    /// Verify the detached signature of the allowed signers file.
+    VerifyFile {
+        /// The public key of the key the allowed signers file was signed with.
+        #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+        public_key: PathBuf,
+        /// The detached signature, defaults to the allowed signers file with a `.sig` extension
+        /// appended.
+        #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+        signature: Option<PathBuf>,
+    },
+    /// Remove signers that no longer exist on any of their sources from the configuration,
+    /// only printing them unless `--yes` is given.
+    PruneConfig {
+        /// Remove the signers instead of only printing them.
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Install git hooks into the repository in the current directory, updating the allowed
+    /// signers file after checking out or merging commits.
+    InstallHook {
+        /// Overwrite existing hooks that were not installed by hanko.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Remove the git hooks installed by hanko from the repository in the current directory.
+    UninstallHook,
+    /// Check the git configuration in the current directory for the settings required to sign
+    /// and verify commits and tags using the allowed signers file, suggesting fixes for
+    /// misconfigured settings. Requires the `detect-allowed-signers` feature.
+    Doctor,
+    /// List the environment variables used by hanko.
+    Env,
+    /// Print the configured names of the given kind for dynamic shell completion.
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        /// The kind of names to complete.
+        #[arg(value_enum)]
+        kind: CompletionKind,
+    },
+}
+
+/// The formats a report can be printed in.
+#[derive(Debug, Default, Clone, Copy, clap::ValueEnum)]
+enum ReportFormat {
+    /// A human readable tree of signers, sources and keys.
+    #[default]
+    Tree,
+    /// A JSON array of signers.
+    Json,
+}
+
+/// The formats entries can be exported in.
+#[derive(Debug, Default, Clone, Copy, clap::ValueEnum)]
+enum ExportFormat {
+    /// A versioned JSON document containing an object per entry.
+    #[default]
+    Json,
+}
+
+/// The kinds of configured names that can be completed dynamically.
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum CompletionKind {
+    Signers,
+    Sources,
 }
 
 #[derive(Debug, clap::Args)]
@@ -45,7 +202,22 @@ struct GlobalArgs {
         global = true,
         default_value = default_config_path()
     )]
-    pub config: PathBuf,
+    pub config: Option<PathBuf>,
+
+    /// A directory of additional configuration files merged in lexical order.
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_hint = ValueHint::DirPath,
+        env = "HANKO_CONFIG_DIR",
+        global = true
+    )]
+    pub config_dir: Option<PathBuf>,
+
+    /// Skip files within the configuration directory that fail to parse with a warning, instead of
+    /// failing. The configuration file itself is never skipped.
+    #[arg(long, global = true)]
+    pub skip_invalid_configs: bool,
 
     /// The allowed signers file.
     #[arg(
@@ -56,11 +228,280 @@ struct GlobalArgs {
         global = true,
         default_value = git_allowed_signers()
     )]
-    pub file: PathBuf,
+    pub file: Option<PathBuf>,
 
     /// Use verbose output.
     #[arg(short, long, global = true, action = clap::ArgAction::Count)]
     pub verbose: u8,
+
+    /// Dump requests made to sources and their responses to stderr, with secrets redacted.
+    #[arg(long, global = true)]
+    pub dump_requests: bool,
+
+    /// Don't authenticate to GitHub and GitLab using the tokens of the conventional `GITHUB_TOKEN`
+    /// and `GITLAB_TOKEN` environment variables, using only explicitly configured tokens.
+    #[arg(long, global = true)]
+    pub no_env_token: bool,
+
+    /// The maximum number of redirects followed per request to a source.
+    #[arg(long, value_name = "N", default_value_t = source::DEFAULT_MAX_REDIRECTS, global = true)]
+    pub max_redirects: usize,
+
+    /// How long connecting to a source may take, overriding the built-in default of 2s for this
+    /// invocation.
+    #[arg(long, value_name = "DURATION", global = true)]
+    pub connect_timeout: Option<Duration>,
+
+    /// How long a request to a source may take in total, overriding the built-in default of 10s
+    /// for this invocation.
+    #[arg(long, value_name = "DURATION", global = true)]
+    pub request_timeout: Option<Duration>,
+
+    /// How long to wait for another run writing the allowed signers file, failing immediately if
+    /// not given.
+    #[arg(
+        long,
+        value_name = "DURATION",
+        env = "HANKO_LOCK_TIMEOUT",
+        global = true
+    )]
+    pub lock_timeout: Option<Duration>,
+
+    /// Evaluate validity periods and key ages at the given RFC 3339 timestamp instead of the
+    /// current time, e.g. to debug entries expiring in the future.
+    #[arg(
+        long,
+        value_name = "TIMESTAMP",
+        env = "HANKO_NOW",
+        global = true,
+        hide = true
+    )]
+    pub now: Option<DateTime<Utc>>,
+
+    /// Additionally write traces to the given file, at least at the info level, e.g. for
+    /// long-running deployments whose output is not captured.
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath,
+        env = "HANKO_TRACE_FILE",
+        global = true
+    )]
+    pub trace_file: Option<PathBuf>,
+
+    /// The format of traces written to the trace file.
+    #[arg(long, value_enum, global = true, default_value_t)]
+    pub trace_file_format: TraceFormat,
+
+    /// The format errors are printed to stderr in.
+    #[arg(long, value_enum, global = true, default_value_t)]
+    pub error_format: ErrorFormat,
+
+    /// Suppress warnings with the given diagnostic code, e.g. HANKO-W003. Can be given multiple
+    /// times.
+    #[arg(long, value_name = "CODE", global = true)]
+    pub suppress_warning: Vec<Warning>,
+}
+
+/// The format errors are printed in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ErrorFormat {
+    /// A human readable description of the error and it's causes.
+    #[default]
+    Human,
+    /// A JSON object containing the kind of error, it's message and causes, as well as the
+    /// affected signer if any.
+    Json,
+}
+
+/// The format traces are written in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TraceFormat {
+    /// Every trace on a single line, including the spans it was recorded in.
+    #[default]
+    Full,
+    /// Every trace on a single line, omitting the names of spans.
+    Compact,
+}
+
+#[derive(Debug, Default, Clone, clap::Args)]
+#[allow(clippy::struct_excessive_bools)]
+struct UpdateArgs {
+    /// Don't check whether the rate limit budget of authenticated sources suffices before updating.
+    #[arg(long)]
+    skip_rate_limit_check: bool,
+
+    /// Run hooks even if the allowed signers file did not change.
+    #[arg(long)]
+    always_run_hooks: bool,
+
+    /// The format the allowed signers file is written in.
+    #[arg(long, value_enum, default_value_t)]
+    output_format: OutputFormat,
+
+    /// Only update the signer(s) with the given name, dropping all other signers from the file.
+    #[arg(long, value_name = "NAME", value_hint = ValueHint::Username)]
+    only: Vec<String>,
+
+    /// Only write entries with principals matching the given glob pattern, e.g. `*@acme.corp`,
+    /// where `*` matches any number of characters and `?` matches exactly one. Entries of multiple
+    /// principals are written if any of them matches.
+    #[arg(long, value_name = "PATTERN")]
+    principal_filter: Option<String>,
+
+    /// Only write entries of multiple principals if all of them match the principal filter.
+    #[arg(long, requires = "principal_filter")]
+    principal_filter_all: bool,
+
+    /// Remove principals matching the given glob pattern from all entries, in addition to the
+    /// configured `exclude_principals`, dropping entries none of whose principals remain.
+    #[arg(long, value_name = "PATTERN")]
+    exclude_principal: Vec<String>,
+
+    /// Warn about keys written for multiple distinct principals, like a shared bot key or a
+    /// misattributed key, which undermine attributing signatures to a single signer.
+    #[arg(long)]
+    report_shared_keys: bool,
+
+    /// Only write entries valid right now, dropping entries not yet valid or already expired
+    /// according to their validity period.
+    #[arg(long)]
+    only_valid: bool,
+
+    /// Keep expired entries when only writing valid entries.
+    #[arg(long, requires = "only_valid")]
+    include_expired: bool,
+
+    /// Re-run the whole update up to this many times if retrieving the keys of any signer failed,
+    /// e.g. during a provider outage affecting many signers at once.
+    #[arg(long, value_name = "N", default_value_t)]
+    retries: u32,
+
+    /// The duration to wait before re-running a failed update, defaults to 10s.
+    #[arg(long, value_name = "DURATION", requires = "retries")]
+    retry_delay: Option<Duration>,
+
+    /// Record the fingerprints of the keys resolved for each signer in `hanko.lock` next to the
+    /// configuration file, locking them for updates using `--locked`.
+    #[arg(long, conflicts_with = "locked")]
+    write_lock: bool,
+
+    /// Refuse to update if the keys resolved for any signer differ from those recorded in
+    /// `hanko.lock` next to the configuration file, which is regenerated using `--write-lock`.
+    #[arg(long)]
+    locked: bool,
+
+    /// Write a plan of the changes to the given JSON file instead of updating the allowed signers
+    /// file, which can be applied later using `hanko apply`.
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    plan_out: Option<PathBuf>,
+
+    /// Refuse to overwrite an existing allowed signers file that was not written by hanko.
+    #[arg(long)]
+    first_run_safe: bool,
+
+    /// Overwrite the allowed signers file even if it was not written by hanko.
+    #[arg(long, requires = "first_run_safe")]
+    force: bool,
+
+    /// How keys that are not well-formed SSH public keys are handled, overriding the
+    /// `validate_keys` output option.
+    #[arg(long, value_enum, value_name = "MODE")]
+    validate_keys: Option<KeyValidation>,
+
+    /// Skip signers whose sources could not be connected to with a warning, keeping the entries
+    /// of all other signers, instead of failing the update. Connection errors remain fatal if keys
+    /// are validated strictly.
+    #[arg(long)]
+    tolerate_connection_errors: bool,
+
+    /// Print an estimate of the requests the update would make, without making any requests or
+    /// writing the allowed signers file.
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Abort without writing the allowed signers file when interrupted, instead of writing the
+    /// entries of signers retrieved so far.
+    #[arg(long)]
+    no_partial: bool,
+
+    /// Pipe the allowed signers file to the stdin of the given command in addition to writing it,
+    /// e.g. `pbcopy`. The command is run using the system shell.
+    #[arg(long, value_name = "COMMAND", conflicts_with = "plan_out")]
+    pipe_to: Option<String>,
+
+    /// Only print a single line summarizing the update, e.g. for shell prompts or status bars,
+    /// instead of the regular output.
+    #[arg(long, conflicts_with_all = ["plan_out", "dry_run", "each_config"])]
+    status_line: bool,
+
+    /// Update each of the given configurations independently instead of the configuration file,
+    /// writing the allowed signers file configured by their `allowed_signers_file` option and
+    /// printing a summary of all updates. Directories update every configuration file they
+    /// contain.
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_hint = ValueHint::AnyPath,
+        conflicts_with_all = ["plan_out", "pipe_to", "dry_run"]
+    )]
+    each_config: Vec<PathBuf>,
+
+    /// The number of configurations given using `--each-config` updated concurrently.
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 4,
+        value_parser = clap::value_parser!(u16).range(1..),
+        requires = "each_config"
+    )]
+    jobs: u16,
+
+    /// Print the rate limits of sources and the connections made to them after updating, set by
+    /// the global `--verbose` option.
+    #[arg(skip)]
+    verbose: bool,
+}
+
+impl Commands {
+    /// Whether the command reads or writes the configuration file.
+    fn requires_config(&self) -> bool {
+        if let Commands::Update(args) = self {
+            return args.each_config.is_empty();
+        }
+        !matches!(
+            self,
+            Commands::Source(ManageSources::Providers)
+                | Commands::VerifyFile { .. }
+                | Commands::InstallHook { .. }
+                | Commands::UninstallHook
+                | Commands::Doctor
+                | Commands::Env
+                | Commands::Complete { .. }
+        )
+    }
+
+    /// Whether the command reads or writes the allowed signers file.
+    fn requires_file(&self) -> bool {
+        match self {
+            Commands::Update(args) => args.each_config.is_empty(),
+            Commands::VerifyFile { .. } => true,
+            Commands::Signer(ManageSigners::Add { no_update, .. }) => !no_update,
+            Commands::Signer(ManageSigners::Enable { .. } | ManageSigners::Disable { .. })
+            | Commands::Source(_)
+            | Commands::Config(_)
+            | Commands::Apply { .. }
+            | Commands::Report { .. }
+            | Commands::Export { .. }
+            | Commands::Fetch { .. }
+            | Commands::PruneConfig { .. }
+            | Commands::InstallHook { .. }
+            | Commands::UninstallHook
+            | Commands::Doctor
+            | Commands::Env
+            | Commands::Complete { .. } => false,
+        }
+    }
 }
 
 #[derive(Debug, Subcommand)]
@@ -74,26 +515,68 @@ enum ManageSigners {
         #[arg(required = true, value_hint = ValueHint::EmailAddress)]
         principals: Vec<String>,
         /// The source(s) of the signer to add.
-        #[arg(short, long, default_values_t = default_user_source())]
+        #[cfg_attr(
+            feature = "public-providers",
+            arg(short, long, default_values_t = crate::config::default_user_source())
+        )]
+        #[cfg_attr(not(feature = "public-providers"), arg(short, long, required = true))]
         source: Vec<String>,
         /// Don't update the allowed signers file with the added signer(s).
         #[arg(long)]
         no_update: bool,
+        /// Don't verify that the signer exists and has signing keys on it's source(s).
+        #[arg(long)]
+        no_verify: bool,
+        /// Don't add the signer if it does not exist or has no signing keys on it's source(s).
+        #[arg(long, conflicts_with = "no_verify")]
+        strict: bool,
+    },
+    /// Enable a disabled signer, using it again when updating.
+    Enable {
+        /// The name of the signer to enable.
+        #[arg(value_hint = ValueHint::Username)]
+        name: String,
+    },
+    /// Disable a signer, skipping it when updating while retaining it's configuration.
+    Disable {
+        /// The name of the signer to disable.
+        #[arg(value_hint = ValueHint::Username)]
+        name: String,
     },
 }
 
+#[derive(Debug, Subcommand)]
+enum ManageSources {
+    /// Add a source.
+    Add {
+        /// The name of the source to add.
+        name: String,
+        /// The URL of the source's API endpoint, defaults to the canonical public instance of
+        /// the provider.
+        #[arg(long, value_hint = ValueHint::Url, required_unless_present = "provider")]
+        url: Option<Url>,
+        /// The provider of the source, detected by the host of the URL if not specified.
+        #[arg(long)]
+        provider: Option<SourceType>,
+    },
+    /// List the supported source providers.
+    Providers,
+}
+
+#[derive(Debug, Subcommand)]
+enum ManageConfig {
+    /// Check that saving the configuration file, as done when editing it using the `signer` and
+    /// `source` commands, preserves it's formatting, printing the lines that would change.
+    Test,
+}
+
 /// The default configuration file path according to the XDG Base Directory Specification.
 /// If neither `$XDG_CONFIG_HOME` nor `$HOME` are set, [`Resettable::Reset`] is returned, forcing the user to specify the path.
+/// Commands requiring the configuration explain why using [`undetermined_config_error`].
 fn default_config_path() -> Resettable<OsStr> {
-    let dirname = env!("CARGO_PKG_NAME");
-    let filename = "config.toml";
-
-    if let Ok(xdg_config_home) = env::var("XDG_CONFIG_HOME") {
-        Resettable::Value(format!("{xdg_config_home}/{dirname}/{filename}").into())
-    } else if let Ok(home) = env::var("HOME") {
-        Resettable::Value(format!("{home}/.config/{dirname}/{filename}").into())
-    } else {
-        Resettable::Reset
+    match paths::config_file() {
+        Some(path) => Resettable::Value(OsStr::from(path.to_string_lossy().to_string())),
+        None => Resettable::Reset,
     }
 }
 
@@ -105,10 +588,7 @@ fn git_allowed_signers() -> Resettable<OsStr> {
     if let Ok(file) = gix_config::File::from_globals() {
         if let Some(path) = file.path("gpg.ssh.allowedsignersfile") {
             if let Ok(interpolated) = path.interpolate(gix_config::path::interpolate::Context {
-                home_dir: env::var("HOME")
-                    .ok()
-                    .map(std::convert::Into::<PathBuf>::into)
-                    .as_deref(),
+                home_dir: paths::home_dir().as_deref(),
                 ..Default::default()
             }) {
                 return Resettable::Value(OsStr::from(interpolated.to_string_lossy().to_string()));
@@ -119,6 +599,24 @@ fn git_allowed_signers() -> Resettable<OsStr> {
     Resettable::Reset
 }
 
+/// Expand a leading `~` or `$HOME` of the given path to the given home directory.
+/// Paths given on the command line are usually expanded by the shell, while paths taken from
+/// environment variables are not.
+fn expand_home(path: &Path, home: Option<&Path>) -> PathBuf {
+    let Some(home) = home else {
+        return path.to_path_buf();
+    };
+    let mut components = path.components();
+    match components.next() {
+        Some(Component::Normal(first))
+            if first == "~" || first == "$HOME" || first == "${HOME}" =>
+        {
+            home.join(components.as_path())
+        }
+        _ => path.to_path_buf(),
+    }
+}
+
 fn long_version() -> &'static str {
     concat!(
         concat!(env!("CARGO_PKG_VERSION"), " (", env!("VERGEN_GIT_SHA"), ")"),
@@ -137,20 +635,76 @@ fn long_version() -> &'static str {
 }
 
 /// The main CLI entrypoint.
+/// Errors are returned for human readable rendering, unless JSON errors are requested, which are
+/// printed to stderr before exiting.
+///
+/// # Errors
+///
+/// When the invoked command fails.
 pub fn entrypoint() -> Result<()> {
-    let cli = Cli::parse();
-    let args = cli.global_args;
-    let signers_file = &args.file;
+    let mut cli = Cli::parse();
+    if let Commands::Update(args) = &mut cli.command {
+        args.verbose = cli.global_args.verbose > 0;
+    }
+    let error_format = cli.global_args.error_format;
+    match run(cli) {
+        Err(err) if error_format == ErrorFormat::Json => {
+            eprintln!("{}", json_error(&err));
+            process::exit(exit_code(&err))
+        }
+        Err(err) if exit_code(&err) != 1 => {
+            eprintln!("Error: {err:?}");
+            process::exit(exit_code(&err))
+        }
+        result => result,
+    }
+}
+
+/// The exit code of the given error, distinguishing interrupted updates from other failures.
+fn exit_code(err: &anyhow::Error) -> i32 {
+    if err
+        .chain()
+        .any(|cause| cause.downcast_ref::<Interrupted>().is_some())
+    {
+        INTERRUPTED_EXIT_CODE
+    } else {
+        1
+    }
+}
+
+/// Run the command given on the command line.
+fn run(cli: Cli) -> Result<()> {
+    let mut args = cli.global_args;
+    let home = paths::home_dir();
+    let (config_path, signers_file) = resolve_paths(&mut args, &cli.command, home.as_deref());
+    let load_options = LoadOptions {
+        drop_ins: args.config_dir.as_deref().map(|dir| DropIns {
+            dir,
+            skip_invalid: args.skip_invalid_configs,
+        }),
+        conventional_tokens: !args.no_env_token,
+    };
 
-    setup_tracing(args.verbose);
+    // Only the status line is printed, which diagnostics like warnings would interleave with.
+    if matches!(&cli.command, Commands::Update(a) if a.status_line) {
+        diagnostic::silence();
+    }
+    apply_global_args(&args)?;
 
     let mut config;
+    let mut update_args = UpdateArgs::default();
     match cli.command {
-        Commands::Update => {
-            config = Configuration::load(&args.config).context(format!(
-                "Failed to load configuration from {}",
-                &args.config.display()
-            ))?;
+        Commands::Update(a) if !a.each_config.is_empty() => {
+            return update_each_config(
+                &a,
+                home.as_deref(),
+                args.lock_timeout,
+                load_options.conventional_tokens,
+            );
+        }
+        Commands::Update(a) => {
+            update_args = a;
+            config = load_config(&config_path, load_options)?;
         }
         Commands::Signer(action) => match action {
             ManageSigners::Add {
@@ -158,86 +712,1617 @@ pub fn entrypoint() -> Result<()> {
                 principals,
                 source,
                 no_update,
+                no_verify,
+                strict,
             } => {
-                config = Configuration::load_or_default(&args.config).context(format!(
-                    "Failed to load configuration from {}",
-                    &args.config.display()
-                ))?;
-                config
-                    .add_signer(name, principals, source)
-                    .context("Failed to add allowed signer")?;
-                config.save().context(format!(
-                    "Failed to save configuration to {}",
-                    &args.config.display()
-                ))?;
-                println!("Updated configuration file {}", &args.config.display());
+                config = load_config_or_default(&config_path, load_options)?;
+                add_signer(&mut config, &name, principals, &source, !no_verify, strict)?;
                 if no_update {
                     return Ok(());
                 }
             }
+            ManageSigners::Enable { name } => {
+                return set_signer_enabled(&config_path, load_options, &name, true);
+            }
+            ManageSigners::Disable { name } => {
+                return set_signer_enabled(&config_path, load_options, &name, false);
+            }
         },
+        Commands::Source(action) => return manage_sources(&config_path, load_options, action),
+        Commands::Config(ManageConfig::Test) => return test_config(&config_path, load_options),
+        Commands::Report { format } => {
+            let config = load_config(&config_path, load_options)?;
+            return print_report(&config, format);
+        }
+        Commands::Export { format } => {
+            let config = load_config(&config_path, load_options)?;
+            return export_entries(&config, format);
+        }
+        Commands::Fetch { signer, source } => {
+            let config = load_config(&config_path, load_options)?;
+            return fetch_signer(&config, &signer, &source);
+        }
+        Commands::Apply { plan } => {
+            let config = load_config(&config_path, load_options)?;
+            return apply_plan(
+                &expand_home(&plan, home.as_deref()),
+                &config,
+                args.lock_timeout,
+            );
+        }
+        Commands::VerifyFile {
+            public_key,
+            signature,
+        } => {
+            let public_key = expand_home(&public_key, home.as_deref());
+            let signature = signature.map(|s| expand_home(&s, home.as_deref()));
+            return verify_file(&signers_file, signature, &public_key);
+        }
+        Commands::PruneConfig { yes } => {
+            let config = load_config(&config_path, load_options)?;
+            return prune_config(config, yes);
+        }
+        Commands::InstallHook { force } => return install_hooks(force),
+        Commands::UninstallHook => return uninstall_hooks(),
+        Commands::Doctor => return diagnose_git_config(args.file.as_deref(), home.as_deref()),
+        Commands::Env => {
+            print_environment_variables();
+            return Ok(());
+        }
+        Commands::Complete { kind } => {
+            // Completion is best effort, a missing or invalid configuration completes the
+            // defaults instead of failing.
+            let config = Configuration::load(&config_path, load_options).unwrap_or_default();
+            print_completions(&config, kind);
+            return Ok(());
+        }
+    }
+
+    let _lock = FileLock::acquire(&signers_file, args.lock_timeout.map(Into::into))?;
+    update(&signers_file, &config, &update_args)
+}
+
+/// Set up tracing and apply the global options affecting how sources are queried.
+fn apply_global_args(args: &GlobalArgs) -> Result<()> {
+    setup_tracing(
+        args.verbose,
+        args.trace_file.as_deref(),
+        args.trace_file_format,
+    )?;
+    if args.dump_requests {
+        source::enable_request_dumps();
+    }
+    source::set_max_redirects(args.max_redirects);
+    source::set_timeouts(
+        args.connect_timeout.map(Into::into),
+        args.request_timeout.map(Into::into),
+    );
+    diagnostic::suppress(args.suppress_warning.clone());
+    if let Some(now) = args.now {
+        clock::set(Clock::Fixed(now));
+    }
+    Ok(())
+}
+
+/// Expand the paths given on the command line, returning the configuration file and allowed
+/// signers file. Exits if a file required by the given command was not given.
+fn resolve_paths(
+    args: &mut GlobalArgs,
+    command: &Commands,
+    home: Option<&Path>,
+) -> (PathBuf, PathBuf) {
+    let config_path = match (&args.config, command.requires_config()) {
+        (Some(config), _) => expand_home(config, home),
+        (None, true) => undetermined_config_error().exit(),
+        (None, false) => PathBuf::default(),
+    };
+    args.config_dir = args.config_dir.as_deref().map(|d| expand_home(d, home));
+    args.file = args.file.as_deref().map(|f| expand_home(f, home));
+    let signers_file = match (&args.file, command.requires_file()) {
+        (Some(file), _) => file.clone(),
+        (None, true) => missing_argument_error("file").exit(),
+        (None, false) => PathBuf::default(),
+    };
+    (config_path, signers_file)
+}
+
+/// Add a signer to the configuration, verifying it exists on it's sources if requested and
+/// refusing to add it if it could not be verified in strict mode.
+fn add_signer(
+    config: &mut Configuration,
+    name: &str,
+    principals: Vec<String>,
+    source: &[String],
+    verify: bool,
+    strict: bool,
+) -> Result<()> {
+    config
+        .add_signer(name.to_string(), principals, source.to_vec())
+        .context("Failed to add allowed signer")?;
+    if verify {
+        let verified = verify_signer(config, name, source);
+        if strict && !verified {
+            bail!("Signer {name} could not be verified, not adding it")
+        }
+    }
+    save_config(config)
+}
+
+/// Enable or disable a signer within the configuration.
+fn set_signer_enabled(
+    config_path: &Path,
+    load_options: LoadOptions,
+    name: &str,
+    enabled: bool,
+) -> Result<()> {
+    let mut config = load_config(config_path, load_options)?;
+    if !config.signer_names().contains(&name) {
+        bail!("Signer {name} is not configured");
+    }
+    config.set_signer_enabled(name, enabled)?;
+    save_config(&config)?;
+    let state = if enabled { "Enabled" } else { "Disabled" };
+    println!("{state} signer {name}, run `hanko update` to update the allowed signers file");
+    Ok(())
+}
+
+/// Check that saving the configuration preserves it's formatting by comparing the content it would
+/// be saved as to the original, without writing it, failing if any lines differ.
+fn test_config(config_path: &Path, load_options: LoadOptions) -> Result<()> {
+    let config = load_config(config_path, load_options)?;
+    let original = fs::read_to_string(config_path).context(format!(
+        "Failed to read configuration file {}",
+        config_path.display()
+    ))?;
+    let saved = config.saved_content();
+
+    if saved == original {
+        println!(
+            "No formatting changes, saving configuration file {} preserves it's formatting",
+            config_path.display()
+        );
+        return Ok(());
     }
+    println!("--- {}\n+++ saved", config_path.display());
+    for line in line_diff(&original, &saved) {
+        println!("{line}");
+    }
+    bail!(
+        "Saving configuration file {} changes it's formatting",
+        config_path.display()
+    )
+}
+
+/// The lines differing between the given contents, prefixed by `-` if only present in the former
+/// and `+` if only present in the latter, in the order they appear.
+fn line_diff(before: &str, after: &str) -> Vec<String> {
+    let before: Vec<&str> = before.split('\n').collect();
+    let after: Vec<&str> = after.split('\n').collect();
+    // The lengths of the longest common subsequences of all suffixes of both contents.
+    let mut common = vec![vec![0_usize; after.len() + 1]; before.len() + 1];
+    for i in (0..before.len()).rev() {
+        for j in (0..after.len()).rev() {
+            common[i][j] = if before[i] == after[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::new();
+    while i < before.len() || j < after.len() {
+        if i < before.len() && j < after.len() && before[i] == after[j] {
+            i += 1;
+            j += 1;
+        } else if j == after.len() || (i < before.len() && common[i + 1][j] >= common[i][j + 1]) {
+            diff.push(format!("-{}", before[i]));
+            i += 1;
+        } else {
+            diff.push(format!("+{}", after[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+/// Run the given source management action.
+fn manage_sources(
+    config_path: &Path,
+    load_options: LoadOptions,
+    action: ManageSources,
+) -> Result<()> {
+    match action {
+        ManageSources::Add {
+            name,
+            url,
+            provider,
+        } => add_source(config_path, load_options, name, url, provider),
+        ManageSources::Providers => {
+            print_providers();
+            Ok(())
+        }
+    }
+}
 
-    update_allowed_singers(signers_file, &config)
+/// Add a source to the configuration, using the default URL of the given provider if no URL is
+/// given and detecting the provider by the host of the URL if no provider is given.
+fn add_source(
+    config_path: &Path,
+    load_options: LoadOptions,
+    name: String,
+    url: Option<Url>,
+    provider: Option<SourceType>,
+) -> Result<()> {
+    let url = match (url, provider) {
+        (Some(url), _) => url,
+        (None, Some(provider)) => provider.default_url().context(format!(
+            "Provider {} does not have a default URL, please specify it using --url",
+            provider.name()
+        ))?,
+        (None, None) => missing_argument_error("url").exit(),
+    };
+    let provider = provider
+        .or_else(|| SourceType::detect(&url))
+        .context(format!(
+            "Unable to detect the provider of {url}, please specify it using --provider"
+        ))?;
+    let url = provider.api_url(url);
+    let mut config = load_config_or_default(config_path, load_options)?;
+    config
+        .add_source(name, provider, url)
+        .context("Failed to add source")?;
+    save_config(&config)
+}
+
+/// Load the configuration from the given file.
+fn load_config(path: &Path, load_options: LoadOptions) -> Result<Configuration> {
+    Configuration::load(path, load_options).context(format!(
+        "Failed to load configuration from {}",
+        path.display()
+    ))
+}
+
+/// Load the configuration from the given file, using the default configuration if it doesn't
+/// exist.
+fn load_config_or_default(path: &Path, load_options: LoadOptions) -> Result<Configuration> {
+    Configuration::load_or_default(path, load_options).context(format!(
+        "Failed to load configuration from {}",
+        path.display()
+    ))
+}
+
+/// Save the configuration back to it's file.
+fn save_config(config: &Configuration) -> Result<()> {
+    config.save().context(format!(
+        "Failed to save configuration to {}",
+        config.path().display()
+    ))?;
+    println!("Updated configuration file {}", config.path().display());
+    Ok(())
 }
 
+/// An error indicating that the configuration file was not specified and its default location
+/// could not be determined.
+fn undetermined_config_error() -> clap::Error {
+    Cli::command().error(
+        ErrorKind::MissingRequiredArgument,
+        "Unable to determine the default configuration file location since neither HOME nor \
+        XDG_CONFIG_HOME are set.\n\nSet one of them or specify the configuration file using \
+        --config or the HANKO_CONFIG environment variable.",
+    )
+}
+
+/// An error indicating that a required argument was not provided.
+fn missing_argument_error(name: &str) -> clap::Error {
+    Cli::command().error(
+        ErrorKind::MissingRequiredArgument,
+        format!("The following required argument was not provided: {name}"),
+    )
+}
+
+/// Update the allowed signers file, see [`update_with_retries`].
 #[tokio::main]
-async fn update_allowed_singers(file: &Path, config: &Configuration) -> Result<()> {
+async fn update(file: &Path, config: &Configuration, args: &UpdateArgs) -> Result<()> {
+    update_with_retries(file, config, args).await
+}
+
+/// Update the allowed signers file, re-running the whole update up to `--retries` times if
+/// retrieving the keys of any signer failed. Other failures, like an invalid configuration or
+/// failing to write the file, are not retried. All attempts run on the runtime of the caller,
+/// since the connections of the HTTP client shared by sources are driven by the runtime they were
+/// opened on.
+async fn update_with_retries(file: &Path, config: &Configuration, args: &UpdateArgs) -> Result<()> {
+    let delay = args.retry_delay.map_or(RUN_RETRY_DELAY, Into::into);
+    let mut retry = 0;
+    let mut failures_before;
+    let result = loop {
+        failures_before = diagnostic::tolerated_failures();
+        match update_allowed_singers(file, config, args).await {
+            Err(err) if retry < args.retries && is_signer_failure(&err) => {
+                retry += 1;
+                warning!(
+                    Warning::UpdateRetried,
+                    "Update failed, re-running it in {delay:?} ({retry}/{}): {err:#}",
+                    args.retries
+                );
+                tokio::time::sleep(delay).await;
+            }
+            result => break result,
+        }
+    };
+    if args.status_line && result.is_err() {
+        // The failure of the update itself adds to the failures tolerated before it.
+        let errors = diagnostic::tolerated_failures() - failures_before + 1;
+        println!("{}", status_line(None, errors, clock::now()));
+    }
+    result
+}
+
+/// A single line summarizing an update at the given point in time, given the number of signers
+/// and keys written if it succeeded and the number of errors that occurred.
+fn status_line(written: Option<(usize, usize)>, errors: usize, now: DateTime<Utc>) -> String {
+    let time = now.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+    let errors = match errors {
+        1 => "1 error".to_string(),
+        errors => format!("{errors} errors"),
+    };
+    match written {
+        Some((signers, keys)) => {
+            format!("hanko: {signers} signers, {keys} keys, {errors} ({time})")
+        }
+        None => format!("hanko: update failed, {errors} ({time})"),
+    }
+}
+
+/// Whether the given error was caused by failing to retrieve the keys of a signer.
+fn is_signer_failure(err: &anyhow::Error) -> bool {
+    err.chain()
+        .any(|cause| cause.downcast_ref::<SignerError>().is_some())
+}
+
+async fn update_allowed_singers(
+    file: &Path,
+    config: &Configuration,
+    args: &UpdateArgs,
+) -> Result<()> {
     let start = Instant::now();
+    let failures_before = diagnostic::tolerated_failures();
+
+    if args.first_run_safe && !args.force && allowed_signers::is_unmanaged(file) {
+        bail!(
+            "Refusing to overwrite allowed signers file {} since it was not written by hanko. Configure it's entries as signers and pass `--force` to overwrite it",
+            file.display()
+        )
+    }
 
     let sources = config.sources();
-    let signers = config.signers(&sources);
+    let mut signers = config.signers(&sources)?;
+    if !args.only.is_empty() {
+        signers = filter_signers(signers, &args.only)?;
+    }
+    if let Some(dir) = &config.cache().dir {
+        let cache: Arc<dyn KeyCache> = Arc::new(FileCache::new(dir.clone()));
+        for signer in &mut signers {
+            signer.cache = Some(cache.clone());
+        }
+    }
+    if let Some(validation) = args.validate_keys {
+        for signer in &mut signers {
+            signer.key_validation = validation;
+        }
+    }
+    if args.tolerate_connection_errors {
+        for signer in &mut signers {
+            signer.tolerate_connection_errors = true;
+        }
+    }
+
+    if args.dry_run {
+        print_request_estimate(&signers, &sources);
+        return Ok(());
+    }
+    if !args.skip_rate_limit_check {
+        allowed_signers::check_rate_limit_budget(&signers).await;
+    }
+
+    if let Some(plan_out) = &args.plan_out {
+        write_plan(file, plan_out, signers, config, args).await?;
+        if args.verbose {
+            print_rate_limits(&sources);
+        }
+        return Ok(());
+    }
+
+    let before = snapshot_before_update(file, config);
+    let locked_signers = signer_names(&signers);
 
-    allowed_signers::update(file, signers)
-        .await
+    let no_partial = args.no_partial;
+    let interrupted = async move {
+        if no_partial {
+            // Without listening for interrupts, interrupting terminates hanko immediately.
+            future::pending::<()>().await;
+        } else {
+            interrupted().await;
+        }
+    };
+    let (mut generated, partial) = allowed_signers::generate_until(
+        file,
+        signers,
+        config.output(),
+        args.output_format,
+        interrupted,
+    )
+    .await
+    .context("Failed to update the allowed signers file")?;
+    if partial {
+        warning!(
+            Warning::PartialUpdate,
+            "Update was interrupted, the allowed signers file only contains signers retrieved so far"
+        );
+    }
+    filter_entries(&mut generated, args);
+    check_lockfile(&generated, &locked_signers, partial, config, args)?;
+    let changed = generated
+        .write_if_changed()
+        .context(format!(
+            "Failed to write allowed signers file to {}",
+            file.display()
+        ))
         .context("Failed to update the allowed signers file")?;
 
-    let duration = start.elapsed();
+    // A partial file is only recorded in the audit log, it is neither signed nor announced or
+    // handed on to hooks since it lacks the signers left out.
+    if partial {
+        append_audit_record(file, config, before.as_deref(), changed)?;
+        bail!(Interrupted(file.to_path_buf()));
+    }
+    record_update(file, config, before.as_deref(), changed)?;
+    notify_webhook(file, config, before.as_deref(), changed).await;
+    if let Some(command) = &args.pipe_to {
+        pipe_to(command, &generated).context(format!(
+            "Failed to pipe allowed signers file to `{command}`"
+        ))?;
+    }
+
+    if !args.status_line {
+        print_update_result(file, changed, start.elapsed(), &sources, args.verbose);
+    }
+    run_post_update_hook(file, config, changed || args.always_run_hooks)?;
+
+    // The status line is only printed once the update succeeded as a whole, a failure is
+    // summarized by `update_with_retries` instead.
+    if args.status_line {
+        let written = (locked_signers.len(), generated.written_entries().len());
+        let errors = diagnostic::tolerated_failures() - failures_before;
+        println!("{}", status_line(Some(written), errors, clock::now()));
+    }
+    Ok(())
+}
+
+/// Print whether the allowed signers file changed, along with the rate limits of sources and the
+/// connections made to them if verbose.
+fn print_update_result(
+    file: &Path,
+    changed: bool,
+    duration: std::time::Duration,
+    sources: &HashMap<String, Arc<Box<dyn Source>>>,
+    verbose: bool,
+) {
+    if changed {
+        outputln!(
+            "Updated allowed signers file {} in {:?}",
+            file.display(),
+            duration
+        );
+    } else {
+        outputln!(
+            "Allowed signers file {} is up to date, checked in {:?}",
+            file.display(),
+            duration
+        );
+    }
+    if verbose {
+        print_rate_limits(sources);
+        print_connection_stats();
+    }
+}
+
+/// Update the allowed signers file of each configuration given using `--each-config`, running up
+/// to `--jobs` updates concurrently on a single runtime, and print a summary of all updates. The
+/// output of each update is printed at once after it finished. Fails if any update failed.
+#[tokio::main]
+async fn update_each_config(
+    args: &UpdateArgs,
+    home: Option<&Path>,
+    lock_timeout: Option<Duration>,
+    conventional_tokens: bool,
+) -> Result<()> {
+    let mut paths = Vec::new();
+    for path in &args.each_config {
+        let path = expand_home(path, home);
+        if path.is_dir() {
+            paths.extend(config_files_in(&path)?);
+        } else {
+            paths.push(path);
+        }
+    }
+
+    let jobs = Arc::new(Semaphore::new(usize::from(args.jobs)));
+    let mut set = JoinSet::new();
+    for (i, path) in paths.iter().enumerate() {
+        let permit = jobs.clone().acquire_owned().await?;
+        let (path, args, home) = (path.clone(), args.clone(), home.map(Path::to_path_buf));
+        set.spawn(OUTPUT.scope(RefCell::default(), async move {
+            let result = update_config(
+                &path,
+                &args,
+                home.as_deref(),
+                lock_timeout,
+                conventional_tokens,
+            )
+            .await;
+            print!("{}", OUTPUT.with(RefCell::take));
+            drop(permit);
+            (i, result)
+        }));
+    }
+    let mut results = set.join_all().await;
+    results.sort_unstable_by_key(|(i, _)| *i);
+
+    println!("Summary of {} update(s):", results.len());
+    let mut failed = 0;
+    for (i, result) in &results {
+        match result {
+            Ok(file) => println!("  ✓ {}: {}", paths[*i].display(), file.display()),
+            Err(err) => {
+                failed += 1;
+                println!("  ✗ {}: {err:#}", paths[*i].display());
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{failed} of {} update(s) failed", results.len())
+    }
+    Ok(())
+}
+
+/// The configuration files within the given directory in lexical order.
+fn config_files_in(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = fs::read_dir(dir)
+        .and_then(|entries| {
+            entries
+                .map(|e| e.map(|e| e.path()))
+                .collect::<io::Result<Vec<_>>>()
+        })
+        .context(format!(
+            "Failed to read configuration directory {}",
+            dir.display()
+        ))?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "toml"));
+    paths.sort();
+    Ok(paths)
+}
+
+/// Update the allowed signers file configured by the configuration at the given path, returning
+/// the path of the allowed signers file.
+async fn update_config(
+    path: &Path,
+    args: &UpdateArgs,
+    home: Option<&Path>,
+    lock_timeout: Option<Duration>,
+    conventional_tokens: bool,
+) -> Result<PathBuf> {
+    // Drop-in files only extend the main configuration, not each of the configurations.
+    let load_options = LoadOptions {
+        drop_ins: None,
+        conventional_tokens,
+    };
+    let config = load_config(path, load_options)?;
+    let Some(file) = config.allowed_signers_file() else {
+        bail!("Configuration does not configure an allowed_signers_file")
+    };
+    let file = expand_home(file, home);
+    // Waiting for the lock blocks, so it is waited for outside of the runtime.
+    let lock_file = file.clone();
+    let _lock =
+        task::spawn_blocking(move || FileLock::acquire(&lock_file, lock_timeout.map(Into::into)))
+            .await??;
+    update_with_retries(&file, &config, args).await?;
+    Ok(file)
+}
+
+/// Wait for the user to interrupt hanko, e.g. using Ctrl-C. Interrupting hanko a second time
+/// terminates it immediately.
+async fn interrupted() {
+    if tokio::signal::ctrl_c().await.is_err() {
+        // Without being able to listen for interrupts, hanko is never interrupted gracefully.
+        return future::pending().await;
+    }
+    if !diagnostic::is_silenced() {
+        eprintln!("Interrupted, finishing requests in flight. Interrupt again to abort");
+    }
+    tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            process::exit(INTERRUPTED_EXIT_CODE);
+        }
+    });
+}
+
+/// Print the estimated number of requests the given signers make in total and to each source.
+fn print_request_estimate(signers: &[Signer], sources: &HashMap<String, Arc<Box<dyn Source>>>) {
+    let estimate = allowed_signers::estimate_requests(signers);
+    let total: usize = estimate.iter().map(|(_, n)| n).sum();
+    println!(
+        "Would make ~{total} request(s) across {} source(s)",
+        estimate.len()
+    );
+    let mut per_source: Vec<(&String, usize)> = estimate
+        .into_iter()
+        .filter_map(|(source, n)| {
+            sources
+                .iter()
+                .find(|(_, s)| Arc::ptr_eq(s, source))
+                .map(|(name, _)| (name, n))
+        })
+        .collect();
+    per_source.sort();
+    for (name, n) in per_source {
+        println!("  {name}: ~{n} request(s)");
+    }
+}
+
+/// Write a plan of updating the given allowed signers file to the given path, without modifying
+/// the allowed signers file.
+async fn write_plan(
+    file: &Path,
+    plan_out: &Path,
+    signers: Vec<Signer>,
+    config: &Configuration,
+    args: &UpdateArgs,
+) -> Result<()> {
+    let locked_signers = signer_names(&signers);
+    let mut generated =
+        allowed_signers::generate(file, signers, config.output(), args.output_format)
+            .await
+            .context("Failed to generate the allowed signers file")?;
+    filter_entries(&mut generated, args);
+    check_lockfile(&generated, &locked_signers, false, config, args)?;
+    let plan = Plan::new(&generated).context("Failed to plan the update")?;
+    plan.save(plan_out)
+        .context(format!("Failed to write plan to {}", plan_out.display()))?;
+    println!(
+        "Wrote plan for allowed signers file {} to {}: {}",
+        file.display(),
+        plan_out.display(),
+        plan.summary()
+    );
+    Ok(())
+}
+
+/// Filter the entries of the generated allowed signers file according to the given arguments,
+/// reporting keys shared by the remaining entries if requested.
+fn filter_entries(generated: &mut allowed_signers::File, args: &UpdateArgs) {
+    if let Some(pattern) = &args.principal_filter {
+        generated.retain_principals(pattern, args.principal_filter_all);
+    }
+    generated.exclude_principals(&args.exclude_principal);
+    if args.only_valid {
+        generated.retain_valid(clock::now(), args.include_expired);
+    }
+    if args.report_shared_keys {
+        report_shared_keys(generated);
+    }
+}
+
+/// Warn about each key written for multiple distinct principals.
+fn report_shared_keys(file: &allowed_signers::File) {
+    for (fingerprint, principals) in file.shared_keys() {
+        let principals: Vec<String> = principals.iter().map(|p| p.join(",")).collect();
+        warning!(
+            Warning::SharedKey,
+            "Key {fingerprint} is shared by principals {}",
+            principals.join(" and ")
+        );
+    }
+}
+
+/// The distinct names of the given signers, which the keys of the lockfile are recorded by. Names
+/// are only listed once for each configured signer, even if it's principals are routed to
+/// different sources.
+fn signer_names(signers: &[Signer]) -> Vec<String> {
+    let mut names: Vec<String> = signers.iter().map(|signer| signer.name.clone()).collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+/// Compare the keys resolved for each signer against the lockfile of the configuration if
+/// updating using `--locked`, or record them in it using `--write-lock`. The lockfile is not
+/// written for partial updates, since it would lack the keys of signers left out.
+fn check_lockfile(
+    generated: &allowed_signers::File,
+    signers: &[String],
+    partial: bool,
+    config: &Configuration,
+    args: &UpdateArgs,
+) -> Result<()> {
+    let path = Lockfile::path(config.path());
+    let resolved = Lockfile::new(generated, signers);
+    if args.write_lock {
+        if partial {
+            warning!(
+                Warning::LockfileNotWritten,
+                "Not writing lockfile {} since the update was interrupted",
+                path.display()
+            );
+            return Ok(());
+        }
+        return resolved
+            .save(&path)
+            .context(format!("Failed to write lockfile {}", path.display()));
+    }
+    if args.locked {
+        let locked =
+            Lockfile::load(&path).context(format!("Failed to read lockfile {}", path.display()))?;
+        let differences = locked.differences(&resolved);
+        if !differences.is_empty() {
+            bail!(
+                "Resolved keys differ from lockfile {}, review them and regenerate it using `--write-lock`:\n{}",
+                path.display(),
+                differences.join("\n")
+            )
+        }
+    }
+    Ok(())
+}
+
+/// Print the given line of output, buffering it within [`OUTPUT`] while updating one of the
+/// configurations given using `--each-config`.
+fn print_output(line: Arguments) {
+    let buffered = OUTPUT.try_with(|output| {
+        let mut output = output.borrow_mut();
+        output.push_str(&line.to_string());
+        output.push('\n');
+    });
+    if buffered.is_err() {
+        println!("{line}");
+    }
+}
+
+/// Print the rate limit status last reported by each source, for sources reporting it.
+fn print_rate_limits(sources: &HashMap<String, Arc<Box<dyn Source>>>) {
+    // Rate limits reset at the real time reported by the source, so the time until the reset is
+    // based on the system clock rather than a simulated clock.
+    let now = chrono::Utc::now();
+    let mut names: Vec<_> = sources.keys().collect();
+    names.sort();
+    for name in names {
+        if let Some(rate_limit) = sources[name].observed_rate_limit() {
+            outputln!("Rate limit of source {name}: {}", rate_limit.summary(now));
+        }
+    }
+}
+
+/// Print the number of requests made to sources and the connections they were made over, if any.
+fn print_connection_stats() {
+    let stats = source::connection_stats();
+    if stats.requests > 0 {
+        outputln!("Made {}", stats.summary());
+    }
+}
+
+/// Apply a previously computed plan to the allowed signers file it was made for.
+#[tokio::main]
+async fn apply_plan(
+    path: &Path,
+    config: &Configuration,
+    lock_timeout: Option<Duration>,
+) -> Result<()> {
+    let plan = Plan::load(path).context(format!("Failed to load plan from {}", path.display()))?;
+    let file = plan.file();
+    let _lock = FileLock::acquire(file, lock_timeout.map(Into::into))?;
+
+    let before = snapshot_before_update(file, config);
+    let changed = plan.apply().context("Failed to apply plan")?;
+    record_update(file, config, before.as_deref(), changed)?;
+    notify_webhook(file, config, before.as_deref(), changed).await;
+
+    if changed {
+        println!(
+            "Applied plan to allowed signers file {}: {}",
+            file.display(),
+            plan.summary()
+        );
+    } else {
+        println!("Allowed signers file {} is up to date", file.display());
+    }
+
+    run_post_update_hook(file, config, changed)
+}
+
+/// A snapshot of the allowed signers file before updating it, if required to record the changes
+/// in the audit log or notify the webhook about them.
+fn snapshot_before_update(file: &Path, config: &Configuration) -> Option<Vec<String>> {
+    (config.audit().log.is_some() || config.notify().webhook.is_some())
+        .then(|| audit::snapshot(file))
+}
+
+/// Record an update of the allowed signers file in the audit log, sign it and export revoked keys,
+/// if configured.
+/// The given snapshot of the file before the update is required for the audit log.
+fn record_update(
+    file: &Path,
+    config: &Configuration,
+    before: Option<&[String]>,
+    changed: bool,
+) -> Result<()> {
+    append_audit_record(file, config, before, changed)?;
+
+    if let Some(key) = &config.signing().key {
+        if changed || !signature::signature_path(file).exists() {
+            sign_allowed_signers(file, key)?;
+        }
+    }
+
+    if let Some(krl) = &config.revocation().krl {
+        if revocation::write_krl(krl, &config.revocation().keys)? {
+            info!(path = %krl.display(), "Wrote key revocation list");
+        }
+    }
+    Ok(())
+}
+
+/// Append a record of the changes to the allowed signers file to the audit log, if configured and
+/// it changed.
+fn append_audit_record(
+    file: &Path,
+    config: &Configuration,
+    before: Option<&[String]>,
+    changed: bool,
+) -> Result<()> {
+    if let (Some(log), Some(before), true) = (&config.audit().log, before, changed) {
+        audit::append_record(log, config.path(), before, &audit::snapshot(file))
+            .context(format!("Failed to append to audit log {}", log.display()))?;
+    }
+    Ok(())
+}
+
+/// Notify the webhook about the changes to the allowed signers file, if configured and it changed.
+/// Failing to notify only logs a warning, since the file was already written.
+async fn notify_webhook(
+    file: &Path,
+    config: &Configuration,
+    before: Option<&[String]>,
+    changed: bool,
+) {
+    if let (Some(webhook), Some(before), true) = (&config.notify().webhook, before, changed) {
+        let after = audit::snapshot(file);
+        if let Err(err) = notify::send(webhook, config.path(), file, before, &after).await {
+            warning!(Warning::NotificationFailed, "{err:#}");
+        }
+    }
+}
+
+/// Run the post-update hook, if configured and the given condition holds.
+fn run_post_update_hook(file: &Path, config: &Configuration, run: bool) -> Result<()> {
+    if let Some(hook) = &config.hooks().post_update {
+        if run {
+            run_hook(hook, file).context("Failed to run post-update hook")?;
+        } else {
+            info!("Skipping post-update hook since the allowed signers file did not change");
+        }
+    }
+    Ok(())
+}
+
+/// Print a report of the keys currently configured for each signer on each of it's sources.
+#[tokio::main]
+async fn print_report(config: &Configuration, format: ReportFormat) -> Result<()> {
+    let sources = config.sources();
+    let signers = config.signers(&sources)?;
+    let reports = report::collect(&signers, &sources, config.output()).await;
+    match format {
+        ReportFormat::Tree => print!("{}", report::render_tree(&reports)),
+        ReportFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&reports).context("Failed to serialize report")?
+        ),
+    }
+    Ok(())
+}
+
+/// Print all entries of the allowed signers file in the given format.
+#[tokio::main]
+async fn export_entries(config: &Configuration, format: ExportFormat) -> Result<()> {
+    let sources = config.sources();
+    let file = allowed_signers::generate(
+        Path::new(""),
+        config.signers(&sources)?,
+        config.output(),
+        OutputFormat::AllowedSigners,
+    )
+    .await
+    .context("Failed to retrieve keys of allowed signers")?;
+    let export = export::Export::new(&file.written_entries());
+    match format {
+        ExportFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&export).context("Failed to serialize export")?
+        ),
+    }
+    Ok(())
+}
+
+/// Print the allowed signers file entries of the given signer, retrieving it's keys only from the
+/// given source. Principals split off by routes are fetched from the given source as well.
+#[tokio::main]
+async fn fetch_signer(config: &Configuration, name: &str, source_name: &str) -> Result<()> {
+    let sources = config.sources();
+    let Some(source) = sources.get(source_name) else {
+        bail!("Source {source_name} is not configured");
+    };
+    let mut signers: Vec<Signer> = config
+        .signers(&sources)?
+        .into_iter()
+        .filter(|signer| signer.name == name)
+        .collect();
+    if signers.is_empty() {
+        bail!("Signer {name} is not configured");
+    }
+    for signer in &mut signers {
+        signer.sources = vec![source.clone()];
+        signer.source_names = vec![source_name.to_string()];
+    }
+
+    let file = allowed_signers::generate(
+        Path::new(""),
+        signers,
+        config.output(),
+        OutputFormat::AllowedSigners,
+    )
+    .await
+    .context(format!(
+        "Failed to fetch keys of signer {name} from source {source_name}"
+    ))?;
+    if file.entries.is_empty() {
+        eprintln!("Signer {name} does not have any signing keys on source {source_name}");
+        return Ok(());
+    }
+    let mut entries = file.entries;
+    entries.sort();
+    entries.dedup();
+    for entry in entries {
+        println!("{entry}");
+    }
+    Ok(())
+}
+
+/// Verify that a signer exists and has at least one signing key on each of the given sources,
+/// printing a warning for every source it could not be verified on.
+#[tokio::main]
+async fn verify_signer(config: &Configuration, name: &str, source_names: &[String]) -> bool {
+    let sources = config.sources();
+    let mut verified = true;
+    for source_name in source_names {
+        let source = &sources[source_name];
+        let warning = match source.get_keys_by_username(name).await {
+            Ok(keys) if keys.is_empty() => format!(
+                "Signer {name} does not have any signing keys configured on source {source_name}"
+            ),
+            Ok(keys) => {
+                info!(
+                    "Signer {name} has {} signing key(s) configured on source {source_name}",
+                    keys.len()
+                );
+                continue;
+            }
+            Err(source::Error::UserNotFound) => format!(
+                "Signer {name} does not exist on source {source_name}, check the name for typos"
+            ),
+            Err(err) => format!("Failed to verify signer {name} on source {source_name}: {err}"),
+        };
+        warning!(Warning::SignerNotVerified, "{warning}");
+        verified = false;
+    }
+    verified
+}
+
+/// Remove signers that don't exist on any of their sources from the configuration, or only print
+/// them unless `remove` is set.
+/// Only signers all of whose sources definitively report them as not existing are removed, any
+/// other error, e.g. failing to connect, keeps a signer since it's existence is unknown.
+#[tokio::main]
+async fn prune_config(mut config: Configuration, remove: bool) -> Result<()> {
+    let sources = config.sources();
+    let signers = config.signers(&sources)?;
+    let mut vanished = Vec::new();
+    for name in config.signer_names() {
+        // Disabled signers are suspended on purpose, their existence is not checked.
+        if !signers.iter().any(|s| s.name == name) {
+            continue;
+        }
+        let mut exists = false;
+        for source in signers
+            .iter()
+            .filter(|s| s.name == name)
+            .flat_map(|s| &s.sources)
+        {
+            match source.get_keys_by_username(name).await {
+                Err(source::Error::UserNotFound) => {}
+                Ok(_) => exists = true,
+                Err(err) => {
+                    warning!(
+                        Warning::SignerExistenceUnknown,
+                        "Failed to determine whether signer {name} exists: {err}"
+                    );
+                    exists = true;
+                }
+            }
+            if exists {
+                break;
+            }
+        }
+        if !exists {
+            vanished.push(name.to_string());
+        }
+    }
+
+    if vanished.is_empty() {
+        println!("All signers exist on their sources");
+        return Ok(());
+    }
+    let mut removed = 0;
+    for name in &vanished {
+        if !remove {
+            println!("Would remove signer {name} since it does not exist on any of it's sources");
+            continue;
+        }
+        // Signers configured within drop-in files can't be removed, since they are never modified.
+        if let Err(err) = config.remove_signer(name) {
+            warning!(
+                Warning::VanishedSignerKept,
+                "Skipping signer {name}: {err:#}"
+            );
+            continue;
+        }
+        removed += 1;
+        println!("Removed signer {name} since it does not exist on any of it's sources");
+    }
+    if !remove {
+        println!("Run with --yes to remove them");
+    } else if removed > 0 {
+        save_config(&config)?;
+    }
+    Ok(())
+}
+
+/// Install the git hooks into the repository in the current directory.
+fn install_hooks(force: bool) -> Result<()> {
+    for path in git_hook::install(&git_hook::hooks_dir()?, force)? {
+        println!("Installed hook {}", path.display());
+    }
+    Ok(())
+}
+
+/// Remove the git hooks installed by hanko from the repository in the current directory.
+fn uninstall_hooks() -> Result<()> {
+    let dir = git_hook::hooks_dir()?;
+    let removed = git_hook::uninstall(&dir)?;
+    if removed.is_empty() {
+        println!("No hooks installed by hanko found in {}", dir.display());
+    }
+    for path in removed {
+        println!("Removed hook {}", path.display());
+    }
+    Ok(())
+}
+
+/// Print the checks of the git configuration in the current directory, failing if any setting is
+/// misconfigured.
+#[cfg(feature = "detect-allowed-signers")]
+fn diagnose_git_config(signers_file: Option<&Path>, home: Option<&Path>) -> Result<()> {
+    let checks = crate::doctor::diagnose(&crate::doctor::git_config()?, signers_file, home);
+    for check in &checks {
+        println!("{check}");
+    }
+    let failed = checks.iter().filter(|check| !check.passed()).count();
+    if failed > 0 {
+        bail!("{failed} git setting(s) misconfigured")
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "detect-allowed-signers"))]
+fn diagnose_git_config(_signers_file: Option<&Path>, _home: Option<&Path>) -> Result<()> {
+    bail!("Checking the git configuration requires the `detect-allowed-signers` feature")
+}
+
+/// Verify the detached signature of the allowed signers file using the given public key, reading
+/// the signature from next to the file if no path is given.
+fn verify_file(file: &Path, signature: Option<PathBuf>, public_key: &Path) -> Result<()> {
+    let signature = signature.unwrap_or_else(|| signature::signature_path(file));
+    let public_key = fs::read_to_string(public_key).context(format!(
+        "Failed to read public key {}",
+        public_key.display()
+    ))?;
+    signature::verify(file, &signature, &public_key).context(format!(
+        "Failed to verify allowed signers file {}",
+        file.display()
+    ))?;
     println!(
-        "Updated allowed signers file {} in {:?}",
+        "Verified allowed signers file {} using signature {}",
         file.display(),
-        duration
+        signature.display()
+    );
+    Ok(())
+}
+
+/// Sign the allowed signers file using the given key.
+/// Only regular files are signed, since other files like FIFOs cannot be read again without
+/// blocking.
+fn sign_allowed_signers(file: &Path, key: &Path) -> Result<()> {
+    if !fs::metadata(file).is_ok_and(|metadata| metadata.is_file()) {
+        warning!(
+            Warning::FileNotSigned,
+            "Not signing {} since it is not a regular file",
+            file.display()
+        );
+        return Ok(());
+    }
+    let signature = signature::sign(file, key).context(format!(
+        "Failed to sign allowed signers file {}",
+        file.display()
+    ))?;
+    info!("Wrote signature {}", signature.display());
+    Ok(())
+}
+
+/// Filter the given signers, keeping only the ones with the given names.
+/// Since the allowed signers file is replaced as a whole, a warning is logged for every signer that
+/// will be dropped from it.
+///
+/// # Errors
+///
+/// When any of the given names is not a configured signer or no signer is selected, which would
+/// otherwise remove all entries from the allowed signers file.
+fn filter_signers(signers: Vec<Signer>, names: &[String]) -> Result<Vec<Signer>> {
+    let unknown: Vec<&str> = names
+        .iter()
+        .filter(|name| !signers.iter().any(|s| s.name == **name))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        bail!("Selected signer(s) {} not configured", unknown.join(", "))
+    }
+    let (kept, dropped): (Vec<_>, Vec<_>) =
+        signers.into_iter().partition(|s| names.contains(&s.name));
+    if kept.is_empty() {
+        bail!("No signers selected")
+    }
+    let mut dropped: Vec<String> = dropped.into_iter().map(|s| s.name).collect();
+    dropped.dedup();
+    for name in dropped {
+        warning!(
+            Warning::SignerNotSelected,
+            "Signer {name} is not selected and will be removed from the allowed signers file"
+        );
+    }
+    Ok(kept)
+}
+
+/// A command running the given command line using the system shell.
+fn shell_command(command_line: &str) -> process::Command {
+    let mut command = if cfg!(windows) {
+        let mut command = process::Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = process::Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    command.arg(command_line);
+    command
+}
+
+/// Run a hook using the system shell, exposing the path of the allowed signers file to it using
+/// the `HANKO_ALLOWED_SIGNERS` environment variable.
+fn run_hook(hook: &str, file: &Path) -> Result<()> {
+    let status = shell_command(hook)
+        .env("HANKO_ALLOWED_SIGNERS", file)
+        .status()
+        .context("Failed to spawn shell")?;
+    if !status.success() {
+        bail!("Hook exited with {status}")
+    }
+    Ok(())
+}
+
+/// Pipe the content of the given allowed signers file to the stdin of the given command, run
+/// using the system shell.
+fn pipe_to(command: &str, file: &allowed_signers::File) -> Result<()> {
+    let mut child = shell_command(command)
+        .stdin(process::Stdio::piped())
+        .spawn()
+        .context("Failed to spawn shell")?;
+    let stdin = child.stdin.take().expect("stdin is piped");
+    // The exit status takes precedence, since commands failing early close their stdin.
+    let written = file.write_to(stdin);
+    let status = child.wait().context("Failed to wait for command")?;
+    if !status.success() {
+        bail!("Command exited with {status}")
+    }
+    written.context("Failed to write to command")
+}
+
+/// Print the configured names of the given kind, one per line.
+fn print_completions(config: &Configuration, kind: CompletionKind) {
+    let names = match kind {
+        CompletionKind::Signers => config.signer_names(),
+        CompletionKind::Sources => config.source_names(),
+    };
+    for name in names {
+        println!("{name}");
+    }
+}
+
+/// Print a table of the supported source providers.
+fn print_providers() {
+    let yes_no = |b: bool| if b { "yes" } else { "no" };
+    let rows: Vec<[String; 5]> = SourceType::all()
+        .map(|p| {
+            let known_hosts: Vec<&str> = p.known_hosts().collect();
+            [
+                p.name().to_string(),
+                p.default_url()
+                    .map_or("-".to_string(), |url| url.to_string()),
+                yes_no(p.supports_authentication()).to_string(),
+                yes_no(p.supports_pagination()).to_string(),
+                if known_hosts.is_empty() {
+                    "-".to_string()
+                } else {
+                    known_hosts.join(", ")
+                },
+            ]
+        })
+        .collect();
+    let header = [
+        "PROVIDER",
+        "DEFAULT URL",
+        "AUTHENTICATION",
+        "PAGINATION",
+        "KNOWN HOSTS",
+    ]
+    .map(String::from);
+    print_table(&header, &rows);
+}
+
+/// Print a table of the environment variables used by hanko and their effect.
+fn print_environment_variables() {
+    let rows: Vec<[String; 2]> = environment_variables()
+        .into_iter()
+        .map(|(name, effect)| [name, effect])
+        .collect();
+    let header = ["VARIABLE", "EFFECT"].map(String::from);
+    print_table(&header, &rows);
+}
+
+/// The environment variables used by hanko along with their effect.
+/// Variables setting arguments are taken from the argument definitions, followed by variables
+/// that are consulted otherwise.
+fn environment_variables() -> Vec<(String, String)> {
+    fn arg_variables(cmd: &clap::Command, variables: &mut Vec<(String, String)>) {
+        for arg in cmd.get_arguments().filter(|arg| !arg.is_hide_set()) {
+            let Some(name) = arg.get_env() else {
+                continue;
+            };
+            let name = name.to_string_lossy().to_string();
+            if variables.iter().any(|(n, _)| *n == name) {
+                continue;
+            }
+            let help = arg.get_help().map(ToString::to_string).unwrap_or_default();
+            let effect = match arg.get_long() {
+                Some(long) => format!("{help}, same as --{long}."),
+                None => help,
+            };
+            variables.push((name, effect));
+        }
+        for subcommand in cmd.get_subcommands() {
+            arg_variables(subcommand, variables);
+        }
+    }
+
+    let mut variables = Vec::new();
+    arg_variables(&Cli::command(), &mut variables);
+    variables.extend(
+        [
+            (
+                "HANKO_SOURCE_<NAME>_TOKEN",
+                "Overrides the token of the source <NAME>.",
+            ),
+            (
+                "HANKO_SOURCE_<NAME>_URL",
+                "Overrides the URL of the source <NAME>.",
+            ),
+            (
+                "<VARIABLE>",
+                "Read by tokens, auth_headers and the webhook configured as env:<VARIABLE>.",
+            ),
+            (
+                "GITHUB_TOKEN",
+                "Token of sources using api.github.com without a configured token, unless --no-env-token is given.",
+            ),
+            (
+                "GITLAB_TOKEN",
+                "Token of sources using gitlab.com without a configured token, unless --no-env-token is given.",
+            ),
+            (
+                "XDG_CONFIG_HOME",
+                "Base directory of the default configuration file.",
+            ),
+            (
+                "HOME",
+                "Base directory of the default configuration file if XDG_CONFIG_HOME is not set, and expansion of a leading ~ in paths.",
+            ),
+            (
+                "USER, USERNAME",
+                "Name of the user reported when reading the configuration file is denied.",
+            ),
+            (
+                "HTTPS_PROXY, HTTP_PROXY, ALL_PROXY, NO_PROXY",
+                "Proxy requests to sources are sent through, and hosts excluded from it.",
+            ),
+            ("NO_COLOR", "Disables colored output."),
+        ]
+        .map(|(name, effect)| (name.to_string(), effect.to_string())),
     );
+    variables
+}
+
+/// Print a table with columns aligned to their widest cell.
+fn print_table<const N: usize>(header: &[String; N], rows: &[[String; N]]) {
+    let widths: Vec<usize> = (0..N)
+        .map(|i| {
+            std::iter::once(header)
+                .chain(rows)
+                .map(|r| r[i].len())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    for row in std::iter::once(header).chain(rows) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    }
+}
+
+/// Render the given error as a JSON object for consumption by scripts.
+/// The `kind` of the error is taken from the first typed error within it's chain of causes, with
+/// the affected signer and further details of the error added if known.
+fn json_error(err: &anyhow::Error) -> serde_json::Value {
+    let mut error = json!({
+        "kind": "other",
+        "message": err.to_string(),
+        "causes": err.chain().skip(1).map(ToString::to_string).collect::<Vec<_>>(),
+    });
+    for cause in err.chain() {
+        if let Some(signer_error) = cause.downcast_ref::<SignerError>() {
+            error["signer"] = json!(signer_error.signer);
+        } else if let Some(source_error) = cause.downcast_ref::<source::Error>() {
+            if let Ok(serde_json::Value::Object(typed)) = serde_json::to_value(source_error) {
+                error.as_object_mut().unwrap().extend(typed);
+            }
+            break;
+        } else if cause.is::<io::Error>() {
+            error["kind"] = json!("io");
+            break;
+        }
+    }
+    error
+}
+
+/// Trace to the terminal according to the given verbosity level, and to the given trace file if
+/// any, independent of the terminal.
+fn setup_tracing(
+    vebosity_level: u8,
+    trace_file: Option<&Path>,
+    trace_file_format: TraceFormat,
+) -> Result<()> {
+    let mut layers: Vec<Box<dyn Layer<Registry> + Send + Sync>> = Vec::new();
+    // Without a verbosity level given by the user or with diagnostics silenced, nothing is traced
+    // to the terminal.
+    if vebosity_level > 0 && !diagnostic::is_silenced() {
+        layers.push(
+            fmt::layer()
+                .compact()
+                .with_filter(tracing_filter(vebosity_level))
+                .boxed(),
+        );
+    }
+    if let Some(path) = trace_file {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .context(format!("Failed to open trace file {}", path.display()))?;
+        let layer = fmt::layer().with_ansi(false).with_writer(Mutex::new(file));
+        let filter = tracing_filter(vebosity_level.max(1));
+        layers.push(match trace_file_format {
+            TraceFormat::Full => layer.with_filter(filter).boxed(),
+            TraceFormat::Compact => layer.compact().with_filter(filter).boxed(),
+        });
+    }
+    if !layers.is_empty() {
+        tracing_subscriber::registry().with(layers).init();
+    }
     Ok(())
 }
 
-fn setup_tracing(vebosity_level: u8) {
+/// The filter of traces for the given verbosity level, which must be at least one.
+fn tracing_filter(vebosity_level: u8) -> EnvFilter {
     let level = match vebosity_level {
-        0 => return, // The user did not specify a verbosity level, do not configure tracing.
         1 => Level::INFO,
         2 => Level::DEBUG,
         _ => Level::TRACE,
     };
-    let filter = {
-        // For verbosity levels of 3 and above, given a debug build, traces from external crates are included.
-        if vebosity_level > 3 && cfg!(debug_assertions) {
-            tracing_subscriber::filter::EnvFilter::new(format!("{level}"))
-        } else {
-            // Otherwise, traces from external crates are filtered.
-            tracing_subscriber::filter::EnvFilter::new(format!(
-                "{}={level}",
-                env!("CARGO_PKG_NAME")
-            ))
-        }
-    };
-    tracing_subscriber::fmt()
-        .compact()
-        .with_env_filter(filter)
-        .init();
+    // For verbosity levels of 3 and above, given a debug build, traces from external crates are included.
+    if vebosity_level > 3 && cfg!(debug_assertions) {
+        EnvFilter::new(format!("{level}"))
+    } else {
+        // Otherwise, traces from external crates are filtered.
+        EnvFilter::new(format!("{}={level}", env!("CARGO_PKG_NAME")))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use assert_cmd::Command;
+    use chrono::TimeZone;
     use predicates::prelude::*;
+    use rstest::*;
 
     #[test]
     fn verify_cli() {
-        use clap::CommandFactory;
         Cli::command().debug_assert();
     }
 
+    /// Only lines differing between both contents are returned, in the order they appear.
+    #[rstest]
+    #[case("a\nb\nc\n", "a\nb\nc\n", &[])]
+    #[case("a\nb\nc\n", "a\nx\nc\n", &["-b", "+x"])]
+    #[case("a = 1 # one\nb = 2\n", "a = 1\nb = 2\nc = 3\n", &["-a = 1 # one", "+a = 1", "+c = 3"])]
+    #[case("a\n", "a", &["-"])]
+    fn differing_lines_returned(
+        #[case] before: &str,
+        #[case] after: &str,
+        #[case] expected: &[&str],
+    ) {
+        assert_eq!(line_diff(before, after), expected);
+    }
+
+    /// A leading `~` or `$HOME` is expanded to the home directory, other paths are kept as-is.
+    #[rstest]
+    #[case(
+        "~/.ssh/allowed_signers",
+        Some("/home/octocat"),
+        "/home/octocat/.ssh/allowed_signers"
+    )]
+    #[case(
+        "$HOME/.ssh/allowed_signers",
+        Some("/home/octocat"),
+        "/home/octocat/.ssh/allowed_signers"
+    )]
+    #[case(
+        "${HOME}/.ssh/allowed_signers",
+        Some("/home/octocat"),
+        "/home/octocat/.ssh/allowed_signers"
+    )]
+    #[case("~", Some("/home/octocat"), "/home/octocat")]
+    #[case(
+        "/etc/~/allowed_signers",
+        Some("/home/octocat"),
+        "/etc/~/allowed_signers"
+    )]
+    #[case(
+        "~octocat/allowed_signers",
+        Some("/home/octocat"),
+        "~octocat/allowed_signers"
+    )]
+    #[case("~/.ssh/allowed_signers", None, "~/.ssh/allowed_signers")]
+    fn home_expanded(#[case] path: &str, #[case] home: Option<&str>, #[case] expected: &str) {
+        let expanded = expand_home(Path::new(path), home.map(Path::new));
+
+        assert_eq!(expanded, PathBuf::from(expected));
+    }
+
+    /// Errors are rendered as JSON containing the kind and details of the first typed error
+    /// within their chain of causes, as well as the affected signer.
+    #[test]
+    fn error_rendered_as_json() {
+        let err = anyhow::Error::new(SignerError {
+            signer: "jsnow".to_string(),
+            error: source::Error::ClientError(reqwest::StatusCode::FORBIDDEN),
+        })
+        .context("Failed to update the allowed signers file");
+
+        assert_eq!(
+            json_error(&err),
+            json!({
+                "kind": "client_error",
+                "detail": 403,
+                "message": "Failed to update the allowed signers file",
+                "causes": ["failed to get keys of signer jsnow", "client request error"],
+                "signer": "jsnow",
+            })
+        );
+
+        let err = anyhow::Error::new(io::Error::from(io::ErrorKind::NotFound))
+            .context("Configuration file does not exist");
+        assert_eq!(json_error(&err)["kind"], "io");
+        assert_eq!(json_error(&err).get("signer"), None);
+    }
+
+    /// Interrupted updates exit with the interrupted exit code, regardless of added context,
+    /// while other failures exit with 1.
+    #[test]
+    fn interrupted_update_exits_with_interrupted_code() {
+        let err = anyhow::Error::new(Interrupted(PathBuf::from("allowed_signers")))
+            .context("Failed to update config.toml");
+        assert_eq!(exit_code(&err), INTERRUPTED_EXIT_CODE);
+
+        let err = anyhow::anyhow!("Failed to update config.toml");
+        assert_eq!(exit_code(&err), 1);
+    }
+
+    /// The status line summarizes successful and failed updates on a single line, along with the
+    /// number of errors that occurred.
+    #[test]
+    fn status_line_rendered() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let time = now.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+
+        assert_eq!(
+            status_line(Some((12, 18)), 0, now),
+            format!("hanko: 12 signers, 18 keys, 0 errors ({time})")
+        );
+        assert_eq!(
+            status_line(Some((12, 18)), 1, now),
+            format!("hanko: 12 signers, 18 keys, 1 error ({time})")
+        );
+        assert_eq!(
+            status_line(None, 1, now),
+            format!("hanko: update failed, 1 error ({time})")
+        );
+        assert_eq!(
+            status_line(None, 3, now),
+            format!("hanko: update failed, 3 errors ({time})")
+        );
+    }
+
+    /// Only failures retrieving the keys of a signer re-run the update.
+    #[test]
+    fn only_signer_failures_retried() {
+        let err = anyhow::Error::new(SignerError {
+            signer: "jsnow".to_string(),
+            error: source::Error::ConnectionError,
+        })
+        .context("Failed to update the allowed signers file");
+        assert!(is_signer_failure(&err));
+
+        let err = anyhow::Error::new(io::Error::from(io::ErrorKind::PermissionDenied))
+            .context("Failed to write allowed signers file");
+        assert!(!is_signer_failure(&err));
+    }
+
     #[test]
     fn version_contains_version() {
         let version = format!("hanko {}", env!("CARGO_PKG_VERSION"));
@@ -0,0 +1,53 @@
+//! The clock hanko reads the current time from, which can be fixed to simulate running at another
+//! point in time, e.g. to debug validity periods and key ages using the hidden `HANKO_NOW`
+//! environment variable.
+use chrono::{DateTime, Utc};
+use std::sync::OnceLock;
+
+static CLOCK: OnceLock<Clock> = OnceLock::new();
+
+/// A source of the current time.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Clock {
+    /// The real time of the system.
+    #[default]
+    System,
+    /// A fixed point in time.
+    Fixed(DateTime<Utc>),
+}
+
+impl Clock {
+    /// The current time according to the clock.
+    pub(crate) fn now(self) -> DateTime<Utc> {
+        match self {
+            Clock::System => Utc::now(),
+            Clock::Fixed(now) => now,
+        }
+    }
+}
+
+/// Use the given clock for the rest of the invocation. Only the first clock set is used.
+pub(crate) fn set(clock: Clock) {
+    let _ = CLOCK.set(clock);
+}
+
+/// The current time according to the clock set for the invocation, the system clock by default.
+pub(crate) fn now() -> DateTime<Utc> {
+    CLOCK.get().copied().unwrap_or_default().now()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// A fixed clock always returns it's point in time, while the system clock advances.
+    #[test]
+    fn fixed_clock_returns_fixed_time() {
+        let fixed = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        let before = Utc::now();
+
+        assert_eq!(Clock::Fixed(fixed).now(), fixed);
+        assert!(Clock::System.now() >= before);
+    }
+}
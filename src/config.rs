@@ -3,13 +3,21 @@
 //! Fallible functions in this module return an [`anyhow::Result`] since any errors that occur
 //! when interacting with configuration will be reported to the user without further processing.
 
-use crate::{allowed_signers::Signer, Github, Gitlab, Source};
+use crate::{
+    allowed_signers::{ssh::Fingerprint, OutputOptions, Signer, SourceMode},
+    configure_pool,
+    diagnostic::{warning, Warning},
+    revocation::{self, RevokedKey},
+    source, Command, ConcurrencyLimited, Github, Gitlab, PlainUrl, PoolOptions, Protocol,
+    RetryPolicy, Retrying, Source, Token,
+};
 use anyhow::{bail, Context, Error, Result};
-use reqwest::Url;
+use reqwest::{header::HeaderValue, Url};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
-    collections::{HashMap, HashSet},
-    fs, io,
+    collections::{BTreeMap, HashMap, HashSet},
+    env, fs, io,
+    num::NonZeroUsize,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -54,11 +62,123 @@ impl TomlFile {
         }
     }
 
+    /// Add a source to the file.
+    fn add_source(&mut self, name: &str, provider: SourceType, url: &Url) {
+        use toml_edit::{ArrayOfTables, Item, Table, Value};
+
+        let mut table = Table::new();
+        table.insert("name", name.into());
+        table.insert("provider", provider.name().into());
+        table.insert("url", url.as_str().into());
+
+        match self.document.get_mut("sources") {
+            None => {
+                let mut item = ArrayOfTables::new();
+                item.push(table);
+                self.document.insert("sources", Item::ArrayOfTables(item));
+            }
+            Some(Item::Value(Value::Array(a))) if a.iter().all(Value::is_inline_table) => {
+                a.push(table.into_inline_table());
+            }
+            Some(Item::ArrayOfTables(a)) => a.push(table),
+            _ => unreachable!("sources key has invalid format"),
+        }
+    }
+
+    /// Remove all allowed signers with the given name from the file, along with their principals
+    /// in the principals table. Returns whether any signer was removed.
+    fn remove_signer(&mut self, name: &str) -> bool {
+        use toml_edit::{Item, Value};
+
+        let is_named = |table: &dyn toml_edit::TableLike| {
+            table.get("name").and_then(Item::as_str) == Some(name)
+        };
+        let removed = match self.document.get_mut("signers") {
+            Some(Item::Value(Value::Array(a))) => {
+                let len = a.len();
+                a.retain(|v| !v.as_inline_table().is_some_and(|t| is_named(t)));
+                a.len() != len
+            }
+            Some(Item::ArrayOfTables(a)) => {
+                let len = a.len();
+                a.retain(|t| !is_named(t));
+                a.len() != len
+            }
+            _ => false,
+        };
+        if let Some(principals) = self
+            .document
+            .get_mut("principals")
+            .and_then(Item::as_table_like_mut)
+        {
+            principals.remove(name);
+        }
+        removed
+    }
+
+    /// Enable or disable all allowed signers with the given name within the file, omitting the
+    /// `enabled` option of enabled signers since it is the default. Returns whether any signer
+    /// with the given name is configured within the file.
+    fn set_signer_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        use toml_edit::{Item, Value};
+
+        // Returns whether the given table is one of the signers.
+        let update = |table: &mut dyn toml_edit::TableLike| {
+            if table.get("name").and_then(Item::as_str) != Some(name) {
+                return false;
+            }
+            if enabled {
+                table.remove("enabled");
+            } else {
+                table.insert("enabled", toml_edit::value(false));
+            }
+            true
+        };
+        let mut found = false;
+        match self.document.get_mut("signers") {
+            Some(Item::Value(Value::Array(a))) => {
+                for table in a.iter_mut().filter_map(Value::as_inline_table_mut) {
+                    if update(table) {
+                        // Normalize the spacing around the last value, which changed.
+                        table.fmt();
+                        found = true;
+                    }
+                }
+            }
+            Some(Item::ArrayOfTables(a)) => {
+                for table in a.iter_mut() {
+                    found |= update(table);
+                }
+            }
+            _ => {}
+        }
+        found
+    }
+
     /// Load from a TOML file.
+    /// Errors reading the file are described according to their kind, while still allowing the
+    /// underlying [`io::Error`] to be inspected.
     fn load(path: PathBuf) -> Result<Self> {
         info!("Loading TOML configuration file");
-        let content = fs::read_to_string(&path)?;
-        let document = content.parse()?;
+        let content = fs::read_to_string(&path).map_err(|err| {
+            let message = match err.kind() {
+                io::ErrorKind::NotFound => format!(
+                    "Configuration file {} does not exist, create it by adding a signer using `hanko signer add`",
+                    path.display()
+                ),
+                io::ErrorKind::PermissionDenied => format!(
+                    "Permission denied reading configuration file {} as user {}",
+                    path.display(),
+                    current_user()
+                ),
+                _ => format!("Failed to read configuration file {}", path.display()),
+            };
+            Error::new(err).context(message)
+        })?;
+        let document = content.parse().context(format!(
+            "Configuration file {} is not valid TOML",
+            path.display()
+        ))?;
         Ok(Self { path, document })
     }
 
@@ -69,12 +189,57 @@ impl TomlFile {
     }
 }
 
+/// The name of the user running hanko, as far as it is known from the environment.
+fn current_user() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_else(|_| "<unknown>".to_string())
+}
+
+/// A directory of drop-in configuration files merged into the main configuration.
+#[derive(Debug, Clone, Copy)]
+pub struct DropIns<'a> {
+    /// The directory containing the drop-in files.
+    pub dir: &'a Path,
+    /// Whether drop-in files failing to parse are skipped with a warning instead of failing to
+    /// load the configuration.
+    pub skip_invalid: bool,
+}
+
+/// Options of loading the configuration.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoadOptions<'a> {
+    /// A directory of drop-in files merged into the configuration, if any.
+    pub drop_ins: Option<DropIns<'a>>,
+    /// Whether sources of the public providers without a configured token use the token given by
+    /// the conventional environment variable of their provider, like `GITHUB_TOKEN`, see
+    /// [`SourceType::conventional_token_var`].
+    pub conventional_tokens: bool,
+}
+
 /// The main configuration.
 #[derive(Debug, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct Configuration {
+    /// The allowed signers file updated using this configuration when updating multiple
+    /// configurations at once.
+    allowed_signers_file: Option<PathBuf>,
     signers: Vec<SignerConfiguration>,
     sources: Vec<SourceConfiguration>,
+    /// Principals by signer name, as an alternative to configuring them within signers.
+    principals: HashMap<String, Vec<String>>,
+    routes: Vec<RouteConfiguration>,
+    /// Fingerprints of keys denied for all signers.
+    denied_fingerprints: Vec<Fingerprint>,
+    output: OutputOptions,
+    hooks: HooksConfiguration,
+    notify: NotifyConfiguration,
+    audit: AuditConfiguration,
+    cache: CacheConfiguration,
+    signing: SigningConfiguration,
+    revocation: RevocationConfiguration,
+    retry: RetryPolicy,
+    pool: PoolOptions,
     #[serde(skip)]
     file: TomlFile,
 }
@@ -94,38 +259,89 @@ impl TryFrom<TomlFile> for Configuration {
 impl Default for Configuration {
     fn default() -> Self {
         Self {
+            allowed_signers_file: None,
             signers: Vec::default(),
             sources: Self::default_sources(),
+            principals: HashMap::default(),
+            routes: Vec::default(),
+            denied_fingerprints: Vec::default(),
+            output: OutputOptions::default(),
+            hooks: HooksConfiguration::default(),
+            notify: NotifyConfiguration::default(),
+            audit: AuditConfiguration::default(),
+            cache: CacheConfiguration::default(),
+            signing: SigningConfiguration::default(),
+            revocation: RevocationConfiguration::default(),
+            retry: RetryPolicy::default(),
+            pool: PoolOptions::default(),
             file: TomlFile::default(),
         }
     }
 }
 
 /// A `HashMap` containing sources by name.
+/// A signer or route referring to a source that is not configured.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("Signer {signer} references source {source_name} which is not configured")]
+pub struct UnknownSourceError {
+    /// The name of the affected signer.
+    pub signer: String,
+    /// The name of the missing source.
+    pub source_name: String,
+}
+
 /// Since signers need to contain references to sources and can move between threads,
 /// an Arc is used for sources.
 type NamedSources = HashMap<String, Arc<Box<dyn Source>>>;
 
 impl Configuration {
     /// Returns configuration for the default GitHub and GitLab sources.
+    #[cfg(feature = "public-providers")]
     fn default_sources() -> Vec<SourceConfiguration> {
         vec![
             SourceConfiguration {
                 name: "github".to_string(),
                 provider: SourceType::Github,
-                url: "https://api.github.com".parse().unwrap(),
+                url: SourceType::Github.default_url(),
+                command: None,
+                token: None,
+                tokens: Vec::new(),
+                auth_headers: BTreeMap::new(),
+                max_concurrent_requests: None,
+                protocol: None,
+                signing_only: None,
+                accept: None,
             },
             SourceConfiguration {
                 name: "gitlab".to_string(),
                 provider: SourceType::Gitlab,
-                url: "https://gitlab.com".parse().unwrap(),
+                url: SourceType::Gitlab.default_url(),
+                command: None,
+                token: None,
+                tokens: Vec::new(),
+                auth_headers: BTreeMap::new(),
+                max_concurrent_requests: None,
+                protocol: None,
+                signing_only: None,
+                accept: None,
             },
         ]
     }
 
-    /// Extend the configuration by the default sources.
+    /// Without the `public-providers` feature, there are no default sources and only explicitly
+    /// configured sources are used.
+    #[cfg(not(feature = "public-providers"))]
+    fn default_sources() -> Vec<SourceConfiguration> {
+        Vec::new()
+    }
+
+    /// Extend the configuration by the default sources, unless sources of the same name are
+    /// configured, which take precedence.
     fn add_default_sources(&mut self) {
-        let default_sources = Self::default_sources();
+        let default_sources: Vec<SourceConfiguration> = Self::default_sources()
+            .into_iter()
+            .filter(|d| !self.sources.iter().any(|c| c.name == d.name))
+            .collect();
         debug!(
             ?default_sources,
             "Extending configuration with default sources"
@@ -133,23 +349,198 @@ impl Configuration {
         self.sources.extend(default_sources);
     }
 
+    /// Override source configuration using `HANKO_SOURCE_<NAME>_<FIELD>` environment variables,
+    /// where `<NAME>` is the uppercased source name with dashes replaced by underscores.
+    /// Supported fields are `TOKEN` and `URL`, environment variables take precedence over the file.
+    fn apply_env_overrides<I>(&mut self, vars: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        const PREFIX: &str = "HANKO_SOURCE_";
+
+        for (key, value) in vars {
+            let Some(rest) = key.strip_prefix(PREFIX) else {
+                continue;
+            };
+            let (name, field) = if let Some(name) = rest.strip_suffix("_TOKEN") {
+                (name, "token")
+            } else if let Some(name) = rest.strip_suffix("_URL") {
+                (name, "url")
+            } else {
+                warning!(
+                    Warning::IgnoredEnvOverride,
+                    "Ignoring environment variable {key} with unknown source field"
+                );
+                continue;
+            };
+            let Some(source) = self
+                .sources
+                .iter_mut()
+                .find(|c| c.name.to_uppercase().replace('-', "_") == name)
+            else {
+                warning!(
+                    Warning::IgnoredEnvOverride,
+                    "Ignoring environment variable {key} for unknown source"
+                );
+                continue;
+            };
+
+            debug!(
+                source = source.name,
+                "Overriding source {field} from environment"
+            );
+            match field {
+                "token" => {
+                    source.token = Some(Token::new(value));
+                    source.tokens.clear();
+                }
+                _ => {
+                    source.url = Some(
+                        value
+                            .parse()
+                            .context(format!("Invalid URL in environment variable {key}"))?,
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve source tokens, auth headers and the webhook referring to an environment variable
+    /// using the `env:<VARIABLE>` form to the value of that variable, looked up using the given
+    /// function. Webhook URLs may carry a secret, e.g. within their path.
+    fn resolve_token_references<F>(&mut self, lookup: F) -> Result<()>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        if let Some(webhook) = &mut self.notify.webhook {
+            if webhook.scheme() == "env" {
+                let var = webhook.path().to_string();
+                let value = lookup(&var).context(format!(
+                    "Webhook refers to environment variable {var} which is not set"
+                ))?;
+                *webhook = value
+                    .parse()
+                    .context(format!("Invalid webhook URL in environment variable {var}"))?;
+            }
+        }
+        for source in &mut self.sources {
+            for token in source
+                .token
+                .iter_mut()
+                .chain(&mut source.tokens)
+                .chain(source.auth_headers.values_mut())
+            {
+                if let Some(var) = token.env_reference() {
+                    let value = lookup(var).context(format!(
+                        "Source {} refers to environment variable {var} which is not set",
+                        source.name
+                    ))?;
+                    *token = Token::new(value);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Use the token given by the conventional environment variable of their provider, looked up
+    /// using the given function, for sources of public providers without a configured token.
+    /// Sources of other instances, e.g. GitHub Enterprise, never use them, since the variables
+    /// usually hold tokens for the public instance, e.g. in CI.
+    fn apply_conventional_tokens<F>(&mut self, lookup: F)
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        for source in &mut self.sources {
+            if source.token.is_some()
+                || !source.tokens.is_empty()
+                || source.url.is_none()
+                || source.url != source.provider.default_url()
+            {
+                continue;
+            }
+            let Some(var) = source.provider.conventional_token_var() else {
+                continue;
+            };
+            if let Some(value) = lookup(var).filter(|value| !value.is_empty()) {
+                debug!(source = source.name, var, "Using token from environment");
+                source.token = Some(Token::new(value));
+            }
+        }
+    }
+
+    /// Merge the principals table into the signers it refers to.
+    /// Principals of a signer may either be configured inline or within the principals table,
+    /// but not both.
+    fn merge_principals(&mut self) -> Result<()> {
+        for (name, principals) in &self.principals {
+            let mut signers = self
+                .signers
+                .iter_mut()
+                .filter(|c| c.name == *name)
+                .peekable();
+            if signers.peek().is_none() {
+                warning!(
+                    Warning::UnknownPrincipalsSigner,
+                    "Ignoring principals of unknown signer {name}"
+                );
+                continue;
+            }
+            for signer in signers {
+                if !signer.principals.is_empty() {
+                    bail!("Signer {name} has principals configured both inline and in the principals table")
+                }
+                signer.principals.clone_from(principals);
+            }
+        }
+        Ok(())
+    }
+
+    /// Use the email address returned by the given function, usually the one configured within
+    /// Git, as the principal of the only signer if it has none. Configurations with multiple
+    /// signers never use a default principal, since it would be wrong for all but one of them.
+    fn default_principal<F>(&mut self, lookup: F)
+    where
+        F: FnOnce() -> Option<String>,
+    {
+        let [signer] = self.signers.as_mut_slice() else {
+            return;
+        };
+        if !signer.principals.is_empty() || signer.saml_organization.is_some() {
+            return;
+        }
+        if let Some(email) = lookup() {
+            info!(
+                "Using {email} as the principal of signer {} since it has none configured",
+                signer.name
+            );
+            signer.principals.push(email);
+        }
+    }
+
     /// Add an allowed signer to the configuration.
     ///
     /// # Errors
     ///
-    /// Returns an error if any of the given sources don't exist.
+    /// Returns an error if no principals are given, any of them is invalid or any of the given
+    /// sources don't exist.
     pub fn add_signer(
         &mut self,
         name: String,
         principals: Vec<String>,
         source_names: Vec<String>,
     ) -> Result<()> {
+        if principals.is_empty() {
+            bail!("Signer {name} missing principals")
+        }
+        check_principals(&name, &principals)?;
         self.check_sources_exist(source_names.iter().map(String::as_str))?;
 
         let signer = SignerConfiguration {
             name,
             principals,
             source_names,
+            ..Default::default()
         };
         self.file.add_signer(
             &signer.name,
@@ -161,59 +552,271 @@ impl Configuration {
         Ok(())
     }
 
-    /// Returns sources generated from their configuration.
+    /// Remove all allowed signers with the given name from the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no signer with the given name is configured within the configuration
+    /// file itself, e.g. since it is configured within a drop-in file, which is never modified.
+    pub fn remove_signer(&mut self, name: &str) -> Result<()> {
+        if !self.file.remove_signer(name) {
+            bail!(
+                "Signer {name} is not configured within {}, drop-in files are not modified",
+                self.file.path.display()
+            )
+        }
+        self.signers.retain(|c| c.name != name);
+        self.principals.remove(name);
+
+        Ok(())
+    }
+
+    /// Enable or disable all allowed signers with the given name within the configuration.
+    /// Disabled signers are retained in the configuration, but skipped when updating.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no signer with the given name is configured within the configuration
+    /// file itself, e.g. since it is configured within a drop-in file, which is never modified.
+    pub fn set_signer_enabled(&mut self, name: &str, enabled: bool) -> Result<()> {
+        if !self.file.set_signer_enabled(name, enabled) {
+            bail!(
+                "Signer {name} is not configured within {}, drop-in files are not modified",
+                self.file.path.display()
+            )
+        }
+        for c in self.signers.iter_mut().filter(|c| c.name == name) {
+            c.enabled = enabled;
+        }
+
+        Ok(())
+    }
+
+    /// Add a source to the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a source with the given name already exists.
+    pub fn add_source(&mut self, name: String, provider: SourceType, url: Url) -> Result<()> {
+        if self.sources.iter().any(|c| c.name == name) {
+            bail!("Source {name} already exists")
+        }
+
+        self.file.add_source(&name, provider, &url);
+        self.sources.push(SourceConfiguration {
+            name,
+            provider,
+            url: Some(url),
+            command: None,
+            token: None,
+            tokens: Vec::new(),
+            auth_headers: BTreeMap::new(),
+            max_concurrent_requests: None,
+            protocol: None,
+            signing_only: None,
+            accept: None,
+        });
+
+        Ok(())
+    }
+
+    /// Returns sources generated from their configuration, configuring the connection pool of the
+    /// clients shared by them beforehand.
     #[must_use]
     pub fn sources(&self) -> NamedSources {
+        configure_pool(self.pool.clone());
         self.sources
             .iter()
-            .map(|c| (c.name.clone(), Arc::new(c.build_source())))
+            .map(|c| (c.name.clone(), Arc::new(c.build_source(&self.retry))))
             .collect()
     }
 
     /// Returns signers generated from their configuration.
+    /// Principals matching a route are split off into a separate signer using the sources of
+    /// that route, so that their keys are never retrieved from any other source.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Will panic if the given sources are missing a source configured within a signer.
-    #[must_use]
-    pub fn signers(&self, sources: &NamedSources) -> Vec<Signer> {
-        let configs = &self.signers;
-        configs
-            .iter()
-            .map(|c| {
-                Signer {
+    /// If the given sources are missing a source configured within a signer or route, which
+    /// loading the configuration rejects, but configurations constructed otherwise may contain.
+    pub fn signers(&self, sources: &NamedSources) -> Result<Vec<Signer>, UnknownSourceError> {
+        let get_sources = |signer: &str, names: &[String]| {
+            names
+                .iter()
+                .map(|name| {
+                    sources
+                        .get(name)
+                        .cloned()
+                        .ok_or_else(|| UnknownSourceError {
+                            signer: signer.to_string(),
+                            source_name: name.clone(),
+                        })
+                })
+                .collect::<Result<Vec<Arc<Box<dyn Source>>>, _>>()
+        };
+
+        let mut signers = Vec::new();
+        for c in &self.signers {
+            if !c.enabled {
+                debug!("Skipping disabled signer {}", c.name);
+                continue;
+            }
+            // Principals grouped by the names of the sources their keys are retrieved from.
+            let mut groups: Vec<(&[String], Vec<String>)> = Vec::new();
+            if c.saml_organization.is_some() {
+                // The SAML identity is retrieved using the signers own sources.
+                groups.push((c.source_names.as_slice(), Vec::new()));
+            }
+            for principal in &c.principals {
+                let source_names = self
+                    .route_sources(principal)
+                    .unwrap_or(c.source_names.as_slice());
+                match groups.iter_mut().find(|(names, _)| *names == source_names) {
+                    Some((_, principals)) => principals.push(principal.clone()),
+                    None => groups.push((source_names, vec![principal.clone()])),
+                }
+            }
+            for (source_names, principals) in groups {
+                signers.push(Signer {
                     name: c.name.clone(),
-                    principals: c.principals.clone(),
-                    sources: c
-                        .source_names
+                    principals,
+                    sources: get_sources(&c.name, source_names)?,
+                    source_names: source_names.to_vec(),
+                    source_mode: c.source_mode,
+                    resolve_email: c.resolve_email,
+                    saml_organization: c
+                        .saml_organization
+                        .clone()
+                        .filter(|_| source_names == c.source_names.as_slice()),
+                    allowed_fingerprints: c.allowed_fingerprints.clone(),
+                    denied_fingerprints: self
+                        .denied_fingerprints
                         .iter()
-                        .map(|name| {
-                            sources
-                                .get(name)
-                                .expect("signer references source that does not exist, config not validated correctly")
-                                .clone()
-                        })
+                        .chain(&c.denied_fingerprints)
+                        .cloned()
+                        .chain(self.revocation.keys.iter().map(RevokedKey::fingerprint))
                         .collect(),
-                }
+                    strict_fingerprints: c.strict_fingerprints,
+                    expected_keys: c.expected_keys,
+                    key_title_pattern: c.key_title_pattern.clone(),
+                    cache: None,
+                    comment: c.comment.clone(),
+                    key_validation: self.output.validate_keys,
+                    tolerate_connection_errors: false,
+                    warn_expiry_within: self.output.warn_expiry_within,
+                });
+            }
+        }
+        Ok(signers)
+    }
+
+    /// The names of the sources of the first route matching the given principal, if any.
+    fn route_sources(&self, principal: &str) -> Option<&[String]> {
+        self.routes
+            .iter()
+            .find(|r| {
+                // Domains are case insensitive, so the suffix is matched ignoring ASCII case.
+                principal
+                    .len()
+                    .checked_sub(r.principal_suffix.len())
+                    .and_then(|start| principal.get(start..))
+                    .is_some_and(|suffix| suffix.eq_ignore_ascii_case(&r.principal_suffix))
             })
-            .collect()
+            .map(|r| {
+                trace!(
+                    principal,
+                    suffix = %r.principal_suffix,
+                    "Principal matches route"
+                );
+                r.source_names.as_slice()
+            })
+    }
+
+    /// Returns the unique names of the configured signers, in order of configuration.
+    #[must_use]
+    pub fn signer_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for c in &self.signers {
+            if !names.contains(&c.name.as_str()) {
+                names.push(&c.name);
+            }
+        }
+        names
+    }
+
+    /// Returns the names of the configured sources, including the default sources.
+    #[must_use]
+    pub fn source_names(&self) -> Vec<&str> {
+        self.sources.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    /// Returns the options affecting the content of the allowed signers file.
+    #[must_use]
+    pub fn output(&self) -> &OutputOptions {
+        &self.output
+    }
+
+    /// Returns the commands run in response to updates.
+    #[must_use]
+    pub fn hooks(&self) -> &HooksConfiguration {
+        &self.hooks
+    }
+
+    /// Returns the options of notifying about changes to the allowed signers file.
+    #[must_use]
+    pub fn notify(&self) -> &NotifyConfiguration {
+        &self.notify
+    }
+
+    /// Returns the options of the audit log.
+    #[must_use]
+    pub fn audit(&self) -> &AuditConfiguration {
+        &self.audit
+    }
+
+    /// Returns the allowed signers file updated using this configuration when updating multiple
+    /// configurations at once, if configured.
+    #[must_use]
+    pub fn allowed_signers_file(&self) -> Option<&Path> {
+        self.allowed_signers_file.as_deref()
+    }
+
+    /// Returns the options of the key cache.
+    #[must_use]
+    pub fn cache(&self) -> &CacheConfiguration {
+        &self.cache
+    }
+
+    /// Returns the options of signing the allowed signers file.
+    #[must_use]
+    pub fn signing(&self) -> &SigningConfiguration {
+        &self.signing
+    }
+
+    /// Returns the options of revoking keys, including the keys read from the revocation file.
+    #[must_use]
+    pub fn revocation(&self) -> &RevocationConfiguration {
+        &self.revocation
     }
 
-    /// Load the configuration from a TOML file.
+    /// Returns the path of the configuration file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.file.path
+    }
+
+    /// Load the configuration from a TOML file, merging the TOML files within the drop-in directory
+    /// of the given options if any.
     /// Extends the configuration by default sources and performs semantic validation before returning.
     ///
     /// # Errors
     ///
     /// When the file fails to load or it's content is invalid.
     #[tracing::instrument]
-    pub fn load(path: &Path) -> Result<Self> {
+    pub fn load(path: &Path, options: LoadOptions) -> Result<Self> {
         let file = TomlFile::load(path.to_path_buf())?;
 
-        let mut c = Self::try_from(file)?;
-        c.add_default_sources();
-        c.validate_semantics()?;
-
-        Ok(c)
+        Self::try_from(file)?.prepare(options)
     }
 
     /// Load the configuration from a TOML file, returning a default instance if it doesn't exist.
@@ -221,8 +824,8 @@ impl Configuration {
     /// # Errors
     ///
     /// When the file at the given path has invalid content.
-    pub fn load_or_default(path: &Path) -> Result<Self> {
-        Self::load(path).or_else(|err| match err.downcast_ref::<io::Error>() {
+    pub fn load_or_default(path: &Path, options: LoadOptions) -> Result<Self> {
+        Self::load(path, options).or_else(|err| match err.downcast_ref::<io::Error>() {
             Some(io_err) if io_err.kind() == io::ErrorKind::NotFound => {
                 info!("Configuration file does not exist yet and will be created");
                 let dir = path
@@ -232,18 +835,135 @@ impl Configuration {
                     "Failed to create configuration directory {}",
                     dir.display()
                 ))?;
-                Ok(Configuration {
+                Configuration {
                     file: TomlFile {
                         path: path.to_path_buf(),
                         ..Default::default()
                     },
+                    sources: Vec::new(),
                     ..Default::default()
-                })
+                }
+                .prepare(options)
             }
             _ => Err(err),
         })
     }
 
+    /// Prepare a configuration read from file for use by merging drop-in files, adding default
+    /// sources, applying overrides and performing semantic validation.
+    fn prepare(mut self, options: LoadOptions) -> Result<Self> {
+        if let Some(drop_ins) = options.drop_ins {
+            self.merge_drop_ins(drop_ins)?;
+        }
+        self.add_default_sources();
+        self.apply_env_overrides(env::vars())?;
+        self.resolve_token_references(|var| env::var(var).ok())?;
+        if options.conventional_tokens {
+            self.apply_conventional_tokens(|var| env::var(var).ok());
+        }
+        self.read_revocation_file()?;
+        self.merge_principals()?;
+        self.default_principal(git_user_email);
+        self.validate_semantics()?;
+
+        Ok(self)
+    }
+
+    /// Add the keys within the revocation file, if configured, to the revoked keys.
+    fn read_revocation_file(&mut self) -> Result<()> {
+        if let Some(path) = &self.revocation.file {
+            let keys = revocation::read_file(path)?;
+            self.revocation.keys.extend(keys);
+        }
+        Ok(())
+    }
+
+    /// Merge all TOML files within the given directory into the configuration in lexical order.
+    /// Drop-in files may add signers, sources, principals and routes. A source may only be
+    /// redefined using identical settings, while signers and the principals of a signer may only
+    /// be defined within a single file. Files that fail to parse are skipped with a warning if
+    /// requested, other errors are never skipped.
+    fn merge_drop_ins(&mut self, drop_ins: DropIns) -> Result<()> {
+        let dir = drop_ins.dir;
+        let mut paths = fs::read_dir(dir)
+            .and_then(|entries| {
+                entries
+                    .map(|e| e.map(|e| e.path()))
+                    .collect::<io::Result<Vec<_>>>()
+            })
+            .context(format!(
+                "Failed to read configuration directory {}",
+                dir.display()
+            ))?;
+        paths.retain(|path| path.extension().is_some_and(|ext| ext == "toml"));
+        paths.sort();
+
+        // The file each signer is defined in, to name both files of a redefined signer.
+        let mut signer_files: HashMap<String, PathBuf> = self
+            .signers
+            .iter()
+            .map(|signer| (signer.name.clone(), self.file.path.clone()))
+            .collect();
+        for path in paths {
+            debug!(path = %path.display(), "Merging drop-in configuration file");
+            let drop_in = match fs::read_to_string(&path)
+                .map_err(Error::from)
+                .and_then(|content| Ok(toml_edit::de::from_str::<DropInConfiguration>(&content)?))
+                .context(format!("Invalid configuration file {}", path.display()))
+            {
+                Ok(drop_in) => drop_in,
+                Err(err) if drop_ins.skip_invalid => {
+                    warning!(
+                        Warning::InvalidConfigSkipped,
+                        "Skipping configuration file: {err:#}"
+                    );
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            for source in drop_in.sources {
+                match self.sources.iter().find(|c| c.name == source.name) {
+                    Some(existing) if *existing != source => bail!(
+                        "Source {} redefined with conflicting settings in {}",
+                        source.name,
+                        path.display()
+                    ),
+                    Some(_) => {}
+                    None => self.sources.push(source),
+                }
+            }
+            for (name, principals) in drop_in.principals {
+                if self.principals.contains_key(&name) {
+                    bail!(
+                        "Principals of signer {name} redefined in {}",
+                        path.display()
+                    )
+                }
+                self.principals.insert(name, principals);
+            }
+            for signer in &drop_in.signers {
+                if let Some(existing) = signer_files.get(&signer.name) {
+                    bail!(
+                        "Signer {} defined in both {} and {}",
+                        signer.name,
+                        existing.display(),
+                        path.display()
+                    )
+                }
+            }
+            signer_files.extend(
+                drop_in
+                    .signers
+                    .iter()
+                    .map(|signer| (signer.name.clone(), path.clone())),
+            );
+            self.signers.extend(drop_in.signers);
+            self.routes.extend(drop_in.routes);
+        }
+        Ok(())
+    }
+
     /// Save the configuration back to file.
     ///
     /// # Errors
@@ -253,20 +973,48 @@ impl Configuration {
         self.file.save()
     }
 
+    /// The content saving the configuration would write to it's file, without writing it.
+    #[must_use]
+    pub fn saved_content(&self) -> String {
+        self.file.document.to_string()
+    }
+
     /// Perform semantic validation of the configuration.
     fn validate_semantics(&self) -> Result<()> {
         trace!(?self, "Validating configuration semantics");
 
+        self.check_source_names_unique()?;
         self.check_sources_exist(
             self.signers
                 .iter()
-                .flat_map(|c| c.source_names.iter().map(String::as_str)),
+                .flat_map(|c| c.source_names.iter().map(String::as_str))
+                .chain(
+                    self.routes
+                        .iter()
+                        .flat_map(|c| c.source_names.iter().map(String::as_str)),
+                ),
         )?;
         self.check_signers_have_one_or_more_principals()?;
+        for config in &self.signers {
+            check_principals(&config.name, &config.principals)?;
+        }
+        self.check_resolved_signers_are_emails()?;
+        self.check_sources_have_provider_options()?;
 
         Ok(())
     }
 
+    /// Check that no two sources share the same name, since only one of them could be used.
+    fn check_source_names_unique(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        for config in &self.sources {
+            if !names.insert(config.name.as_str()) {
+                bail!("Source {} is configured multiple times", config.name)
+            }
+        }
+        Ok(())
+    }
+
     /// Check if the given sources exist, returning an error if not.
     fn check_sources_exist<'a>(
         &self,
@@ -286,15 +1034,123 @@ impl Configuration {
         Ok(())
     }
 
-    /// Check that all signers have at least one principal configured.
+    /// Check that all sources configure the options required by their provider and no options
+    /// unsupported by it.
+    fn check_sources_have_provider_options(&self) -> Result<()> {
+        for config in &self.sources {
+            match config.provider {
+                SourceType::Command => {
+                    if config
+                        .command
+                        .as_deref()
+                        .is_none_or(|c| c.trim().is_empty())
+                    {
+                        bail!("Source {} missing command", config.name)
+                    }
+                    if config.url.is_some() {
+                        bail!("Source {} does not support a url", config.name)
+                    }
+                    if config.token.is_some() || !config.tokens.is_empty() {
+                        bail!("Source {} does not support a token", config.name)
+                    }
+                    if config.protocol.is_some() {
+                        bail!("Source {} does not support a protocol", config.name)
+                    }
+                    if !config.auth_headers.is_empty() {
+                        bail!("Source {} does not support auth_headers", config.name)
+                    }
+                }
+                SourceType::Url => {
+                    match &config.url {
+                        None => bail!("Source {} missing url", config.name),
+                        Some(url) if !PlainUrl::is_template(url) => {
+                            bail!(
+                                "Source {} url missing {{username}} placeholder",
+                                config.name
+                            )
+                        }
+                        Some(_) => {}
+                    }
+                    if config.command.is_some() {
+                        bail!("Source {} does not support a command", config.name)
+                    }
+                    if config.token.is_some() || !config.tokens.is_empty() {
+                        bail!("Source {} does not support a token", config.name)
+                    }
+                }
+                SourceType::Github | SourceType::Gitlab => {
+                    if config.url.is_none() {
+                        bail!("Source {} missing url", config.name)
+                    }
+                    if config.command.is_some() {
+                        bail!("Source {} does not support a command", config.name)
+                    }
+                    if config.provider == SourceType::Gitlab && !config.tokens.is_empty() {
+                        bail!("Source {} does not support multiple tokens", config.name)
+                    }
+                    if config.token.is_some() && !config.tokens.is_empty() {
+                        bail!(
+                            "Source {} has both token and tokens configured",
+                            config.name
+                        )
+                    }
+                }
+            }
+            if config.signing_only.is_some() && config.provider != SourceType::Gitlab {
+                bail!("Source {} does not support signing_only", config.name)
+            }
+            if let Some(accept) = &config.accept {
+                if !matches!(config.provider, SourceType::Github | SourceType::Gitlab) {
+                    bail!("Source {} does not support accept", config.name)
+                }
+                if HeaderValue::from_str(accept).is_err() {
+                    bail!("Source {} has invalid accept header {accept}", config.name)
+                }
+            }
+            if let Err(name) = source::auth_headers(&config.auth_headers) {
+                bail!("Source {} has invalid auth header {name}", config.name)
+            }
+            if let Some(url) = &config.url {
+                match url.scheme() {
+                    "https" => {}
+                    "http" => warning!(
+                        Warning::PlainHttpToken,
+                        "Source {} uses plain http, transmitting it's token unencrypted",
+                        config.name
+                    ),
+                    scheme => bail!(
+                        "Source {} url has unsupported scheme {scheme}, expected http or https",
+                        config.name
+                    ),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that all signers have at least one principal configured, unless their principal
+    /// is taken from their SAML identity.
     fn check_signers_have_one_or_more_principals(&self) -> Result<()> {
         for config in &self.signers {
-            if config.principals.is_empty() {
+            if config.principals.is_empty() && config.saml_organization.is_none() {
                 bail!("Signer {} missing principals", config.name)
             }
         }
         Ok(())
     }
+
+    /// Check that all signers resolving their name as an email address are named by one.
+    fn check_resolved_signers_are_emails(&self) -> Result<()> {
+        for config in &self.signers {
+            if config.resolve_email && !config.name.contains('@') {
+                bail!(
+                    "Signer {} resolves it's name as email address, but it is not one",
+                    config.name
+                )
+            }
+        }
+        Ok(())
+    }
 }
 
 /// The type of source.
@@ -303,65 +1159,395 @@ impl Configuration {
 pub enum SourceType {
     Github,
     Gitlab,
+    Command,
+    Url,
 }
 
-#[must_use]
-pub fn default_user_source() -> Vec<String> {
-    vec!["github".to_string()]
-}
-
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
-#[serde(default, deny_unknown_fields)]
-pub struct SignerConfiguration {
-    pub name: String,
-    pub principals: Vec<String>,
-    #[serde(rename = "sources")]
-    pub source_names: Vec<String>,
-}
+impl SourceType {
+    /// All supported providers.
+    pub fn all() -> impl Iterator<Item = SourceType> {
+        <Self as clap::ValueEnum>::value_variants().iter().copied()
+    }
 
-impl Default for SignerConfiguration {
-    fn default() -> Self {
-        Self {
-            name: String::default(),
-            principals: Vec::default(),
-            source_names: default_user_source(),
+    /// The identifier of the provider as used in configuration.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            SourceType::Github => "github",
+            SourceType::Gitlab => "gitlab",
+            SourceType::Command => "command",
+            SourceType::Url => "url",
         }
     }
-}
 
-/// The representation of a [`Source`] in configuration.
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
-#[serde(deny_unknown_fields)]
-struct SourceConfiguration {
+    /// The URL of the providers canonical public instance, if it has one.
+    /// Without the `public-providers` feature, no provider has a default URL.
+    ///
+    /// # Panics
+    ///
+    /// Never, since all default URLs are valid.
+    #[must_use]
+    pub fn default_url(self) -> Option<Url> {
+        match self {
+            #[cfg(feature = "public-providers")]
+            SourceType::Github => Some("https://api.github.com".parse().unwrap()),
+            #[cfg(feature = "public-providers")]
+            SourceType::Gitlab => Some("https://gitlab.com".parse().unwrap()),
+            _ => None,
+        }
+    }
+
+    /// Well known hosts, the provider serving them and the URL of their API if it is served by
+    /// another host.
+    const KNOWN_HOSTS: &'static [(&'static str, SourceType, Option<&'static str>)] = &[
+        (
+            "github.com",
+            SourceType::Github,
+            Some("https://api.github.com"),
+        ),
+        ("api.github.com", SourceType::Github, None),
+        ("gitlab.com", SourceType::Gitlab, None),
+    ];
+
+    /// The hosts known to be served by this provider.
+    pub fn known_hosts(self) -> impl Iterator<Item = &'static str> {
+        Self::KNOWN_HOSTS
+            .iter()
+            .filter(move |(_, provider, _)| *provider == self)
+            .map(|(host, _, _)| *host)
+    }
+
+    /// Detect the provider of a source by the host of it's URL, if it is well known.
+    #[must_use]
+    pub fn detect(url: &Url) -> Option<Self> {
+        let host = url.host_str()?;
+        Self::KNOWN_HOSTS
+            .iter()
+            .find(|(known, _, _)| known.eq_ignore_ascii_case(host))
+            .map(|(_, provider, _)| *provider)
+    }
+
+    /// The URL of the API of a source of this provider at the given URL, which differs for well
+    /// known hosts whose API is served by another host, e.g. `https://github.com` whose API is
+    /// served by `https://api.github.com`. Other URLs are returned unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Never, since all API URLs of well known hosts are valid.
+    #[must_use]
+    pub fn api_url(self, url: Url) -> Url {
+        let Some(host) = url.host_str() else {
+            return url;
+        };
+        Self::KNOWN_HOSTS
+            .iter()
+            .find(|(known, provider, _)| *provider == self && known.eq_ignore_ascii_case(host))
+            .and_then(|(_, _, api_url)| api_url.map(|u| u.parse().unwrap()))
+            .unwrap_or(url)
+    }
+
+    /// Whether sources of this provider support authenticating using a token.
+    #[must_use]
+    pub fn supports_authentication(self) -> bool {
+        match self {
+            SourceType::Github | SourceType::Gitlab => true,
+            SourceType::Command | SourceType::Url => false,
+        }
+    }
+
+    /// The environment variable conventionally holding a token for the public instance of this
+    /// provider, e.g. within CI. GitLab CI's `CI_JOB_TOKEN` is not used, since job tokens are not
+    /// accepted by the endpoints keys are retrieved from.
+    #[must_use]
+    pub fn conventional_token_var(self) -> Option<&'static str> {
+        match self {
+            SourceType::Github => Some("GITHUB_TOKEN"),
+            SourceType::Gitlab => Some("GITLAB_TOKEN"),
+            SourceType::Command | SourceType::Url => None,
+        }
+    }
+
+    /// Whether sources of this provider follow paginated responses.
+    #[must_use]
+    pub fn supports_pagination(self) -> bool {
+        match self {
+            SourceType::Github | SourceType::Gitlab => true,
+            SourceType::Command | SourceType::Url => false,
+        }
+    }
+}
+
+/// The email address of the user as configured within Git, if the `detect-allowed-signers` feature
+/// is enabled. Unit tests never read it, so that they don't depend on the Git configuration of the
+/// machine running them.
+fn git_user_email() -> Option<String> {
+    #[cfg(all(feature = "detect-allowed-signers", not(test)))]
+    if let Ok(file) = gix_config::File::from_globals() {
+        return file.string("user.email").map(|email| email.to_string());
+    }
+
+    None
+}
+
+/// Whether a signer is enabled, omitting the default from serialized signers.
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_enabled(enabled: &bool) -> bool {
+    *enabled
+}
+
+#[must_use]
+pub fn default_user_source() -> Vec<String> {
+    vec!["github".to_string()]
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SignerConfiguration {
+    pub name: String,
+    /// Whether the signer is used, allowing to suspend it without removing it's configuration.
+    #[serde(skip_serializing_if = "is_enabled")]
+    pub enabled: bool,
+    pub principals: Vec<String>,
+    #[serde(rename = "sources")]
+    pub source_names: Vec<String>,
+    pub source_mode: SourceMode,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub resolve_email: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub saml_organization: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_fingerprints: Vec<Fingerprint>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub denied_fingerprints: Vec<Fingerprint>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub strict_fingerprints: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_keys: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_title_pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+impl Default for SignerConfiguration {
+    fn default() -> Self {
+        Self {
+            name: String::default(),
+            enabled: true,
+            principals: Vec::default(),
+            source_names: default_user_source(),
+            source_mode: SourceMode::default(),
+            resolve_email: false,
+            saml_organization: None,
+            allowed_fingerprints: Vec::default(),
+            denied_fingerprints: Vec::default(),
+            strict_fingerprints: false,
+            expected_keys: None,
+            key_title_pattern: None,
+            comment: None,
+        }
+    }
+}
+
+/// A drop-in configuration file contributing to the main configuration.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DropInConfiguration {
+    signers: Vec<SignerConfiguration>,
+    sources: Vec<SourceConfiguration>,
+    principals: HashMap<String, Vec<String>>,
+    routes: Vec<RouteConfiguration>,
+}
+
+/// Restricts the sources the keys of principals matching the route are retrieved from.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RouteConfiguration {
+    /// The suffix of matching principals, e.g. `@acme.corp`.
+    pub principal_suffix: String,
+    /// The sources used for matching principals instead of the sources of their signer.
+    #[serde(rename = "sources")]
+    pub source_names: Vec<String>,
+}
+
+/// Commands run in response to updates.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfiguration {
+    /// A shell command run after the allowed signers file has changed.
+    pub post_update: Option<String>,
+}
+
+/// Options of notifying about changes to the allowed signers file.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotifyConfiguration {
+    /// A URL the added and removed entries are posted to as JSON whenever the file changes.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_url",
+        deserialize_with = "deserialize_url"
+    )]
+    pub webhook: Option<Url>,
+}
+
+/// Options of the audit log recording changes to the allowed signers file.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuditConfiguration {
+    /// A JSON lines file records of added and removed entries are appended to.
+    pub log: Option<PathBuf>,
+}
+
+/// Options of the cache revalidating previously retrieved keys.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfiguration {
+    /// A directory retrieved keys are cached in.
+    pub dir: Option<PathBuf>,
+}
+
+/// Options of signing the allowed signers file.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SigningConfiguration {
+    /// A private SSH key a detached signature of the allowed signers file is created with.
+    pub key: Option<PathBuf>,
+}
+
+/// Options of revoking keys, which are denied for all signers.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct RevocationConfiguration {
+    /// Revoked keys, given by their SHA256 fingerprint or public key.
+    pub keys: Vec<RevokedKey>,
+    /// A file containing further revoked keys, one per line.
+    pub file: Option<PathBuf>,
+    /// A key revocation list all revoked keys are exported to.
+    pub krl: Option<PathBuf>,
+}
+
+/// The representation of a [`Source`] in configuration.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct SourceConfiguration {
     name: String,
     provider: SourceType,
-    #[serde(serialize_with = "serialize_url", deserialize_with = "deserialize_url")]
-    url: Url,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_url",
+        deserialize_with = "deserialize_url"
+    )]
+    url: Option<Url>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token: Option<Token>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tokens: Vec<Token>,
+    /// Headers sent along with every request by name, e.g. the service credentials of a
+    /// zero-trust proxy in front of the source, separately from it's API token.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    auth_headers: BTreeMap<String, Token>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_concurrent_requests: Option<NonZeroUsize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    protocol: Option<Protocol>,
+    /// Only treat keys used exclusively for signing as signing keys, excluding keys used for
+    /// authentication and signing. Only supported by GitLab sources.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signing_only: Option<bool>,
+    /// The media type requested in the `Accept` header instead of the providers default. Only
+    /// supported by GitHub and GitLab sources.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    accept: Option<String>,
+}
+
+/// Check that none of the given principals of a signer contain a comma or whitespace, which
+/// separate principals and fields of the allowed signers file and would silently corrupt it's
+/// entries.
+fn check_principals(signer: &str, principals: &[String]) -> Result<()> {
+    for principal in principals {
+        if principal.contains(|c: char| c == ',' || c.is_whitespace()) {
+            bail!(
+                "Signer {signer} has invalid principal {principal:?}, principals may not contain commas or whitespace"
+            )
+        }
+    }
+    Ok(())
 }
 
-fn deserialize_url<'de, D>(deserializer: D) -> Result<Url, D::Error>
+fn deserialize_url<'de, D>(deserializer: D) -> Result<Option<Url>, D::Error>
 where
     D: Deserializer<'de>,
 {
     let s = String::deserialize(deserializer)?;
     let url = reqwest::Url::parse(&s).map_err(serde::de::Error::custom)?;
-    Ok(url)
+    Ok(Some(url))
 }
 
-fn serialize_url<U, S>(url: U, serializer: S) -> Result<S::Ok, S::Error>
+#[allow(clippy::ref_option)]
+fn serialize_url<S>(url: &Option<Url>, serializer: S) -> Result<S::Ok, S::Error>
 where
-    U: AsRef<str>,
     S: Serializer,
 {
-    serializer.serialize_str(url.as_ref())
+    match url {
+        Some(url) => serializer.serialize_str(url.as_str()),
+        None => serializer.serialize_none(),
+    }
 }
 
 impl SourceConfiguration {
-    fn build_source(&self) -> Box<dyn Source> {
-        let url = self.url.clone();
-        match self.provider {
-            SourceType::Github => Box::new(Github::new(url)),
-            SourceType::Gitlab => Box::new(Gitlab::new(url)),
+    /// Build the configured source, retrying failed requests according to the given policy.
+    fn build_source(&self, retry: &RetryPolicy) -> Box<dyn Source> {
+        let url = || {
+            self.url
+                .clone()
+                .expect("source missing url, config not validated correctly")
+        };
+        let tokens = self.token.iter().chain(&self.tokens).cloned().collect();
+        let auth_headers = source::auth_headers(&self.auth_headers)
+            .expect("invalid auth header, config not validated correctly");
+        let source: Box<dyn Source> = match self.provider {
+            SourceType::Github => {
+                let mut github = Github::with_tokens(url(), tokens)
+                    .with_protocol(self.protocol.unwrap_or_default())
+                    .with_auth_headers(auth_headers);
+                if let Some(accept) = &self.accept {
+                    github = github.with_accept(accept.clone());
+                }
+                Box::new(github)
+            }
+            SourceType::Gitlab => {
+                let mut gitlab = Gitlab::new(url(), self.token.clone())
+                    .with_protocol(self.protocol.unwrap_or_default())
+                    .with_signing_only(self.signing_only.unwrap_or_default())
+                    .with_auth_headers(auth_headers);
+                if let Some(accept) = &self.accept {
+                    gitlab = gitlab.with_accept(accept.clone());
+                }
+                Box::new(gitlab)
+            }
+            SourceType::Url => Box::new(
+                PlainUrl::new(url())
+                    .with_protocol(self.protocol.unwrap_or_default())
+                    .with_auth_headers(auth_headers),
+            ),
+            SourceType::Command => Box::new(
+                Command::new(self.command.as_deref().unwrap_or_default())
+                    .expect("source missing command, config not validated correctly"),
+            ),
+        };
+        let source: Box<dyn Source> = match self.max_concurrent_requests {
+            Some(max) => Box::new(ConcurrencyLimited::new(source, max)),
+            None => source,
+        };
+        // Retrying outside of the concurrency limit keeps waiting retries from holding a permit.
+        if retry.max_attempts.get() > 1 {
+            Box::new(Retrying::new(source, retry.clone()))
+        } else {
+            source
         }
     }
 }
@@ -369,7 +1555,8 @@ impl SourceConfiguration {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use indoc::indoc;
+    use crate::allowed_signers::ssh::PublicKey;
+    use indoc::{formatdoc, indoc};
     use rstest::*;
     use std::io::Write;
     use tempfile::{NamedTempFile, TempDir};
@@ -384,6 +1571,7 @@ mod tests {
     }
 
     /// When loading a configuration, the returned instance always contains the default sources.
+    #[cfg(feature = "public-providers")]
     #[rstest]
     #[case(
         indoc!{r#"
@@ -398,12 +1586,45 @@ mod tests {
     ) {
         writeln!(tmp_config_toml, "{config}").unwrap();
 
-        let config = Configuration::load(tmp_config_toml.path()).unwrap();
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap();
         for default_source in Configuration::default_sources() {
             assert!(config.sources.contains(&default_source));
         }
     }
 
+    /// Without the `public-providers` feature, no default sources are added, such that signers
+    /// using them fail to load.
+    #[cfg(not(feature = "public-providers"))]
+    #[rstest]
+    fn loaded_configuration_has_no_default_sources_without_public_providers(
+        mut tmp_config_toml: NamedTempFile,
+    ) {
+        writeln!(
+            tmp_config_toml,
+            "{}",
+            indoc! {r#"
+                signers = [
+                    { name = "torvalds", principals = ["torvalds@linux-foundation.org"], sources = ["github"] },
+                ]
+            "#}
+        )
+        .unwrap();
+
+        let err = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap_err();
+
+        assert_eq!(err.to_string(), "Missing sources: github");
+    }
+
+    /// The public providers have a default URL only with the `public-providers` feature.
+    #[rstest]
+    #[case(SourceType::Github, cfg!(feature = "public-providers"))]
+    #[case(SourceType::Gitlab, cfg!(feature = "public-providers"))]
+    #[case(SourceType::Command, false)]
+    #[case(SourceType::Url, false)]
+    fn provider_default_url(#[case] provider: SourceType, #[case] expected: bool) {
+        assert_eq!(provider.default_url().is_some(), expected);
+    }
+
     /// When loading configuration from a path that doesn't exist without using the
     /// explicit `load_or_default` constructor, an error is returned.
     #[rstest]
@@ -412,12 +1633,25 @@ mod tests {
         let path = tmpdir.path().join("config.toml");
         assert!(!path.exists());
 
-        let err = Configuration::load(&path).unwrap_err();
+        let err = Configuration::load(&path, LoadOptions::default()).unwrap_err();
 
         assert_eq!(
             err.downcast_ref::<io::Error>().unwrap().kind(),
             io::ErrorKind::NotFound
         );
+        assert!(err.to_string().contains("hanko signer add"));
+    }
+
+    /// Loading configuration that is not valid TOML returns an error containing the location of
+    /// the parse error.
+    #[rstest]
+    fn loading_invalid_toml_returns_error_with_location(mut tmp_config_toml: NamedTempFile) {
+        writeln!(tmp_config_toml, "signers = [\n  {{ name = }}\n]").unwrap();
+
+        let err = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap_err();
+
+        assert!(err.to_string().contains("is not valid TOML"));
+        assert!(format!("{err:#}").contains("line 2, column 12"));
     }
 
     /// Loading configuration missing sources returns an appropriate error.
@@ -453,68 +1687,1270 @@ mod tests {
         expected_missing.sort();
         writeln!(tmp_config_toml, "{config}").unwrap();
 
-        let err = Configuration::load(tmp_config_toml.path()).unwrap_err();
+        let err = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            format!("Missing sources: {}", expected_missing.join(", "))
+        );
+    }
+
+    /// Loading configuration containing a signer without at least one principal returns an appropriate error.
+    #[cfg(feature = "public-providers")]
+    #[rstest]
+    #[case(
+        indoc!{r#"
+            [[signers]]
+            name = "octocat"
+        "#},
+    )]
+    #[case(
+        indoc!{r#"
+            [[signers]]
+            name = "octocat"
+            principals = []
+        "#},
+    )]
+    fn loading_configuration_with_signer_missing_principal_returns_error(
+        mut tmp_config_toml: NamedTempFile,
+        #[case] config: &str,
+    ) {
+        writeln!(tmp_config_toml, "{config}").unwrap();
+
+        let err = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap_err();
+
+        assert_eq!(err.to_string(), "Signer octocat missing principals");
+    }
+
+    /// Principals containing the separators of the allowed signers file are rejected.
+    #[rstest]
+    #[case("octocat@github.com,admin@github.com")]
+    #[case("octo cat@github.com")]
+    fn loading_configuration_with_malformed_principal_returns_error(
+        mut tmp_config_toml: NamedTempFile,
+        #[case] principal: &str,
+    ) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                [[signers]]
+                name = "octocat"
+                principals = ["{}"]
+                sources = ["acme-corp"]
+
+                [[sources]]
+                name = "acme-corp"
+                provider = "gitlab"
+                url = "https://git.acme.corp"
+            "#},
+            principal
+        )
+        .unwrap();
+
+        let err = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            format!("Signer octocat has invalid principal {principal:?}, principals may not contain commas or whitespace")
+        );
+    }
+
+    /// Signers without principals are valid if their principal is taken from their SAML identity.
+    #[cfg(feature = "public-providers")]
+    #[rstest]
+    fn signer_with_saml_organization_does_not_require_principals(
+        mut tmp_config_toml: NamedTempFile,
+    ) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                [[signers]]
+                name = "octocat"
+                saml_organization = "acme"
+            "#}
+        )
+        .unwrap();
+
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap();
+        let signers = config.signers(&config.sources()).unwrap();
+
+        assert_eq!(signers.len(), 1);
+        assert!(signers[0].principals.is_empty());
+        assert_eq!(signers[0].saml_organization.as_deref(), Some("acme"));
+    }
+
+    /// Signers resolving their name as an email address need to be named by one.
+    #[cfg(feature = "public-providers")]
+    #[rstest]
+    #[case("octocat@github.com", true)]
+    #[case("octocat", false)]
+    fn signer_resolving_email_requires_email_name(
+        mut tmp_config_toml: NamedTempFile,
+        #[case] name: &str,
+        #[case] valid: bool,
+    ) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                [[signers]]
+                name = "{}"
+                principals = ["octocat@github.com"]
+                resolve_email = true
+            "#},
+            name
+        )
+        .unwrap();
+
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default());
+
+        match config {
+            Ok(config) => {
+                assert!(valid);
+                assert!(config.signers(&config.sources()).unwrap()[0].resolve_email);
+            }
+            Err(err) => {
+                assert!(!valid);
+                assert_eq!(
+                    err.to_string(),
+                    "Signer octocat resolves it's name as email address, but it is not one"
+                );
+            }
+        }
+    }
+
+    /// Allowed fingerprints of a signer are loaded, while invalid fingerprints return an error.
+    #[cfg(feature = "public-providers")]
+    #[rstest]
+    #[case(r#"["SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM"]"#, true)]
+    #[case(r#"["MD5:1f:3b:9c:5a:6e:1d:2a:8b:9c:0d:4e:7f:8a:1b:2c:3d"]"#, false)]
+    fn signer_allowed_fingerprints_are_loaded(
+        mut tmp_config_toml: NamedTempFile,
+        #[case] fingerprints: &str,
+        #[case] valid: bool,
+    ) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                [[signers]]
+                name = "octocat"
+                principals = ["octocat@github.com"]
+                allowed_fingerprints = {}
+            "#},
+            fingerprints
+        )
+        .unwrap();
+
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default());
+
+        assert_eq!(config.is_ok(), valid);
+        if valid {
+            assert_eq!(
+                config.unwrap().signers[0].allowed_fingerprints,
+                vec!["SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM"
+                    .parse()
+                    .unwrap()]
+            );
+        }
+    }
+
+    /// Output options are loaded from the `output` table, defaulting to not stripping key comments.
+    #[rstest]
+    #[case("", false)]
+    #[case(indoc!{r"
+        [output]
+        strip_key_comments = true
+    "}, true)]
+    fn output_options_are_loaded(
+        mut tmp_config_toml: NamedTempFile,
+        #[case] config: &str,
+        #[case] expected: bool,
+    ) {
+        writeln!(tmp_config_toml, "{config}").unwrap();
+
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap();
+
+        assert_eq!(config.output().strip_key_comments, expected);
+    }
+
+    /// The retry policy is loaded, while unknown error classes and a maximum of zero attempts
+    /// return an error.
+    #[rstest]
+    #[case(indoc!{r#"
+        [retry]
+        retryable = ["connection", "client_4xx", "user_not_found"]
+        max_attempts = 5
+        backoff = "500ms"
+    "#}, true)]
+    #[case(indoc!{r#"
+        [retry]
+        retryable = ["bad_credentials"]
+    "#}, false)]
+    #[case(indoc!{r"
+        [retry]
+        max_attempts = 0
+    "}, false)]
+    fn retry_policy_is_loaded(
+        mut tmp_config_toml: NamedTempFile,
+        #[case] config: &str,
+        #[case] valid: bool,
+    ) {
+        use crate::RetryClass;
+        use std::num::NonZeroU32;
+
+        writeln!(tmp_config_toml, "{config}").unwrap();
+
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default());
+
+        assert_eq!(config.is_ok(), valid);
+        if let Ok(config) = config {
+            assert_eq!(
+                config.retry,
+                RetryPolicy {
+                    retryable: vec![
+                        RetryClass::Connection,
+                        RetryClass::Client4xx,
+                        RetryClass::UserNotFound
+                    ],
+                    max_attempts: NonZeroU32::new(5).unwrap(),
+                    backoff: "500ms".parse().unwrap(),
+                }
+            );
+        }
+    }
+
+    /// Connection pool options are loaded from the `pool` table, defaulting to those of reqwest.
+    #[rstest]
+    #[case("", PoolOptions::default())]
+    #[case(
+        indoc! {r#"
+            [pool]
+            idle_timeout = "5m"
+            max_idle_per_host = 4
+        "#},
+        PoolOptions { idle_timeout: "5m".parse().unwrap(), max_idle_per_host: Some(4) }
+    )]
+    fn pool_options_are_loaded(
+        mut tmp_config_toml: NamedTempFile,
+        #[case] config: &str,
+        #[case] expected: PoolOptions,
+    ) {
+        writeln!(tmp_config_toml, "{config}").unwrap();
+
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap();
+
+        assert_eq!(config.pool, expected);
+    }
+
+    /// Principals configured in the principals table are merged into the corresponding signers.
+    #[cfg(feature = "public-providers")]
+    #[rstest]
+    fn principals_table_merged_into_signers(mut tmp_config_toml: NamedTempFile) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                [[signers]]
+                name = "octocat"
+
+                [[signers]]
+                name = "torvalds"
+                principals = ["torvalds@linux-foundation.org"]
+
+                [principals]
+                octocat = ["octocat@github.com", "octocat@users.noreply.github.com"]
+            "#}
+        )
+        .unwrap();
+
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap();
+        let signers = config.signers(&config.sources()).unwrap();
+
+        assert_eq!(
+            signers
+                .iter()
+                .map(|s| (s.name.as_str(), s.principals.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "octocat",
+                    vec![
+                        "octocat@github.com".to_string(),
+                        "octocat@users.noreply.github.com".to_string()
+                    ]
+                ),
+                (
+                    "torvalds",
+                    vec!["torvalds@linux-foundation.org".to_string()]
+                ),
+            ]
+        );
+    }
+
+    /// Configuring principals of a signer both inline and in the principals table returns an error.
+    #[rstest]
+    fn principals_configured_inline_and_in_table_returns_error(mut tmp_config_toml: NamedTempFile) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                [[signers]]
+                name = "octocat"
+                principals = ["octocat@github.com"]
+
+                [principals]
+                octocat = ["octocat@users.noreply.github.com"]
+            "#}
+        )
+        .unwrap();
+
+        let err = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Signer octocat has principals configured both inline and in the principals table"
+        );
+    }
+
+    /// Principals matching a route are split off into a separate signer using the sources of that
+    /// route, while other principals keep using the sources of their signer.
+    #[cfg(feature = "public-providers")]
+    #[rstest]
+    fn principals_matching_route_use_route_sources(mut tmp_config_toml: NamedTempFile) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                signers = [
+                    {{ name = "cwoods", principals = ["cwoods@acme.corp", "cwoods@github.com"], sources = ["github"] }},
+                ]
+
+                [[sources]]
+                name = "acme-gitlab"
+                provider = "gitlab"
+                url = "https://git.acme.corp"
+
+                [[routes]]
+                principal_suffix = "@acme.corp"
+                sources = ["acme-gitlab"]
+            "#}
+        )
+        .unwrap();
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap();
+        let sources = config.sources();
+
+        let signers = config.signers(&sources).unwrap();
+
+        assert_eq!(signers.len(), 2);
+        for (signer, expected_principal, expected_source) in [
+            (&signers[0], "cwoods@acme.corp", "acme-gitlab"),
+            (&signers[1], "cwoods@github.com", "github"),
+        ] {
+            assert_eq!(signer.principals, vec![expected_principal]);
+            assert_eq!(signer.sources.len(), 1);
+            assert!(Arc::ptr_eq(&signer.sources[0], &sources[expected_source]));
+        }
+    }
+
+    /// Route suffixes are matched ignoring the case of the domain.
+    #[rstest]
+    #[case("cwoods@acme.corp", true)]
+    #[case("cwoods@ACME.Corp", true)]
+    #[case("cwoods@acme.corp.evil", false)]
+    #[case("corp", false)]
+    #[case("cwoods@äcme.corp", false)]
+    fn route_suffix_matched_case_insensitively(
+        mut tmp_config_toml: NamedTempFile,
+        #[case] principal: &str,
+        #[case] matches: bool,
+    ) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                [[sources]]
+                name = "acme-gitlab"
+                provider = "gitlab"
+                url = "https://git.acme.corp"
+
+                [[routes]]
+                principal_suffix = "@acme.corp"
+                sources = ["acme-gitlab"]
+            "#}
+        )
+        .unwrap();
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap();
+
+        assert_eq!(config.route_sources(principal).is_some(), matches);
+    }
+
+    /// The comment of a signer is passed on to the signer, including signers split up by routes.
+    #[cfg(feature = "public-providers")]
+    #[rstest]
+    fn signer_comment_passed_on(mut tmp_config_toml: NamedTempFile) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                signers = [
+                    {{ name = "cwoods", principals = ["cwoods@universal.exports"], comment = "Contractor until 2025 Q4" }},
+                    {{ name = "jsnow", principals = ["j.snow@wall.com"] }},
+                ]
+            "#}
+        )
+        .unwrap();
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap();
+
+        let signers = config.signers(&config.sources()).unwrap();
+
+        assert_eq!(
+            signers[0].comment.as_deref(),
+            Some("Contractor until 2025 Q4")
+        );
+        assert_eq!(signers[1].comment, None);
+    }
+
+    /// Globally denied fingerprints are combined with the denied fingerprints of each signer.
+    #[cfg(feature = "public-providers")]
+    #[rstest]
+    fn global_denied_fingerprints_apply_to_all_signers(mut tmp_config_toml: NamedTempFile) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                denied_fingerprints = ["SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM"]
+                signers = [
+                    {{ name = "cwoods", principals = ["cwoods@universal.exports"] }},
+                    {{ name = "jsnow", principals = ["j.snow@wall.com"], denied_fingerprints = ["SHA256:AwKAWrobAVuK0DN/1EkXEddUEKNF/soOb+gzQZClxm8"] }},
+                ]
+            "#}
+        )
+        .unwrap();
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap();
+
+        let signers = config.signers(&config.sources()).unwrap();
+
+        let denied = |signer: &Signer| -> Vec<String> {
+            signer
+                .denied_fingerprints
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        };
+        assert_eq!(
+            denied(&signers[0]),
+            vec!["SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM"]
+        );
+        assert_eq!(
+            denied(&signers[1]),
+            vec![
+                "SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM",
+                "SHA256:AwKAWrobAVuK0DN/1EkXEddUEKNF/soOb+gzQZClxm8"
+            ]
+        );
+    }
+
+    /// Keys revoked within the configuration and the revocation file are denied for all signers.
+    #[rstest]
+    fn revoked_keys_denied_for_all_signers(mut tmp_config_toml: NamedTempFile) {
+        let key =
+            "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS";
+        let revocation_file = {
+            let mut file = NamedTempFile::new().unwrap();
+            writeln!(file, "# Lost laptop\n{key} cwoods@laptop").unwrap();
+            file
+        };
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                signers = [
+                    {{ name = "cwoods", principals = ["cwoods@universal.exports"], sources = ["acme-gitlab"] }},
+                    {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["acme-gitlab"] }},
+                ]
+
+                [[sources]]
+                name = "acme-gitlab"
+                provider = "gitlab"
+                url = "https://git.acme.corp"
+
+                [revocation]
+                keys = ["SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM"]
+                file = "{}"
+            "#},
+            revocation_file.path().display()
+        )
+        .unwrap();
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap();
+
+        let signers = config.signers(&config.sources()).unwrap();
+
+        let key_fingerprint = key.parse::<PublicKey>().unwrap().fingerprint().unwrap();
+        for signer in signers {
+            assert_eq!(
+                signer.denied_fingerprints,
+                vec![
+                    "SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM"
+                        .parse()
+                        .unwrap(),
+                    key_fingerprint.clone()
+                ]
+            );
+        }
+    }
+
+    /// Loading configuration with a route referencing a source that does not exist returns an
+    /// error.
+    #[rstest]
+    fn route_with_missing_source_returns_error(mut tmp_config_toml: NamedTempFile) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                [[routes]]
+                principal_suffix = "@acme.corp"
+                sources = ["acme-gitlab"]
+            "#}
+        )
+        .unwrap();
+
+        let err = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap_err();
+
+        assert_eq!(err.to_string(), "Missing sources: acme-gitlab");
+    }
+
+    /// Drop-in files are merged in lexical order, adding signers, sources and principals.
+    #[cfg(feature = "public-providers")]
+    #[rstest]
+    fn drop_in_files_merged(mut tmp_config_toml: NamedTempFile) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                signers = [
+                    {{ name = "torvalds", principals = ["torvalds@linux-foundation.org"] }},
+                ]
+            "#}
+        )
+        .unwrap();
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("20-local.toml"),
+            indoc! {r#"
+                [[signers]]
+                name = "cwoods"
+                sources = ["acme-corp"]
+
+                [principals]
+                cwoods = ["cwoods@acme.corp"]
+            "#},
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("10-package.toml"),
+            indoc! {r#"
+                [[sources]]
+                name = "acme-corp"
+                provider = "gitlab"
+                url = "https://git.acme.corp"
+            "#},
+        )
+        .unwrap();
+        fs::write(dir.path().join("ignored.txt"), "not toml").unwrap();
+
+        let config = Configuration::load(
+            tmp_config_toml.path(),
+            LoadOptions {
+                drop_ins: Some(DropIns {
+                    dir: dir.path(),
+                    skip_invalid: false,
+                }),
+                ..LoadOptions::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(config.signer_names(), vec!["torvalds", "cwoods"]);
+        assert_eq!(config.signers[1].principals, vec!["cwoods@acme.corp"]);
+        assert!(config.source_names().contains(&"acme-corp"));
+    }
+
+    /// Drop-in files redefining a source with conflicting settings or the principals of a signer
+    /// return an error, while identical sources are accepted.
+    #[rstest]
+    #[case(
+        indoc!{r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "gitlab"
+            url = "https://git.acme.corp"
+        "#},
+        None
+    )]
+    #[case(
+        indoc!{r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "gitlab"
+            url = "https://gitlab.acme.corp"
+        "#},
+        Some("Source acme-corp redefined with conflicting settings in")
+    )]
+    #[case(
+        indoc!{r#"
+            [principals]
+            cwoods = ["cwoods@universal.exports"]
+        "#},
+        Some("Principals of signer cwoods redefined in")
+    )]
+    #[case(
+        indoc!{r#"
+            [[signers]]
+            name = "cwoods"
+            principals = ["cwoods@universal.exports"]
+        "#},
+        Some("Signer cwoods defined in both")
+    )]
+    fn drop_in_files_redefining_configuration(
+        mut tmp_config_toml: NamedTempFile,
+        #[case] drop_in: &str,
+        #[case] expected_err: Option<&str>,
+    ) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                [[signers]]
+                name = "cwoods"
+                sources = ["acme-corp"]
+
+                [principals]
+                cwoods = ["cwoods@acme.corp"]
+
+                [[sources]]
+                name = "acme-corp"
+                provider = "gitlab"
+                url = "https://git.acme.corp"
+            "#}
+        )
+        .unwrap();
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("local.toml"), drop_in).unwrap();
+
+        let result = Configuration::load(
+            tmp_config_toml.path(),
+            LoadOptions {
+                drop_ins: Some(DropIns {
+                    dir: dir.path(),
+                    skip_invalid: false,
+                }),
+                ..LoadOptions::default()
+            },
+        );
+
+        match expected_err {
+            Some(expected) => assert!(result.unwrap_err().to_string().starts_with(expected)),
+            None => assert!(result.is_ok()),
+        }
+    }
+
+    /// A signer defined within two drop-in files results in an error naming both files.
+    #[rstest]
+    fn signer_redefined_by_drop_ins_names_both_files(mut tmp_config_toml: NamedTempFile) {
+        writeln!(tmp_config_toml).unwrap();
+        let dir = TempDir::new().unwrap();
+        let signer = indoc! {r#"
+            [[signers]]
+            name = "cwoods"
+            principals = ["cwoods@acme.corp"]
+        "#};
+        fs::write(dir.path().join("10-team.toml"), signer).unwrap();
+        fs::write(dir.path().join("20-local.toml"), signer).unwrap();
+
+        let err = Configuration::load(
+            tmp_config_toml.path(),
+            LoadOptions {
+                drop_ins: Some(DropIns {
+                    dir: dir.path(),
+                    skip_invalid: false,
+                }),
+                ..LoadOptions::default()
+            },
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Signer cwoods defined in both {} and {}",
+                dir.path().join("10-team.toml").display(),
+                dir.path().join("20-local.toml").display()
+            )
+        );
+    }
+
+    /// Drop-in files that fail to parse are skipped if requested, while merging the remaining
+    /// ones. Otherwise they fail loading the configuration.
+    #[cfg(feature = "public-providers")]
+    #[rstest]
+    #[case(true)]
+    #[case(false)]
+    fn invalid_drop_in_files_skipped(mut tmp_config_toml: NamedTempFile, #[case] skip: bool) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                signers = [
+                    {{ name = "torvalds", principals = ["torvalds@linux-foundation.org"] }},
+                ]
+            "#}
+        )
+        .unwrap();
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("10-broken.toml"), "[[signers]\nname = ").unwrap();
+        fs::write(
+            dir.path().join("20-local.toml"),
+            indoc! {r#"
+                [[signers]]
+                name = "cwoods"
+                principals = ["cwoods@acme.corp"]
+            "#},
+        )
+        .unwrap();
+
+        let result = Configuration::load(
+            tmp_config_toml.path(),
+            LoadOptions {
+                drop_ins: Some(DropIns {
+                    dir: dir.path(),
+                    skip_invalid: skip,
+                }),
+                ..LoadOptions::default()
+            },
+        );
+
+        if skip {
+            assert_eq!(result.unwrap().signer_names(), vec!["torvalds", "cwoods"]);
+        } else {
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .starts_with("Invalid configuration file"));
+        }
+    }
+
+    /// Loading configuration containing a source without the options required by it's provider,
+    /// or with options it does not support, returns an appropriate error.
+    #[rstest]
+    #[case(
+        indoc!{r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "gitlab"
+        "#},
+        "Source acme-corp missing url"
+    )]
+    #[case(
+        indoc!{r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "gitlab"
+            url = "https://git.acme.corp"
+            command = "fetch-keys {username}"
+        "#},
+        "Source acme-corp does not support a command"
+    )]
+    #[case(
+        indoc!{r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "command"
+        "#},
+        "Source acme-corp missing command"
+    )]
+    #[case(
+        indoc!{r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "command"
+            command = "fetch-keys {username}"
+            url = "https://git.acme.corp"
+        "#},
+        "Source acme-corp does not support a url"
+    )]
+    #[case(
+        indoc!{r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "command"
+            command = "fetch-keys {username}"
+            protocol = "http2"
+        "#},
+        "Source acme-corp does not support a protocol"
+    )]
+    #[case(
+        indoc!{r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "github"
+            url = "https://github.acme.corp"
+            signing_only = true
+        "#},
+        "Source acme-corp does not support signing_only"
+    )]
+    #[case(
+        indoc!{r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "command"
+            command = "fetch-keys {username}"
+            auth_headers = { CF-Access-Client-Id = "hanko.access" }
+        "#},
+        "Source acme-corp does not support auth_headers"
+    )]
+    #[case(
+        indoc!{r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "gitlab"
+            url = "https://git.acme.corp"
+            auth_headers = { "CF Access" = "secret" }
+        "#},
+        "Source acme-corp has invalid auth header CF Access"
+    )]
+    #[case(
+        indoc!{r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "url"
+            url = "https://keys.acme.corp/{username}"
+            accept = "text/plain"
+        "#},
+        "Source acme-corp does not support accept"
+    )]
+    #[case(
+        indoc!{r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "gitlab"
+            url = "ftp://git.acme.corp"
+        "#},
+        "Source acme-corp url has unsupported scheme ftp, expected http or https"
+    )]
+    #[case(
+        indoc!{r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "url"
+            url = "file:///etc/keys/{username}"
+        "#},
+        "Source acme-corp url has unsupported scheme file, expected http or https"
+    )]
+    #[case(
+        indoc!{r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "url"
+            url = "https://keys.acme.corp/keys"
+        "#},
+        "Source acme-corp url missing {username} placeholder"
+    )]
+    #[case(
+        indoc!{r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "gitlab"
+            url = "https://git.acme.corp"
+            tokens = ["glpat-first", "glpat-second"]
+        "#},
+        "Source acme-corp does not support multiple tokens"
+    )]
+    #[case(
+        indoc!{r#"
+            [[sources]]
+            name = "octo-corp"
+            provider = "github"
+            url = "https://api.github.com"
+            token = "ghp_first"
+            tokens = ["ghp_second"]
+        "#},
+        "Source octo-corp has both token and tokens configured"
+    )]
+    fn loading_configuration_with_invalid_source_options_returns_error(
+        mut tmp_config_toml: NamedTempFile,
+        #[case] config: &str,
+        #[case] expected: &str,
+    ) {
+        writeln!(tmp_config_toml, "{config}").unwrap();
+
+        let err = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap_err();
+
+        assert_eq!(err.to_string(), expected);
+    }
+
+    /// A command source is loaded from configuration.
+    #[rstest]
+    fn command_source_is_loaded(mut tmp_config_toml: NamedTempFile) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                [[sources]]
+                name = "ldap"
+                provider = "command"
+                command = "fetch-keys --user {{username}}"
+            "#}
+        )
+        .unwrap();
+
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap();
+        let source = config.sources.iter().find(|s| s.name == "ldap").unwrap();
+
+        assert_eq!(source.provider, SourceType::Command);
+        assert_eq!(
+            source.command.as_deref(),
+            Some("fetch-keys --user {username}")
+        );
+    }
+
+    /// Sources sharing the same name return an error, since only one of them could be used.
+    #[rstest]
+    fn duplicate_source_names_return_error(mut tmp_config_toml: NamedTempFile) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                [[sources]]
+                name = "acme-corp"
+                provider = "gitlab"
+                url = "https://git.acme.corp"
+
+                [[sources]]
+                name = "acme-corp"
+                provider = "github"
+                url = "https://github.acme.corp"
+            "#}
+        )
+        .unwrap();
+
+        let err = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Source acme-corp is configured multiple times"
+        );
+    }
+
+    /// Configured sources named like a default source take precedence over it.
+    #[cfg(feature = "public-providers")]
+    #[rstest]
+    fn configured_source_overrides_default_source(mut tmp_config_toml: NamedTempFile) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                [[sources]]
+                name = "github"
+                provider = "github"
+                url = "https://github.acme.corp"
+            "#}
+        )
+        .unwrap();
+
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap();
+        let github: Vec<_> = config
+            .sources
+            .iter()
+            .filter(|s| s.name == "github")
+            .collect();
+
+        assert_eq!(github.len(), 1);
+        assert_eq!(
+            github[0].url,
+            Some("https://github.acme.corp".parse().unwrap())
+        );
+        assert!(config.sources.iter().any(|s| s.name == "gitlab"));
+    }
+
+    /// Sources using plain http are accepted, since some internal instances are only reachable
+    /// using http.
+    #[rstest]
+    fn http_source_is_loaded(mut tmp_config_toml: NamedTempFile) {
+        writeln!(
+            tmp_config_toml,
+            indoc! {r#"
+                [[sources]]
+                name = "acme-corp"
+                provider = "gitlab"
+                url = "http://git.acme.corp"
+            "#}
+        )
+        .unwrap();
+
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap();
+        let source = config
+            .sources
+            .iter()
+            .find(|s| s.name == "acme-corp")
+            .unwrap();
+
+        assert_eq!(source.url, Some("http://git.acme.corp".parse().unwrap()));
+    }
+
+    #[rstest]
+    #[case(
+        indoc!{r#"
+            [[signers]]
+            name = "cwoods"
+            principals = ["cwoods@acme.corp"]
+            nonsense = ["acme-corp"]
+
+            [[sources]]
+            name = "acme-corp"
+            provider = "gitlab"
+            url = "https://git.acme.corp"
+        "#},
+        "unknown field `nonsense`"
+    )]
+    fn loading_configuration_with_unknown_field_returns_error(
+        mut tmp_config_toml: NamedTempFile,
+        #[case] config: &str,
+        #[case] expected_msg: &str,
+    ) {
+        writeln!(tmp_config_toml, "{config}").unwrap();
+
+        let err = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap_err();
+
+        assert!(err.to_string().contains(expected_msg));
+    }
+
+    /// Source tokens and URLs are overridden by environment variables matching the source name.
+    #[rstest]
+    #[case(
+        vec![("HANKO_SOURCE_ACME_CORP_TOKEN", "glpat-secret")],
+        Some(Token::new("glpat-secret")),
+        "https://git.acme.corp/"
+    )]
+    #[case(
+        vec![("HANKO_SOURCE_ACME_CORP_URL", "https://gitlab.acme.corp")],
+        Some(Token::new("from-file")),
+        "https://gitlab.acme.corp/"
+    )]
+    #[case(
+        vec![("HANKO_SOURCE_UNKNOWN_TOKEN", "ignored"), ("HANKO_SOURCE_ACME_CORP_NONSENSE", "ignored")],
+        Some(Token::new("from-file")),
+        "https://git.acme.corp/"
+    )]
+    fn source_configuration_overridden_by_env(
+        #[case] vars: Vec<(&str, &str)>,
+        #[case] expected_token: Option<Token>,
+        #[case] expected_url: &str,
+    ) {
+        let toml = indoc! {r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "gitlab"
+            url = "https://git.acme.corp"
+            token = "from-file"
+        "#};
+        let mut config = Configuration::try_from(TomlFile {
+            document: toml.parse().unwrap(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        config
+            .apply_env_overrides(
+                vars.into_iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string())),
+            )
+            .unwrap();
+
+        let source = config.sources.pop().unwrap();
+        assert_eq!(source.token, expected_token);
+        assert_eq!(source.url.as_ref().unwrap().as_str(), expected_url);
+    }
+
+    /// The only signer without principals uses the default principal, while signers with
+    /// principals and multiple signers don't.
+    #[rstest]
+    #[case(r#"signers = [{ name = "octocat" }]"#, vec![vec!["octocat@github.com"]])]
+    #[case(
+        r#"signers = [{ name = "octocat", principals = ["octocat@acme.corp"] }]"#,
+        vec![vec!["octocat@acme.corp"]]
+    )]
+    #[case(
+        r#"signers = [{ name = "octocat" }, { name = "hubot" }]"#,
+        vec![vec![], vec![]]
+    )]
+    fn only_signer_without_principals_uses_default_principal(
+        #[case] toml: &str,
+        #[case] expected: Vec<Vec<&str>>,
+    ) {
+        let mut config = Configuration::try_from(TomlFile {
+            document: toml.parse().unwrap(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        config.default_principal(|| Some("octocat@github.com".to_string()));
+
+        let principals: Vec<Vec<&str>> = config
+            .signers
+            .iter()
+            .map(|s| s.principals.iter().map(String::as_str).collect())
+            .collect();
+        assert_eq!(principals, expected);
+    }
+
+    /// Sources of public providers without a token use the token of the conventional environment
+    /// variable of their provider, while sources with a token or of other instances don't.
+    #[rstest]
+    fn conventional_token_used_by_public_sources() {
+        let toml = formatdoc! {r#"
+            [[sources]]
+            name = "public-github"
+            provider = "github"
+            url = "{github}"
+
+            [[sources]]
+            name = "public-gitlab"
+            provider = "gitlab"
+            url = "{gitlab}"
+            token = "glpat-configured"
+
+            [[sources]]
+            name = "acme-github"
+            provider = "github"
+            url = "https://github.acme.corp/api/v3"
+        "#,
+            github = SourceType::Github
+                .default_url()
+                .map_or(String::from("https://api.github.com"), |url| url.to_string()),
+            gitlab = SourceType::Gitlab
+                .default_url()
+                .map_or(String::from("https://gitlab.com"), |url| url.to_string()),
+        };
+        let mut config = Configuration::try_from(TomlFile {
+            document: toml.parse().unwrap(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        config.apply_conventional_tokens(|var| match var {
+            "GITHUB_TOKEN" => Some("ghp_from_env".to_string()),
+            "GITLAB_TOKEN" => Some("glpat-from-env".to_string()),
+            _ => None,
+        });
+
+        let tokens: Vec<Option<Token>> = config.sources.iter().map(|s| s.token.clone()).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                cfg!(feature = "public-providers").then(|| Token::new("ghp_from_env")),
+                Some(Token::new("glpat-configured")),
+                None,
+            ]
+        );
+    }
+
+    /// Source tokens referring to environment variables are resolved to their values.
+    #[rstest]
+    fn source_token_references_resolved() {
+        let toml = indoc! {r#"
+            [[sources]]
+            name = "octo-corp"
+            provider = "github"
+            url = "https://api.github.com"
+            tokens = ["env:TOKEN_A", "ghp_literal"]
+        "#};
+        let mut config = Configuration::try_from(TomlFile {
+            document: toml.parse().unwrap(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        config
+            .resolve_token_references(|var| (var == "TOKEN_A").then(|| "ghp_from_env".to_string()))
+            .unwrap();
+
+        let source = config.sources.pop().unwrap();
+        assert_eq!(
+            source.tokens,
+            vec![Token::new("ghp_from_env"), Token::new("ghp_literal")]
+        );
+    }
+
+    /// Auth headers referring to environment variables are resolved to their values.
+    #[rstest]
+    fn source_auth_header_references_resolved() {
+        let toml = indoc! {r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "gitlab"
+            url = "https://git.acme.corp"
+            auth_headers = { CF-Access-Client-Id = "hanko.access", CF-Access-Client-Secret = "env:CF_SECRET" }
+        "#};
+        let mut config = Configuration::try_from(TomlFile {
+            document: toml.parse().unwrap(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        config
+            .resolve_token_references(|var| (var == "CF_SECRET").then(|| "s3cr3t".to_string()))
+            .unwrap();
 
+        let source = config.sources.pop().unwrap();
         assert_eq!(
-            err.to_string(),
-            format!("Missing sources: {}", expected_missing.join(", "))
+            source.auth_headers,
+            BTreeMap::from([
+                (
+                    "CF-Access-Client-Id".to_string(),
+                    Token::new("hanko.access")
+                ),
+                ("CF-Access-Client-Secret".to_string(), Token::new("s3cr3t")),
+            ])
         );
     }
 
-    /// Loading configuration containing a signer without at least one principal returns an appropriate error.
+    /// A webhook referring to an environment variable is resolved to it's value.
     #[rstest]
-    #[case(
-        indoc!{r#"
-            [[signers]]
-            name = "octocat"
-        "#},
-    )]
-    #[case(
-        indoc!{r#"
-            [[signers]]
-            name = "octocat"
-            principals = []
-        "#},
-    )]
-    fn loading_configuration_with_signer_missing_principal_returns_error(
-        mut tmp_config_toml: NamedTempFile,
-        #[case] config: &str,
-    ) {
-        writeln!(tmp_config_toml, "{config}").unwrap();
+    fn webhook_reference_resolved() {
+        let toml = indoc! {r#"
+            [notify]
+            webhook = "env:HANKO_WEBHOOK"
+        "#};
+        let mut config = Configuration::try_from(TomlFile {
+            document: toml.parse().unwrap(),
+            ..Default::default()
+        })
+        .unwrap();
 
-        let err = Configuration::load(tmp_config_toml.path()).unwrap_err();
+        config
+            .resolve_token_references(|var| {
+                (var == "HANKO_WEBHOOK")
+                    .then(|| "https://hooks.slack.com/services/T0/B0/s3cr3t".to_string())
+            })
+            .unwrap();
 
-        assert_eq!(err.to_string(), "Signer octocat missing principals");
+        assert_eq!(
+            config.notify.webhook.unwrap().as_str(),
+            "https://hooks.slack.com/services/T0/B0/s3cr3t"
+        );
     }
 
+    /// A source token referring to an environment variable that is not set returns an error.
     #[rstest]
-    #[case(
-        indoc!{r#"
-            [[signers]]
-            name = "cwoods"
-            principals = ["cwoods@acme.corp"]
-            nonsense = ["acme-corp"]
-
+    fn source_token_reference_to_unset_variable_returns_error() {
+        let toml = indoc! {r#"
             [[sources]]
-            name = "acme-corp"
-            provider = "gitlab"
-            url = "https://git.acme.corp"
-        "#},
-        "unknown field `nonsense`"
-    )]
-    fn loading_configuration_with_unknown_field_returns_error(
-        mut tmp_config_toml: NamedTempFile,
-        #[case] config: &str,
-        #[case] expected_msg: &str,
-    ) {
-        writeln!(tmp_config_toml, "{config}").unwrap();
+            name = "octo-corp"
+            provider = "github"
+            url = "https://api.github.com"
+            token = "env:UNSET"
+        "#};
+        let mut config = Configuration::try_from(TomlFile {
+            document: toml.parse().unwrap(),
+            ..Default::default()
+        })
+        .unwrap();
 
-        let err = Configuration::load(tmp_config_toml.path()).unwrap_err();
+        let err = config.resolve_token_references(|_| None).unwrap_err();
 
-        assert!(err.to_string().contains(expected_msg));
+        assert_eq!(
+            err.to_string(),
+            "Source octo-corp refers to environment variable UNSET which is not set"
+        );
     }
 
     /// Signers have a default GitHub source if no sources were configured explicitly.
+    #[cfg(feature = "public-providers")]
     #[rstest]
     #[case(
         indoc! {r#"
@@ -529,13 +2965,48 @@ mod tests {
     ) {
         writeln!(tmp_config_toml, "{config}").unwrap();
 
-        let mut config = Configuration::load(tmp_config_toml.path()).unwrap();
+        let mut config =
+            Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap();
         let signer_sources = config.signers.pop().unwrap().source_names;
 
         assert_eq!(signer_sources, vec!["github"]);
     }
 
+    /// The source mode of a signer can be configured and defaults to merging all sources.
+    #[cfg(feature = "public-providers")]
+    #[rstest]
+    #[case(
+        indoc! {r#"
+            signers = [
+                { name = "torvalds", principals = ["torvalds@linux-foundation.org"] },
+            ]
+        "#},
+        SourceMode::Merge
+    )]
+    #[case(
+        indoc! {r#"
+            signers = [
+                { name = "torvalds", principals = ["torvalds@linux-foundation.org"], sources = ["gitlab", "github"], source_mode = "first-match" },
+            ]
+        "#},
+        SourceMode::FirstMatch
+    )]
+    fn signer_source_mode_is_loaded(
+        mut tmp_config_toml: NamedTempFile,
+        #[case] config: &str,
+        #[case] expected: SourceMode,
+    ) {
+        writeln!(tmp_config_toml, "{config}").unwrap();
+
+        let mut config =
+            Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap();
+        let source_mode = config.signers.pop().unwrap().source_mode;
+
+        assert_eq!(source_mode, expected);
+    }
+
     /// When saving a configuration back to file, the TOML formatting matches that of the original file.
+    #[cfg(feature = "public-providers")]
     #[rstest]
     #[case(
         indoc! {r#"
@@ -556,7 +3027,7 @@ mod tests {
         #[case] content: &str,
     ) {
         write!(tmp_config_toml, "{content}").unwrap();
-        let config = Configuration::load(tmp_config_toml.path()).unwrap();
+        let config = Configuration::load(tmp_config_toml.path(), LoadOptions::default()).unwrap();
         tmp_config_toml.as_file().set_len(0).unwrap();
 
         config.save().unwrap();
@@ -566,6 +3037,7 @@ mod tests {
     }
 
     /// When adding a signer to a configuration, it is added to the contained signers.
+    #[cfg(feature = "public-providers")]
     #[rstest]
     #[case(
         SignerConfiguration {
@@ -588,7 +3060,22 @@ mod tests {
         assert!(config.signers.contains(&signer));
     }
 
+    /// Adding a signer without principals returns an error instead of panicking when generating
+    /// it's entries later on.
+    #[rstest]
+    fn adding_signer_without_principals_returns_error() {
+        let mut config = Configuration::default();
+
+        let err = config
+            .add_signer("octocat".to_string(), vec![], default_user_source())
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "Signer octocat missing principals");
+        assert!(config.signers.is_empty());
+    }
+
     /// When adding a signer to a configuration, it is added to the TOML configuration file contained within.
+    #[cfg(feature = "public-providers")]
     #[rstest]
     #[case(
         "",
@@ -639,6 +3126,7 @@ mod tests {
             name: "octocat".to_string(),
             principals: vec!["octocat@github.com".to_string()],
             source_names: vec!["acme-corp".to_string()],
+            ..Default::default()
         },
         indoc! {r#"
             [[signers]]
@@ -693,6 +3181,242 @@ mod tests {
         assert_eq!(config.file.document.to_string(), expected);
     }
 
+    /// When adding a source to a configuration, it is added to the TOML configuration file contained within.
+    #[rstest]
+    #[case(
+        "",
+        indoc! {r#"
+            [[sources]]
+            name = "acme-corp"
+            provider = "gitlab"
+            url = "https://git.acme.corp/"
+        "#},
+    )]
+    #[case(
+        indoc! {r#"
+            [[signers]]
+            name = "torvalds"
+            principals = ["torvalds@linux-foundation.org"]
+        "#},
+        indoc! {r#"
+            [[signers]]
+            name = "torvalds"
+            principals = ["torvalds@linux-foundation.org"]
+
+            [[sources]]
+            name = "acme-corp"
+            provider = "gitlab"
+            url = "https://git.acme.corp/"
+        "#},
+    )]
+    fn adding_source_adds_to_file(#[case] toml: &str, #[case] expected: &str) {
+        let mut config = Configuration::try_from(TomlFile {
+            document: toml.parse().unwrap(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        config
+            .add_source(
+                "acme-corp".to_string(),
+                SourceType::Gitlab,
+                "https://git.acme.corp".parse().unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(config.file.document.to_string(), expected);
+    }
+
+    #[cfg(feature = "public-providers")]
+    #[rstest]
+    fn adding_existing_source_returns_error() {
+        let mut config = Configuration::default();
+
+        let err = config
+            .add_source(
+                "github".to_string(),
+                SourceType::Github,
+                "https://api.github.com".parse().unwrap(),
+            )
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "Source github already exists");
+    }
+
+    /// Disabling a signer marks it as disabled within the file, skipping it when generating
+    /// signers, while enabling it removes the mark again.
+    #[cfg(feature = "public-providers")]
+    #[rstest]
+    #[case(
+        indoc! {r#"
+            signers = [
+                { name = "torvalds", principals = ["torvalds@linux-foundation.org"] },
+                { name = "octocat", principals = ["octocat@github.com"] },
+            ]
+        "#},
+        indoc! {r#"
+            signers = [
+                { name = "torvalds", principals = ["torvalds@linux-foundation.org"] },
+                { name = "octocat", principals = ["octocat@github.com"], enabled = false },
+            ]
+        "#}
+    )]
+    #[case(
+        indoc! {r#"
+            [[signers]]
+            name = "torvalds"
+            principals = ["torvalds@linux-foundation.org"]
+
+            [[signers]]
+            name = "octocat"
+            principals = ["octocat@github.com"]
+        "#},
+        indoc! {r#"
+            [[signers]]
+            name = "torvalds"
+            principals = ["torvalds@linux-foundation.org"]
+
+            [[signers]]
+            name = "octocat"
+            principals = ["octocat@github.com"]
+            enabled = false
+        "#}
+    )]
+    fn disabling_signer_skips_it(#[case] toml: &str, #[case] disabled: &str) {
+        let mut config = Configuration::try_from(TomlFile {
+            document: toml.parse().unwrap(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        config.set_signer_enabled("octocat", false).unwrap();
+
+        assert_eq!(config.file.document.to_string(), disabled);
+        let signers = config.signers(&config.sources()).unwrap();
+        assert_eq!(signers.len(), 1);
+        assert_eq!(signers[0].name, "torvalds");
+        assert_eq!(config.signer_names(), vec!["torvalds", "octocat"]);
+
+        config.set_signer_enabled("octocat", true).unwrap();
+
+        assert_eq!(config.file.document.to_string(), toml);
+        assert_eq!(config.signers(&config.sources()).unwrap().len(), 2);
+        assert!(config.set_signer_enabled("cwoods", false).is_err());
+    }
+
+    /// Signers referring to a source that is not configured return an error instead of
+    /// panicking, even if the configuration was not validated.
+    #[rstest]
+    fn signer_with_unknown_source_returns_error() {
+        let toml = indoc! {r#"
+            signers = [
+                { name = "octocat", principals = ["octocat@github.com"], sources = ["commented-out"] },
+            ]
+        "#};
+        let config = Configuration::try_from(TomlFile {
+            document: toml.parse().unwrap(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let err = config.signers(&config.sources()).unwrap_err();
+
+        assert_eq!(
+            err,
+            UnknownSourceError {
+                signer: "octocat".to_string(),
+                source_name: "commented-out".to_string(),
+            }
+        );
+    }
+
+    /// Removing a signer removes it from the file along with it's principals, preserving the
+    /// formatting of the remaining configuration.
+    #[rstest]
+    #[case(
+        indoc! {r#"
+            signers = [
+                { name = "torvalds", principals = ["torvalds@linux-foundation.org"] },
+                { name = "octocat" },
+            ]
+
+            [principals]
+            octocat = ["octocat@github.com"]
+        "#},
+        indoc! {r#"
+            signers = [
+                { name = "torvalds", principals = ["torvalds@linux-foundation.org"] },
+            ]
+
+            [principals]
+        "#}
+    )]
+    #[case(
+        indoc! {r#"
+            # Kernel maintainers
+            [[signers]]
+            name = "torvalds"
+            principals = ["torvalds@linux-foundation.org"]
+
+            [[signers]]
+            name = "octocat"
+            principals = ["octocat@github.com"]
+        "#},
+        indoc! {r#"
+            # Kernel maintainers
+            [[signers]]
+            name = "torvalds"
+            principals = ["torvalds@linux-foundation.org"]
+        "#}
+    )]
+    fn removing_signer_removes_from_file(#[case] toml: &str, #[case] expected: &str) {
+        let mut config = Configuration::try_from(TomlFile {
+            document: toml.parse().unwrap(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        config.remove_signer("octocat").unwrap();
+
+        assert_eq!(config.file.document.to_string(), expected);
+        assert_eq!(config.signer_names(), vec!["torvalds"]);
+        assert!(config.remove_signer("octocat").is_err());
+    }
+
+    #[rstest]
+    #[case("https://github.com", Some(SourceType::Github))]
+    #[case("https://api.github.com", Some(SourceType::Github))]
+    #[case("https://GitLab.com/", Some(SourceType::Gitlab))]
+    #[case("https://git.acme.corp", None)]
+    fn provider_detected_by_host(#[case] url: &str, #[case] expected: Option<SourceType>) {
+        assert_eq!(SourceType::detect(&url.parse().unwrap()), expected);
+    }
+
+    /// The web URL of a well known host is replaced by the URL of it's API, while other URLs are
+    /// kept.
+    #[rstest]
+    #[case(SourceType::Github, "https://github.com", "https://api.github.com/")]
+    #[case(SourceType::Github, "https://GitHub.com/", "https://api.github.com/")]
+    #[case(
+        SourceType::Github,
+        "https://api.github.com",
+        "https://api.github.com/"
+    )]
+    #[case(
+        SourceType::Github,
+        "https://git.acme.corp/api/v3",
+        "https://git.acme.corp/api/v3"
+    )]
+    #[case(SourceType::Gitlab, "https://github.com", "https://github.com/")]
+    #[case(SourceType::Gitlab, "https://gitlab.com", "https://gitlab.com/")]
+    fn api_url_of_well_known_hosts(
+        #[case] provider: SourceType,
+        #[case] url: &str,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(provider.api_url(url.parse().unwrap()).as_str(), expected);
+    }
+
     #[rstest]
     #[case(
         Configuration::default(),
@@ -700,6 +3424,7 @@ mod tests {
             name: "cwoods".to_string(),
             principals: vec!["cwoods@acme.corp".to_string()],
             source_names: vec!["acme-corp".to_string()],
+            ..Default::default()
         },
         vec!["acme-corp".to_string()]
     )]
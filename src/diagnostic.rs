@@ -0,0 +1,312 @@
+//! Warnings identified by stable diagnostic codes, which can be suppressed individually.
+//!
+//! Warnings are emitted using the [`warning`] macro, prefixing their message with their code,
+//! e.g. `HANKO-W005: User octocat does not exist on source`.
+use std::{
+    fmt,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        OnceLock,
+    },
+};
+
+/// The warnings that can be suppressed.
+static SUPPRESSED: OnceLock<Vec<Warning>> = OnceLock::new();
+/// Whether all warnings are silenced.
+static SILENCED: AtomicBool = AtomicBool::new(false);
+/// The number of failures tolerated so far, whether they were warned about or not.
+static TOLERATED_FAILURES: AtomicUsize = AtomicUsize::new(0);
+
+/// A warning condition. The code of every variant is stable and never reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Warning {
+    /// An environment variable overriding a source refers to an unknown source or field.
+    IgnoredEnvOverride,
+    /// The principals table refers to a signer that is not configured.
+    UnknownPrincipalsSigner,
+    /// A source transmits it's token over plain http.
+    PlainHttpToken,
+    /// A signer has no principals, so it's keys are skipped.
+    SignerWithoutPrincipals,
+    /// A user does not exist on a source.
+    UserNotFound,
+    /// A user does not have any signing keys on a source.
+    NoSigningKeys,
+    /// An email address does not resolve to any user on a source.
+    EmailNotResolved,
+    /// A key was dropped since it is denied.
+    DeniedKey,
+    /// A key was dropped since it does not match any allowed fingerprint.
+    FingerprintNotAllowed,
+    /// A key was dropped since it's title does not match the configured pattern.
+    KeyTitleMismatch,
+    /// A key was dropped since it is not a well-formed SSH public key.
+    InvalidKey,
+    /// A key was dropped since it does not comply with the configured key age.
+    KeyAgeRejected,
+    /// The SAML identity of a signer could not be retrieved.
+    SamlIdentityFailed,
+    /// The rate limit budget of a source does not suffice for the update.
+    InsufficientRateLimit,
+    /// The remaining rate limit of a source could not be determined.
+    UnknownRateLimit,
+    /// Retrieving signers was aborted after an interrupt.
+    RetrievalAborted,
+    /// A failed request to a source is retried.
+    RetryingRequest,
+    /// A token was rejected by a source.
+    TokenRejected,
+    /// The API path was removed from the base URL of a GitLab source.
+    GitlabApiPathRemoved,
+    /// Keys could not be read from or written to the cache.
+    CacheFailure,
+    /// A signer selected using `--only` is not configured. No longer emitted, since selecting an
+    /// unknown signer fails the update.
+    UnknownSelectedSigner,
+    /// A signer is removed from the allowed signers file since it was not selected using `--only`.
+    SignerNotSelected,
+    /// A GitLab user was not found by an unauthenticated request, possibly since it is private.
+    GitlabUserMaybePrivate,
+    /// A signer has a different number of keys than expected.
+    UnexpectedKeyCount,
+    /// The webhook could not be notified about changes to the allowed signers file.
+    NotificationFailed,
+    /// A signer was skipped since a source could not be connected to.
+    ConnectionErrorTolerated,
+    /// A key expires within the configured window.
+    KeyExpiringSoon,
+    /// A key is written for multiple distinct principals.
+    SharedKey,
+    /// A failed update is re-run.
+    UpdateRetried,
+    /// A drop-in configuration file was skipped since it is invalid.
+    InvalidConfigSkipped,
+    /// Whether a signer exists on it's sources could not be determined while pruning.
+    SignerExistenceUnknown,
+    /// A vanished signer configured within a drop-in file was not removed while pruning.
+    VanishedSignerKept,
+    /// An interrupted update wrote only the signers retrieved so far.
+    PartialUpdate,
+    /// A signer added to the configuration could not be verified on one of it's sources.
+    SignerNotVerified,
+    /// The allowed signers file was not signed since it is not a regular file.
+    FileNotSigned,
+    /// The lockfile was not written since the update was interrupted.
+    LockfileNotWritten,
+    /// Connection pool options were ignored since the shared connection pool was configured
+    /// differently before.
+    PoolOptionsIgnored,
+}
+
+impl Warning {
+    /// All warnings in the order of their codes.
+    pub(crate) const ALL: [Warning; 37] = [
+        Warning::IgnoredEnvOverride,
+        Warning::UnknownPrincipalsSigner,
+        Warning::PlainHttpToken,
+        Warning::SignerWithoutPrincipals,
+        Warning::UserNotFound,
+        Warning::NoSigningKeys,
+        Warning::EmailNotResolved,
+        Warning::DeniedKey,
+        Warning::FingerprintNotAllowed,
+        Warning::KeyTitleMismatch,
+        Warning::InvalidKey,
+        Warning::KeyAgeRejected,
+        Warning::SamlIdentityFailed,
+        Warning::InsufficientRateLimit,
+        Warning::UnknownRateLimit,
+        Warning::RetrievalAborted,
+        Warning::RetryingRequest,
+        Warning::TokenRejected,
+        Warning::GitlabApiPathRemoved,
+        Warning::CacheFailure,
+        Warning::UnknownSelectedSigner,
+        Warning::SignerNotSelected,
+        Warning::GitlabUserMaybePrivate,
+        Warning::UnexpectedKeyCount,
+        Warning::NotificationFailed,
+        Warning::ConnectionErrorTolerated,
+        Warning::KeyExpiringSoon,
+        Warning::SharedKey,
+        Warning::UpdateRetried,
+        Warning::InvalidConfigSkipped,
+        Warning::SignerExistenceUnknown,
+        Warning::VanishedSignerKept,
+        Warning::PartialUpdate,
+        Warning::SignerNotVerified,
+        Warning::FileNotSigned,
+        Warning::LockfileNotWritten,
+        Warning::PoolOptionsIgnored,
+    ];
+
+    /// The stable diagnostic code of the warning, e.g. `HANKO-W005`.
+    pub(crate) fn code(self) -> &'static str {
+        match self {
+            Warning::IgnoredEnvOverride => "HANKO-W001",
+            Warning::UnknownPrincipalsSigner => "HANKO-W002",
+            Warning::PlainHttpToken => "HANKO-W003",
+            Warning::SignerWithoutPrincipals => "HANKO-W004",
+            Warning::UserNotFound => "HANKO-W005",
+            Warning::NoSigningKeys => "HANKO-W006",
+            Warning::EmailNotResolved => "HANKO-W007",
+            Warning::DeniedKey => "HANKO-W008",
+            Warning::FingerprintNotAllowed => "HANKO-W009",
+            Warning::KeyTitleMismatch => "HANKO-W010",
+            Warning::InvalidKey => "HANKO-W011",
+            Warning::KeyAgeRejected => "HANKO-W012",
+            Warning::SamlIdentityFailed => "HANKO-W013",
+            Warning::InsufficientRateLimit => "HANKO-W014",
+            Warning::UnknownRateLimit => "HANKO-W015",
+            Warning::RetrievalAborted => "HANKO-W016",
+            Warning::RetryingRequest => "HANKO-W017",
+            Warning::TokenRejected => "HANKO-W018",
+            Warning::GitlabApiPathRemoved => "HANKO-W019",
+            Warning::CacheFailure => "HANKO-W020",
+            Warning::UnknownSelectedSigner => "HANKO-W021",
+            Warning::SignerNotSelected => "HANKO-W022",
+            Warning::GitlabUserMaybePrivate => "HANKO-W023",
+            Warning::UnexpectedKeyCount => "HANKO-W024",
+            Warning::NotificationFailed => "HANKO-W025",
+            Warning::ConnectionErrorTolerated => "HANKO-W026",
+            Warning::KeyExpiringSoon => "HANKO-W027",
+            Warning::SharedKey => "HANKO-W028",
+            Warning::UpdateRetried => "HANKO-W029",
+            Warning::InvalidConfigSkipped => "HANKO-W030",
+            Warning::SignerExistenceUnknown => "HANKO-W031",
+            Warning::VanishedSignerKept => "HANKO-W032",
+            Warning::PartialUpdate => "HANKO-W033",
+            Warning::SignerNotVerified => "HANKO-W034",
+            Warning::FileNotSigned => "HANKO-W035",
+            Warning::LockfileNotWritten => "HANKO-W036",
+            Warning::PoolOptionsIgnored => "HANKO-W037",
+        }
+    }
+
+    /// Whether the warning was suppressed using [`suppress`] or all warnings were silenced using
+    /// [`silence`].
+    pub(crate) fn is_suppressed(self) -> bool {
+        is_silenced()
+            || SUPPRESSED
+                .get()
+                .is_some_and(|suppressed| suppressed.contains(&self))
+    }
+
+    /// Whether the warning reports a failure that was tolerated instead of failing, like a signer
+    /// skipped since it's source could not be connected to.
+    pub(crate) fn is_tolerated_failure(self) -> bool {
+        matches!(
+            self,
+            Warning::SamlIdentityFailed
+                | Warning::CacheFailure
+                | Warning::NotificationFailed
+                | Warning::ConnectionErrorTolerated
+                | Warning::InvalidConfigSkipped
+        )
+    }
+
+    /// Record that the warning was emitted, counting tolerated failures.
+    pub(crate) fn record(self) {
+        if self.is_tolerated_failure() {
+            TOLERATED_FAILURES.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// A diagnostic code not belonging to any warning.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("unknown warning code {0}, expected a code like HANKO-W001")]
+pub struct UnknownCode(String);
+
+impl FromStr for Warning {
+    type Err = UnknownCode;
+
+    /// Parse a warning from it's code, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Warning::ALL
+            .into_iter()
+            .find(|warning| warning.code().eq_ignore_ascii_case(s))
+            .ok_or_else(|| UnknownCode(s.to_string()))
+    }
+}
+
+/// Suppress the given warnings for the remainder of the process.
+/// Only the first set of suppressed warnings takes effect.
+pub(crate) fn suppress(warnings: Vec<Warning>) {
+    let _ = SUPPRESSED.set(warnings);
+}
+
+/// Silence all warnings and other diagnostics for the remainder of the process, e.g. while only
+/// printing a status line.
+pub(crate) fn silence() {
+    SILENCED.store(true, Ordering::Relaxed);
+}
+
+/// Whether all warnings and other diagnostics were silenced using [`silence`].
+pub(crate) fn is_silenced() -> bool {
+    SILENCED.load(Ordering::Relaxed)
+}
+
+/// The number of failures tolerated so far, including those whose warnings were suppressed.
+pub(crate) fn tolerated_failures() -> usize {
+    TOLERATED_FAILURES.load(Ordering::Relaxed)
+}
+
+/// Emit a warning unless it is suppressed, prefixing the message with the code of the warning.
+/// Tolerated failures are counted even if their warning is suppressed.
+/// Fields recorded along with the message are given in brackets before it, e.g.
+/// `warning!(Warning::UserNotFound, [?source], "User {username} does not exist on source")`.
+macro_rules! warning {
+    ($warning:expr, [$($field:tt)+], $($message:tt)+) => {{
+        let warning: $crate::diagnostic::Warning = $warning;
+        warning.record();
+        if !warning.is_suppressed() {
+            tracing::warn!($($field)+, "{warning}: {}", format!($($message)+));
+        }
+    }};
+    ($warning:expr, $($message:tt)+) => {{
+        let warning: $crate::diagnostic::Warning = $warning;
+        warning.record();
+        if !warning.is_suppressed() {
+            tracing::warn!("{warning}: {}", format!($($message)+));
+        }
+    }};
+}
+pub(crate) use warning;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use std::collections::HashSet;
+
+    /// Every warning has a unique code, numbered consecutively in the order of [`Warning::ALL`].
+    #[rstest]
+    fn codes_unique_and_consecutive() {
+        let codes: Vec<&str> = Warning::ALL.iter().map(|w| w.code()).collect();
+
+        assert_eq!(codes.iter().collect::<HashSet<_>>().len(), codes.len());
+        for (i, code) in codes.iter().enumerate() {
+            assert_eq!(*code, format!("HANKO-W{:03}", i + 1));
+        }
+    }
+
+    /// Warnings are parsed from their code regardless of case, rejecting unknown codes.
+    #[rstest]
+    #[case("HANKO-W005", Ok(Warning::UserNotFound))]
+    #[case("hanko-w022", Ok(Warning::SignerNotSelected))]
+    #[case("HANKO-W999", Err(UnknownCode("HANKO-W999".to_string())))]
+    fn warning_parsed_from_code(
+        #[case] code: &str,
+        #[case] expected: Result<Warning, UnknownCode>,
+    ) {
+        assert_eq!(code.parse::<Warning>(), expected);
+    }
+}
@@ -0,0 +1,26 @@
+//! Hashes of content identifying it, e.g. to detect changes or name cache files after it.
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+
+/// The hex encoded SHA-256 hash of the given data.
+pub(crate) fn sha256_hex(data: impl AsRef<[u8]>) -> String {
+    Sha256::digest(data)
+        .iter()
+        .fold(String::new(), |mut hex, b| {
+            let _ = write!(hex, "{b:02x}");
+            hex
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_hex_encoded() {
+        assert_eq!(
+            sha256_hex("hanko"),
+            "d35f513a620d4cd3187b0cd1d54d9d6207f2e7796273f5e820469a175e450e37"
+        );
+    }
+}
@@ -0,0 +1,200 @@
+//! Diagnose the git configuration surrounding the allowed signers file, explaining why signatures
+//! may fail to verify even though the file was generated successfully.
+use anyhow::{Context, Result};
+use std::{
+    borrow::Cow,
+    fmt, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// The result of checking a single git setting.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Check {
+    /// The name of the git setting.
+    pub key: &'static str,
+    /// The effective value of the setting, if set.
+    pub value: Option<String>,
+    /// The command fixing the setting, if it is misconfigured.
+    pub fix: Option<String>,
+}
+
+impl Check {
+    /// Check that the given setting has the expected value, suggesting to set it otherwise.
+    fn expect(key: &'static str, value: Option<String>, expected: &str) -> Self {
+        let fix = (value.as_deref() != Some(expected)).then(|| fix_command(key, expected));
+        Self { key, value, fix }
+    }
+
+    /// Whether the setting is configured as expected.
+    pub fn passed(&self) -> bool {
+        self.fix.is_none()
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mark = if self.passed() { "✓" } else { "✗" };
+        match &self.value {
+            Some(value) => write!(f, "{mark} {} = {value}", self.key)?,
+            None => write!(f, "{mark} {} is not set", self.key)?,
+        }
+        if let Some(fix) = &self.fix {
+            write!(f, "\n  fix: {fix}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The command setting the given git setting, quoting the value if necessary.
+fn fix_command(key: &str, value: &str) -> String {
+    if value.contains(char::is_whitespace) {
+        format!("git config {key} '{value}'")
+    } else {
+        format!("git config {key} {value}")
+    }
+}
+
+/// The git configuration effective in the current working directory, including the configuration
+/// of the repository if the current working directory is within one.
+pub(crate) fn git_config() -> Result<gix_config::File<'static>> {
+    match git_dir() {
+        Some(dir) => {
+            gix_config::File::from_git_dir(dir).context("Failed to read git configuration")
+        }
+        None => gix_config::File::from_globals().context("Failed to read git configuration"),
+    }
+}
+
+/// The git directory of the repository in the current working directory, if any.
+fn git_dir() -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--absolute-git-dir"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+/// Check the given git configuration for the settings required to sign and verify commits and
+/// tags using the allowed signers file managed by hanko, if known.
+pub(crate) fn diagnose(
+    config: &gix_config::File<'_>,
+    signers_file: Option<&Path>,
+    home: Option<&Path>,
+) -> Vec<Check> {
+    let string = |key: &str| config.string(key).map(|value| value.to_string());
+    let allowed_signers = config.path("gpg.ssh.allowedSignersFile").map(|path| {
+        let raw = PathBuf::from(path.to_string());
+        path.interpolate(gix_config::path::interpolate::Context {
+            home_dir: home,
+            ..Default::default()
+        })
+        .map_or(raw, Cow::into_owned)
+    });
+    let allowed_signers_fix = match (signers_file, &allowed_signers) {
+        (Some(expected), Some(actual)) if same_file(expected, actual) => None,
+        (Some(expected), _) => Some(fix_command(
+            "gpg.ssh.allowedSignersFile",
+            &expected.to_string_lossy(),
+        )),
+        (None, Some(_)) => None,
+        (None, None) => Some(fix_command("gpg.ssh.allowedSignersFile", "PATH")),
+    };
+
+    vec![
+        Check::expect("gpg.format", string("gpg.format"), "ssh"),
+        Check {
+            key: "gpg.ssh.allowedSignersFile",
+            value: allowed_signers.map(|path| path.to_string_lossy().to_string()),
+            fix: allowed_signers_fix,
+        },
+        Check::expect("commit.gpgsign", boolean(config, "commit.gpgsign"), "true"),
+        Check::expect("tag.gpgsign", boolean(config, "tag.gpgsign"), "true"),
+    ]
+}
+
+/// The given boolean setting normalized to `true` or `false`, or it's raw value if it is invalid.
+fn boolean(config: &gix_config::File<'_>, key: &str) -> Option<String> {
+    match config.boolean(key)? {
+        Ok(value) => Some(value.to_string()),
+        Err(_) => config.string(key).map(|value| value.to_string()),
+    }
+}
+
+/// Whether both paths refer to the same file, comparing them literally if either does not exist.
+fn same_file(a: &Path, b: &Path) -> bool {
+    a == b || matches!((fs::canonicalize(a), fs::canonicalize(b)), (Ok(a), Ok(b)) if a == b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use rstest::*;
+    use std::str::FromStr;
+
+    /// A configuration set up for signing using the allowed signers file passes all checks.
+    #[rstest]
+    fn configured_repository_passes() {
+        let config = gix_config::File::from_str(indoc! {r#"
+            [gpg]
+                format = ssh
+            [gpg "ssh"]
+                allowedSignersFile = ~/.config/git/allowed_signers
+            [commit]
+                gpgsign = yes
+            [tag]
+                gpgsign = true
+        "#})
+        .unwrap();
+
+        let checks = diagnose(
+            &config,
+            Some(Path::new("/home/jsnow/.config/git/allowed_signers")),
+            Some(Path::new("/home/jsnow")),
+        );
+
+        assert!(checks.iter().all(Check::passed), "{checks:?}");
+        assert_eq!(checks[2].value.as_deref(), Some("true"));
+    }
+
+    /// Misconfigured and missing settings are reported along with commands fixing them.
+    #[rstest]
+    fn misconfigurations_reported() {
+        let config = gix_config::File::from_str(indoc! {r#"
+            [gpg]
+                format = openpgp
+            [gpg "ssh"]
+                allowedSignersFile = /etc/ssh/allowed_signers
+            [commit]
+                gpgsign = false
+        "#})
+        .unwrap();
+
+        let checks = diagnose(
+            &config,
+            Some(Path::new("/home/jsnow/allowed signers")),
+            None,
+        );
+
+        assert_eq!(
+            checks
+                .iter()
+                .map(|check| check.fix.as_deref())
+                .collect::<Vec<_>>(),
+            vec![
+                Some("git config gpg.format ssh"),
+                Some("git config gpg.ssh.allowedSignersFile '/home/jsnow/allowed signers'"),
+                Some("git config commit.gpgsign true"),
+                Some("git config tag.gpgsign true"),
+            ]
+        );
+        assert_eq!(
+            checks[3].to_string(),
+            "✗ tag.gpgsign is not set\n  fix: git config tag.gpgsign true"
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! A duration parsed from either a human readable or an ISO 8601 representation, used for all
+//! time related configuration values and command line arguments.
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr, time};
+
+/// A duration given either in a human readable form of numbers followed by a unit, e.g. `1h30m`,
+/// or as an ISO 8601 duration, e.g. `PT1H30M`.
+///
+/// Supported units are `ms`, `s`, `m`, `h`, `d` and `w`, as well as their spelled out forms.
+/// Months and years are not supported since their length varies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Duration(time::Duration);
+
+const MILLISECOND: u64 = 1;
+const SECOND: u64 = 1000 * MILLISECOND;
+const MINUTE: u64 = 60 * SECOND;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+
+impl Duration {
+    #[must_use]
+    pub fn new(duration: time::Duration) -> Self {
+        Self(duration)
+    }
+
+    /// The duration as a [`TimeDelta`], saturating at it's maximum.
+    #[must_use]
+    pub fn as_time_delta(&self) -> TimeDelta {
+        TimeDelta::from_std(self.0).unwrap_or(TimeDelta::MAX)
+    }
+
+    /// Parse a human readable duration like `1h30m`.
+    fn parse_human(s: &str) -> Option<time::Duration> {
+        let mut millis: u64 = 0;
+        let mut rest = s.trim_start();
+        if rest.is_empty() {
+            return None;
+        }
+        while !rest.is_empty() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let (n, tail) = rest.split_at(digits);
+            let letters = tail
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(tail.len());
+            let (unit, tail) = tail.split_at(letters);
+            let unit = match unit {
+                "ms" | "msec" | "millis" => MILLISECOND,
+                "s" | "sec" | "secs" | "second" | "seconds" => SECOND,
+                "m" | "min" | "mins" | "minute" | "minutes" => MINUTE,
+                "h" | "hr" | "hrs" | "hour" | "hours" => HOUR,
+                "d" | "day" | "days" => DAY,
+                "w" | "week" | "weeks" => WEEK,
+                _ => return None,
+            };
+            let n: u64 = n.parse().ok()?;
+            millis = millis.checked_add(n.checked_mul(unit)?)?;
+            rest = tail.trim_start();
+        }
+        Some(time::Duration::from_millis(millis))
+    }
+
+    /// Parse an ISO 8601 duration like `PT1H30M`, supporting weeks, days, hours, minutes and
+    /// seconds with a fractional part.
+    fn parse_iso8601(s: &str) -> Option<time::Duration> {
+        let s = s.strip_prefix(['P', 'p'])?;
+        let (date, time) = match s.split_once(['T', 't']) {
+            Some((_, "")) => return None,
+            Some((date, time)) => (date, Some(time)),
+            None => (s, None),
+        };
+        if date.is_empty() && time.is_none() {
+            return None;
+        }
+
+        let mut millis: u64 = 0;
+        for (part, units) in [
+            (date, &[('W', WEEK), ('D', DAY)][..]),
+            (
+                time.unwrap_or_default(),
+                &[('H', HOUR), ('M', MINUTE), ('S', SECOND)][..],
+            ),
+        ] {
+            let mut rest = part;
+            let mut units = units.iter();
+            while !rest.is_empty() {
+                let end = rest.find(|c: char| c.is_ascii_alphabetic())?;
+                let (n, tail) = rest.split_at(end);
+                let designator = tail.chars().next()?.to_ascii_uppercase();
+                // Designators must appear in order, each at most once.
+                let &(_, unit) = units.find(|(d, _)| *d == designator)?;
+                millis = millis.checked_add(Self::iso8601_component(n, unit)?)?;
+                rest = &tail[1..];
+            }
+        }
+        Some(time::Duration::from_millis(millis))
+    }
+
+    /// The number of milliseconds of a single ISO 8601 component, allowing a fractional part
+    /// separated by a dot or comma.
+    fn iso8601_component(n: &str, unit: u64) -> Option<u64> {
+        let (whole, fraction) = n.split_once(['.', ',']).unwrap_or((n, ""));
+        if whole.is_empty() || !whole.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let millis = whole.parse::<u64>().ok()?.checked_mul(unit)?;
+        // Fractions beyond millisecond precision are truncated.
+        let fraction = fraction
+            .bytes()
+            .take(9)
+            .zip(1..)
+            .map(|(b, i)| u64::from(b - b'0') * unit / 10u64.pow(i))
+            .sum::<u64>();
+        millis.checked_add(fraction)
+    }
+}
+
+impl FromStr for Duration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_iso8601(s)
+            .or_else(|| Self::parse_human(s))
+            .map(Self)
+            .ok_or_else(|| {
+                format!("invalid duration `{s}`, expected e.g. `1h30m` or an ISO 8601 duration like `PT1H30M`")
+            })
+    }
+}
+
+impl fmt::Display for Duration {
+    /// Display the duration in it's human readable form, using weeks only for whole weeks.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut millis = u64::try_from(self.0.as_millis()).unwrap_or(u64::MAX);
+        if millis == 0 {
+            return write!(f, "0s");
+        }
+        if millis % WEEK == 0 {
+            return write!(f, "{}w", millis / WEEK);
+        }
+        for (unit, suffix) in [
+            (DAY, "d"),
+            (HOUR, "h"),
+            (MINUTE, "m"),
+            (SECOND, "s"),
+            (MILLISECOND, "ms"),
+        ] {
+            if millis >= unit {
+                write!(f, "{}{suffix}", millis / unit)?;
+                millis %= unit;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<time::Duration> for Duration {
+    fn from(duration: time::Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl From<Duration> for time::Duration {
+    fn from(duration: Duration) -> Self {
+        duration.0
+    }
+}
+
+impl TryFrom<String> for Duration {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Duration> for String {
+    fn from(duration: Duration) -> Self {
+        duration.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    fn secs(secs: u64) -> time::Duration {
+        time::Duration::from_secs(secs)
+    }
+
+    /// Human readable and ISO 8601 durations are parsed to the same value.
+    #[rstest]
+    #[case("12h", secs(12 * 3600))]
+    #[case("90d", secs(90 * 86400))]
+    #[case("2w", secs(14 * 86400))]
+    #[case("1h30m", secs(5400))]
+    #[case("1h 30m", secs(5400))]
+    #[case("2hours", secs(7200))]
+    #[case("1500ms", time::Duration::from_millis(1500))]
+    #[case("PT1H30M", secs(5400))]
+    #[case("P90D", secs(90 * 86400))]
+    #[case("P2W", secs(14 * 86400))]
+    #[case("P1DT12H", secs(36 * 3600))]
+    #[case("PT0.5S", time::Duration::from_millis(500))]
+    #[case("PT1,25S", time::Duration::from_millis(1250))]
+    #[case("pt10m", secs(600))]
+    fn duration_parsed(#[case] s: &str, #[case] expected: time::Duration) {
+        let duration: Duration = s.parse().unwrap();
+
+        assert_eq!(time::Duration::from(duration), expected);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("90")]
+    #[case("d")]
+    #[case("90 d")]
+    #[case("3 months")]
+    #[case("1y")]
+    #[case("P")]
+    #[case("PT")]
+    #[case("P1Y")]
+    #[case("P1M")]
+    #[case("PT1D")]
+    #[case("P1H")]
+    #[case("PT30M1H")]
+    #[case("PT.5S")]
+    #[case("-1h")]
+    fn invalid_duration_returns_error(#[case] s: &str) {
+        assert!(s.parse::<Duration>().is_err());
+    }
+
+    /// Durations are displayed in their human readable form, which can be parsed again.
+    #[rstest]
+    #[case("12h", "12h")]
+    #[case("90d", "90d")]
+    #[case("14d", "2w")]
+    #[case("PT1H30M", "1h30m")]
+    #[case("PT1.5S", "1s500ms")]
+    #[case("0s", "0s")]
+    fn duration_displayed(#[case] s: &str, #[case] expected: &str) {
+        let duration: Duration = s.parse().unwrap();
+
+        assert_eq!(duration.to_string(), expected);
+        assert_eq!(expected.parse::<Duration>().unwrap(), duration);
+    }
+}
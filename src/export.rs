@@ -0,0 +1,96 @@
+//! A structured export of the entries of the allowed signers file for consumption by other tools.
+use crate::allowed_signers::Entry;
+use serde::Serialize;
+
+/// The version of the export schema, incremented whenever fields are removed or their meaning
+/// changes. Adding fields does not change the version.
+pub(crate) const SCHEMA_VERSION: u32 = 1;
+
+/// All entries of the allowed signers file.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub(crate) struct Export {
+    version: u32,
+    entries: Vec<ExportedEntry>,
+}
+
+/// A single entry of the allowed signers file.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct ExportedEntry {
+    principals: Vec<String>,
+    /// The key type, e.g. `ssh-ed25519`.
+    algorithm: String,
+    /// The base64 encoded key material.
+    key: String,
+    fingerprint: Option<String>,
+    valid_after: Option<String>,
+    valid_before: Option<String>,
+    /// The name of the configured source the key was retrieved from.
+    source: Option<String>,
+}
+
+impl Export {
+    /// Export the given entries, which are expected to be deduplicated already.
+    pub(crate) fn new(entries: &[Entry]) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            entries: entries
+                .iter()
+                .map(|entry| {
+                    let key = entry.key();
+                    let (algorithm, material) = key.material();
+                    ExportedEntry {
+                        principals: entry.principals().to_vec(),
+                        algorithm: algorithm.to_string(),
+                        key: material.to_string(),
+                        fingerprint: key.fingerprint().map(|f| f.to_string()),
+                        valid_after: entry.valid_after().map(|dt| dt.to_rfc3339()),
+                        valid_before: entry.valid_before().map(|dt| dt.to_rfc3339()),
+                        source: key.source().map(ToString::to_string),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use rstest::*;
+    use serde_json::json;
+
+    /// Entries are exported using stable field names along with the schema version.
+    #[rstest]
+    fn entries_exported_as_json() {
+        let key =
+            "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS"
+                .parse::<crate::allowed_signers::ssh::PublicKey>()
+                .unwrap()
+                .with_source(Some("github".to_string()));
+        let entry = Entry::new(
+            vec!["j.snow@wall.com".to_string()],
+            None,
+            Some(Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()),
+            key,
+        );
+
+        let export = serde_json::to_value(Export::new(&[entry])).unwrap();
+
+        assert_eq!(
+            export,
+            json!({
+                "version": 1,
+                "entries": [{
+                    "principals": ["j.snow@wall.com"],
+                    "algorithm": "ssh-ed25519",
+                    "key": "AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS",
+                    "fingerprint": "SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM",
+                    "valid_after": null,
+                    "valid_before": "2030-01-01T00:00:00+00:00",
+                    "source": "github"
+                }]
+            })
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Git hooks updating the allowed signers file whenever the checked out commit changes, e.g. after
+//! pulling, keeping it fresh for developers using hanko locally.
+use anyhow::{bail, Context, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// The hooks run after the checked out commit changed.
+const HOOKS: [&str; 2] = ["post-checkout", "post-merge"];
+
+/// The line marking hooks installed by hanko, distinguishing them from other hooks.
+const MARKER: &str = "# Installed by hanko, keeping the allowed signers file up to date.";
+
+/// The content of hooks installed by hanko. Errors are reported without failing the hook, since
+/// git ignores the exit status of these hooks anyway.
+fn hook_content() -> String {
+    format!(
+        "#!/bin/sh\n{MARKER}\n{} update >/dev/null || echo \"hanko: failed to update the allowed signers file\" >&2\n",
+        env!("CARGO_PKG_NAME")
+    )
+}
+
+/// The hooks directory of the git repository in the current working directory, respecting the
+/// `core.hooksPath` option.
+pub(crate) fn hooks_dir() -> Result<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--path-format=absolute", "--git-path", "hooks"])
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "Failed to determine git hooks directory: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    }
+    Ok(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim(),
+    ))
+}
+
+/// Install the hooks into the given hooks directory, returning their paths.
+/// Existing hooks that were not installed by hanko are only overwritten if `force` is set.
+pub(crate) fn install(dir: &Path, force: bool) -> Result<Vec<PathBuf>> {
+    let paths: Vec<PathBuf> = HOOKS.iter().map(|hook| dir.join(hook)).collect();
+    if !force {
+        if let Some(existing) = paths.iter().find(|path| is_foreign_hook(path)) {
+            bail!(
+                "Refusing to overwrite existing hook {}, pass `--force` to overwrite it",
+                existing.display()
+            )
+        }
+    }
+
+    fs::create_dir_all(dir).context(format!("Failed to create directory {}", dir.display()))?;
+    for path in &paths {
+        fs::write(path, hook_content())
+            .context(format!("Failed to write hook {}", path.display()))?;
+        make_executable(path)?;
+    }
+    Ok(paths)
+}
+
+/// Remove the hooks installed by hanko from the given hooks directory, returning their paths.
+/// Hooks that were not installed by hanko are kept.
+pub(crate) fn uninstall(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut removed = Vec::new();
+    for path in HOOKS.iter().map(|hook| dir.join(hook)) {
+        if is_own_hook(&path) {
+            fs::remove_file(&path).context(format!("Failed to remove hook {}", path.display()))?;
+            removed.push(path);
+        }
+    }
+    Ok(removed)
+}
+
+/// Whether the hook at the given path was installed by hanko.
+fn is_own_hook(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| content.lines().any(|line| line == MARKER))
+}
+
+/// Whether a hook exists at the given path that was not installed by hanko.
+fn is_foreign_hook(path: &Path) -> bool {
+    path.exists() && !is_own_hook(path)
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .context(format!("Failed to make hook {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use tempfile::TempDir;
+
+    /// Hooks are installed and uninstalled, refusing to overwrite existing hooks not installed
+    /// by hanko unless forced, and never removing them.
+    #[rstest]
+    fn hooks_installed_and_uninstalled() {
+        let dir = TempDir::new().unwrap();
+        let foreign = dir.path().join("post-merge");
+        fs::write(&foreign, "#!/bin/sh\nmake\n").unwrap();
+
+        let err = install(dir.path(), false).unwrap_err();
+        assert!(
+            err.to_string().starts_with("Refusing to overwrite"),
+            "{err}"
+        );
+        assert!(uninstall(dir.path()).unwrap().is_empty());
+        assert!(foreign.exists());
+
+        let installed = install(dir.path(), true).unwrap();
+        assert_eq!(installed.len(), 2);
+        assert!(installed.iter().all(|path| is_own_hook(path)));
+        // Reinstalling replaces the hooks installed by hanko.
+        assert_eq!(install(dir.path(), false).unwrap(), installed);
+
+        assert_eq!(uninstall(dir.path()).unwrap(), installed);
+        assert!(installed.iter().all(|path| !path.exists()));
+    }
+}
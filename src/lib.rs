@@ -4,9 +4,31 @@
 
 pub const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
 
-pub use source::{Error, Github, Gitlab, Source};
+pub use source::{
+    configure_pool, connection_stats, Command, ConcurrencyLimited, ConnectionStats, Error, Fetched,
+    Github, Gitlab, ObservedRateLimit, PlainUrl, PoolOptions, Protocol, RetryClass, RetryPolicy,
+    Retrying, Source, Token,
+};
 
 pub mod allowed_signers;
+mod audit;
+pub mod cache;
 pub mod cli;
+mod clock;
 pub mod config;
+mod diagnostic;
+mod digest;
+#[cfg(feature = "detect-allowed-signers")]
+mod doctor;
+pub mod duration;
+mod export;
+mod git_hook;
+mod lock;
+mod lockfile;
+mod notify;
+pub mod paths;
+mod plan;
+mod report;
+pub mod revocation;
+mod signature;
 mod source;
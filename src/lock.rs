@@ -0,0 +1,100 @@
+//! Advisory locking of the allowed signers file, preventing concurrent runs from clobbering it.
+use anyhow::{bail, Context, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+use tracing::debug;
+
+/// The interval in which a held lock is retried while waiting for it.
+const RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An exclusive lock of the allowed signers file, held until it is dropped.
+/// The lock is taken on a separate lock file next to the allowed signers file, since the file
+/// itself may be replaced or be a special file. Being an advisory lock held by the operating
+/// system, it is also released if the process exits without dropping it, e.g. on a panic.
+#[derive(Debug)]
+pub(crate) struct FileLock {
+    _file: fs::File,
+}
+
+impl FileLock {
+    /// Acquire the lock of the given allowed signers file, waiting up to the given timeout for
+    /// another run holding it. Without a timeout, fails immediately if the lock is held.
+    /// Special files like FIFOs are not locked, since lock files can not necessarily be created
+    /// next to them, e.g. within `/dev`.
+    pub(crate) fn acquire(path: &Path, timeout: Option<Duration>) -> Result<Option<Self>> {
+        if fs::metadata(path).is_ok_and(|metadata| !metadata.is_file()) {
+            debug!(
+                "Not locking {} since it is not a regular file",
+                path.display()
+            );
+            return Ok(None);
+        }
+        let lock_path = lock_path(path);
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .context(format!("Failed to open lock file {}", lock_path.display()))?;
+
+        let deadline = Instant::now() + timeout.unwrap_or_default();
+        loop {
+            match file.try_lock() {
+                Ok(()) => {
+                    debug!("Acquired lock {}", lock_path.display());
+                    return Ok(Some(Self { _file: file }));
+                }
+                Err(fs::TryLockError::WouldBlock) if Instant::now() < deadline => {
+                    thread::sleep(RETRY_INTERVAL);
+                }
+                Err(fs::TryLockError::WouldBlock) => bail!(
+                    "Allowed signers file {} is locked by another run of hanko",
+                    path.display()
+                ),
+                Err(fs::TryLockError::Error(err)) => {
+                    return Err(err).context(format!("Failed to lock {}", lock_path.display()))
+                }
+            }
+        }
+    }
+}
+
+/// The path of the lock file of the given allowed signers file, with a `.lock` extension appended
+/// to it's name.
+pub(crate) fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A held lock can not be acquired again until it is dropped.
+    #[test]
+    fn held_lock_not_acquired_until_dropped() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("allowed_signers");
+
+        let lock = FileLock::acquire(&path, None).unwrap();
+        assert!(lock_path(&path).exists());
+        assert!(FileLock::acquire(&path, Some(Duration::from_millis(200))).is_err());
+
+        drop(lock);
+        assert!(FileLock::acquire(&path, None).unwrap().is_some());
+    }
+
+    /// Special files are not locked.
+    #[test]
+    #[cfg(unix)]
+    fn special_file_not_locked() {
+        assert!(FileLock::acquire(Path::new("/dev/null"), None)
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,179 @@
+//! A lockfile recording the fingerprints of the keys resolved for each signer, so that changes to
+//! the keys returned by sources require deliberately regenerating it, similar to the lockfile of a
+//! package manager.
+use crate::allowed_signers::{ssh::Fingerprint, write_atomic, File};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The name of the lockfile, written next to the configuration file.
+pub(crate) const LOCKFILE_NAME: &str = "hanko.lock";
+
+/// The fingerprints of the keys resolved for each signer.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Lockfile {
+    /// The fingerprints of the keys of each signer by signer name.
+    signers: BTreeMap<String, BTreeSet<Fingerprint>>,
+}
+
+impl Lockfile {
+    /// Lock the keys written to the given file for each of the given signers by the name of the
+    /// signer they were retrieved for, regardless of the principals they were written for. Keys
+    /// that cannot be fingerprinted are not locked.
+    pub(crate) fn new(file: &File, signers: &[String]) -> Self {
+        let mut locked: BTreeMap<String, BTreeSet<Fingerprint>> = signers
+            .iter()
+            .map(|name| (name.clone(), BTreeSet::new()))
+            .collect();
+        for entry in &file.entries {
+            let key = entry.key();
+            if let (Some(fingerprints), Some(fingerprint)) = (
+                key.signer().and_then(|name| locked.get_mut(name)),
+                key.fingerprint(),
+            ) {
+                fingerprints.insert(fingerprint);
+            }
+        }
+        Self { signers: locked }
+    }
+
+    /// The path of the lockfile belonging to the given configuration file.
+    pub(crate) fn path(config: &Path) -> PathBuf {
+        config.with_file_name(LOCKFILE_NAME)
+    }
+
+    /// Load a lockfile from the given TOML file.
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Ok(toml_edit::de::from_str(&content)?)
+    }
+
+    /// Save the lockfile to the given TOML file, atomically replacing any existing lockfile.
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        let content = toml_edit::ser::to_string_pretty(self)?;
+        write_atomic(path, content.as_bytes())?;
+        Ok(())
+    }
+
+    /// Describe each key resolved now but not locked, as well as each locked key no longer
+    /// resolved, of every signer.
+    pub(crate) fn differences(&self, resolved: &Self) -> Vec<String> {
+        let none = BTreeSet::new();
+        let names: BTreeSet<&String> = self.signers.keys().chain(resolved.signers.keys()).collect();
+        let mut differences = Vec::new();
+        for name in names {
+            let locked = self.signers.get(name).unwrap_or(&none);
+            let current = resolved.signers.get(name).unwrap_or(&none);
+            for fingerprint in current.difference(locked) {
+                differences.push(format!("Signer {name} has unlocked key {fingerprint}"));
+            }
+            for fingerprint in locked.difference(current) {
+                differences.push(format!(
+                    "Signer {name} no longer has locked key {fingerprint}"
+                ));
+            }
+        }
+        differences
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allowed_signers::{ssh::PublicKey, Entry};
+    use std::path::PathBuf;
+
+    const KEY_A: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS";
+    const KEY_B: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILWtK6WxXw7NVhbn6fTQ0dECF8y98fahSIsqKMh+sSo9";
+
+    fn entry(principal: &str, key: &str, signer: &str) -> Entry {
+        Entry::new(
+            vec![principal.to_string()],
+            None,
+            None,
+            key.parse::<PublicKey>()
+                .unwrap()
+                .with_signer(Some(signer.to_string())),
+        )
+    }
+
+    fn signers() -> Vec<String> {
+        vec!["jsnow".to_string(), "cwoods".to_string()]
+    }
+
+    /// Keys are locked for the signer they were retrieved for and a lockfile survives saving and
+    /// loading it.
+    #[test]
+    fn keys_locked_by_signer() {
+        let file = File::from_entries(
+            PathBuf::from("allowed_signers"),
+            [
+                entry("j.snow@wall.com", KEY_A, "jsnow"),
+                entry("j.snow@wall.com", KEY_B, "jsnow"),
+            ],
+        );
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = Lockfile::path(&dir.path().join("config.toml"));
+
+        let lockfile = Lockfile::new(&file, &signers());
+        lockfile.save(&path).unwrap();
+
+        assert_eq!(path, dir.path().join(LOCKFILE_NAME));
+        assert_eq!(lockfile.signers["jsnow"].len(), 2);
+        assert!(lockfile.signers["cwoods"].is_empty());
+        assert_eq!(Lockfile::load(&path).unwrap(), lockfile);
+    }
+
+    /// Keys are not locked for other signers sharing a principal with the signer they were
+    /// retrieved for.
+    #[test]
+    fn keys_not_locked_by_shared_principal() {
+        let file = File::from_entries(
+            PathBuf::from("allowed_signers"),
+            [
+                entry("j.snow@wall.com", KEY_A, "jsnow"),
+                entry("j.snow@wall.com", KEY_B, "jon"),
+            ],
+        );
+
+        let lockfile = Lockfile::new(&file, &["jsnow".to_string(), "jon".to_string()]);
+
+        assert_eq!(lockfile.signers["jsnow"].len(), 1);
+        assert_eq!(lockfile.signers["jon"].len(), 1);
+        assert_ne!(lockfile.signers["jsnow"], lockfile.signers["jon"]);
+    }
+
+    /// Keys added and removed since locking are reported by signer, while identical keys are not.
+    #[test]
+    fn changed_keys_reported() {
+        let path = PathBuf::from("allowed_signers");
+        let locked = Lockfile::new(
+            &File::from_entries(path.clone(), [entry("j.snow@wall.com", KEY_A, "jsnow")]),
+            &signers(),
+        );
+        let resolved = Lockfile::new(
+            &File::from_entries(path, [entry("j.snow@wall.com", KEY_B, "jsnow")]),
+            &signers(),
+        );
+        let fingerprint = |key: &str| key.parse::<PublicKey>().unwrap().fingerprint().unwrap();
+
+        assert!(locked.differences(&locked).is_empty());
+        assert_eq!(
+            locked.differences(&resolved),
+            vec![
+                format!("Signer jsnow has unlocked key {}", fingerprint(KEY_B)),
+                format!(
+                    "Signer jsnow no longer has locked key {}",
+                    fingerprint(KEY_A)
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! Notify a webhook about changes to the allowed signers file, e.g. to feed chat or paging
+//! integrations on changes of trust.
+use anyhow::{Context, Result};
+use chrono::Local;
+use reqwest::Url;
+use serde_json::json;
+use std::path::Path;
+
+use crate::{
+    audit, clock,
+    source::{base_client, Protocol},
+    USER_AGENT,
+};
+
+/// Post the entries added and removed between two snapshots of the allowed signers file to the
+/// given webhook as JSON.
+/// Errors only name the host of the webhook, since it's URL may carry a secret, e.g. within the
+/// path of Slack webhooks.
+pub(crate) async fn send(
+    webhook: &Url,
+    config: &Path,
+    file: &Path,
+    before: &[String],
+    after: &[String],
+) -> Result<()> {
+    let (added, removed) = audit::changes(before, after);
+    let payload = json!({
+        "timestamp": clock::now().with_timezone(&Local).to_rfc3339(),
+        "config": config,
+        "file": file,
+        "added": added,
+        "removed": removed,
+    });
+
+    base_client(Protocol::default(), false)
+        .post(webhook.clone())
+        .header("User-Agent", USER_AGENT)
+        .json(&payload)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(reqwest::Error::without_url)
+        .context(format!(
+            "Failed to notify webhook at {}",
+            webhook.host_str().unwrap_or_default()
+        ))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+    use rstest::*;
+
+    /// The added and removed entries are posted to the webhook.
+    #[rstest]
+    #[tokio::test]
+    async fn changes_posted_to_webhook() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/hook").json_body_partial(
+                r#"{"file": "allowed_signers", "added": ["c"], "removed": ["a"]}"#,
+            );
+            then.status(204);
+        });
+        let line = |s: &str| s.to_string();
+
+        send(
+            &server.url("/hook").parse().unwrap(),
+            Path::new("config.toml"),
+            Path::new("allowed_signers"),
+            &[line("a"), line("b")],
+            &[line("b"), line("c")],
+        )
+        .await
+        .unwrap();
+
+        mock.assert();
+    }
+
+    /// Error status codes of the webhook are reported as errors, without the secret path of the
+    /// webhook.
+    #[rstest]
+    #[tokio::test]
+    async fn error_status_returns_error() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/hook/s3cr3t");
+            then.status(500);
+        });
+
+        let result = send(
+            &server.url("/hook/s3cr3t").parse().unwrap(),
+            Path::new("config.toml"),
+            Path::new("allowed_signers"),
+            &[],
+            &[],
+        )
+        .await;
+
+        let err = format!("{:#}", result.unwrap_err());
+        assert!(err.starts_with("Failed to notify webhook at 127.0.0.1"));
+        assert!(!err.contains("s3cr3t"));
+    }
+}
@@ -0,0 +1,130 @@
+//! Locations of the files used by hanko according to the XDG Base Directory Specification.
+//!
+//! Every base directory is given by it's environment variable, e.g. `$XDG_CONFIG_HOME`, falling
+//! back to it's default location within `$HOME`, e.g. `$HOME/.config`. Files of hanko are placed
+//! in a `hanko` directory within the base directory.
+//!
+//! [XDG Base Directory Specification](https://specifications.freedesktop.org/basedir-spec/latest/)
+use std::{env, ffi::OsString, path::PathBuf};
+
+/// The name of the directory containing the files of hanko within every base directory.
+const APP_DIR: &str = env!("CARGO_PKG_NAME");
+
+/// A base directory of the XDG Base Directory Specification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseDir {
+    /// User specific configuration files.
+    Config,
+    /// User specific non-essential data, which may be deleted at any time.
+    Cache,
+    /// User specific state that should persist between runs, like logs.
+    State,
+}
+
+impl BaseDir {
+    /// The environment variable giving the location of the base directory.
+    #[must_use]
+    pub fn env_var(self) -> &'static str {
+        match self {
+            BaseDir::Config => "XDG_CONFIG_HOME",
+            BaseDir::Cache => "XDG_CACHE_HOME",
+            BaseDir::State => "XDG_STATE_HOME",
+        }
+    }
+
+    /// The default location of the base directory relative to the home directory.
+    fn home_default(self) -> &'static str {
+        match self {
+            BaseDir::Config => ".config",
+            BaseDir::Cache => ".cache",
+            BaseDir::State => ".local/state",
+        }
+    }
+
+    /// The directory of hanko within the base directory.
+    /// Returns `None` if neither the environment variable of the base directory nor `$HOME` are
+    /// set.
+    #[must_use]
+    pub fn app_dir(self) -> Option<PathBuf> {
+        self.app_dir_with(|var| env::var_os(var))
+    }
+
+    /// The directory of hanko within the base directory, looking up environment variables using
+    /// the given function. Per specification, the environment variable of the base directory is
+    /// ignored if it is empty or not an absolute path.
+    fn app_dir_with<F>(self, lookup: F) -> Option<PathBuf>
+    where
+        F: Fn(&str) -> Option<OsString>,
+    {
+        let base = lookup(self.env_var())
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| {
+                lookup("HOME")
+                    .filter(|home| !home.is_empty())
+                    .map(|home| PathBuf::from(home).join(self.home_default()))
+            })?;
+        Some(base.join(APP_DIR))
+    }
+}
+
+/// The home directory of the current user, used to expand and interpolate paths.
+#[must_use]
+pub fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME").map(PathBuf::from)
+}
+
+/// The default location of the configuration file.
+/// Returns `None` if neither `$XDG_CONFIG_HOME` nor `$HOME` are set.
+#[must_use]
+pub fn config_file() -> Option<PathBuf> {
+    BaseDir::Config.app_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// The default directory of non-essential cached data, like retrieved keys.
+/// Returns `None` if neither `$XDG_CACHE_HOME` nor `$HOME` are set.
+#[must_use]
+pub fn cache_dir() -> Option<PathBuf> {
+    BaseDir::Cache.app_dir()
+}
+
+/// The default directory of state persisting between runs, like logs.
+/// Returns `None` if neither `$XDG_STATE_HOME` nor `$HOME` are set.
+#[must_use]
+pub fn state_dir() -> Option<PathBuf> {
+    BaseDir::State.app_dir()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use std::collections::HashMap;
+
+    /// The directory of hanko is placed within the base directory given by it's environment
+    /// variable, falling back to it's default location within the home directory if the variable
+    /// is unset, empty or relative.
+    #[rstest]
+    #[case(BaseDir::Config, &[("XDG_CONFIG_HOME", "/xdg/config"), ("HOME", "/home/jsnow")], Some("/xdg/config/hanko"))]
+    #[case(BaseDir::Config, &[("HOME", "/home/jsnow")], Some("/home/jsnow/.config/hanko"))]
+    #[case(BaseDir::Cache, &[("XDG_CACHE_HOME", "/xdg/cache"), ("HOME", "/home/jsnow")], Some("/xdg/cache/hanko"))]
+    #[case(BaseDir::Cache, &[("HOME", "/home/jsnow")], Some("/home/jsnow/.cache/hanko"))]
+    #[case(BaseDir::State, &[("XDG_STATE_HOME", "/xdg/state"), ("HOME", "/home/jsnow")], Some("/xdg/state/hanko"))]
+    #[case(BaseDir::State, &[("HOME", "/home/jsnow")], Some("/home/jsnow/.local/state/hanko"))]
+    #[case(BaseDir::State, &[("XDG_STATE_HOME", ""), ("HOME", "/home/jsnow")], Some("/home/jsnow/.local/state/hanko"))]
+    #[case(BaseDir::State, &[("XDG_STATE_HOME", "state"), ("HOME", "/home/jsnow")], Some("/home/jsnow/.local/state/hanko"))]
+    #[case(BaseDir::Config, &[("XDG_CONFIG_HOME", "/xdg/config")], Some("/xdg/config/hanko"))]
+    #[case(BaseDir::Cache, &[], None)]
+    #[case(BaseDir::Cache, &[("HOME", "")], None)]
+    fn app_dir_resolved(
+        #[case] base: BaseDir,
+        #[case] vars: &[(&str, &str)],
+        #[case] expected: Option<&str>,
+    ) {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+
+        let dir = base.app_dir_with(|var| vars.get(var).map(OsString::from));
+
+        assert_eq!(dir, expected.map(PathBuf::from));
+    }
+}
@@ -0,0 +1,245 @@
+//! Plans of changes to the allowed signers file, computed ahead of applying them.
+use crate::{
+    allowed_signers::{write_atomic, File, OutputFormat},
+    digest::sha256_hex,
+};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The changes to the allowed signers file an update would make, along with the content it would
+/// write and the hash of the file the changes were computed against.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub(crate) struct Plan {
+    file: PathBuf,
+    /// The SHA-256 hash of the file at the time the plan was made, `None` if it did not exist.
+    file_sha256: Option<String>,
+    add: Vec<Change>,
+    remove: Vec<Change>,
+    content: String,
+}
+
+/// A line added to or removed from the allowed signers file.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+struct Change {
+    line: String,
+    reason: Reason,
+}
+
+/// Why a line is added to or removed from the allowed signers file.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Reason {
+    /// A key was added for principals that already have other keys.
+    NewKey,
+    /// Principals that did not have any keys before were added.
+    NewPrincipals,
+    /// A key was removed from principals that keep other keys.
+    KeyRemoved,
+    /// Principals lost all of their keys.
+    PrincipalsRemoved,
+    /// A key was added to a file in the authorized keys format, which has no principals.
+    KeyAdded,
+}
+
+impl Plan {
+    /// Plan writing the given file, comparing it against the file currently on disk.
+    pub(crate) fn new(file: &File) -> Result<Self> {
+        let existing = read_existing(&file.path)?;
+        let before = lines(existing.as_deref().unwrap_or_default());
+        let content = file.content();
+        let after = lines(&content);
+
+        let has_principals =
+            |lines: &[&str], line: &str| lines.iter().any(|l| principals(l) == principals(line));
+        let reason = |added: bool, line: &str| match (file.format, added) {
+            (OutputFormat::AuthorizedKeys, true) => Reason::KeyAdded,
+            (OutputFormat::AuthorizedKeys, false) => Reason::KeyRemoved,
+            (OutputFormat::AllowedSigners, true) if has_principals(&before, line) => Reason::NewKey,
+            (OutputFormat::AllowedSigners, true) => Reason::NewPrincipals,
+            (OutputFormat::AllowedSigners, false) if has_principals(&after, line) => {
+                Reason::KeyRemoved
+            }
+            (OutputFormat::AllowedSigners, false) => Reason::PrincipalsRemoved,
+        };
+        let changes = |from: &[&str], to: &[&str], added: bool| -> Vec<Change> {
+            to.iter()
+                .filter(|line| !from.contains(line))
+                .map(|line| Change {
+                    line: (*line).to_string(),
+                    reason: reason(added, line),
+                })
+                .collect()
+        };
+
+        Ok(Self {
+            file: file.path.clone(),
+            file_sha256: existing.as_deref().map(sha256_hex),
+            add: changes(&before, &after, true),
+            remove: changes(&after, &before, false),
+            content,
+        })
+    }
+
+    /// The allowed signers file the plan applies to.
+    pub(crate) fn file(&self) -> &Path {
+        &self.file
+    }
+
+    /// A short summary of the planned changes.
+    pub(crate) fn summary(&self) -> String {
+        format!(
+            "{} line(s) to add, {} line(s) to remove",
+            self.add.len(),
+            self.remove.len()
+        )
+    }
+
+    /// Load a plan from the given JSON file.
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save the plan to the given JSON file.
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content + "\n")?;
+        Ok(())
+    }
+
+    /// Write the planned content to the allowed signers file, returning whether it's content
+    /// changed.
+    /// Fails without writing if the file changed since the plan was made.
+    pub(crate) fn apply(&self) -> Result<bool> {
+        let existing = read_existing(&self.file)?;
+        if existing.as_deref().map(sha256_hex) != self.file_sha256 {
+            bail!(
+                "Allowed signers file {} changed since the plan was made, create a new plan",
+                self.file.display()
+            )
+        }
+        if existing.as_deref() == Some(self.content.as_str()) {
+            return Ok(false);
+        }
+        write_atomic(&self.file, self.content.as_bytes()).context(format!(
+            "Failed to write allowed signers file to {}",
+            self.file.display()
+        ))?;
+        Ok(true)
+    }
+}
+
+/// The content of the allowed signers file at the given path, `None` if it does not exist.
+/// Only regular files can be planned, since the content of special files like FIFOs cannot be
+/// read without consuming it.
+fn read_existing(path: &Path) -> Result<Option<String>> {
+    match fs::metadata(path) {
+        Ok(metadata) if !metadata.is_file() => bail!(
+            "Unable to plan changes to {} since it is not a regular file",
+            path.display()
+        ),
+        Ok(_) => Ok(Some(fs::read_to_string(path)?)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// The principals of a line in the allowed signers format.
+fn principals(line: &str) -> &str {
+    line.split_whitespace().next().unwrap_or_default()
+}
+
+/// The non-blank lines of the given content, excluding comments.
+fn lines(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allowed_signers::Entry;
+
+    fn entry(principal: &str, key: &str) -> Entry {
+        Entry::new(
+            vec![principal.to_string()],
+            None,
+            None,
+            key.parse().unwrap(),
+        )
+    }
+
+    const KEY_A: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS";
+    const KEY_B: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILWtK6WxXw7NVhbn6fTQ0dECF8y98fahSIsqKMh+sSo9";
+
+    /// A plan lists the added and removed lines with their reason, and applying it writes the
+    /// planned content.
+    #[test]
+    fn plan_lists_changes_and_applies_content() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("allowed_signers");
+        fs::write(
+            &path,
+            format!("j.snow@wall.com {KEY_A}\nian.malcom@acme.corp {KEY_B}\n\n"),
+        )
+        .unwrap();
+        let file = File::from_entries(
+            path.clone(),
+            [
+                entry("j.snow@wall.com", KEY_A),
+                entry("j.snow@wall.com", KEY_B),
+                entry("c.woods@universal.exports", KEY_A),
+            ],
+        );
+
+        let plan = Plan::new(&file).unwrap();
+        let reasons = |changes: &[Change]| -> Vec<(String, Reason)> {
+            changes.iter().map(|c| (c.line.clone(), c.reason)).collect()
+        };
+
+        assert_eq!(
+            reasons(&plan.add),
+            vec![
+                (
+                    format!("c.woods@universal.exports {KEY_A}"),
+                    Reason::NewPrincipals
+                ),
+                (format!("j.snow@wall.com {KEY_B}"), Reason::NewKey),
+            ]
+        );
+        assert_eq!(
+            reasons(&plan.remove),
+            vec![(
+                format!("ian.malcom@acme.corp {KEY_B}"),
+                Reason::PrincipalsRemoved
+            )]
+        );
+        assert!(plan.apply().unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), file.content());
+    }
+
+    /// A plan is not applied if the allowed signers file changed since it was made.
+    #[test]
+    fn plan_not_applied_to_changed_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("allowed_signers");
+        let file = File::from_entries(path.clone(), [entry("j.snow@wall.com", KEY_A)]);
+
+        let plan = Plan::new(&file).unwrap();
+        fs::write(&path, format!("ian.malcom@acme.corp {KEY_B}\n")).unwrap();
+
+        assert!(plan.apply().is_err());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("ian.malcom@acme.corp {KEY_B}\n")
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//! A report of the keys currently configured for each signer on each of it's sources.
+use crate::{
+    allowed_signers::{ssh::PublicKey, OutputOptions, Signer},
+    clock,
+    source::Source,
+};
+use serde::Serialize;
+use std::{collections::HashMap, fmt::Write, sync::Arc};
+
+/// The keys of a signer on each of it's sources.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub(crate) struct SignerReport {
+    name: String,
+    principals: Vec<String>,
+    sources: Vec<SourceReport>,
+}
+
+/// The keys of a signer on a single source, or the error that occurred retrieving them.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct SourceReport {
+    source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    keys: Vec<KeyReport>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct KeyReport {
+    key: String,
+    fingerprint: Option<String>,
+    created_at: Option<String>,
+    /// Whether the key complies with the configured key age and allowed fingerprints and would
+    /// therefore be written to the allowed signers file.
+    accepted: bool,
+}
+
+/// Retrieve the keys of the given signers from each of their sources, identifying sources by the
+/// given names.
+pub(crate) async fn collect(
+    signers: &[Signer],
+    sources: &HashMap<String, Arc<Box<dyn Source>>>,
+    options: &OutputOptions,
+) -> Vec<SignerReport> {
+    let now = clock::now();
+    let source_name = |source: &Arc<Box<dyn Source>>| {
+        sources
+            .iter()
+            .find(|(_, s)| Arc::ptr_eq(s, source))
+            .map_or_else(|| "unknown".to_string(), |(name, _)| name.clone())
+    };
+    let key_report = |signer: &Signer, key: PublicKey| KeyReport {
+        fingerprint: key.fingerprint().map(|f| f.to_string()),
+        created_at: key.created_at().map(|dt| dt.to_rfc3339()),
+        accepted: options.accepts_key_age(&key, now) && signer.accepts_fingerprint(&key),
+        key: key.to_string(),
+    };
+
+    let mut reports = Vec::with_capacity(signers.len());
+    for signer in signers {
+        let mut source_reports = Vec::with_capacity(signer.sources.len());
+        for source in &signer.sources {
+            let (keys, error) = match source.get_keys_by_username(&signer.name).await {
+                Ok(keys) => (keys, None),
+                Err(err) => (Vec::new(), Some(err.to_string())),
+            };
+            source_reports.push(SourceReport {
+                source: source_name(source),
+                error,
+                keys: keys.into_iter().map(|k| key_report(signer, k)).collect(),
+            });
+        }
+        reports.push(SignerReport {
+            name: signer.name.clone(),
+            principals: signer.principals.clone(),
+            sources: source_reports,
+        });
+    }
+    reports
+}
+
+/// Render the given reports as a human readable tree.
+pub(crate) fn render_tree(reports: &[SignerReport]) -> String {
+    let branch = |last: bool| if last { "└── " } else { "├── " };
+    let indent = |last: bool| if last { "    " } else { "│   " };
+
+    let mut out = String::new();
+    for signer in reports {
+        let _ = writeln!(out, "{} ({})", signer.name, signer.principals.join(", "));
+        for (i, source) in signer.sources.iter().enumerate() {
+            let last_source = i + 1 == signer.sources.len();
+            let _ = write!(out, "{}{}", branch(last_source), source.source);
+            if let Some(error) = &source.error {
+                let _ = writeln!(out, ": {error}");
+                continue;
+            }
+            if source.keys.is_empty() {
+                let _ = writeln!(out, ": no keys");
+                continue;
+            }
+            let _ = writeln!(out);
+            for (j, key) in source.keys.iter().enumerate() {
+                let last_key = j + 1 == source.keys.len();
+                let key_type = key.key.split_whitespace().next().unwrap_or_default();
+                let _ = write!(
+                    out,
+                    "{}{}{} {key_type}",
+                    indent(last_source),
+                    branch(last_key),
+                    key.fingerprint.as_deref().unwrap_or("<invalid key>"),
+                );
+                if let Some(created_at) = &key.created_at {
+                    let _ = write!(out, ", created {created_at}");
+                }
+                if !key.accepted {
+                    let _ = write!(out, " (rejected)");
+                }
+                let _ = writeln!(out);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    /// Reports are rendered as a tree of signers, sources and keys.
+    #[test]
+    fn report_rendered_as_tree() {
+        let reports = vec![SignerReport {
+            name: "octocat".to_string(),
+            principals: vec!["octocat@github.com".to_string()],
+            sources: vec![
+                SourceReport {
+                    source: "github".to_string(),
+                    error: None,
+                    keys: vec![
+                        KeyReport {
+                            key: "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS".to_string(),
+                            fingerprint: Some("SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM".to_string()),
+                            created_at: Some("2023-05-23T09:35:15.638+00:00".to_string()),
+                            accepted: true,
+                        },
+                        KeyReport {
+                            key: "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILWtK6WxXw7NVhbn6fTQ0dECF8y98fahSIsqKMh+sSo9".to_string(),
+                            fingerprint: Some("SHA256:AwKAWrobAVuK0DN/1EkXEddUEKNF/soOb+gzQZClxm8".to_string()),
+                            created_at: None,
+                            accepted: false,
+                        },
+                    ],
+                },
+                SourceReport {
+                    source: "gitlab".to_string(),
+                    error: Some("requested user could not be found".to_string()),
+                    keys: vec![],
+                },
+            ],
+        }];
+
+        assert_eq!(
+            render_tree(&reports),
+            indoc! {"
+                octocat (octocat@github.com)
+                ├── github
+                │   ├── SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM ssh-ed25519, created 2023-05-23T09:35:15.638+00:00
+                │   └── SHA256:AwKAWrobAVuK0DN/1EkXEddUEKNF/soOb+gzQZClxm8 ssh-ed25519 (rejected)
+                └── gitlab: requested user could not be found
+            "}
+        );
+    }
+}
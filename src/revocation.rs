@@ -0,0 +1,277 @@
+//! Revoked keys, which never appear in the allowed signers file and can be exported to an OpenSSH
+//! key revocation list (KRL).
+//!
+//! [KRL Format](https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.krl?rev=HEAD)
+use crate::{
+    allowed_signers::{
+        ssh::{Fingerprint, PublicKey},
+        write_atomic,
+    },
+    clock,
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, path::Path, str::FromStr};
+
+const KRL_MAGIC: u64 = 0x5353_484b_524c_0a00;
+const KRL_FORMAT_VERSION: u32 = 1;
+/// The comment embedded in key revocation lists written by hanko.
+const KRL_COMMENT: &str = "hanko";
+/// The length of the header of key revocation lists written by hanko, which is followed by the
+/// sections containing revoked keys.
+const KRL_HEADER_LEN: usize = 8 + 4 + 8 + 8 + 8 + 4 + 4 + KRL_COMMENT.len();
+const KRL_SECTION_EXPLICIT_KEY: u8 = 2;
+const KRL_SECTION_FINGERPRINT_SHA256: u8 = 5;
+
+/// A revoked key, given either by it's SHA256 fingerprint or the public key itself.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum RevokedKey {
+    Fingerprint(Fingerprint),
+    Key(PublicKey),
+}
+
+impl RevokedKey {
+    /// The SHA256 fingerprint of the revoked key.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a revoked public key contains invalid base64, which is rejected when parsing.
+    #[must_use]
+    pub fn fingerprint(&self) -> Fingerprint {
+        match self {
+            Self::Fingerprint(fingerprint) => fingerprint.clone(),
+            Self::Key(key) => key
+                .fingerprint()
+                .expect("revoked key contains valid base64 since it is validated when parsed"),
+        }
+    }
+}
+
+impl FromStr for RevokedKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.starts_with("SHA256:") {
+            return s.parse().map(Self::Fingerprint);
+        }
+        let key: PublicKey = s.parse().expect("parsing a public key is infallible");
+        if key.material().1.is_empty() || key.fingerprint().is_none() {
+            return Err(format!(
+                "invalid revoked key `{s}`, expected a SHA256 fingerprint or public key"
+            ));
+        }
+        Ok(Self::Key(key))
+    }
+}
+
+impl fmt::Display for RevokedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fingerprint(fingerprint) => write!(f, "{fingerprint}"),
+            Self::Key(key) => write!(f, "{key}"),
+        }
+    }
+}
+
+impl TryFrom<String> for RevokedKey {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<RevokedKey> for String {
+    fn from(key: RevokedKey) -> Self {
+        key.to_string()
+    }
+}
+
+/// Read revoked keys from the file at the given path, containing one fingerprint or public key per
+/// line. Blank lines and lines starting with `#` are ignored.
+pub(crate) fn read_file(path: &Path) -> Result<Vec<RevokedKey>> {
+    let content = fs::read_to_string(path)
+        .context(format!("Failed to read revocation file {}", path.display()))?;
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| {
+            line.parse().map_err(|err: String| {
+                anyhow::anyhow!(err).context(format!(
+                    "Invalid revocation file {} at line {number}",
+                    path.display()
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Write the given revoked keys to a key revocation list at the given path.
+/// Keys given as public keys are revoked explicitly, while keys given by their fingerprint are
+/// revoked by their SHA256 hash. The list is only written if it revokes different keys than the
+/// existing one, returning whether it was written.
+pub(crate) fn write_krl(path: &Path, keys: &[RevokedKey]) -> Result<bool> {
+    let sections = krl_sections(keys);
+    if fs::read(path).is_ok_and(|existing| {
+        existing.starts_with(&KRL_MAGIC.to_be_bytes())
+            && existing.get(KRL_HEADER_LEN..) == Some(sections.as_slice())
+    }) {
+        return Ok(false);
+    }
+
+    let mut krl = Vec::with_capacity(KRL_HEADER_LEN + sections.len());
+    krl.extend(KRL_MAGIC.to_be_bytes());
+    krl.extend(KRL_FORMAT_VERSION.to_be_bytes());
+    // The version of the list, which hanko does not keep track of.
+    krl.extend(0u64.to_be_bytes());
+    krl.extend(
+        u64::try_from(clock::now().timestamp())
+            .unwrap_or_default()
+            .to_be_bytes(),
+    );
+    // Flags
+    krl.extend(0u64.to_be_bytes());
+    // Reserved
+    put_string(&mut krl, &[]);
+    put_string(&mut krl, KRL_COMMENT.as_bytes());
+    krl.extend(sections);
+
+    write_atomic(path, &krl).context(format!(
+        "Failed to write key revocation list {}",
+        path.display()
+    ))?;
+    Ok(true)
+}
+
+/// The sections of a key revocation list revoking the given keys, omitting empty sections.
+fn krl_sections(keys: &[RevokedKey]) -> Vec<u8> {
+    let mut blobs = Vec::new();
+    let mut digests = Vec::new();
+    for key in keys {
+        match key {
+            RevokedKey::Key(key) => blobs.extend(key.blob()),
+            RevokedKey::Fingerprint(fingerprint) => digests.push(fingerprint.digest()),
+        }
+    }
+
+    let mut sections = Vec::new();
+    for (section_type, mut items) in [
+        (KRL_SECTION_EXPLICIT_KEY, blobs),
+        (KRL_SECTION_FINGERPRINT_SHA256, digests),
+    ] {
+        if items.is_empty() {
+            continue;
+        }
+        items.sort();
+        items.dedup();
+        let mut data = Vec::new();
+        for item in items {
+            put_string(&mut data, &item);
+        }
+        sections.push(section_type);
+        put_string(&mut sections, &data);
+    }
+    sections
+}
+
+/// Append the given bytes as a length prefixed string of the SSH wire format.
+fn put_string(buf: &mut Vec<u8>, bytes: &[u8]) {
+    let len = u32::try_from(bytes.len()).expect("strings within a KRL are smaller than 4GiB");
+    buf.extend(len.to_be_bytes());
+    buf.extend(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use std::process::Command;
+
+    const KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS";
+    const OTHER_KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAINWVnTOPhbyAN0fd0bbZ+8b1GkxfqL/kbebhrgj4OAbq";
+    const UNREVOKED_KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIMfNgP4lpVFSyLzHPj5B1wCTsHwVfJTaMJ8jbr1vbqSX";
+
+    /// Revoked keys are parsed from fingerprints and public keys, rejecting anything else.
+    #[rstest]
+    #[case("SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM", true)]
+    #[case(KEY, true)]
+    #[case("ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQ!!!", false)]
+    #[case("ssh-ed25519", false)]
+    #[case("SHA256:invalid", false)]
+    fn revoked_key_parsed(#[case] s: &str, #[case] valid: bool) {
+        assert_eq!(s.parse::<RevokedKey>().is_ok(), valid);
+    }
+
+    /// Keys revoked by their public key or fingerprint within the written KRL are reported as
+    /// revoked by `ssh-keygen -Q`, while other keys are not.
+    #[rstest]
+    fn krl_checked_by_ssh_keygen() {
+        if Command::new("ssh-keygen").arg("-?").output().is_err() {
+            // The test is skipped if ssh-keygen is not available.
+            return;
+        }
+        let dir = tempfile::TempDir::new().unwrap();
+        let krl = dir.path().join("revoked.krl");
+        let other_fingerprint = OTHER_KEY
+            .parse::<PublicKey>()
+            .unwrap()
+            .fingerprint()
+            .unwrap();
+        let keys = vec![
+            RevokedKey::Key(KEY.parse().unwrap()),
+            RevokedKey::Fingerprint(other_fingerprint),
+        ];
+
+        assert!(write_krl(&krl, &keys).unwrap());
+        for (key, revoked) in [(KEY, true), (OTHER_KEY, true), (UNREVOKED_KEY, false)] {
+            let public_key = dir.path().join("key.pub");
+            fs::write(&public_key, format!("{key}\n")).unwrap();
+            let status = Command::new("ssh-keygen")
+                .arg("-Q")
+                .arg("-f")
+                .arg(&krl)
+                .arg(&public_key)
+                .output()
+                .unwrap()
+                .status;
+            assert_eq!(!status.success(), revoked, "{key}");
+        }
+    }
+
+    /// The KRL is only rewritten if the revoked keys change.
+    #[rstest]
+    fn krl_only_written_on_change() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let krl = dir.path().join("revoked.krl");
+        let keys = vec![RevokedKey::Key(KEY.parse().unwrap())];
+
+        assert!(write_krl(&krl, &keys).unwrap());
+        assert!(!write_krl(&krl, &keys).unwrap());
+        assert!(write_krl(&krl, &[]).unwrap());
+    }
+
+    /// Revocation files are read skipping blank and comment lines, reporting invalid lines.
+    #[rstest]
+    fn revocation_file_read() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("revoked");
+        fs::write(&path, format!("# Lost laptop\n{KEY} jsnow@laptop\n\n")).unwrap();
+        assert_eq!(
+            read_file(&path).unwrap(),
+            vec![RevokedKey::Key(
+                format!("{KEY} jsnow@laptop").parse().unwrap()
+            )]
+        );
+
+        fs::write(&path, format!("{KEY}\ninvalid\n")).unwrap();
+        let err = read_file(&path).unwrap_err();
+        assert!(err.to_string().ends_with("at line 2"), "{err}");
+    }
+}
@@ -0,0 +1,128 @@
+//! Detached signatures of the allowed signers file, created and verified using `ssh-keygen`.
+use anyhow::{bail, Context, Result};
+use std::{
+    ffi::OsString,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+/// The namespace signatures are created in, preventing them from being mistaken for signatures of
+/// other data like commits.
+const NAMESPACE: &str = "hanko-allowed-signers";
+
+/// The principal the trusted public key is given when verifying a signature.
+const PRINCIPAL: &str = "hanko";
+
+/// The path of the detached signature of the given file.
+pub(crate) fn signature_path(file: &Path) -> PathBuf {
+    let mut path = OsString::from(file.as_os_str());
+    path.push(".sig");
+    PathBuf::from(path)
+}
+
+/// Sign the given file using the given SSH key, writing the detached signature next to it.
+pub(crate) fn sign(file: &Path, key: &Path) -> Result<PathBuf> {
+    let output = Command::new("ssh-keygen")
+        .args(["-Y", "sign", "-n", NAMESPACE, "-f"])
+        .arg(key)
+        .stdin(fs::File::open(file).context(format!("Failed to open {}", file.display()))?)
+        .stderr(Stdio::piped())
+        .output()
+        .context("Failed to run ssh-keygen")?;
+    if !output.status.success() {
+        bail!(
+            "ssh-keygen failed to sign: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    }
+
+    let path = signature_path(file);
+    fs::write(&path, output.stdout)
+        .context(format!("Failed to write signature to {}", path.display()))?;
+    Ok(path)
+}
+
+/// Verify the detached signature of the given file was made by the given public key.
+pub(crate) fn verify(file: &Path, signature: &Path, public_key: &str) -> Result<()> {
+    let content = fs::File::open(file).context(format!("Failed to open {}", file.display()))?;
+    // ssh-keygen only accepts trusted keys in the allowed signers format, which is written to a
+    // temporary file. The file is created exclusively under a random name, so that other users
+    // cannot swap the trusted key by placing a file or symlink at a predictable path beforehand.
+    let mut trusted = tempfile::Builder::new()
+        .prefix("hanko-")
+        .suffix(".allowed_signers")
+        .tempfile()
+        .context("Failed to create temporary file for trusted public key")?;
+    writeln!(trusted, "{PRINCIPAL} {}", public_key.trim())
+        .and_then(|()| trusted.flush())
+        .context("Failed to write trusted public key")?;
+
+    let output = Command::new("ssh-keygen")
+        .args(["-Y", "verify", "-n", NAMESPACE, "-I", PRINCIPAL, "-f"])
+        .arg(trusted.path())
+        .arg("-s")
+        .arg(signature)
+        .stdin(content)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .context("Failed to run ssh-keygen");
+    // Removing the temporary file is best effort and happens on drop, ignoring failures.
+    drop(trusted);
+    let output = output?;
+    if !output.status.success() {
+        bail!(
+            "Signature verification failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Generate an SSH key within the given directory, returning `None` if `ssh-keygen` is not
+    /// available.
+    fn generate_key(dir: &Path, name: &str) -> Option<PathBuf> {
+        let key = dir.join(name);
+        let status = Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+            .arg(&key)
+            .status()
+            .ok()?;
+        status.success().then_some(key)
+    }
+
+    /// A signed file is verified using the public key of the signing key, while modifications
+    /// of the file or other keys fail verification.
+    #[test]
+    fn signed_file_verified() {
+        let dir = TempDir::new().unwrap();
+        // The test is skipped if ssh-keygen is not available.
+        let (Some(key), Some(other_key)) = (
+            generate_key(dir.path(), "key"),
+            generate_key(dir.path(), "other"),
+        ) else {
+            return;
+        };
+        let public_key = fs::read_to_string(key.with_extension("pub")).unwrap();
+        let other_public_key = fs::read_to_string(other_key.with_extension("pub")).unwrap();
+        let file = dir.path().join("allowed_signers");
+        fs::write(&file, "j.snow@wall.com ssh-ed25519 AAAA\n").unwrap();
+
+        sign(&file, &key).unwrap();
+        // Signing again overwrites the existing signature.
+        let signature = sign(&file, &key).unwrap();
+
+        assert_eq!(signature, dir.path().join("allowed_signers.sig"));
+        assert!(verify(&file, &signature, &public_key).is_ok());
+        assert!(verify(&file, &signature, &other_public_key).is_err());
+        fs::write(&file, "mallory@evil.com ssh-ed25519 AAAA\n").unwrap();
+        assert!(verify(&file, &signature, &public_key).is_err());
+    }
+}
@@ -0,0 +1,488 @@
+//! HTTP clients shared by all sources, so that requests to the same host reuse connections.
+use super::main::Token;
+use crate::{
+    diagnostic::{warning, Warning},
+    duration::Duration as ConfigDuration,
+    USER_AGENT,
+};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    redirect, Client, Url,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        OnceLock,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+use tower_layer::Layer;
+use tower_service::Service;
+use tracing::debug;
+
+/// The number of requests made to sources.
+static REQUESTS: AtomicUsize = AtomicUsize::new(0);
+/// The number of connections opened to sources, including failed attempts.
+static CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+/// The connection pool options of the shared clients.
+static POOL: OnceLock<PoolOptions> = OnceLock::new();
+/// The maximum number of redirects followed per request.
+static MAX_REDIRECTS: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_REDIRECTS);
+/// The timeouts of requests made by the shared clients.
+static TIMEOUTS: OnceLock<Timeouts> = OnceLock::new();
+
+/// The maximum number of redirects followed per request unless configured otherwise.
+pub const DEFAULT_MAX_REDIRECTS: usize = 5;
+
+/// The timeouts of requests made to sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Timeouts {
+    /// How long establishing a connection may take.
+    connect: Duration,
+    /// How long a request may take in total, from connecting until the response body was read.
+    request: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            connect: Duration::from_secs(2),
+            request: Duration::from_secs(10),
+        }
+    }
+}
+
+/// The HTTP protocol used for requests to a source.
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Protocol {
+    /// Negotiate HTTP/2 during the TLS handshake, falling back to HTTP/1.1.
+    #[default]
+    Auto,
+    /// Use HTTP/2 with prior knowledge. Concurrent requests to the same host are multiplexed over
+    /// a single connection right away, instead of each opening a connection while the protocol is
+    /// still being negotiated. Fails for hosts not supporting HTTP/2.
+    Http2,
+}
+
+/// Options of the connection pools of the shared clients, tuning how connections to sources are
+/// reused. The defaults match those of reqwest.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PoolOptions {
+    /// How long idle connections are kept open for reuse.
+    pub idle_timeout: ConfigDuration,
+    /// The maximum number of idle connections kept open per host, unlimited if unset.
+    pub max_idle_per_host: Option<usize>,
+}
+
+impl Default for PoolOptions {
+    fn default() -> Self {
+        Self {
+            idle_timeout: ConfigDuration::new(Duration::from_secs(90)),
+            max_idle_per_host: None,
+        }
+    }
+}
+
+/// Set the connection pool options of the shared clients.
+/// Since clients are shared by all sources, only the first options set before any client is
+/// built take effect. Differing options set later on are ignored with a warning, e.g. those of
+/// further configurations updated using `--each-config`.
+pub fn configure_pool(options: PoolOptions) {
+    if let Err(options) = POOL.set(options) {
+        if POOL.get() == Some(&options) {
+            debug!("Connection pool options already configured");
+        } else {
+            warning!(
+                Warning::PoolOptionsIgnored,
+                "Ignoring connection pool options {options:?} since the shared connection pool was already configured using {:?}",
+                POOL.get()
+            );
+        }
+    }
+}
+
+/// The number of requests made to sources and the connections they were made over.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectionStats {
+    pub requests: usize,
+    pub connections: usize,
+}
+
+impl ConnectionStats {
+    /// A human readable summary of the statistics, e.g. `120 request(s) over 1 connection(s)`.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        format!(
+            "{} request(s) over {} connection(s)",
+            self.requests, self.connections
+        )
+    }
+}
+
+/// The number of requests made to sources and connections opened by the shared clients so far.
+#[must_use]
+pub fn connection_stats() -> ConnectionStats {
+    ConnectionStats {
+        requests: REQUESTS.load(Ordering::Relaxed),
+        connections: CONNECTIONS.load(Ordering::Relaxed),
+    }
+}
+
+/// Set the maximum number of redirects followed per request, failing requests redirected more
+/// often.
+pub(crate) fn set_max_redirects(max: usize) {
+    MAX_REDIRECTS.store(max, Ordering::Relaxed);
+}
+
+/// Override the built-in timeouts of requests made to sources, keeping the defaults of timeouts
+/// not given. Since clients are shared by all sources, this only takes effect before any client
+/// is built.
+pub(crate) fn set_timeouts(connect: Option<Duration>, request: Option<Duration>) {
+    let defaults = Timeouts::default();
+    let timeouts = Timeouts {
+        connect: connect.unwrap_or(defaults.connect),
+        request: request.unwrap_or(defaults.request),
+    };
+    if TIMEOUTS.set(timeouts).is_err() {
+        debug!("Timeouts already configured, ignoring new timeouts");
+    }
+}
+
+/// Count a request made to a source.
+pub(super) fn count_request() {
+    REQUESTS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The reqwest client to be used by sources using the given protocol.
+/// Clients are shared by all sources using the same protocol and redirect policy, since
+/// connections are only reused within the pool of a single client.
+///
+/// Clients of sources sending auth headers only follow redirects to the same origin, since reqwest
+/// only strips the `Authorization` and `Cookie` headers when redirected to another host and would
+/// send the credentials of a proxy to any host the source redirects to.
+pub(crate) fn base_client(protocol: Protocol, auth_headers: bool) -> Client {
+    static AUTO: OnceLock<Client> = OnceLock::new();
+    static HTTP2: OnceLock<Client> = OnceLock::new();
+    static AUTO_SAME_ORIGIN: OnceLock<Client> = OnceLock::new();
+    static HTTP2_SAME_ORIGIN: OnceLock<Client> = OnceLock::new();
+
+    let client = match (protocol, auth_headers) {
+        (Protocol::Auto, false) => &AUTO,
+        (Protocol::Http2, false) => &HTTP2,
+        (Protocol::Auto, true) => &AUTO_SAME_ORIGIN,
+        (Protocol::Http2, true) => &HTTP2_SAME_ORIGIN,
+    };
+    client
+        .get_or_init(|| {
+            build_client(
+                protocol,
+                POOL.get_or_init(PoolOptions::default),
+                *TIMEOUTS.get_or_init(Timeouts::default),
+                auth_headers,
+                &CONNECTIONS,
+            )
+        })
+        .clone()
+}
+
+/// Build the headers sent along with every request to a source, e.g. the service credentials of a
+/// zero-trust proxy in front of it. Their values are marked sensitive, redacting them from logs and
+/// request dumps. Fails with the name of the first header whose name or value is invalid.
+pub(crate) fn auth_headers(headers: &BTreeMap<String, Token>) -> Result<HeaderMap, String> {
+    let mut map = HeaderMap::with_capacity(headers.len());
+    for (name, value) in headers {
+        let (Ok(header_name), Ok(mut header_value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value.expose()),
+        ) else {
+            return Err(name.clone());
+        };
+        header_value.set_sensitive(true);
+        map.insert(header_name, header_value);
+    }
+    Ok(map)
+}
+
+/// Build a client using the given protocol, connection pool options and timeouts, counting the
+/// connections it opens. Redirects to other origins are refused if `same_origin` is set.
+fn build_client(
+    protocol: Protocol,
+    pool: &PoolOptions,
+    timeouts: Timeouts,
+    same_origin: bool,
+    connections: &'static AtomicUsize,
+) -> Client {
+    debug!(
+        ?protocol,
+        ?pool,
+        ?timeouts,
+        same_origin,
+        "Building HTTP client"
+    );
+    let mut builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(timeouts.connect)
+        .timeout(timeouts.request)
+        .pool_idle_timeout(Duration::from(pool.idle_timeout))
+        .pool_max_idle_per_host(pool.max_idle_per_host.unwrap_or(usize::MAX))
+        .redirect(redirect_policy(&MAX_REDIRECTS, same_origin))
+        .use_rustls_tls()
+        .connector_layer(CountConnections(connections));
+    if protocol == Protocol::Http2 {
+        builder = builder.http2_prior_knowledge();
+    }
+    builder.build().unwrap()
+}
+
+/// A redirect to another origin refused, since it would send the auth headers of a source there.
+#[derive(thiserror::Error, Debug)]
+#[error("refused redirect to {0}, which would send auth headers to another origin")]
+pub(super) struct CrossOriginRedirect(pub(super) Url);
+
+/// A redirect policy following at most the given number of redirects, logging every redirect
+/// followed to diagnose requests ending up somewhere unexpected, like a login page. Redirects to
+/// another origin than the one requested are refused if `same_origin` is set.
+fn redirect_policy(max_redirects: &'static AtomicUsize, same_origin: bool) -> redirect::Policy {
+    redirect::Policy::custom(move |attempt| {
+        let max = max_redirects.load(Ordering::Relaxed);
+        if attempt.previous().len() > max {
+            return attempt.error(format!("more than {max} redirects"));
+        }
+        if same_origin
+            && attempt
+                .previous()
+                .first()
+                .is_some_and(|first| first.origin() != attempt.url().origin())
+        {
+            let url = attempt.url().clone();
+            return attempt.error(CrossOriginRedirect(url));
+        }
+        if let Some(from) = attempt.previous().last() {
+            debug!(%from, to = %attempt.url(), status = %attempt.status(), "Following redirect");
+        }
+        attempt.follow()
+    })
+}
+
+/// A layer of the connector of a client, counting the connections it opens.
+#[derive(Debug, Clone, Copy)]
+struct CountConnections(&'static AtomicUsize);
+
+impl<S> Layer<S> for CountConnections {
+    type Service = CountedConnector<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CountedConnector {
+            inner,
+            connections: self.0,
+        }
+    }
+}
+
+/// A connector counting the connections opened using it.
+#[derive(Debug, Clone)]
+struct CountedConnector<S> {
+    inner: S,
+    connections: &'static AtomicUsize,
+}
+
+impl<S, R> Service<R> for CountedConnector<S>
+where
+    S: Service<R>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        self.connections.fetch_add(1, Ordering::Relaxed);
+        self.inner.call(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Error;
+    use super::*;
+    use httpmock::prelude::*;
+    use rstest::*;
+
+    /// Subsequent requests to the same host reuse the connection opened by the first request.
+    #[tokio::test]
+    async fn connections_reused_for_same_host() {
+        static TEST_CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET);
+            then.status(200);
+        });
+        let client = build_client(
+            Protocol::Auto,
+            &PoolOptions::default(),
+            Timeouts::default(),
+            false,
+            &TEST_CONNECTIONS,
+        );
+
+        for _ in 0..3 {
+            let response = client.get(server.base_url()).send().await.unwrap();
+            response.bytes().await.unwrap();
+        }
+
+        assert_eq!(TEST_CONNECTIONS.load(Ordering::Relaxed), 1);
+    }
+
+    /// Redirects are followed up to the maximum number of redirects, failing requests redirected
+    /// more often.
+    #[rstest]
+    #[case(2, true)]
+    #[case(1, false)]
+    #[tokio::test]
+    async fn redirects_followed_up_to_maximum(#[case] max: usize, #[case] followed: bool) {
+        static TEST_MAX_REDIRECTS: AtomicUsize = AtomicUsize::new(0);
+        TEST_MAX_REDIRECTS.store(max, Ordering::Relaxed);
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/first");
+            then.status(302).header("Location", server.url("/second"));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/second");
+            then.status(302).header("Location", server.url("/last"));
+        });
+        let last = server.mock(|when, then| {
+            when.method(GET).path("/last");
+            then.status(200);
+        });
+        let client = Client::builder()
+            .redirect(redirect_policy(&TEST_MAX_REDIRECTS, false))
+            .build()
+            .unwrap();
+
+        let result = client.get(server.url("/first")).send().await;
+
+        assert_eq!(result.is_ok(), followed);
+        if let Err(err) = result {
+            assert!(matches!(Error::from(err), Error::TooManyRedirects));
+        }
+        last.assert_hits(usize::from(followed));
+    }
+
+    /// Clients of sources sending auth headers follow redirects within the same origin, but refuse
+    /// redirects to other origins instead of sending the headers there.
+    #[rstest]
+    #[case(false, true)]
+    #[case(true, false)]
+    #[tokio::test]
+    async fn cross_origin_redirects_refused_for_same_origin(
+        #[case] same_origin: bool,
+        #[case] followed: bool,
+    ) {
+        static TEST_MAX_REDIRECTS: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_REDIRECTS);
+        let source = MockServer::start();
+        let other = MockServer::start();
+        source.mock(|when, then| {
+            when.method(GET).path("/first");
+            then.status(302).header("Location", source.url("/second"));
+        });
+        source.mock(|when, then| {
+            when.method(GET).path("/second");
+            then.status(302).header("Location", other.url("/keys"));
+        });
+        let leaked = other.mock(|when, then| {
+            when.method(GET)
+                .path("/keys")
+                .header_exists("CF-Access-Client-Secret");
+            then.status(200);
+        });
+        let client = Client::builder()
+            .redirect(redirect_policy(&TEST_MAX_REDIRECTS, same_origin))
+            .build()
+            .unwrap();
+
+        let result = client
+            .get(source.url("/first"))
+            .header("CF-Access-Client-Secret", "secret")
+            .send()
+            .await;
+
+        assert_eq!(result.is_ok(), followed);
+        if let Err(err) = result {
+            assert_eq!(
+                Error::from(err),
+                Error::CrossOriginRedirect(other.url("/keys"))
+            );
+        }
+        leaked.assert_hits(usize::from(followed));
+    }
+
+    /// Connections are not reused if no idle connections are kept open.
+    #[tokio::test]
+    async fn connections_not_reused_without_idle_connections() {
+        static TEST_CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET);
+            then.status(200);
+        });
+        let pool = PoolOptions {
+            max_idle_per_host: Some(0),
+            ..PoolOptions::default()
+        };
+        let client = build_client(
+            Protocol::Auto,
+            &pool,
+            Timeouts::default(),
+            false,
+            &TEST_CONNECTIONS,
+        );
+
+        for _ in 0..3 {
+            let response = client.get(server.base_url()).send().await.unwrap();
+            response.bytes().await.unwrap();
+        }
+
+        assert_eq!(TEST_CONNECTIONS.load(Ordering::Relaxed), 3);
+    }
+
+    /// Requests taking longer than the request timeout fail with a timeout.
+    #[rstest]
+    #[case(Duration::from_millis(100), false)]
+    #[case(Duration::from_secs(5), true)]
+    #[tokio::test]
+    async fn requests_time_out(#[case] request: Duration, #[case] succeeds: bool) {
+        static TEST_CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET);
+            then.status(200).delay(Duration::from_millis(500));
+        });
+        let timeouts = Timeouts {
+            request,
+            ..Timeouts::default()
+        };
+        let client = build_client(
+            Protocol::Auto,
+            &PoolOptions::default(),
+            timeouts,
+            false,
+            &TEST_CONNECTIONS,
+        );
+
+        let result = client.get(server.base_url()).send().await;
+
+        assert_eq!(result.is_ok(), succeeds);
+        if let Err(err) = result {
+            assert!(err.is_timeout(), "{err}");
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use super::{main::Result, Error, Source};
+use crate::allowed_signers::ssh::PublicKey;
+use async_trait::async_trait;
+use std::process::Stdio;
+use tokio::process;
+use tracing::{debug, trace};
+
+/// The placeholder substituted by the username in a command's arguments.
+const USERNAME_PLACEHOLDER: &str = "{username}";
+
+/// The exit code used by a command to signal that the requested user does not exist.
+const EXIT_CODE_USER_NOT_FOUND: i32 = 1;
+
+/// A source retrieving public keys by running an external command.
+///
+/// The command is split into a program and its arguments on whitespace and executed directly,
+/// without a shell, substituting every occurrence of `{username}` within its arguments.
+/// A successful command prints one public key per line to stdout, empty lines and lines starting
+/// with `#` are ignored. Exiting with code 1 signals that the user does not exist.
+#[derive(Debug)]
+pub struct Command {
+    program: String,
+    args: Vec<String>,
+}
+
+impl Command {
+    /// Create a new command source from the given command line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command line does not contain a program.
+    pub fn new(command: &str) -> Result<Self> {
+        let mut parts = command.split_whitespace().map(ToString::to_string);
+        let program = parts
+            .next()
+            .ok_or_else(|| Error::CommandFailed("command is empty".to_string()))?;
+        Ok(Self {
+            program,
+            args: parts.collect(),
+        })
+    }
+
+    /// The command arguments with the given username substituted.
+    fn args(&self, username: &str) -> impl Iterator<Item = String> + '_ {
+        let username = username.to_string();
+        self.args
+            .iter()
+            .map(move |arg| arg.replace(USERNAME_PLACEHOLDER, &username))
+    }
+}
+
+#[async_trait]
+impl Source for Command {
+    async fn get_keys_by_username(&self, username: &str) -> Result<Vec<PublicKey>> {
+        debug!(program = self.program, "Running command to retrieve keys");
+        let output = process::Command::new(&self.program)
+            .args(self.args(username))
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|err| {
+                Error::CommandFailed(format!("failed to run {}: {err}", self.program))
+            })?;
+        trace!(?output, "Command finished");
+
+        match output.status.code() {
+            Some(0) => Ok(String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|line| line.parse().expect("parsing a public key is infallible"))
+                .collect()),
+            Some(EXIT_CODE_USER_NOT_FOUND) => Err(Error::UserNotFound),
+            _ => Err(Error::CommandFailed(format!(
+                "{} exited with {}: {}",
+                self.program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))),
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    const KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS";
+
+    /// The username placeholder is substituted in every argument it occurs in.
+    #[rstest]
+    fn username_substituted_in_args() {
+        let command = Command::new("fetch-keys --user {username} --path /{username}/keys").unwrap();
+
+        let args: Vec<String> = command.args("octocat").collect();
+
+        assert_eq!(args, ["--user", "octocat", "--path", "/octocat/keys"]);
+    }
+
+    /// An empty command is rejected.
+    #[rstest]
+    #[case("")]
+    #[case("   ")]
+    fn empty_command_is_rejected(#[case] command: &str) {
+        assert!(matches!(
+            Command::new(command),
+            Err(Error::CommandFailed(_))
+        ));
+    }
+
+    /// Keys printed to stdout by a successful command are returned, skipping blank and comment
+    /// lines.
+    #[rstest]
+    #[tokio::test]
+    async fn keys_read_from_stdout() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("keys.sh");
+        fs::write(
+            &script,
+            format!("#!/bin/sh\necho '{KEY}'\necho\necho '# comment'\necho '{KEY}'\n"),
+        )
+        .unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        let command = Command::new(script.to_str().unwrap()).unwrap();
+
+        let keys = command.get_keys_by_username("octocat").await.unwrap();
+
+        assert_eq!(keys, vec![KEY.parse().unwrap(), KEY.parse().unwrap()]);
+    }
+
+    /// A username containing shell metacharacters is passed as a single argument without being
+    /// interpreted.
+    #[rstest]
+    #[tokio::test]
+    async fn username_not_interpreted_by_shell() {
+        let command = Command::new("echo {username}").unwrap();
+
+        let keys = command.get_keys_by_username("$(id);`id`").await.unwrap();
+
+        assert_eq!(keys, vec!["$(id);`id`".parse().unwrap()]);
+    }
+
+    /// Exit code 1 signals that the user does not exist.
+    #[rstest]
+    #[tokio::test]
+    async fn exit_code_one_returns_user_not_found() {
+        let command = Command::new("false").unwrap();
+
+        let err = command.get_keys_by_username("octocat").await.unwrap_err();
+
+        assert_eq!(err, Error::UserNotFound);
+    }
+
+    /// Other failures are returned as a failed command.
+    #[rstest]
+    #[case("ls /hanko-path-that-does-not-exist")]
+    #[case("hanko-command-that-does-not-exist")]
+    #[tokio::test]
+    async fn failure_returns_command_failed(#[case] command: &str) {
+        let command = Command::new(command).unwrap();
+
+        let err = command.get_keys_by_username("octocat").await.unwrap_err();
+
+        assert!(matches!(err, Error::CommandFailed(_)));
+    }
+}
@@ -0,0 +1,138 @@
+//! Dumping of HTTP requests made to sources and their responses for debugging purposes.
+use reqwest::{
+    header::{HeaderMap, HeaderValue},
+    Client, Request, Response, ResponseBuilderExt, StatusCode,
+};
+use std::{
+    io::{self, Write},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Whether requests and responses are dumped to stderr.
+static DUMP_REQUESTS: AtomicBool = AtomicBool::new(false);
+
+/// Headers that contain secrets and are redacted from dumps.
+const SENSITIVE_HEADERS: [&str; 4] = ["authorization", "private-token", "cookie", "set-cookie"];
+
+/// Dump all requests made to sources and their responses to stderr.
+pub(crate) fn enable_request_dumps() {
+    DUMP_REQUESTS.store(true, Ordering::Relaxed);
+}
+
+/// Execute a request using the given client, dumping the request and it's response to stderr if
+/// enabled. Every executed request is counted towards the [`super::ConnectionStats`].
+pub(super) async fn execute(client: &Client, request: Request) -> reqwest::Result<Response> {
+    super::client::count_request();
+    if !DUMP_REQUESTS.load(Ordering::Relaxed) {
+        return client.execute(request).await;
+    }
+
+    // Dumps are best effort, failing to write to stderr should not fail the request.
+    let _ = dump_request(&mut io::stderr().lock(), &request);
+    let response = client.execute(request).await?;
+
+    let url = response.url().clone();
+    let status = response.status();
+    let version = response.version();
+    let headers = response.headers().clone();
+    let body = response.bytes().await?;
+    let _ = dump_response(&mut io::stderr().lock(), status, &headers, &body);
+
+    // The body has been consumed, so an equivalent response is rebuilt from it's parts.
+    let mut builder = http::Response::builder()
+        .status(status)
+        .version(version)
+        .url(url);
+    if let Some(h) = builder.headers_mut() {
+        *h = headers;
+    }
+    Ok(builder
+        .body(body)
+        .expect("response parts are valid since they originate from a response")
+        .into())
+}
+
+/// Write a request in a curl like format, with sensitive headers redacted.
+fn dump_request(out: &mut impl Write, request: &Request) -> io::Result<()> {
+    writeln!(out, "> {} {}", request.method(), request.url())?;
+    dump_headers(out, '>', request.headers())?;
+    writeln!(out, ">")?;
+    if let Some(body) = request.body().and_then(reqwest::Body::as_bytes) {
+        writeln!(out, "{}", String::from_utf8_lossy(body))?;
+    }
+    Ok(())
+}
+
+/// Write a response in a curl like format, with sensitive headers redacted.
+fn dump_response(
+    out: &mut impl Write,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> io::Result<()> {
+    writeln!(out, "< {status}")?;
+    dump_headers(out, '<', headers)?;
+    writeln!(out, "<")?;
+    writeln!(out, "{}", String::from_utf8_lossy(body))
+}
+
+fn dump_headers(out: &mut impl Write, prefix: char, headers: &HeaderMap) -> io::Result<()> {
+    for (name, value) in headers {
+        writeln!(out, "{prefix} {name}: {}", redacted(name.as_str(), value))?;
+    }
+    Ok(())
+}
+
+/// The printable value of a header, redacted if it is sensitive.
+fn redacted<'a>(name: &str, value: &'a HeaderValue) -> &'a str {
+    if value.is_sensitive() || SENSITIVE_HEADERS.contains(&name) {
+        "<redacted>"
+    } else {
+        value.to_str().unwrap_or("<non-ascii>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    /// Headers containing secrets are redacted from the dumped request.
+    #[rstest]
+    fn request_dump_redacts_secrets() {
+        let request = Client::new()
+            .get("https://gitlab.com/api/v4/users/tanuki/keys")
+            .header("Accept", "application/json")
+            .header("PRIVATE-TOKEN", "glpat-secret")
+            .bearer_auth("ghp_secret")
+            .build()
+            .unwrap();
+        let mut out = Vec::new();
+
+        dump_request(&mut out, &request).unwrap();
+        let dump = String::from_utf8(out).unwrap();
+
+        assert!(dump.starts_with("> GET https://gitlab.com/api/v4/users/tanuki/keys\n"));
+        assert!(dump.contains("> accept: application/json\n"));
+        assert!(dump.contains("> private-token: <redacted>\n"));
+        assert!(dump.contains("> authorization: <redacted>\n"));
+        assert!(!dump.contains("secret"));
+    }
+
+    /// The dumped response contains it's status, headers and body.
+    #[rstest]
+    fn response_dump_contains_status_headers_and_body() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        headers.insert("set-cookie", HeaderValue::from_static("session=secret"));
+        let mut out = Vec::new();
+
+        dump_response(&mut out, StatusCode::OK, &headers, br#"{"error": "nope"}"#).unwrap();
+        let dump = String::from_utf8(out).unwrap();
+
+        assert_eq!(
+            dump,
+            "< 200 OK\n< content-type: application/json\n< set-cookie: <redacted>\n<\n{\"error\": \"nope\"}\n"
+        );
+    }
+}
@@ -1,54 +1,442 @@
-use std::{fmt::Debug, ops::Deref, str::FromStr};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    ops::Deref,
+    str::FromStr,
+    sync::{Mutex, PoisonError},
+};
 
 use async_trait::async_trait;
-use chrono::{Local, TimeZone};
-use reqwest::{header::HeaderValue, Client, Request, Response, StatusCode, Url};
+use chrono::{TimeZone, Utc};
+use reqwest::{
+    header::{HeaderMap, HeaderValue, ETAG, IF_NONE_MATCH},
+    Client, Request, Response, StatusCode, Url,
+};
 use serde::Deserialize;
-use tracing::trace;
+use serde_json::json;
+use tracing::{debug, trace};
 
-use super::main::{base_client, Error, Result, Source};
-use crate::{allowed_signers::ssh::PublicKey, USER_AGENT};
+use super::{
+    client::{base_client, Protocol},
+    dump,
+    link::next_url_from_link_header,
+    main::{json_array, Error, Fetched, ObservedRateLimit, Result, ServerError, Source, Token},
+    pool::TokenPool,
+};
+use crate::{
+    allowed_signers::ssh::PublicKey,
+    diagnostic::{warning, Warning},
+    USER_AGENT,
+};
 
 #[derive(Debug)]
 pub struct Github {
     /// The base URL of the API.
     base_url: Url,
+    /// The tokens used in turn to authenticate requests, if any.
+    tokens: TokenPool,
+    /// Headers sent along with every request, e.g. the credentials of a proxy.
+    auth_headers: HeaderMap,
+    /// The protocol used for requests.
+    protocol: Protocol,
+    /// The media type requested in the `Accept` header.
+    accept: String,
     client: Client,
+    /// The rate limit status reported by the latest response.
+    rate_limit: Mutex<Option<ObservedRateLimit>>,
+    /// The usernames email addresses were resolved to, if any user was found.
+    logins: Mutex<HashMap<String, Option<String>>>,
 }
 
 impl Github {
     const VERSION: &'static str = "2022-11-28";
     const ACCEPT_HEADER: &'static str = "application/vnd.github+json";
+    /// Messages are requested in English, since a few errors are only distinguishable by them.
+    const ACCEPT_LANGUAGE_HEADER: &'static str = "en";
+    /// The number of keys requested per page, which is the maximum allowed by the API.
+    const PER_PAGE: &'static str = "100";
+    /// The maximum number of pages followed, keeping next links from causing countless requests.
+    const MAX_PAGES: usize = 100;
 
     #[must_use]
-    pub fn new(base_url: Url) -> Self {
+    pub fn new(base_url: Url, token: Option<Token>) -> Self {
+        Self::with_tokens(base_url, token.into_iter().collect())
+    }
+
+    /// Create a source using multiple tokens in turn, multiplying the rate limit available to
+    /// very large updates since GitHub limits requests per token.
+    #[must_use]
+    pub fn with_tokens(base_url: Url, tokens: Vec<Token>) -> Self {
         Self {
             base_url,
-            client: base_client(),
+            tokens: TokenPool::new(tokens),
+            auth_headers: HeaderMap::new(),
+            protocol: Protocol::default(),
+            accept: Self::ACCEPT_HEADER.to_string(),
+            client: base_client(Protocol::default(), false),
+            rate_limit: Mutex::default(),
+            logins: Mutex::default(),
+        }
+    }
+
+    /// Use the given protocol for requests.
+    #[must_use]
+    pub fn with_protocol(self, protocol: Protocol) -> Self {
+        Self {
+            protocol,
+            client: base_client(protocol, !self.auth_headers.is_empty()),
+            ..self
+        }
+    }
+
+    /// Send the given headers along with every request, e.g. the service credentials of a
+    /// zero-trust proxy in front of the source. Redirects to other origins are refused, so that the
+    /// headers are never sent to another host.
+    #[must_use]
+    pub fn with_auth_headers(self, auth_headers: HeaderMap) -> Self {
+        Self {
+            client: base_client(self.protocol, !auth_headers.is_empty()),
+            auth_headers,
+            ..self
+        }
+    }
+
+    /// Request the given media type in the `Accept` header instead of [`Github::ACCEPT_HEADER`],
+    /// e.g. a specific API version of a compatible instance.
+    #[must_use]
+    pub fn with_accept(self, accept: String) -> Self {
+        Self { accept, ..self }
+    }
+
+    /// Build a GET request to the given URL, authenticated using the given token if any.
+    fn request(&self, url: Url, token: Option<&Token>) -> Request {
+        let mut request = self
+            .client
+            .get(url)
+            .header("User-Agent", USER_AGENT)
+            .header("Accept", &self.accept)
+            .header("Accept-Language", Self::ACCEPT_LANGUAGE_HEADER)
+            .header("X-GitHub-Api-Version", Self::VERSION)
+            .headers(self.auth_headers.clone());
+        if let Some(token) = token {
+            request = request.bearer_auth(token.expose());
+        }
+        request.build().unwrap()
+    }
+
+    /// Make an HTTP request to the GitHub API, keeping track of the rate limit status reported by
+    /// the response.
+    async fn make_api_request(&self, request: Request) -> Result<Response> {
+        trace!(?request, "Sending request to GitHub API");
+        let response = handle_github_errors(dump::execute(&self.client, request).await).await?;
+        trace!(?response, "Received response from GitHub API.");
+
+        if let Some(rate_limit) = rate_limit(response.headers()) {
+            trace!(
+                ?rate_limit,
+                "{} requests remaining until ratelimit is hit. Counter resets at {}.",
+                rate_limit.remaining,
+                rate_limit.reset
+            );
+            *self
+                .rate_limit
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = Some(rate_limit);
+        }
+        Ok(response)
+    }
+
+    /// Send a GET request to the given URL, conditional on the given `ETag` if any.
+    /// Tokens rejected by the API are skipped in favor of the remaining ones if multiple tokens
+    /// are configured.
+    async fn send(&self, url: &Url, etag: Option<&str>) -> Result<Response> {
+        loop {
+            let token = self.tokens.next();
+            if token.is_none() && !self.tokens.is_empty() {
+                return Err(Error::BadCredentials);
+            }
+            let mut request = self.request(url.clone(), token.map(|(_, token)| token));
+            if let Some(value) = etag.and_then(|etag| HeaderValue::from_str(etag).ok()) {
+                request.headers_mut().insert(IF_NONE_MATCH, value);
+            }
+
+            match self.make_api_request(request).await {
+                Err(Error::BadCredentials) if self.tokens.len() > 1 => {
+                    let (index, _) = token.expect("requests are authenticated if tokens exist");
+                    warning!(
+                        Warning::TokenRejected,
+                        "Token {index} was rejected by GitHub, using the remaining tokens"
+                    );
+                    self.tokens.reject(index);
+                }
+                response => return response,
+            }
         }
     }
 }
 
 #[async_trait]
 impl Source for Github {
-    // [API documentation](https://docs.github.com/en/rest/users/ssh-signing-keys?apiVersion=2022-11-28#list-ssh-signing-keys-for-a-user)
     async fn get_keys_by_username(&self, username: &str) -> Result<Vec<PublicKey>> {
-        let url = self
+        match self.get_keys_if_changed(username, None).await? {
+            Fetched::Keys { keys, .. } => Ok(keys),
+            // A misbehaving server or caching proxy may answer without being asked conditionally.
+            Fetched::NotModified => Err(Error::Other(
+                "unconditional request returned not modified".to_string(),
+            )),
+        }
+    }
+
+    // [API documentation](https://docs.github.com/en/rest/users/ssh-signing-keys?apiVersion=2022-11-28#list-ssh-signing-keys-for-a-user)
+    /// Keys of all pages are returned by following the next link of paginated responses, up to
+    /// [`Github::MAX_PAGES`] pages and only as long as it has the origin of the base URL.
+    /// Conditional requests returning `304 Not Modified` don't count against the rate limit, the
+    /// `ETag` is only returned for keys fitting on a single page since it doesn't cover later pages.
+    async fn get_keys_if_changed(&self, username: &str, etag: Option<&str>) -> Result<Fetched> {
+        let mut url = self
             .base_url
             .join(&format!("/users/{username}/ssh_signing_keys"))
             .unwrap();
+        url.query_pairs_mut()
+            .append_pair("per_page", Self::PER_PAGE);
+
+        let response = self.send(&url, etag).await?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(Fetched::NotModified);
+        }
+        let mut etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string);
+        let mut next_url = next_url_from_link_header(response.headers(), &url);
+        let mut keys = json_array(response).await?;
+        let mut pages = 1;
+        while let Some(next) = next_url.filter(|next| *next != url) {
+            pages += 1;
+            if pages > Self::MAX_PAGES {
+                return Err(Error::Other(format!(
+                    "next links exceed the maximum of {} pages",
+                    Self::MAX_PAGES
+                )));
+            }
+            etag = None;
+            url = next;
+            let response = self.send(&url, None).await?;
+            next_url = next_url_from_link_header(response.headers(), &url);
+            keys.extend(json_array(response).await?);
+        }
+        Ok(Fetched::Keys { keys, etag })
+    }
+
+    fn url(&self) -> Option<&Url> {
+        Some(&self.base_url)
+    }
+
+    fn auth_identity(&self) -> Option<String> {
+        self.tokens.identity()
+    }
+
+    // [API documentation](https://docs.github.com/en/rest/rate-limit/rate-limit?apiVersion=2022-11-28#get-rate-limit-status-for-the-authenticated-user)
+    /// Only available when authenticated, since the budget of unauthenticated requests is shared
+    /// by IP address and too small to plan for. Given multiple tokens, their budgets are added up.
+    async fn remaining_rate_limit(&self) -> Result<Option<usize>> {
+        if self.tokens.is_empty() {
+            return Ok(None);
+        }
+        let url = self.base_url.join("/rate_limit").unwrap();
+
+        let mut remaining = 0;
+        for token in self.tokens.usable() {
+            let response = self
+                .make_api_request(self.request(url.clone(), Some(token)))
+                .await?;
+            let status: RateLimitStatus = response.json().await?;
+            remaining += status.resources.core.remaining;
+        }
+        Ok(Some(remaining))
+    }
+
+    // [API documentation](https://docs.github.com/en/graphql/reference/objects#externalidentity)
+    /// Only available when authenticated using a token of an organization owner, granting the
+    /// `admin:org` scope. Organizations without SAML single sign-on have no linked identities.
+    async fn saml_name_id(&self, organization: &str, username: &str) -> Result<Option<String>> {
+        const QUERY: &str = "query($organization: String!, $login: String!) {
+            organization(login: $organization) {
+                samlIdentityProvider {
+                    externalIdentities(first: 1, login: $login) {
+                        nodes { samlIdentity { nameId } }
+                    }
+                }
+            }
+        }";
+
+        let Some((_, token)) = self.tokens.next() else {
+            return Err(Error::SamlIdentityInaccessible(
+                "authentication is required".to_string(),
+            ));
+        };
         let request = self
             .client
-            .get(url)
+            .post(graphql_url(&self.base_url))
             .header("User-Agent", USER_AGENT)
-            .header("Accept", Self::ACCEPT_HEADER)
-            .header("X-GitHub-Api-Version", Self::VERSION)
+            .headers(self.auth_headers.clone())
+            .bearer_auth(token.expose())
+            .json(&json!({
+                "query": QUERY,
+                "variables": {"organization": organization, "login": username},
+            }))
             .build()
             .unwrap();
 
-        let response = make_api_request(request, &self.client).await?;
-        Ok(response.json().await?)
+        let response = self.make_api_request(request).await?;
+        let response: GraphqlResponse<SamlIdentityData> = response.json().await?;
+        if let Some(error) = response.errors.into_iter().next() {
+            return Err(Error::SamlIdentityInaccessible(error.message));
+        }
+        Ok(response
+            .data
+            .and_then(|data| data.organization)
+            .and_then(|organization| organization.saml_identity_provider)
+            .and_then(|provider| provider.external_identities.nodes.into_iter().next())
+            .and_then(|identity| identity.saml_identity)
+            .and_then(|identity| identity.name_id))
+    }
+
+    // [API documentation](https://docs.github.com/en/rest/search/search?apiVersion=2022-11-28#search-commits)
+    /// Resolved to the author of the most recently authored commit using the email address, since
+    /// GitHub attributes commits to the account that verified their email address. Results are
+    /// kept for the lifetime of the source, as the search API has a much lower rate limit.
+    async fn login_by_email(&self, email: &str) -> Result<Option<String>> {
+        if let Some(login) = self
+            .logins
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(email)
+        {
+            return Ok(login.clone());
+        }
+        let mut url = self.base_url.join("/search/commits").unwrap();
+        url.query_pairs_mut()
+            .append_pair("q", &format!("author-email:{email}"))
+            .append_pair("sort", "author-date")
+            .append_pair("order", "desc")
+            .append_pair("per_page", "1");
+
+        let response = self.send(&url, None).await?;
+        let result: CommitSearchResult = response.json().await?;
+        let login = result
+            .items
+            .into_iter()
+            .next()
+            .and_then(|commit| commit.author)
+            .map(|author| author.login);
+        self.logins
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(email.to_string(), login.clone());
+        Ok(login)
     }
+
+    fn observed_rate_limit(&self) -> Option<ObservedRateLimit> {
+        *self
+            .rate_limit
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The result of searching commits using the GitHub API.
+#[derive(Debug, Deserialize)]
+struct CommitSearchResult {
+    items: Vec<Commit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Commit {
+    /// The account the commit is attributed to, missing if the email address of the author is not
+    /// verified for any account.
+    author: Option<Account>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Account {
+    login: String,
+}
+
+/// The URL of the GraphQL API served alongside the REST API at the given URL. GitHub serves it at
+/// `/graphql` of the API host, while GitHub Enterprise Server serves it at `/api/graphql` next to
+/// the REST API at `/api/v3`.
+fn graphql_url(base_url: &Url) -> Url {
+    let mut url = base_url.clone();
+    match base_url
+        .path()
+        .trim_end_matches('/')
+        .strip_suffix("/api/v3")
+    {
+        Some(prefix) => url.set_path(&format!("{prefix}/api/graphql")),
+        None => url.set_path("/graphql"),
+    }
+    url
+}
+
+/// A response of the GitHub GraphQL API.
+#[derive(Debug, Deserialize)]
+struct GraphqlResponse<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<Message>,
+}
+
+/// The SAML identity of an organization member as returned by the GitHub GraphQL API.
+#[derive(Debug, Deserialize)]
+struct SamlIdentityData {
+    organization: Option<SamlOrganization>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SamlOrganization {
+    saml_identity_provider: Option<SamlIdentityProvider>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SamlIdentityProvider {
+    external_identities: ExternalIdentities,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExternalIdentities {
+    nodes: Vec<ExternalIdentity>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExternalIdentity {
+    saml_identity: Option<SamlIdentity>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SamlIdentity {
+    name_id: Option<String>,
+}
+
+/// The rate limit status of the GitHub API.
+#[derive(Debug, Deserialize)]
+struct RateLimitStatus {
+    resources: RateLimitResources,
+}
+
+#[derive(Debug, Deserialize)]
+struct RateLimitResources {
+    core: RateLimit,
+}
+
+#[derive(Debug, Deserialize)]
+struct RateLimit {
+    remaining: usize,
 }
 
 /// A message from the GitHub API.
@@ -65,40 +453,28 @@ impl Deref for Message {
     }
 }
 
-/// Make an HTTP request to the GitHub API.
-async fn make_api_request(request: Request, client: &Client) -> Result<Response> {
-    trace!(?request, "Sending request to GitHub API");
-    let response = handle_github_errors(client.execute(request).await).await?;
-    trace!(?response, "Received response from GitHub API.");
+/// The rate limit status reported by the headers of a response, if all of them are present and
+/// valid.
+fn rate_limit(headers: &HeaderMap) -> Option<ObservedRateLimit> {
+    Some(ObservedRateLimit {
+        remaining: parse_header_value(headers, "x-ratelimit-remaining")?,
+        limit: parse_header_value(headers, "x-ratelimit-limit")?,
+        reset: Utc
+            .timestamp_opt(parse_header_value(headers, "x-ratelimit-reset")?, 0)
+            .single()?,
+    })
+}
 
-    let headers = response.headers();
-    if let (Some(ratelimit_remaining), Some(ratelimit_reset)) = (
-        headers.get("x-ratelimit-remaining"),
-        headers.get("x-ratelimit-reset"),
-    ) {
-        let ratelimit_remaining: usize = unwrap_header_value(ratelimit_remaining);
-        let ratelimit_reset = Local
-            .timestamp_opt(unwrap_header_value(ratelimit_reset), 0)
-            .unwrap();
-        trace!(
-            ?ratelimit_remaining,
-            ?ratelimit_reset,
-            "{ratelimit_remaining} requests remaining until ratelimit is hit. Counter resets at {ratelimit_reset}.",
-        );
-    }
-    Ok(response)
+/// Parse the value of the given header, returning `None` if it is missing or invalid.
+fn parse_header_value<T: FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+    headers.get(name)?.to_str().ok()?.parse().ok()
 }
 
-/// Unwrap a reqwest header value, panicking if it contains an invalid value.
-/// TODO: Handle this by returning and error instead of panicking.
-fn unwrap_header_value<T>(value: &HeaderValue) -> T
-where
-    T: FromStr,
-    <T as FromStr>::Err: Debug,
-{
-    let expect_msg = "response contains invalid header";
-    let s = value.to_str().expect(expect_msg);
-    s.parse().expect(expect_msg)
+/// Whether the message of a forbidden response indicates that the token lacks a permission, like
+/// a fine-grained token without access to the requested resource.
+fn is_insufficient_scope(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("not accessible by") || message.contains("permission")
 }
 
 /// Handle GitHub specific HTTP errors.
@@ -107,6 +483,11 @@ where
 /// response that can be deserialized.
 /// If the error is not specific to GitHub, it is converted into a `SourceError` using the
 /// more generic `From<reqwest::Error>` implementation.
+/// The `X-GitHub-Request-Id` header of failed responses is logged and included in server errors,
+/// since GitHub support needs it to investigate provider-side issues.
+/// Rate limiting is detected using the status code and rate limit headers, falling back to the
+/// message only if they are inconclusive, so that localized or translated messages are classified
+/// all the same.
 async fn handle_github_errors(request_result: reqwest::Result<Response>) -> Result<Response> {
     let response = request_result?;
 
@@ -114,23 +495,38 @@ async fn handle_github_errors(request_result: reqwest::Result<Response>) -> Resu
         let status = error
             .status()
             .expect("Status code error must contain status code");
+        let request_id: Option<String> =
+            parse_header_value(response.headers(), "x-github-request-id");
+        if let Some(request_id) = &request_id {
+            debug!(%status, request_id, "GitHub request failed");
+        }
+        let rate_limit_exhausted =
+            parse_header_value::<u64>(response.headers(), "x-ratelimit-remaining") == Some(0);
         let message = response.json::<Message>().await.ok();
 
         match status {
             StatusCode::NOT_FOUND => return Err(Error::UserNotFound),
+            StatusCode::TOO_MANY_REQUESTS => return Err(Error::RatelimitExceeded),
             StatusCode::FORBIDDEN
-                if message
-                    .as_ref()
-                    .is_some_and(|m| m.to_lowercase().contains("rate limit exceeded")) =>
+                if rate_limit_exhausted
+                    || message
+                        .as_ref()
+                        .is_some_and(|m| m.to_lowercase().contains("rate limit exceeded")) =>
             {
                 return Err(Error::RatelimitExceeded);
             }
-            StatusCode::UNAUTHORIZED
-                if message
-                    .as_ref()
-                    .is_some_and(|m| m.to_lowercase().contains("bad credentials")) =>
-            {
-                return Err(Error::BadCredentials);
+            StatusCode::FORBIDDEN if message.as_ref().is_some_and(|m| is_insufficient_scope(m)) => {
+                return Err(Error::InsufficientScope {
+                    message: message.map(|m| m.message).unwrap_or_default(),
+                });
+            }
+            // The message may be localized, so a rejected token is only detected by the status.
+            StatusCode::UNAUTHORIZED => return Err(Error::BadCredentials),
+            _ if status.is_server_error() => {
+                if let Some(request_id) = request_id {
+                    return Err(ServerError::StatusCodeWithRequestId { status, request_id }.into());
+                }
+                return Err(Error::from(error));
             }
             _ => return Err(Error::from(error)),
         }
@@ -156,7 +552,7 @@ mod tests {
     #[fixture]
     fn api_w_mock_server() -> (Github, MockServer) {
         let server = MockServer::start();
-        let api = Github::new(server.base_url().parse().unwrap());
+        let api = Github::new(server.base_url().parse().unwrap(), None);
         (api, server)
     }
 
@@ -169,6 +565,7 @@ mod tests {
             when.method(GET)
                 .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"))
                 .header("accept", API_ACCEPT_HEADER)
+                .header("accept-language", "en")
                 .header("x-github-api-version", API_VERSION)
                 .header("user-agent", USER_AGENT);
         });
@@ -178,6 +575,342 @@ mod tests {
         mock.assert();
     }
 
+    /// A configured media type is requested instead of the default one.
+    #[rstest]
+    #[tokio::test]
+    async fn api_request_contains_configured_accept_header() {
+        let server = MockServer::start();
+        let api = Github::new(server.base_url().parse().unwrap(), None)
+            .with_accept("application/vnd.github.v3+json".to_string());
+        let mock = server.mock(|when, _| {
+            when.method(GET)
+                .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"))
+                .header("accept", "application/vnd.github.v3+json");
+        });
+
+        let _ = api.get_keys_by_username(EXAMPLE_USERNAME).await;
+
+        mock.assert();
+    }
+
+    /// A configured token is used to authenticate API requests.
+    #[rstest]
+    #[tokio::test]
+    async fn api_request_contains_token() {
+        let server = MockServer::start();
+        let api = Github::new(
+            server.base_url().parse().unwrap(),
+            Some(Token::new("ghp_secret")),
+        );
+        let mock = server.mock(|when, _| {
+            when.method(GET)
+                .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"))
+                .header("authorization", "Bearer ghp_secret");
+        });
+
+        let _ = api.get_keys_by_username(EXAMPLE_USERNAME).await;
+
+        mock.assert();
+    }
+
+    /// The remaining rate limit is requested from the API when authenticated.
+    #[rstest]
+    #[tokio::test]
+    async fn remaining_rate_limit_requested_when_authenticated() {
+        let server = MockServer::start();
+        let api = Github::new(
+            server.base_url().parse().unwrap(),
+            Some(Token::new("ghp_secret")),
+        );
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/rate_limit")
+                .header("authorization", "Bearer ghp_secret");
+            then.status(200).json_body(json!({
+                "resources": {
+                    "core": {"limit": 5000, "used": 58, "remaining": 4942, "reset": 1_691_591_363}
+                },
+                "rate": {"limit": 5000, "used": 58, "remaining": 4942, "reset": 1_691_591_363}
+            }));
+        });
+
+        let remaining = api.remaining_rate_limit().await.unwrap();
+
+        mock.assert();
+        assert_eq!(remaining, Some(4942));
+    }
+
+    /// The rate limit status reported by the latest response is kept, ignoring responses with
+    /// missing or invalid rate limit headers.
+    #[rstest]
+    #[tokio::test]
+    async fn rate_limit_of_latest_response_observed(api_w_mock_server: (Github, MockServer)) {
+        let (api, server) = api_w_mock_server;
+        server.mock(|when, then| {
+            when.method(GET).path("/users/octocat/ssh_signing_keys");
+            then.status(200)
+                .header("x-ratelimit-remaining", "4821")
+                .header("x-ratelimit-limit", "5000")
+                .header("x-ratelimit-reset", "1691591363")
+                .json_body(json!([]));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/users/hubot/ssh_signing_keys");
+            then.status(200)
+                .header("x-ratelimit-remaining", "invalid")
+                .json_body(json!([]));
+        });
+        assert_eq!(api.observed_rate_limit(), None);
+
+        api.get_keys_by_username("octocat").await.unwrap();
+        api.get_keys_by_username("hubot").await.unwrap();
+
+        let rate_limit = api.observed_rate_limit().unwrap();
+        assert_eq!(
+            rate_limit,
+            ObservedRateLimit {
+                remaining: 4821,
+                limit: 5000,
+                reset: Utc.timestamp_opt(1_691_591_363, 0).unwrap(),
+            }
+        );
+        assert_eq!(
+            rate_limit.summary(Utc.timestamp_opt(1_691_588_843, 0).unwrap()),
+            "4821/5000 remaining, resets in 42m"
+        );
+    }
+
+    /// Multiple tokens are used in turn.
+    #[rstest]
+    #[tokio::test]
+    async fn multiple_tokens_used_in_turn() {
+        let server = MockServer::start();
+        let api = Github::with_tokens(
+            server.base_url().parse().unwrap(),
+            vec![Token::new("ghp_first"), Token::new("ghp_second")],
+        );
+        let mocks = ["ghp_first", "ghp_second"].map(|token| {
+            server.mock(|when, then| {
+                when.method(GET)
+                    .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"))
+                    .header("authorization", format!("Bearer {token}"));
+                then.status(200).json_body(json!([]));
+            })
+        });
+
+        for _ in 0..4 {
+            api.get_keys_by_username(EXAMPLE_USERNAME).await.unwrap();
+        }
+
+        for mock in mocks {
+            mock.assert_hits(2);
+        }
+    }
+
+    /// A token rejected by the API does not prevent the remaining tokens from being used.
+    #[rstest]
+    #[tokio::test]
+    async fn rejected_token_skipped() {
+        let server = MockServer::start();
+        let api = Github::with_tokens(
+            server.base_url().parse().unwrap(),
+            vec![Token::new("ghp_revoked"), Token::new("ghp_valid")],
+        );
+        let revoked = server.mock(|when, then| {
+            when.header("authorization", "Bearer ghp_revoked");
+            then.status(StatusCode::UNAUTHORIZED.into())
+                .json_body(json!({"message": "Bad credentials"}));
+        });
+        let valid = server.mock(|when, then| {
+            when.header("authorization", "Bearer ghp_valid");
+            then.status(200).json_body(json!([]));
+        });
+
+        for _ in 0..3 {
+            api.get_keys_by_username(EXAMPLE_USERNAME).await.unwrap();
+        }
+
+        revoked.assert_hits(1);
+        valid.assert_hits(3);
+    }
+
+    /// If all tokens are rejected, a bad credentials error is returned.
+    #[rstest]
+    #[tokio::test]
+    async fn all_tokens_rejected_returns_bad_credentials() {
+        let server = MockServer::start();
+        let api = Github::with_tokens(
+            server.base_url().parse().unwrap(),
+            vec![Token::new("ghp_first"), Token::new("ghp_second")],
+        );
+        server.mock(|_, then| {
+            then.status(StatusCode::UNAUTHORIZED.into())
+                .json_body(json!({"message": "Bad credentials"}));
+        });
+
+        let error_result = api
+            .get_keys_by_username(EXAMPLE_USERNAME)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error_result, Error::BadCredentials);
+    }
+
+    /// The remaining rate limit is not requested when unauthenticated.
+    #[rstest]
+    #[tokio::test]
+    async fn remaining_rate_limit_unknown_when_unauthenticated(
+        api_w_mock_server: (Github, MockServer),
+    ) {
+        let (api, server) = api_w_mock_server;
+        let mock = server.mock(|when, _| {
+            when.any_request();
+        });
+
+        let remaining = api.remaining_rate_limit().await.unwrap();
+
+        mock.assert_hits(0);
+        assert_eq!(remaining, None);
+    }
+
+    /// The SAML `NameID` of a user is requested from the GraphQL API.
+    #[rstest]
+    #[tokio::test]
+    async fn saml_name_id_requested_from_graphql_api() {
+        let server = MockServer::start();
+        let api = Github::new(
+            server.base_url().parse().unwrap(),
+            Some(Token::new("ghp_secret")),
+        );
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/graphql")
+                .header("authorization", "Bearer ghp_secret")
+                .json_body_partial(
+                    r#"{"variables": {"organization": "acme", "login": "octocat"}}"#,
+                );
+            then.status(200).json_body(json!({
+                "data": {
+                    "organization": {
+                        "samlIdentityProvider": {
+                            "externalIdentities": {
+                                "nodes": [{"samlIdentity": {"nameId": "octocat@acme.corp"}}]
+                            }
+                        }
+                    }
+                }
+            }));
+        });
+
+        let name_id = api.saml_name_id("acme", EXAMPLE_USERNAME).await.unwrap();
+
+        mock.assert();
+        assert_eq!(name_id, Some("octocat@acme.corp".to_string()));
+    }
+
+    /// The GraphQL API is derived from the URL of the REST API, which GitHub Enterprise Server
+    /// serves at `/api/v3`.
+    #[rstest]
+    #[case("https://api.github.com", "https://api.github.com/graphql")]
+    #[case("https://git.acme.corp/api/v3", "https://git.acme.corp/api/graphql")]
+    #[case("https://git.acme.corp/api/v3/", "https://git.acme.corp/api/graphql")]
+    #[case(
+        "https://acme.corp/github/api/v3",
+        "https://acme.corp/github/api/graphql"
+    )]
+    fn graphql_url_derived_from_rest_url(#[case] base_url: &str, #[case] expected: &str) {
+        assert_eq!(graphql_url(&base_url.parse().unwrap()).as_str(), expected);
+    }
+
+    /// The SAML `NameID` of a user on GitHub Enterprise Server is requested from it's GraphQL API.
+    #[rstest]
+    #[tokio::test]
+    async fn saml_name_id_requested_from_enterprise_server_graphql_api() {
+        let server = MockServer::start();
+        let api = Github::new(
+            server.url("/api/v3").parse().unwrap(),
+            Some(Token::new("ghp_secret")),
+        );
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/api/graphql");
+            then.status(200).json_body(json!({
+                "data": {
+                    "organization": {
+                        "samlIdentityProvider": {
+                            "externalIdentities": {
+                                "nodes": [{"samlIdentity": {"nameId": "octocat@acme.corp"}}]
+                            }
+                        }
+                    }
+                }
+            }));
+        });
+
+        let name_id = api.saml_name_id("acme", EXAMPLE_USERNAME).await.unwrap();
+
+        mock.assert();
+        assert_eq!(name_id, Some("octocat@acme.corp".to_string()));
+    }
+
+    /// Email addresses are resolved to the author of the most recent commit using them, once per
+    /// email address, while email addresses without attributed commits resolve to no user.
+    #[rstest]
+    #[case(json!([{"author": {"login": "octocat"}}, {"author": {"login": "octodog"}}]), Some("octocat"))]
+    #[case(json!([{"author": null}]), None)]
+    #[case(json!([]), None)]
+    #[tokio::test]
+    async fn login_resolved_by_email(
+        api_w_mock_server: (Github, MockServer),
+        #[case] items: JsonValue,
+        #[case] expected: Option<&str>,
+    ) {
+        let (api, server) = api_w_mock_server;
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search/commits")
+                .query_param("q", "author-email:octocat@github.com")
+                .query_param("sort", "author-date")
+                .query_param("order", "desc");
+            then.status(200)
+                .json_body(json!({"total_count": 2, "items": items}));
+        });
+
+        for _ in 0..2 {
+            let login = api.login_by_email("octocat@github.com").await.unwrap();
+            assert_eq!(login.as_deref(), expected);
+        }
+
+        mock.assert_hits(1);
+    }
+
+    /// Organizations without SAML single sign-on have no linked identities, while errors
+    /// returned by the GraphQL API, e.g. due to missing scopes, indicate inaccessible identities.
+    #[rstest]
+    #[case(json!({"data": {"organization": {"samlIdentityProvider": null}}}), Ok(None))]
+    #[case(
+        json!({"data": {"organization": null}, "errors": [{"message": "Resource not accessible"}]}),
+        Err(Error::SamlIdentityInaccessible("Resource not accessible".to_string()))
+    )]
+    #[tokio::test]
+    async fn saml_name_id_degrades_gracefully(
+        #[case] body: JsonValue,
+        #[case] expected: Result<Option<String>>,
+    ) {
+        let server = MockServer::start();
+        let api = Github::new(
+            server.base_url().parse().unwrap(),
+            Some(Token::new("ghp_secret")),
+        );
+        server.mock(|when, then| {
+            when.method(POST).path("/graphql");
+            then.status(200).json_body(body);
+        });
+
+        let name_id = api.saml_name_id("acme", EXAMPLE_USERNAME).await;
+
+        assert_eq!(name_id, expected);
+    }
+
     /// Keys returned from the API are deserialized correctly.
     #[rstest]
     #[case(json!([]), vec![])]
@@ -228,6 +961,245 @@ mod tests {
         assert_eq!(keys, expected);
     }
 
+    /// Not modified returned to an unconditional request, e.g. by a caching proxy, results in an
+    /// error instead of a panic.
+    #[rstest]
+    #[tokio::test]
+    async fn unconditional_not_modified_returns_error(api_w_mock_server: (Github, MockServer)) {
+        let (api, server) = api_w_mock_server;
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"));
+            then.status(304);
+        });
+
+        let err = api
+            .get_keys_by_username(EXAMPLE_USERNAME)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::Other(_)));
+    }
+
+    /// The `ETag` of a response is returned along with the keys, while conditional requests for
+    /// unchanged keys return not modified.
+    #[rstest]
+    #[tokio::test]
+    async fn conditional_request_returns_not_modified(api_w_mock_server: (Github, MockServer)) {
+        let (api, server) = api_w_mock_server;
+        let etag = r#"W/"6d82cbb050ddc7fa9cbb659014546e59""#;
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"))
+                .header("If-None-Match", etag);
+            then.status(304);
+        });
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"))
+                .matches(|req| {
+                    req.headers
+                        .iter()
+                        .flatten()
+                        .all(|(name, _)| !name.eq_ignore_ascii_case("If-None-Match"))
+                });
+            then.status(200).header("ETag", etag).json_body(json!([]));
+        });
+
+        let fetched = api
+            .get_keys_if_changed(EXAMPLE_USERNAME, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            fetched,
+            Fetched::Keys {
+                keys: vec![],
+                etag: Some(etag.to_string())
+            }
+        );
+
+        let fetched = api
+            .get_keys_if_changed(EXAMPLE_USERNAME, Some(etag))
+            .await
+            .unwrap();
+        assert_eq!(fetched, Fetched::NotModified);
+    }
+
+    /// The creation date of keys returned from the API is captured.
+    #[rstest]
+    #[tokio::test]
+    async fn key_creation_date_captured(api_w_mock_server: (Github, MockServer)) {
+        let (api, server) = api_w_mock_server;
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"));
+            then.status(200).json_body(json!([
+                {
+                    "id": 773_452,
+                    "key": "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS",
+                    "title": "key-1",
+                    "created_at": "2023-05-23T09:35:15.638Z"
+                }
+            ]));
+        });
+
+        let keys = api.get_keys_by_username(EXAMPLE_USERNAME).await.unwrap();
+
+        assert_eq!(
+            keys[0].created_at(),
+            Some("2023-05-23T09:35:15.638Z".parse().unwrap())
+        );
+    }
+
+    /// Keys of all pages are returned by following the next link of paginated responses, without
+    /// returning the `ETag` of the first page.
+    #[rstest]
+    #[tokio::test]
+    async fn paginated_keys_are_followed(api_w_mock_server: (Github, MockServer)) {
+        let (api, server) = api_w_mock_server;
+        let key_a =
+            "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS";
+        let key_b =
+            "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILWtK6WxXw7NVhbn6fTQ0dECF8y98fahSIsqKMh+sSo9";
+        // Mocks are matched in order of creation, the second page needs to be created first since
+        // the first page matches it's requests as well.
+        let second_page = server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"))
+                .query_param("page", "2");
+            then.status(200)
+                .json_body(json!([{"id": 2, "key": key_b, "title": "key-2"}]));
+        });
+        let first_page = server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"))
+                .query_param("per_page", "100");
+            then.status(200)
+                .header("ETag", r#"W/"6d82cbb050ddc7fa9cbb659014546e59""#)
+                .header(
+                    "Link",
+                    format!(
+                        r#"<{}?per_page=100&page=2>; rel="next", <{}?per_page=100&page=2>; rel="last""#,
+                        server.url(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys")),
+                        server.url(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys")),
+                    ),
+                )
+                .json_body(json!([{"id": 1, "key": key_a, "title": "key-1"}]));
+        });
+
+        let fetched = api
+            .get_keys_if_changed(EXAMPLE_USERNAME, None)
+            .await
+            .unwrap();
+
+        first_page.assert();
+        second_page.assert();
+        assert_eq!(
+            fetched,
+            Fetched::Keys {
+                keys: vec![key_a.parse().unwrap(), key_b.parse().unwrap()],
+                etag: None
+            }
+        );
+    }
+
+    /// Next links to another origin are not followed, so that the token is never sent to it.
+    #[rstest]
+    #[tokio::test]
+    async fn next_link_to_other_origin_not_followed() {
+        let server = MockServer::start();
+        let other = MockServer::start();
+        let api = Github::new(
+            server.base_url().parse().unwrap(),
+            Some(Token::new("ghp_secret")),
+        );
+        let key =
+            "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS";
+        let foreign_page = other.mock(|when, then| {
+            when.any_request();
+            then.status(200).json_body(json!([]));
+        });
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"));
+            then.status(200)
+                .header(
+                    "Link",
+                    format!(
+                        r#"<{}?per_page=100&page=2>; rel="next""#,
+                        other.url(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"))
+                    ),
+                )
+                .json_body(json!([{"id": 1, "key": key, "title": "key-1"}]));
+        });
+
+        let keys = api.get_keys_by_username(EXAMPLE_USERNAME).await.unwrap();
+
+        foreign_page.assert_hits(0);
+        assert_eq!(keys, vec![key.parse().unwrap()]);
+    }
+
+    /// Next links cycling between pages are followed up to the maximum number of pages only.
+    #[rstest]
+    #[tokio::test]
+    async fn cyclic_next_links_return_error(api_w_mock_server: (Github, MockServer)) {
+        let (api, server) = api_w_mock_server;
+        let url = server.url(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"));
+        let second_page = server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"))
+                .query_param("page", "2");
+            then.status(200)
+                .header("Link", format!(r#"<{url}?per_page=100>; rel="next""#))
+                .json_body(json!([]));
+        });
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"));
+            then.status(200)
+                .header(
+                    "Link",
+                    format!(r#"<{url}?per_page=100&page=2>; rel="next""#),
+                )
+                .json_body(json!([]));
+        });
+
+        let err = api
+            .get_keys_by_username(EXAMPLE_USERNAME)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::Other(_)));
+        second_page.assert_hits(Github::MAX_PAGES / 2);
+    }
+
+    /// A JSON object returned instead of an array of keys results in an error describing it's shape.
+    #[rstest]
+    #[tokio::test]
+    async fn json_object_response_returns_unexpected_json_shape_error(
+        api_w_mock_server: (Github, MockServer),
+    ) {
+        let (api, server) = api_w_mock_server;
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"));
+            then.status(200)
+                .json_body(json!({"error": "upstream unavailable"}));
+        });
+
+        let error_result = api
+            .get_keys_by_username(EXAMPLE_USERNAME)
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            error_result,
+            Error::from(ServerError::UnexpectedJsonShape(
+                "an object with keys `error`".to_string()
+            ))
+        );
+    }
+
     #[test]
     fn json_message_parsed_correctly() {
         let content = "I've Gotta Get a Message to You";
@@ -259,19 +1231,22 @@ mod tests {
         assert!(matches!(error_result, Error::UserNotFound));
     }
 
-    /// A HTTP unauthorized status code along with a body containing a bad credentials message
-    /// returns a `SourceError::BadCredentials`.
+    /// A HTTP unauthorized status code returns a `SourceError::BadCredentials`, regardless of the
+    /// language of the message or whether there is any.
     #[rstest]
+    #[case(json!({"message": "Bad credentials"}))]
+    #[case(json!({"message": "Ungültige Anmeldedaten"}))]
+    #[case(json!({}))]
     #[tokio::test]
-    async fn get_keys_by_username_http_unauthorized_bad_credentials_returns_bad_credentials(
+    async fn get_keys_by_username_http_unauthorized_returns_bad_credentials(
         api_w_mock_server: (Github, MockServer),
+        #[case] body: JsonValue,
     ) {
         let (api, server) = api_w_mock_server;
         server.mock(|when, then| {
             when.method(GET)
                 .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"));
-            then.status(StatusCode::UNAUTHORIZED.into())
-                .json_body(json!({"message": "Bad credentials"}));
+            then.status(StatusCode::UNAUTHORIZED.into()).json_body(body);
         });
 
         let error_result = api
@@ -282,17 +1257,22 @@ mod tests {
         assert!(matches!(error_result, Error::BadCredentials));
     }
 
-    /// A HTTP unauthorized status code without a known error message in the body returns a `SourceError::Other`.
+    /// A HTTP forbidden status code along with a body indicating a missing token permission
+    /// returns a `SourceError::InsufficientScope`.
     #[rstest]
+    #[case("Resource not accessible by personal access token")]
+    #[case("This token is missing the required permission")]
     #[tokio::test]
-    async fn get_keys_by_username_http_unauthorized_other_returns_client_error(
+    async fn get_keys_by_username_http_forbidden_missing_permission_returns_insufficient_scope(
         api_w_mock_server: (Github, MockServer),
+        #[case] message: &str,
     ) {
         let (api, server) = api_w_mock_server;
         server.mock(|when, then| {
             when.method(GET)
                 .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"));
-            then.status(StatusCode::UNAUTHORIZED.into());
+            then.status(StatusCode::FORBIDDEN.into())
+                .json_body(json!({"message": message}));
         });
 
         let error_result = api
@@ -300,7 +1280,12 @@ mod tests {
             .await
             .unwrap_err();
 
-        assert!(matches!(error_result, Error::ClientError(..)));
+        assert_eq!(
+            error_result,
+            Error::InsufficientScope {
+                message: message.to_string()
+            }
+        );
     }
 
     /// A HTTP forbidden status code along with a body containing a rate limit exceeded message
@@ -326,6 +1311,51 @@ mod tests {
         assert!(matches!(error_result, Error::RatelimitExceeded));
     }
 
+    /// A HTTP forbidden status code along with an exhausted rate limit header returns a
+    /// `SourceError::RatelimitExceeded`, regardless of the language of the message.
+    #[rstest]
+    #[tokio::test]
+    async fn get_keys_by_username_http_forbidden_exhausted_rate_limit_returns_rate_limit_exceeded(
+        api_w_mock_server: (Github, MockServer),
+    ) {
+        let (api, server) = api_w_mock_server;
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"));
+            then.status(StatusCode::FORBIDDEN.into())
+                .header("x-ratelimit-remaining", "0")
+                .json_body(json!({"message": "Limite de requêtes dépassée"}));
+        });
+
+        let error_result = api
+            .get_keys_by_username(EXAMPLE_USERNAME)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error_result, Error::RatelimitExceeded);
+    }
+
+    /// A HTTP too many requests status code returns a `SourceError::RatelimitExceeded`.
+    #[rstest]
+    #[tokio::test]
+    async fn get_keys_by_username_http_too_many_requests_returns_rate_limit_exceeded(
+        api_w_mock_server: (Github, MockServer),
+    ) {
+        let (api, server) = api_w_mock_server;
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"));
+            then.status(StatusCode::TOO_MANY_REQUESTS.into());
+        });
+
+        let error_result = api
+            .get_keys_by_username(EXAMPLE_USERNAME)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error_result, Error::RatelimitExceeded);
+    }
+
     /// A HTTP forbidden status code without a known error message in the body returns a `SourceError::ClientError`.
     #[rstest]
     #[tokio::test]
@@ -346,4 +1376,45 @@ mod tests {
 
         assert!(matches!(error_result, Error::ClientError(..)));
     }
+
+    /// The request ID GitHub assigned to a request failing with a server error is included in the
+    /// error, while server errors without request ID are returned as is.
+    #[rstest]
+    #[case(Some("C4D2:3A1F:1B2E4C:1D3F5A:66E1B2C3"))]
+    #[case(None)]
+    #[tokio::test]
+    async fn server_error_contains_request_id(
+        api_w_mock_server: (Github, MockServer),
+        #[case] request_id: Option<&str>,
+    ) {
+        let (api, server) = api_w_mock_server;
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/users/{EXAMPLE_USERNAME}/ssh_signing_keys"));
+            let then = then.status(StatusCode::BAD_GATEWAY.into());
+            if let Some(request_id) = request_id {
+                then.header("x-github-request-id", request_id);
+            }
+        });
+
+        let error = api
+            .get_keys_by_username(EXAMPLE_USERNAME)
+            .await
+            .unwrap_err();
+
+        let expected = match request_id {
+            Some(request_id) => ServerError::StatusCodeWithRequestId {
+                status: StatusCode::BAD_GATEWAY,
+                request_id: request_id.to_string(),
+            },
+            None => ServerError::StatusCode(StatusCode::BAD_GATEWAY),
+        };
+        assert_eq!(error, Error::from(expected));
+        if let Some(request_id) = request_id {
+            let Error::ServerError(server_error) = error else {
+                unreachable!()
+            };
+            assert!(server_error.to_string().contains(request_id));
+        }
+    }
 }
@@ -1,28 +1,176 @@
 use async_trait::async_trait;
-use reqwest::{Client, Request, Response, StatusCode, Url};
+use chrono::{DateTime, Utc};
+use reqwest::{header::HeaderMap, Client, Request, Response, StatusCode, Url};
 use serde::Deserialize;
+use tokio::task::JoinSet;
 use tracing::trace;
 
-use super::main::{base_client, Error, Result, Source};
-use crate::{allowed_signers::ssh::PublicKey, USER_AGENT};
+use super::{
+    client::{base_client, Protocol},
+    dump,
+    link::next_url_from_link_header,
+    main::{json_array, Error, Result, Source, Token},
+};
+use crate::{
+    allowed_signers::ssh::{deserialize_lenient_timestamp, PublicKey},
+    diagnostic::{warning, Warning},
+    USER_AGENT,
+};
 
 #[derive(Debug)]
 pub struct Gitlab {
     /// The base URL of the API.
     base_url: Url,
+    /// The token used to authenticate requests, if any.
+    token: Option<Token>,
+    /// Only treat keys used exclusively for signing as signing keys.
+    signing_only: bool,
+    /// Headers sent along with every request, e.g. the credentials of a proxy.
+    auth_headers: HeaderMap,
+    /// The protocol used for requests.
+    protocol: Protocol,
+    /// The media type requested in the `Accept` header.
+    accept: String,
     client: Client,
 }
 
 impl Gitlab {
     const VERSION: &'static str = "v4";
     const ACCEPT_HEADER: &'static str = "application/json";
+    /// The header a token is sent in.
+    const TOKEN_HEADER: &'static str = "PRIVATE-TOKEN";
+    /// The number of keys requested per page, which is the maximum allowed by the API.
+    const PER_PAGE: &'static str = "100";
+    /// The maximum number of pages requested concurrently once the total number of pages is known.
+    const MAX_CONCURRENT_PAGES: usize = 4;
+    /// The maximum total number of pages, keeping a bogus total from causing countless requests.
+    const MAX_PAGES: usize = 100;
+
+    /// Create a source for the GitLab instance at the given base URL, which is normalized using
+    /// [`Gitlab::normalize_base_url`].
+    #[must_use]
+    pub fn new(base_url: Url, token: Option<Token>) -> Self {
+        Self {
+            base_url: Self::normalize_base_url(base_url),
+            token,
+            signing_only: false,
+            auth_headers: HeaderMap::new(),
+            protocol: Protocol::default(),
+            accept: Self::ACCEPT_HEADER.to_string(),
+            client: base_client(Protocol::default(), false),
+        }
+    }
 
+    /// Only treat keys whose usage type is `signing` as signing keys, excluding keys used for
+    /// both authentication and signing.
     #[must_use]
-    pub fn new(base_url: Url) -> Self {
+    pub fn with_signing_only(self, signing_only: bool) -> Self {
         Self {
-            base_url,
-            client: base_client(),
+            signing_only,
+            ..self
+        }
+    }
+
+    /// Use the given protocol for requests.
+    #[must_use]
+    pub fn with_protocol(self, protocol: Protocol) -> Self {
+        Self {
+            protocol,
+            client: base_client(protocol, !self.auth_headers.is_empty()),
+            ..self
+        }
+    }
+
+    /// Send the given headers along with every request, e.g. the service credentials of a
+    /// zero-trust proxy in front of the source. Redirects to other origins are refused, so that the
+    /// headers are never sent to another host.
+    #[must_use]
+    pub fn with_auth_headers(self, auth_headers: HeaderMap) -> Self {
+        Self {
+            client: base_client(self.protocol, !auth_headers.is_empty()),
+            auth_headers,
+            ..self
+        }
+    }
+
+    /// Request the given media type in the `Accept` header instead of [`Gitlab::ACCEPT_HEADER`],
+    /// e.g. a specific API version of a compatible instance.
+    #[must_use]
+    pub fn with_accept(self, accept: String) -> Self {
+        Self { accept, ..self }
+    }
+
+    /// Normalize the base URL of an instance to end with a slash, so that API paths are joined
+    /// onto the path of self-hosted instances served from a subpath rather than replacing it.
+    /// An API path the URL already ends with is removed with a warning, since it is added to
+    /// every request.
+    fn normalize_base_url(mut url: Url) -> Url {
+        let api_path = format!("/api/{}", Self::VERSION);
+        let mut path = url.path().trim_end_matches('/').to_string();
+        if let Some(base_path) = path.strip_suffix(&api_path) {
+            warning!(
+                Warning::GitlabApiPathRemoved,
+                [%url],
+                "Removing {api_path} from GitLab base URL since it is added to requests"
+            );
+            path = base_path.to_string();
+        }
+        path.push('/');
+        url.set_path(&path);
+        url
+    }
+
+    /// Build a GET request to the given URL, authenticated if a token is configured.
+    fn request(&self, url: Url) -> Request {
+        let mut request = self
+            .client
+            .get(url)
+            .header("User-Agent", USER_AGENT)
+            .header("Accept", &self.accept)
+            .headers(self.auth_headers.clone());
+        if let Some(token) = &self.token {
+            request = request.header(Self::TOKEN_HEADER, token.expose());
+        }
+        request.build().unwrap()
+    }
+
+    /// Get the keys of the pages following the first page of the given URL up to the total
+    /// number of pages, requesting up to [`Gitlab::MAX_CONCURRENT_PAGES`] concurrently.
+    /// Fails without any requests if the total exceeds [`Gitlab::MAX_PAGES`].
+    async fn get_remaining_pages(&self, url: &Url, total_pages: usize) -> Result<Vec<ApiSshKey>> {
+        if total_pages > Self::MAX_PAGES {
+            return Err(Error::Other(format!(
+                "total of {total_pages} pages exceeds the maximum of {}",
+                Self::MAX_PAGES
+            )));
         }
+        let mut pages = 2..=total_pages;
+        let mut set = JoinSet::new();
+        let mut spawn_next = |set: &mut JoinSet<_>| {
+            let Some(page) = pages.next() else {
+                return;
+            };
+            let mut url = url.clone();
+            url.query_pairs_mut().append_pair("page", &page.to_string());
+            let request = self.request(url);
+            let client = self.client.clone();
+            set.spawn(async move {
+                let response = make_api_request(request, &client).await?;
+                Ok::<_, Error>((page, json_array::<ApiSshKey>(response).await?))
+            });
+        };
+        for _ in 0..Self::MAX_CONCURRENT_PAGES {
+            spawn_next(&mut set);
+        }
+        let mut fetched = Vec::new();
+        while let Some(output) = set.join_next().await {
+            let output =
+                output.map_err(|err| Error::Other(format!("request of page failed: {err}")))?;
+            fetched.push(output?);
+            spawn_next(&mut set);
+        }
+        fetched.sort_by_key(|(page, _)| *page);
+        Ok(fetched.into_iter().flat_map(|(_, keys)| keys).collect())
     }
 }
 
@@ -30,44 +178,73 @@ impl Gitlab {
 impl Source for Gitlab {
     // [API Documentation](https://docs.gitlab.com/16.10/ee/api/users.html#list-ssh-keys-for-user)
     async fn get_keys_by_username(&self, username: &str) -> Result<Vec<PublicKey>> {
-        let url = self
+        let mut url = self
             .base_url
             .join(&format!(
-                "/api/{version}/users/{username}/keys",
+                "api/{version}/users/{username}/keys",
                 version = Self::VERSION,
             ))
             .unwrap();
-        let request = self
-            .client
-            .get(url)
-            .header("User-Agent", USER_AGENT)
-            .header("Accept", Self::ACCEPT_HEADER)
-            .build()
-            .unwrap();
+        url.query_pairs_mut()
+            .append_pair("per_page", Self::PER_PAGE);
 
-        let response = make_api_request(request, &self.client).await?;
         // The API has no way to filter keys by usage type, so this contains all the user's keys.
-        let all_keys: Vec<ApiSshKey> = response.json().await?;
+        let mut all_keys: Vec<ApiSshKey> = Vec::new();
+        let response = make_api_request(self.request(url.clone()), &self.client).await?;
+        let total_pages = total_pages(response.headers());
+        let mut next_url = next_url_from_link_header(response.headers(), &url);
+        all_keys.extend(json_array(response).await?);
+        if let Some(total_pages) = total_pages {
+            all_keys.extend(self.get_remaining_pages(&url, total_pages).await?);
+            next_url = None;
+        }
+        // Without the total number of pages, pages are followed one at a time.
+        let mut pages = 1;
+        while let Some(next) = next_url.take().filter(|next| *next != url) {
+            pages += 1;
+            if pages > Self::MAX_PAGES {
+                return Err(Error::Other(format!(
+                    "next links exceed the maximum of {} pages",
+                    Self::MAX_PAGES
+                )));
+            }
+            url = next;
+            let response = make_api_request(self.request(url.clone()), &self.client).await?;
+            next_url = next_url_from_link_header(response.headers(), &url);
+            all_keys.extend(json_array(response).await?);
+        }
         // Filter out the keys that are not used for signing.
         let signing_keys = all_keys
             .into_iter()
-            .filter(|key| key.usage_type.is_signing());
+            .filter(|key| key.usage_type.is_signing(self.signing_only));
 
         Ok(signing_keys.map(PublicKey::from).collect())
     }
 }
 
+/// The total number of pages of a paginated response, which GitLab omits for large collections.
+fn total_pages(headers: &HeaderMap) -> Option<usize> {
+    headers.get("X-Total-Pages")?.to_str().ok()?.parse().ok()
+}
+
 /// Make an HTTP request to the GitLab API.
 async fn make_api_request(request: Request, client: &Client) -> Result<Response> {
     trace!(?request, "Sending request to GitLab API");
-    let response = handle_gitlab_errors(client.execute(request).await)?;
+    let authenticated = request.headers().contains_key(Gitlab::TOKEN_HEADER);
+    let response = handle_gitlab_errors(dump::execute(client, request).await, authenticated)?;
     trace!(?response, "Received response from GitLab API.");
 
     Ok(response)
 }
 
 /// Handle GitLab specific HTTP errors.
-fn handle_gitlab_errors(request_result: reqwest::Result<Response>) -> Result<Response> {
+///
+/// Instances may hide the keys of private users from unauthenticated requests, responding as if
+/// the user did not exist, so a hint to configure a token is given for unauthenticated requests.
+fn handle_gitlab_errors(
+    request_result: reqwest::Result<Response>,
+    authenticated: bool,
+) -> Result<Response> {
     let response = request_result?;
 
     if let Err(error) = response.error_for_status_ref() {
@@ -76,7 +253,16 @@ fn handle_gitlab_errors(request_result: reqwest::Result<Response>) -> Result<Res
             .expect("Status code error must contain status code");
 
         match status {
-            StatusCode::NOT_FOUND => return Err(Error::UserNotFound),
+            StatusCode::NOT_FOUND => {
+                if !authenticated {
+                    warning!(
+                        Warning::GitlabUserMaybePrivate,
+                        [url = %response.url()],
+                        "GitLab did not find the user without a token, if the user exists it may be private and require a token to be configured for the source"
+                    );
+                }
+                return Err(Error::UserNotFound);
+            }
             StatusCode::UNAUTHORIZED => {
                 return Err(Error::BadCredentials);
             }
@@ -98,12 +284,14 @@ pub enum ApiSshKeyUsage {
 }
 
 impl ApiSshKeyUsage {
-    /// Returns true if the key is used for signing.
-    pub fn is_signing(&self) -> bool {
-        matches!(
-            self,
-            ApiSshKeyUsage::Signing | ApiSshKeyUsage::AuthAndSigning
-        )
+    /// Returns true if the key is used for signing, only considering keys used exclusively for
+    /// signing if `signing_only` is set.
+    pub fn is_signing(&self, signing_only: bool) -> bool {
+        match self {
+            ApiSshKeyUsage::Signing => true,
+            ApiSshKeyUsage::AuthAndSigning => !signing_only,
+            ApiSshKeyUsage::Auth => false,
+        }
     }
 }
 
@@ -114,33 +302,79 @@ pub struct ApiSshKey {
     pub title: String,
     pub key: String,
     pub usage_type: ApiSshKeyUsage,
+    #[serde(default, deserialize_with = "deserialize_lenient_timestamp")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "deserialize_lenient_timestamp")]
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 impl From<ApiSshKey> for PublicKey {
     fn from(api_key: ApiSshKey) -> Self {
-        api_key.key.parse().unwrap()
+        api_key
+            .key
+            .parse::<PublicKey>()
+            .unwrap()
+            .with_created_at(api_key.created_at)
+            .with_expires_at(api_key.expires_at)
+            .with_title(Some(api_key.title))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::{client::auth_headers, main::ServerError};
     use super::*;
     use httpmock::prelude::*;
     use reqwest::StatusCode;
     use rstest::*;
+    use std::collections::BTreeMap;
 
     const API_ACCEPT_HEADER: &str = "application/json";
 
     const EXAMPLE_USERNAME: &str = "tanuki";
 
+    const KEY_A: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS";
+    const KEY_B: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILWtK6WxXw7NVhbn6fTQ0dECF8y98fahSIsqKMh+sSo9";
+
     /// An API instance and a mock server with the APIs base url configured to that of the mock server.
     #[fixture]
     fn api_w_mock_server() -> (Gitlab, MockServer) {
         let server = MockServer::start();
-        let api = Gitlab::new(server.base_url().parse().unwrap());
+        let api = Gitlab::new(server.base_url().parse().unwrap(), None);
         (api, server)
     }
 
+    /// Base URLs are normalized to end with a slash, without an API path.
+    #[rstest]
+    #[case("https://git.example.com", "https://git.example.com/")]
+    #[case("https://git.example.com/", "https://git.example.com/")]
+    #[case("https://git.example.com/api/v4", "https://git.example.com/")]
+    #[case("https://git.example.com/api/v4/", "https://git.example.com/")]
+    #[case("https://example.com/gitlab", "https://example.com/gitlab/")]
+    fn base_url_normalized(#[case] url: &str, #[case] expected: &str) {
+        let api = Gitlab::new(url.parse().unwrap(), None);
+
+        assert_eq!(api.base_url.as_str(), expected);
+    }
+
+    /// Requests to instances served from a subpath retain that path.
+    #[rstest]
+    #[tokio::test]
+    async fn api_request_retains_base_url_path() {
+        let server = MockServer::start();
+        let api = Gitlab::new(server.url("/gitlab").parse().unwrap(), None);
+        let mock = server.mock(|when, _| {
+            when.method(GET)
+                .path(format!("/gitlab/api/v4/users/{EXAMPLE_USERNAME}/keys"));
+        });
+
+        let _ = api.get_keys_by_username(EXAMPLE_USERNAME).await;
+
+        mock.assert();
+    }
+
     /// The API request made to get a users signing keys is correct.
     #[rstest]
     #[tokio::test]
@@ -158,6 +392,83 @@ mod tests {
         mock.assert();
     }
 
+    /// Requests use the configured protocol instead of forcing HTTP/2, succeeding against a
+    /// server only speaking HTTP/1.1 unless HTTP/2 with prior knowledge is configured.
+    #[rstest]
+    #[case(Protocol::Auto, true)]
+    #[case(Protocol::Http2, false)]
+    #[tokio::test]
+    async fn api_request_uses_configured_protocol(
+        #[case] protocol: Protocol,
+        #[case] succeeds: bool,
+    ) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            use std::io::{Read, Write};
+
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let len = stream.read(&mut request).unwrap();
+            if request[..len].starts_with(b"GET ") {
+                let _ = stream.write_all(
+                    b"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 2\r\nconnection: close\r\n\r\n[]",
+                );
+            }
+        });
+        let api = Gitlab::new(url.parse().unwrap(), None).with_protocol(protocol);
+
+        let result = api.get_keys_by_username(EXAMPLE_USERNAME).await;
+
+        assert_eq!(result.is_ok(), succeeds, "{result:?}");
+    }
+
+    /// A configured token is used to authenticate API requests.
+    #[rstest]
+    #[tokio::test]
+    async fn api_request_contains_token() {
+        let server = MockServer::start();
+        let api = Gitlab::new(
+            server.base_url().parse().unwrap(),
+            Some(Token::new("glpat-secret")),
+        );
+        let mock = server.mock(|when, _| {
+            when.method(GET)
+                .path(format!("/api/v4/users/{EXAMPLE_USERNAME}/keys"))
+                .header("private-token", "glpat-secret");
+        });
+
+        let _ = api.get_keys_by_username(EXAMPLE_USERNAME).await;
+
+        mock.assert();
+    }
+
+    /// Configured auth headers are sent along with API requests.
+    #[rstest]
+    #[tokio::test]
+    async fn api_request_contains_auth_headers() {
+        let server = MockServer::start();
+        let headers = BTreeMap::from([
+            (
+                "CF-Access-Client-Id".to_string(),
+                Token::new("hanko.access"),
+            ),
+            ("CF-Access-Client-Secret".to_string(), Token::new("s3cr3t")),
+        ]);
+        let api = Gitlab::new(server.base_url().parse().unwrap(), None)
+            .with_auth_headers(auth_headers(&headers).unwrap());
+        let mock = server.mock(|when, _| {
+            when.method(GET)
+                .path(format!("/api/v4/users/{EXAMPLE_USERNAME}/keys"))
+                .header("cf-access-client-id", "hanko.access")
+                .header("cf-access-client-secret", "s3cr3t");
+        });
+
+        let _ = api.get_keys_by_username(EXAMPLE_USERNAME).await;
+
+        mock.assert();
+    }
+
     /// Keys returned from the API are deserialized correctly.
     #[rstest]
     #[case("[]", vec![])]
@@ -213,6 +524,267 @@ mod tests {
         assert_eq!(keys, expected);
     }
 
+    /// The expiry date of keys is retained, since keys are compared by their content only.
+    #[rstest]
+    #[tokio::test]
+    async fn key_expiry_retained(api_w_mock_server: (Gitlab, MockServer)) {
+        let (api, server) = api_w_mock_server;
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/api/v4/users/{EXAMPLE_USERNAME}/keys"));
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(format!(
+                    r#"[
+                        {{"id": 1, "title": "key-1", "key": "{KEY_A}", "usage_type": "signing", "expires_at": "2025-04-10T00:00:00.000Z"}},
+                        {{"id": 2, "title": "key-2", "key": "{KEY_B}", "usage_type": "signing", "expires_at": null}}
+                    ]"#
+                ));
+        });
+
+        let keys = api.get_keys_by_username(EXAMPLE_USERNAME).await.unwrap();
+
+        assert_eq!(
+            keys.iter().map(PublicKey::expires_at).collect::<Vec<_>>(),
+            vec![Some("2025-04-10T00:00:00Z".parse().unwrap()), None]
+        );
+    }
+
+    /// Keys used for both authentication and signing are excluded if only keys used exclusively
+    /// for signing are requested.
+    #[rstest]
+    #[case(false, vec![KEY_A, KEY_B])]
+    #[case(true, vec![KEY_B])]
+    #[tokio::test]
+    async fn auth_and_signing_keys_excluded_if_signing_only(
+        #[case] signing_only: bool,
+        #[case] expected: Vec<&str>,
+        api_w_mock_server: (Gitlab, MockServer),
+    ) {
+        let (api, server) = api_w_mock_server;
+        let api = api.with_signing_only(signing_only);
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/api/v4/users/{EXAMPLE_USERNAME}/keys"));
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(format!(
+                    r#"[
+                        {{"id": 1, "title": "key-1", "key": "{KEY_A}", "usage_type": "auth_and_signing"}},
+                        {{"id": 2, "title": "key-2", "key": "{KEY_B}", "usage_type": "signing"}},
+                        {{"id": 3, "title": "key-3", "key": "{KEY_B}", "usage_type": "auth"}}
+                    ]"#
+                ));
+        });
+
+        let keys = api.get_keys_by_username(EXAMPLE_USERNAME).await.unwrap();
+
+        assert_eq!(
+            keys,
+            expected
+                .into_iter()
+                .map(|key| key.parse().unwrap())
+                .collect::<Vec<PublicKey>>()
+        );
+    }
+
+    /// Keys of all pages are returned by following the next link of paginated responses.
+    #[rstest]
+    #[tokio::test]
+    async fn paginated_keys_are_followed(api_w_mock_server: (Gitlab, MockServer)) {
+        let (api, server) = api_w_mock_server;
+        let key = |id: usize, key: &str| {
+            format!(
+                r#"{{"id": {id}, "title": "key-{id}", "key": "{key}", "usage_type": "signing"}}"#
+            )
+        };
+        // Mocks are matched in order of creation, the second page needs to be created first since
+        // the first page matches it's requests as well.
+        let second_page = server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/api/v4/users/{EXAMPLE_USERNAME}/keys"))
+                .query_param("page", "2");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(format!("[{}]", key(2, KEY_B)));
+        });
+        let first_page = server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/api/v4/users/{EXAMPLE_USERNAME}/keys"))
+                .query_param("per_page", "100");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .header(
+                    "Link",
+                    format!(
+                        r#"<{}?page=2&per_page=100>; rel="next", <{}?page=2&per_page=100>; rel="last""#,
+                        server.url(format!("/api/v4/users/{EXAMPLE_USERNAME}/keys")),
+                        server.url(format!("/api/v4/users/{EXAMPLE_USERNAME}/keys")),
+                    ),
+                )
+                .body(format!("[{}]", key(1, KEY_A)));
+        });
+
+        let keys = api.get_keys_by_username(EXAMPLE_USERNAME).await.unwrap();
+
+        first_page.assert();
+        second_page.assert();
+        assert_eq!(keys, vec![KEY_A.parse().unwrap(), KEY_B.parse().unwrap()]);
+    }
+
+    /// Next links to another origin are not followed, so that the token is never sent to it.
+    #[rstest]
+    #[tokio::test]
+    async fn next_link_to_other_origin_not_followed() {
+        let server = MockServer::start();
+        let other = MockServer::start();
+        let api = Gitlab::new(
+            server.base_url().parse().unwrap(),
+            Some(Token::new("glpat-secret")),
+        );
+        let foreign_page = other.mock(|when, then| {
+            when.any_request();
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body("[]");
+        });
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/api/v4/users/{EXAMPLE_USERNAME}/keys"));
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .header(
+                    "Link",
+                    format!(
+                        r#"<{}?page=2>; rel="next""#,
+                        other.url(format!("/api/v4/users/{EXAMPLE_USERNAME}/keys"))
+                    ),
+                )
+                .body(format!(
+                    r#"[{{"id": 1, "title": "key-1", "key": "{KEY_A}", "usage_type": "signing"}}]"#
+                ));
+        });
+
+        let keys = api.get_keys_by_username(EXAMPLE_USERNAME).await.unwrap();
+
+        foreign_page.assert_hits(0);
+        assert_eq!(keys, vec![KEY_A.parse().unwrap()]);
+    }
+
+    /// The pages following the first are requested concurrently up to the total number of pages
+    /// given by the first response, without following next links, and returned in page order.
+    #[rstest]
+    #[tokio::test]
+    async fn total_pages_fetched_concurrently(api_w_mock_server: (Gitlab, MockServer)) {
+        let (api, server) = api_w_mock_server;
+        let keys = [KEY_A, KEY_B, KEY_A, KEY_B];
+        let body = |page: usize| {
+            format!(
+                r#"[{{"id": {page}, "title": "key-{page}", "key": "{}", "usage_type": "signing"}}]"#,
+                keys[page - 1]
+            )
+        };
+        // Mocks are matched in order of creation, the following pages need to be created first
+        // since the first page matches their requests as well.
+        let following_pages: Vec<_> = (2..=keys.len())
+            .map(|page| {
+                server.mock(|when, then| {
+                    when.method(GET)
+                        .path(format!("/api/v4/users/{EXAMPLE_USERNAME}/keys"))
+                        .query_param("per_page", "100")
+                        .query_param("page", page.to_string());
+                    then.status(200)
+                        .header("Content-Type", "application/json")
+                        .body(body(page));
+                })
+            })
+            .collect();
+        let first_page = server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/api/v4/users/{EXAMPLE_USERNAME}/keys"));
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .header("X-Total-Pages", keys.len().to_string())
+                .body(body(1));
+        });
+
+        let fetched = api.get_keys_by_username(EXAMPLE_USERNAME).await.unwrap();
+
+        first_page.assert();
+        for page in following_pages {
+            page.assert();
+        }
+        assert_eq!(
+            fetched,
+            keys.iter()
+                .map(|key| key.parse().unwrap())
+                .collect::<Vec<PublicKey>>()
+        );
+    }
+
+    /// A total number of pages exceeding the maximum results in an error without requesting any
+    /// of the following pages.
+    #[rstest]
+    #[tokio::test]
+    async fn excessive_total_pages_return_error(api_w_mock_server: (Gitlab, MockServer)) {
+        let (api, server) = api_w_mock_server;
+        let following_pages = server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/api/v4/users/{EXAMPLE_USERNAME}/keys"))
+                .query_param_exists("page");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body("[]");
+        });
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/api/v4/users/{EXAMPLE_USERNAME}/keys"));
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .header("X-Total-Pages", "1000000")
+                .body("[]");
+        });
+
+        let err = api
+            .get_keys_by_username(EXAMPLE_USERNAME)
+            .await
+            .unwrap_err();
+
+        following_pages.assert_hits(0);
+        assert_eq!(
+            err,
+            Error::Other("total of 1000000 pages exceeds the maximum of 100".to_string())
+        );
+    }
+
+    /// A JSON object returned instead of an array of keys results in an error describing it's shape.
+    #[rstest]
+    #[tokio::test]
+    async fn json_object_response_returns_unexpected_json_shape_error(
+        api_w_mock_server: (Gitlab, MockServer),
+    ) {
+        let (api, server) = api_w_mock_server;
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/api/v4/users/{EXAMPLE_USERNAME}/keys"));
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"error": "upstream unavailable"}"#);
+        });
+
+        let error_result = api
+            .get_keys_by_username(EXAMPLE_USERNAME)
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            error_result,
+            Error::from(ServerError::UnexpectedJsonShape(
+                "an object with keys `error`".to_string()
+            ))
+        );
+    }
+
     /// A HTTP not found status code returns a `SourceError::UserNotFound`.
     #[rstest]
     #[tokio::test]
@@ -234,6 +806,32 @@ mod tests {
         assert!(matches!(error_result, Error::UserNotFound));
     }
 
+    /// A HTTP not found status code of an authenticated request also returns a
+    /// `SourceError::UserNotFound`.
+    #[rstest]
+    #[tokio::test]
+    async fn authenticated_http_not_found_returns_user_not_found_error() {
+        let server = MockServer::start();
+        let api = Gitlab::new(
+            server.base_url().parse().unwrap(),
+            Some(Token::new("glpat-secret")),
+        );
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/api/v4/users/{EXAMPLE_USERNAME}/keys"))
+                .header("private-token", "glpat-secret");
+            then.status(StatusCode::NOT_FOUND.into());
+        });
+
+        let error_result = api
+            .get_keys_by_username(EXAMPLE_USERNAME)
+            .await
+            .unwrap_err();
+
+        mock.assert();
+        assert!(matches!(error_result, Error::UserNotFound));
+    }
+
     /// A HTTP unauthorized status code returns a `SourceError::BadCredentials`.
     #[rstest]
     #[tokio::test]
@@ -0,0 +1,136 @@
+use async_trait::async_trait;
+use reqwest::Url;
+use std::num::NonZeroUsize;
+use tokio::sync::Semaphore;
+
+use super::main::{Fetched, ObservedRateLimit, Result, Source};
+use crate::allowed_signers::ssh::PublicKey;
+
+/// A source limiting the number of concurrent requests made to the wrapped source.
+#[derive(Debug)]
+pub struct ConcurrencyLimited {
+    inner: Box<dyn Source>,
+    semaphore: Semaphore,
+}
+
+impl ConcurrencyLimited {
+    #[must_use]
+    pub fn new(inner: Box<dyn Source>, max_concurrent_requests: NonZeroUsize) -> Self {
+        Self {
+            inner,
+            semaphore: Semaphore::new(max_concurrent_requests.get()),
+        }
+    }
+}
+
+#[async_trait]
+impl Source for ConcurrencyLimited {
+    async fn get_keys_by_username(&self, username: &str) -> Result<Vec<PublicKey>> {
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .expect("semaphore is never closed");
+        self.inner.get_keys_by_username(username).await
+    }
+
+    async fn get_keys_if_changed(&self, username: &str, etag: Option<&str>) -> Result<Fetched> {
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .expect("semaphore is never closed");
+        self.inner.get_keys_if_changed(username, etag).await
+    }
+
+    fn url(&self) -> Option<&Url> {
+        self.inner.url()
+    }
+
+    fn auth_identity(&self) -> Option<String> {
+        self.inner.auth_identity()
+    }
+
+    async fn remaining_rate_limit(&self) -> Result<Option<usize>> {
+        self.inner.remaining_rate_limit().await
+    }
+
+    async fn saml_name_id(&self, organization: &str, username: &str) -> Result<Option<String>> {
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .expect("semaphore is never closed");
+        self.inner.saml_name_id(organization, username).await
+    }
+
+    async fn login_by_email(&self, email: &str) -> Result<Option<String>> {
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .expect("semaphore is never closed");
+        self.inner.login_by_email(email).await
+    }
+
+    fn observed_rate_limit(&self) -> Option<ObservedRateLimit> {
+        self.inner.observed_rate_limit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+    use tokio::task::JoinSet;
+
+    /// A source keeping track of the maximum number of concurrent requests made to it.
+    #[derive(Debug, Default)]
+    struct ConcurrencyTracking {
+        in_flight: AtomicUsize,
+        max_in_flight: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Source for ConcurrencyTracking {
+        async fn get_keys_by_username(&self, _username: &str) -> Result<Vec<PublicKey>> {
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(vec![])
+        }
+    }
+
+    /// The number of concurrent requests made to the wrapped source does not exceed the limit.
+    #[tokio::test]
+    async fn concurrent_requests_do_not_exceed_limit() {
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let inner = ConcurrencyTracking {
+            max_in_flight: max_in_flight.clone(),
+            ..Default::default()
+        };
+        let source = Arc::new(ConcurrencyLimited::new(
+            Box::new(inner),
+            NonZeroUsize::new(2).unwrap(),
+        ));
+
+        let mut set: JoinSet<_> = (0..10)
+            .map(|_| {
+                let source = source.clone();
+                async move { source.get_keys_by_username("octocat").await }
+            })
+            .collect();
+        while let Some(result) = set.join_next().await {
+            result.unwrap().unwrap();
+        }
+
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+    }
+}
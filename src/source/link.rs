@@ -0,0 +1,247 @@
+//! Parsing of the HTTP `Link` header as specified in [RFC 8288](https://www.rfc-editor.org/rfc/rfc8288).
+use reqwest::{header::HeaderMap, Url};
+use tracing::debug;
+
+/// A single link contained in a `Link` header.
+#[derive(Debug, PartialEq, Eq)]
+struct Link {
+    /// The unresolved target URI reference.
+    target: String,
+    /// The lowercased relation types.
+    rels: Vec<String>,
+}
+
+/// The URL of the next page as advertised by the `Link` headers of a paginated response.
+/// Relative references are resolved against the given URL of the response.
+/// Links to another origin than the response are ignored, since following them would send the
+/// credentials of the source to another host.
+pub(super) fn next_url_from_link_header(headers: &HeaderMap, base: &Url) -> Option<Url> {
+    headers
+        .get_all(reqwest::header::LINK)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(parse_links)
+        .find(|link| link.rels.iter().any(|rel| rel == "next"))
+        .and_then(|link| base.join(&link.target).ok())
+        .filter(|next| {
+            let same_origin = next.origin() == base.origin();
+            if !same_origin {
+                debug!(%next, "Ignoring next link to another origin");
+            }
+            same_origin
+        })
+}
+
+/// Parse the links contained in a `Link` header value.
+/// Parsing stops at the first malformed link, returning the links parsed up to that point.
+fn parse_links(value: &str) -> Vec<Link> {
+    let mut links = Vec::new();
+    let mut rest = value;
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        let Some(target_start) = rest.strip_prefix('<') else {
+            break;
+        };
+        let Some(target_end) = target_start.find('>') else {
+            break;
+        };
+        let target = target_start[..target_end].trim().to_string();
+        rest = &target_start[target_end + 1..];
+
+        let mut rels = None;
+        while let Some(param) = rest.trim_start().strip_prefix(';') {
+            let (name, value, remaining) = parse_param(param);
+            rest = remaining;
+            // Occurrences of the rel parameter after the first one must be ignored.
+            if rels.is_none() && name.eq_ignore_ascii_case("rel") {
+                rels = Some(
+                    value
+                        .unwrap_or_default()
+                        .split_ascii_whitespace()
+                        .map(str::to_ascii_lowercase)
+                        .collect(),
+                );
+            }
+        }
+        links.push(Link {
+            target,
+            rels: rels.unwrap_or_default(),
+        });
+
+        rest = rest.trim_start();
+        if !(rest.is_empty() || rest.starts_with(',')) {
+            break;
+        }
+    }
+
+    links
+}
+
+/// Parse a single link parameter following it's `;` delimiter, returning it's name, value and
+/// the remaining input.
+fn parse_param(input: &str) -> (&str, Option<String>, &str) {
+    let input = input.trim_start();
+    let name_end = input
+        .find(|c: char| matches!(c, '=' | ';' | ',') || c.is_whitespace())
+        .unwrap_or(input.len());
+    let name = &input[..name_end];
+
+    let rest = input[name_end..].trim_start();
+    let Some(value) = rest.strip_prefix('=') else {
+        return (name, None, rest);
+    };
+    let value = value.trim_start();
+    if let Some(quoted) = value.strip_prefix('"') {
+        let (value, rest) = parse_quoted_string(quoted);
+        (name, Some(value), rest)
+    } else {
+        let value_end = value
+            .find(|c: char| matches!(c, ';' | ',') || c.is_whitespace())
+            .unwrap_or(value.len());
+        (
+            name,
+            Some(value[..value_end].to_string()),
+            &value[value_end..],
+        )
+    }
+}
+
+/// Parse the content of a quoted string following it's opening quote, returning the unescaped
+/// content and the remaining input after the closing quote.
+fn parse_quoted_string(input: &str) -> (String, &str) {
+    let mut content = String::new();
+    let mut chars = input.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return (content, &input[i + 1..]),
+            '\\' => {
+                if let Some((_, escaped)) = chars.next() {
+                    content.push(escaped);
+                }
+            }
+            _ => content.push(c),
+        }
+    }
+    (content, "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use reqwest::header::{HeaderValue, LINK};
+    use rstest::*;
+
+    #[fixture]
+    fn base() -> Url {
+        "https://gitlab.com/api/v4/users/tanuki/keys"
+            .parse()
+            .unwrap()
+    }
+
+    fn headers<'a>(values: impl IntoIterator<Item = &'a str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(LINK, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    /// The URL of the link with the next relation type is returned, unless it has another origin.
+    #[rstest]
+    #[case(
+        vec![r#"<https://gitlab.com/api/v4/users/tanuki/keys?page=2>; rel="next", <https://gitlab.com/api/v4/users/tanuki/keys?page=3>; rel="last""#],
+        Some("https://gitlab.com/api/v4/users/tanuki/keys?page=2")
+    )]
+    #[case(
+        vec![r#"<https://gitlab.com/keys?page=1>; rel="prev", <https://gitlab.com/keys?page=3>; rel="next last""#],
+        Some("https://gitlab.com/keys?page=3")
+    )]
+    #[case(
+        vec![r"<https://gitlab.com/keys?page=2&filter=a,b;c>;rel=next"],
+        Some("https://gitlab.com/keys?page=2&filter=a,b;c")
+    )]
+    #[case(
+        vec![r#"<https://gitlab.com/keys?page=1>; title="first, then; next"; rel="first", <https://gitlab.com/keys?page=2>  ;  REL = "Next""#],
+        Some("https://gitlab.com/keys?page=2")
+    )]
+    #[case(
+        vec![r#"<https://gitlab.com/keys?page=2>; rel="prev"; rel="next""#],
+        None
+    )]
+    #[case(
+        vec![r#"<https://gitlab.com/keys?page=1>; rel="first""#, r#"<https://gitlab.com/keys?page=2>; rel="next""#],
+        Some("https://gitlab.com/keys?page=2")
+    )]
+    #[case(vec![r#"</api/v4/users/tanuki/keys?page=2>; rel="next""#], Some("https://gitlab.com/api/v4/users/tanuki/keys?page=2"))]
+    #[case(vec![r#"<https://gitlab.com/keys?page=1>; rel="first""#], None)]
+    #[case(vec![r#"<https://attacker.example/keys?page=2>; rel="next""#], None)]
+    #[case(vec![r#"<http://gitlab.com/keys?page=2>; rel="next""#], None)]
+    #[case(vec![r#"<https://gitlab.com:8443/keys?page=2>; rel="next""#], None)]
+    #[case(vec![], None)]
+    fn next_url_is_parsed(base: Url, #[case] values: Vec<&str>, #[case] expected: Option<&str>) {
+        let next = next_url_from_link_header(&headers(values), &base);
+
+        assert_eq!(next.as_ref().map(Url::as_str), expected);
+    }
+
+    /// Malformed header values do not yield a next URL.
+    #[rstest]
+    #[case(r#"https://gitlab.com/keys?page=2; rel="next""#)]
+    #[case(r#"<https://gitlab.com/keys?page=2; rel="next""#)]
+    #[case(r#"<https://gitlab.com/keys?page=1>; rel="first" <https://gitlab.com/keys?page=2>; rel="next""#)]
+    #[case(r"<>; rel=")]
+    #[case(";;;,,,")]
+    fn malformed_header_returns_none(base: Url, #[case] value: &str) {
+        assert_eq!(next_url_from_link_header(&headers([value]), &base), None);
+    }
+
+    prop_compose! {
+        /// An absolute URL whose query may contain characters used as delimiters in a Link header.
+        fn link_target()(
+            path in "[a-z0-9/]{0,16}",
+            query in "[a-z0-9=&,;%]{0,16}",
+        ) -> String {
+            format!("https://example.com/{path}?{query}")
+        }
+    }
+
+    prop_compose! {
+        /// A link along with it's formatted header value, using arbitrary whitespace, additional
+        /// parameters and quoted as well as unquoted relation types.
+        fn link()(
+            target in link_target(),
+            rels in prop::collection::vec("[a-z]{1,8}", 1..4),
+            title in r#"[a-zA-Z0-9 ,;="\\]{0,16}"#,
+            ws in "[ \t]{0,2}",
+            quote_rel in any::<bool>(),
+        ) -> (Link, String) {
+            let rel = if quote_rel || rels.len() > 1 {
+                format!("\"{}\"", rels.join(" "))
+            } else {
+                rels[0].clone()
+            };
+            let title = title.replace('\\', "\\\\").replace('"', "\\\"");
+            let value = format!("<{target}>{ws};{ws}title=\"{title}\"{ws};{ws}rel{ws}={ws}{rel}");
+            (Link { target, rels }, value)
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn valid_link_header_is_parsed(links in prop::collection::vec(link(), 0..5), ws in "[ \t]{0,2}") {
+            let value = links.iter().map(|(_, v)| v.as_str()).collect::<Vec<_>>().join(&format!("{ws},{ws}"));
+            let expected: Vec<&Link> = links.iter().map(|(l, _)| l).collect();
+
+            let parsed = parse_links(&value);
+
+            prop_assert_eq!(parsed.iter().collect::<Vec<_>>(), expected);
+        }
+
+        #[test]
+        fn parsing_arbitrary_header_does_not_panic(value in "\\PC*") {
+            let _ = parse_links(&value);
+        }
+    }
+}
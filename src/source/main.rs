@@ -1,6 +1,11 @@
-use crate::{allowed_signers::ssh::PublicKey, USER_AGENT};
+use super::client::CrossOriginRedirect;
+use crate::allowed_signers::ssh::PublicKey;
 use async_trait::async_trait;
-use std::{fmt::Debug, time::Duration};
+use chrono::{DateTime, Utc};
+use reqwest::{Response, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::fmt::{self, Debug};
 
 /// A `Result` alias where the `Err` case is a source [`Error`].
 pub type Result<T> = std::result::Result<T, Error>;
@@ -10,10 +15,124 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub trait Source: Debug + Send + Sync {
     /// Get a users public keys by their username.
     async fn get_keys_by_username(&self, username: &str) -> Result<Vec<PublicKey>>;
+
+    /// Get a users public keys by their username unless they are unchanged since the response
+    /// identified by the given `ETag`. Sources not supporting conditional requests always return
+    /// the users keys.
+    async fn get_keys_if_changed(&self, username: &str, _etag: Option<&str>) -> Result<Fetched> {
+        Ok(Fetched::Keys {
+            keys: self.get_keys_by_username(username).await?,
+            etag: None,
+        })
+    }
+
+    /// The base URL of the source, used to identify cached keys. Keys of sources without a URL
+    /// are not cached.
+    fn url(&self) -> Option<&Url> {
+        None
+    }
+
+    /// A digest identifying the credentials used to authenticate requests, if any, without
+    /// exposing the credentials themselves. Cached keys are identified by it as well, since
+    /// authenticated requests may legitimately return different keys than unauthenticated ones.
+    fn auth_identity(&self) -> Option<String> {
+        None
+    }
+
+    /// The number of requests that can be made before the rate limit is exceeded, if known.
+    async fn remaining_rate_limit(&self) -> Result<Option<usize>> {
+        Ok(None)
+    }
+
+    /// Get the SAML `NameID` a user is linked to within an organization, if the source supports
+    /// SAML single sign-on and the user has a linked identity.
+    async fn saml_name_id(&self, _organization: &str, _username: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Get the username of the user owning the given email address, if the source supports
+    /// resolving email addresses and any user was found.
+    async fn login_by_email(&self, _email: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// The rate limit status reported by the latest response of the source, if any.
+    fn observed_rate_limit(&self) -> Option<ObservedRateLimit> {
+        None
+    }
+}
+
+/// The rate limit status of a source as reported by one of it's responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObservedRateLimit {
+    /// The number of requests remaining until the rate limit is exceeded.
+    pub remaining: usize,
+    /// The number of requests allowed within the current rate limit window.
+    pub limit: usize,
+    /// The time the current rate limit window resets at.
+    pub reset: DateTime<Utc>,
+}
+
+impl ObservedRateLimit {
+    /// A human readable summary of the rate limit status relative to the given time, e.g.
+    /// `4821/5000 remaining, resets in 42m`.
+    #[must_use]
+    pub fn summary(&self, now: DateTime<Utc>) -> String {
+        let minutes = (self.reset - now).num_minutes().max(0);
+        format!(
+            "{}/{} remaining, resets in {minutes}m",
+            self.remaining, self.limit
+        )
+    }
+}
+
+/// The result of a conditional request for a users public keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fetched {
+    /// The keys did not change since the response identified by the given `ETag`.
+    NotModified,
+    /// The current keys, along with the `ETag` identifying the response if any.
+    Keys {
+        keys: Vec<PublicKey>,
+        etag: Option<String>,
+    },
+}
+
+/// A secret token used to authenticate with a source.
+/// The token is redacted from its `Debug` representation to prevent it from leaking into logs.
+#[derive(Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Token(String);
+
+impl Token {
+    #[must_use]
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(token.into())
+    }
+
+    /// The secret value of the token.
+    pub(super) fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// The name of the environment variable the token refers to using the `env:<VARIABLE>` form,
+    /// if any.
+    pub(crate) fn env_reference(&self) -> Option<&str> {
+        self.0.strip_prefix("env:")
+    }
+}
+
+impl Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Token(<redacted>)")
+    }
 }
 
 /// An error that can occur when interacting with a source.
-#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+///
+/// Errors are serialized as an object containing their `kind` and `detail`, if any.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "detail", rename_all = "snake_case")]
 pub enum Error {
     #[error("used credentials are invalid")]
     BadCredentials,
@@ -26,59 +145,119 @@ pub enum Error {
     #[error("server error occurred")]
     ServerError(#[from] ServerError),
     #[error("client request error")]
-    ClientError(reqwest::StatusCode),
+    ClientError(#[serde(serialize_with = "serialize_status")] reqwest::StatusCode),
+    #[error("command failed: {0}")]
+    CommandFailed(String),
+    #[error("token is missing the required permission: {message}")]
+    InsufficientScope { message: String },
+    #[error("SAML identity is not accessible: {0}")]
+    SamlIdentityInaccessible(String),
+    #[error("key with fingerprint {0} is not pinned")]
+    UnpinnedKey(String),
+    #[error("key with fingerprint {0} is denied")]
+    DeniedKey(String),
+    #[error("invalid key {0}")]
+    InvalidKey(String),
+    #[error("expected {0} key(s) but retrieved {1}")]
+    UnexpectedKeyCount(usize, usize),
+    #[error("too many redirects")]
+    TooManyRedirects,
+    #[error("refused redirect to {0}, which would send auth headers to another origin")]
+    CrossOriginRedirect(String),
+    #[error("unexpected error: {0}")]
+    Other(String),
 }
 
 /// Conversion for generic reqwest errors not specific to any `Source`.
 ///
-/// # Panics
-///
-/// Since the error type is not and enum and cannot be matched exhaustively, this conversion panics
-/// as a last resort if an unexpected error is encountered.
+/// Since the error type is not an enum and cannot be matched exhaustively, unexpected errors are
+/// converted into [`Error::Other`] as a last resort.
 impl From<reqwest::Error> for Error {
-    #[allow(clippy::panic)]
     fn from(error: reqwest::Error) -> Self {
         if error.is_connect() || error.is_timeout() {
-            Error::ConnectionError
-        } else if error.is_status()
-            && error
-                .status()
-                .expect("missing error status code")
-                .is_server_error()
-        {
-            ServerError::StatusCode(error.status().unwrap()).into()
-        } else if error.is_status()
-            && error
-                .status()
-                .expect("missing error status code")
-                .is_client_error()
-        {
-            Error::ClientError(error.status().unwrap())
-        } else if error.is_body() || error.is_decode() {
-            ServerError::InvalidResponseBody.into()
-        } else {
-            panic!("Unexpected reqwest error: {error:?}");
+            return Error::ConnectionError;
+        }
+        if error.is_redirect() {
+            let mut source = std::error::Error::source(&error);
+            while let Some(err) = source {
+                if let Some(CrossOriginRedirect(url)) = err.downcast_ref() {
+                    return Error::CrossOriginRedirect(url.to_string());
+                }
+                source = err.source();
+            }
+            return Error::TooManyRedirects;
+        }
+        match error.status() {
+            Some(status) if status.is_server_error() => ServerError::StatusCode(status).into(),
+            Some(status) if status.is_client_error() => Error::ClientError(status),
+            _ if error.is_body() || error.is_decode() => ServerError::InvalidResponseBody.into(),
+            _ => Error::Other(format!("{error:?}")),
         }
     }
 }
 
-#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "detail", rename_all = "snake_case")]
 pub enum ServerError {
     #[error("invalid response body")]
     InvalidResponseBody,
+    #[error("expected a JSON array of keys but received {0}")]
+    UnexpectedJsonShape(String),
     #[error("{0}")]
-    StatusCode(reqwest::StatusCode),
+    StatusCode(#[serde(serialize_with = "serialize_status")] reqwest::StatusCode),
+    /// A server error status code along with the ID the provider assigned to the request, which
+    /// their support needs to investigate it.
+    #[error("{status}, request ID {request_id}")]
+    StatusCodeWithRequestId {
+        #[serde(serialize_with = "serialize_status")]
+        status: reqwest::StatusCode,
+        request_id: String,
+    },
+}
+
+/// Serialize an HTTP status code as it's numeric value.
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn serialize_status<S>(
+    status: &reqwest::StatusCode,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_u16(status.as_u16())
+}
+
+/// Deserialize a response body expected to contain a JSON array.
+/// If the body contains valid JSON of another shape, e.g. an error object returned by a gateway
+/// using a success status code, an error describing the received shape is returned.
+pub(super) async fn json_array<T>(response: Response) -> Result<Vec<T>>
+where
+    T: DeserializeOwned,
+{
+    let value: JsonValue = response.json().await?;
+    if !value.is_array() {
+        return Err(ServerError::UnexpectedJsonShape(json_shape(&value)).into());
+    }
+    serde_json::from_value(value).map_err(|_| ServerError::InvalidResponseBody.into())
 }
 
-/// The base reqwest Client to be used by sources.
-pub(super) fn base_client() -> reqwest::Client {
-    reqwest::Client::builder()
-        .user_agent(USER_AGENT)
-        .connect_timeout(Duration::from_secs(2))
-        .timeout(Duration::from_secs(10))
-        .use_rustls_tls()
-        .build()
-        .unwrap()
+/// A human readable description of the top level shape of a JSON value.
+fn json_shape(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => "null".to_string(),
+        JsonValue::Bool(_) => "a boolean".to_string(),
+        JsonValue::Number(_) => "a number".to_string(),
+        JsonValue::String(_) => "a string".to_string(),
+        JsonValue::Array(_) => "an array".to_string(),
+        JsonValue::Object(map) if map.is_empty() => "an empty object".to_string(),
+        JsonValue::Object(map) => format!(
+            "an object with keys {}",
+            map.keys()
+                .map(|k| format!("`{k}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
 }
 
 #[cfg(test)]
@@ -88,6 +267,13 @@ mod tests {
     use proptest::prelude::*;
     use rstest::*;
 
+    #[test]
+    fn token_debug_is_redacted() {
+        let token = Token::new("ghp_supersecret");
+
+        assert!(!format!("{token:?}").contains("supersecret"));
+    }
+
     /// Returns a reqwest error caused by the given status code.
     fn reqwest_status_code_error(status: reqwest::StatusCode) -> reqwest::Error {
         let server = MockServer::start();
@@ -206,6 +392,28 @@ mod tests {
         }
     }
 
+    /// Unexpected reqwest errors, like failing to build a request, are converted into a catch-all
+    /// error instead of panicking.
+    #[test]
+    fn source_error_from_unexpected_reqwest_error_is_other() {
+        let error = reqwest::blocking::Client::new()
+            .get("not a url")
+            .send()
+            .unwrap_err();
+        assert!(error.is_builder());
+
+        assert!(matches!(Error::from(error), Error::Other(_)));
+    }
+
+    #[rstest]
+    #[case(serde_json::json!({"error": "gateway says no", "code": 1}), "an object with keys `code`, `error`")]
+    #[case(serde_json::json!({}), "an empty object")]
+    #[case(serde_json::json!("nope"), "a string")]
+    #[case(serde_json::json!(null), "null")]
+    fn json_shape_describes_value(#[case] value: JsonValue, #[case] expected: &str) {
+        assert_eq!(json_shape(&value), expected);
+    }
+
     #[rstest]
     fn source_error_from_reqwest_decode_error_is_server_error_invalid_response_body(
         reqwest_decode_error: reqwest::Error,
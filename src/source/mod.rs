@@ -1,7 +1,24 @@
+pub(crate) use client::{auth_headers, base_client, set_max_redirects, set_timeouts};
+pub use client::{
+    configure_pool, connection_stats, ConnectionStats, PoolOptions, Protocol, DEFAULT_MAX_REDIRECTS,
+};
+pub use command::Command;
+pub(crate) use dump::enable_request_dumps;
 pub use github::Github;
 pub use gitlab::Gitlab;
-pub use main::{Error, Source};
+pub use limit::ConcurrencyLimited;
+pub use main::{Error, Fetched, ObservedRateLimit, Source, Token};
+pub use retry::{RetryClass, RetryPolicy, Retrying};
+pub use url::PlainUrl;
 
+mod client;
+mod command;
+mod dump;
 mod github;
 mod gitlab;
+mod limit;
+mod link;
 mod main;
+mod pool;
+mod retry;
+mod url;
@@ -0,0 +1,131 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use super::main::Token;
+use crate::digest::sha256_hex;
+
+/// A pool of tokens used in turn, spreading requests across the rate limits of all tokens.
+/// Tokens rejected by the source are skipped by subsequent requests without affecting the others.
+#[derive(Debug, Default)]
+pub(super) struct TokenPool {
+    tokens: Vec<(Token, AtomicBool)>,
+    next: AtomicUsize,
+}
+
+impl TokenPool {
+    pub(super) fn new(tokens: Vec<Token>) -> Self {
+        Self {
+            tokens: tokens
+                .into_iter()
+                .map(|token| (token, AtomicBool::new(false)))
+                .collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// The number of tokens in the pool, including rejected ones.
+    pub(super) fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Whether the pool contains no tokens at all.
+    pub(super) fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// The next token that has not been rejected in round robin order, along with it's index.
+    /// Returns `None` if the pool is empty or all of it's tokens have been rejected.
+    pub(super) fn next(&self) -> Option<(usize, &Token)> {
+        let n = self.tokens.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        (0..n)
+            .map(|i| (start + i) % n)
+            .find(|&i| !self.tokens[i].1.load(Ordering::Relaxed))
+            .map(|i| (i, &self.tokens[i].0))
+    }
+
+    /// Reject the token at the given index, skipping it for subsequent requests.
+    pub(super) fn reject(&self, index: usize) {
+        self.tokens[index].1.store(true, Ordering::Relaxed);
+    }
+
+    /// A SHA256 digest of all tokens in the pool regardless of their order, identifying the
+    /// credentials requests are authenticated with without exposing them.
+    /// Returns `None` if the pool is empty.
+    pub(super) fn identity(&self) -> Option<String> {
+        if self.tokens.is_empty() {
+            return None;
+        }
+        let mut tokens: Vec<&str> = self
+            .tokens
+            .iter()
+            .map(|(token, _)| token.expose())
+            .collect();
+        tokens.sort_unstable();
+        Some(sha256_hex(tokens.join("\n")))
+    }
+
+    /// All tokens that have not been rejected.
+    pub(super) fn usable(&self) -> impl Iterator<Item = &Token> {
+        self.tokens
+            .iter()
+            .filter(|(_, rejected)| !rejected.load(Ordering::Relaxed))
+            .map(|(token, _)| token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool() -> TokenPool {
+        TokenPool::new(vec![Token::new("a"), Token::new("b"), Token::new("c")])
+    }
+
+    /// Tokens are used in turn.
+    #[test]
+    fn tokens_used_round_robin() {
+        let pool = pool();
+
+        let used: Vec<usize> = (0..4).map(|_| pool.next().unwrap().0).collect();
+
+        assert_eq!(used, vec![0, 1, 2, 0]);
+    }
+
+    /// Rejected tokens are skipped without affecting the others.
+    #[test]
+    fn rejected_tokens_skipped() {
+        let pool = pool();
+        pool.reject(1);
+
+        let used: Vec<usize> = (0..3).map(|_| pool.next().unwrap().0).collect();
+
+        assert_eq!(used, vec![0, 2, 2]);
+        assert_eq!(pool.usable().count(), 2);
+    }
+
+    /// No token is returned if all tokens have been rejected or the pool is empty.
+    #[test]
+    fn no_token_if_all_rejected_or_empty() {
+        let pool = pool();
+        for i in 0..pool.len() {
+            pool.reject(i);
+        }
+
+        assert!(pool.next().is_none());
+        assert!(TokenPool::default().next().is_none());
+    }
+
+    /// The identity of a pool depends on it's tokens regardless of their order, while an empty
+    /// pool has none.
+    #[test]
+    fn identity_depends_on_tokens() {
+        let reordered = TokenPool::new(vec![Token::new("c"), Token::new("a"), Token::new("b")]);
+        let other = TokenPool::new(vec![Token::new("d")]);
+
+        let identity = pool().identity().unwrap();
+
+        assert_eq!(reordered.identity().unwrap(), identity);
+        assert_ne!(other.identity().unwrap(), identity);
+        assert_eq!(TokenPool::default().identity(), None);
+    }
+}
@@ -0,0 +1,234 @@
+use async_trait::async_trait;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::{future::Future, num::NonZeroU32, time};
+
+use super::main::{Error, Fetched, ObservedRateLimit, Result, Source};
+use crate::{
+    allowed_signers::ssh::PublicKey,
+    diagnostic::{warning, Warning},
+    duration::Duration,
+};
+
+/// A class of errors that can be configured to be retried.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryClass {
+    /// Failing to connect to the source or timing out.
+    Connection,
+    /// Server errors, e.g. a `5xx` status code or an invalid response.
+    #[serde(rename = "server_5xx")]
+    Server5xx,
+    /// The rate limit of the source being exceeded.
+    Ratelimit,
+    /// Client errors, e.g. a `4xx` status code not covered by any other class.
+    #[serde(rename = "client_4xx")]
+    Client4xx,
+    /// The requested user not existing on the source.
+    UserNotFound,
+}
+
+impl RetryClass {
+    /// The class of the given error, if it belongs to any.
+    fn of(error: &Error) -> Option<Self> {
+        match error {
+            Error::ConnectionError => Some(Self::Connection),
+            Error::ServerError(_) => Some(Self::Server5xx),
+            Error::RatelimitExceeded => Some(Self::Ratelimit),
+            Error::ClientError(_) => Some(Self::Client4xx),
+            Error::UserNotFound => Some(Self::UserNotFound),
+            _ => None,
+        }
+    }
+}
+
+/// Which failed requests to a source are retried, and how often.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryPolicy {
+    /// The classes of errors that are retried.
+    pub retryable: Vec<RetryClass>,
+    /// The maximum number of attempts made for a request, including the first one.
+    pub max_attempts: NonZeroU32,
+    /// The delay before the first retry, doubled for every further retry.
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retryable: vec![
+                RetryClass::Connection,
+                RetryClass::Server5xx,
+                RetryClass::Ratelimit,
+            ],
+            max_attempts: NonZeroU32::new(3).unwrap(),
+            backoff: Duration::new(time::Duration::from_secs(1)),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether the given error is retried according to the policy.
+    fn is_retryable(&self, error: &Error) -> bool {
+        RetryClass::of(error).is_some_and(|class| self.retryable.contains(&class))
+    }
+
+    /// The delay before retrying after the given number of failed attempts.
+    fn delay(&self, attempts: u32) -> time::Duration {
+        let backoff: time::Duration = self.backoff.into();
+        backoff.saturating_mul(2_u32.saturating_pow(attempts - 1))
+    }
+}
+
+/// A source retrying failed requests to the wrapped source according to a [`RetryPolicy`].
+/// Since the number of attempts is always limited, retrying errors that are unlikely to resolve,
+/// like a user not existing, terminates all the same.
+#[derive(Debug)]
+pub struct Retrying {
+    inner: Box<dyn Source>,
+    policy: RetryPolicy,
+}
+
+impl Retrying {
+    #[must_use]
+    pub fn new(inner: Box<dyn Source>, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    /// Make the request created by the given function, retrying it as long as it fails with a
+    /// retryable error and attempts remain.
+    async fn retry<T, F, R>(&self, mut request: F) -> Result<T>
+    where
+        F: FnMut() -> R + Send,
+        R: Future<Output = Result<T>> + Send,
+    {
+        let mut attempts = 1;
+        loop {
+            match request().await {
+                Err(err)
+                    if attempts < self.policy.max_attempts.get()
+                        && self.policy.is_retryable(&err) =>
+                {
+                    let delay = self.policy.delay(attempts);
+                    warning!(
+                        Warning::RetryingRequest,
+                        [source = ?self.inner],
+                        "Attempt {attempts} failed: {err}, retrying in {delay:?}"
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempts += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Source for Retrying {
+    async fn get_keys_by_username(&self, username: &str) -> Result<Vec<PublicKey>> {
+        self.retry(|| self.inner.get_keys_by_username(username))
+            .await
+    }
+
+    async fn get_keys_if_changed(&self, username: &str, etag: Option<&str>) -> Result<Fetched> {
+        self.retry(|| self.inner.get_keys_if_changed(username, etag))
+            .await
+    }
+
+    fn url(&self) -> Option<&Url> {
+        self.inner.url()
+    }
+
+    fn auth_identity(&self) -> Option<String> {
+        self.inner.auth_identity()
+    }
+
+    async fn remaining_rate_limit(&self) -> Result<Option<usize>> {
+        self.retry(|| self.inner.remaining_rate_limit()).await
+    }
+
+    async fn saml_name_id(&self, organization: &str, username: &str) -> Result<Option<String>> {
+        self.retry(|| self.inner.saml_name_id(organization, username))
+            .await
+    }
+
+    async fn login_by_email(&self, email: &str) -> Result<Option<String>> {
+        self.retry(|| self.inner.login_by_email(email)).await
+    }
+
+    fn observed_rate_limit(&self) -> Option<ObservedRateLimit> {
+        self.inner.observed_rate_limit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    /// A source failing every request with the same error, counting the requests made to it.
+    #[derive(Debug)]
+    struct Failing {
+        error: Error,
+        requests: Arc<AtomicU32>,
+    }
+
+    #[async_trait]
+    impl Source for Failing {
+        async fn get_keys_by_username(&self, _username: &str) -> Result<Vec<PublicKey>> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            Err(self.error.clone())
+        }
+    }
+
+    /// Retryable errors are retried until the maximum number of attempts is reached, even if
+    /// they are unlikely to resolve, while other errors are returned immediately.
+    #[rstest]
+    #[case(Error::ConnectionError, 3)]
+    #[case(Error::RatelimitExceeded, 3)]
+    #[case(Error::UserNotFound, 3)]
+    #[case(Error::ClientError(reqwest::StatusCode::BAD_REQUEST), 1)]
+    #[case(Error::BadCredentials, 1)]
+    #[tokio::test]
+    async fn retryable_errors_retried_up_to_max_attempts(
+        #[case] error: Error,
+        #[case] expected_requests: u32,
+    ) {
+        let requests = Arc::new(AtomicU32::new(0));
+        let inner = Failing {
+            error: error.clone(),
+            requests: requests.clone(),
+        };
+        let policy = RetryPolicy {
+            retryable: vec![
+                RetryClass::Connection,
+                RetryClass::Ratelimit,
+                RetryClass::UserNotFound,
+            ],
+            max_attempts: NonZeroU32::new(3).unwrap(),
+            backoff: Duration::new(time::Duration::from_millis(1)),
+        };
+        let source = Retrying::new(Box::new(inner), policy);
+
+        let result = source.get_keys_by_username("octocat").await;
+
+        assert_eq!(result, Err(error));
+        assert_eq!(requests.load(Ordering::SeqCst), expected_requests);
+    }
+
+    /// The backoff is doubled for every retry.
+    #[test]
+    fn backoff_doubled_for_every_retry() {
+        let policy = RetryPolicy::default();
+
+        let delays: Vec<_> = (1..=3).map(|attempts| policy.delay(attempts)).collect();
+
+        assert_eq!(delays, [1, 2, 4].map(time::Duration::from_secs).to_vec());
+    }
+}
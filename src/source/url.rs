@@ -0,0 +1,204 @@
+use async_trait::async_trait;
+use reqwest::{header::HeaderMap, Client, StatusCode, Url};
+use std::fmt::Write;
+use tracing::trace;
+
+use super::{
+    client::{base_client, Protocol},
+    dump,
+    main::{Error, Result, Source},
+};
+use crate::{allowed_signers::ssh::PublicKey, USER_AGENT};
+
+/// The placeholder substituted by the username in the URL template.
+const USERNAME_PLACEHOLDER: &str = "{username}";
+/// The placeholder as contained within the path of a parsed URL, which percent-encodes braces.
+const ENCODED_USERNAME_PLACEHOLDER: &str = "%7Busername%7D";
+
+/// A source retrieving public keys from a plain URL, e.g. `https://keys.corp/{username}.keys`.
+///
+/// Every occurrence of `{username}` within the URL is substituted by the username. A successful
+/// response contains one public key per line in the authorized keys format, empty lines and lines
+/// starting with `#` are ignored. A `404 Not Found` response signals that the user does not exist.
+#[derive(Debug)]
+pub struct PlainUrl {
+    template: Url,
+    /// Headers sent along with every request, e.g. the credentials of a proxy.
+    auth_headers: HeaderMap,
+    /// The protocol used for requests.
+    protocol: Protocol,
+    client: Client,
+}
+
+impl PlainUrl {
+    #[must_use]
+    pub fn new(template: Url) -> Self {
+        Self {
+            template,
+            auth_headers: HeaderMap::new(),
+            protocol: Protocol::default(),
+            client: base_client(Protocol::default(), false),
+        }
+    }
+
+    /// Use the given protocol for requests.
+    #[must_use]
+    pub fn with_protocol(self, protocol: Protocol) -> Self {
+        Self {
+            protocol,
+            client: base_client(protocol, !self.auth_headers.is_empty()),
+            ..self
+        }
+    }
+
+    /// Send the given headers along with every request, e.g. the service credentials of a
+    /// zero-trust proxy in front of the source. Redirects to other origins are refused, so that the
+    /// headers are never sent to another host.
+    #[must_use]
+    pub fn with_auth_headers(self, auth_headers: HeaderMap) -> Self {
+        Self {
+            client: base_client(self.protocol, !auth_headers.is_empty()),
+            auth_headers,
+            ..self
+        }
+    }
+
+    /// Whether the given URL contains the username placeholder.
+    pub(crate) fn is_template(url: &Url) -> bool {
+        url.as_str().contains(USERNAME_PLACEHOLDER)
+            || url.as_str().contains(ENCODED_USERNAME_PLACEHOLDER)
+    }
+
+    /// The URL of the given user's keys. The username is percent-encoded, so that it can't change
+    /// the path or query of the URL, e.g. by containing a `/` or `?`.
+    fn url(&self, username: &str) -> Result<Url> {
+        let url = self
+            .template
+            .as_str()
+            .replace(ENCODED_USERNAME_PLACEHOLDER, USERNAME_PLACEHOLDER)
+            .replace(USERNAME_PLACEHOLDER, &percent_encode(username));
+        url.parse()
+            .map_err(|err| Error::Other(format!("invalid URL {url}: {err}")))
+    }
+}
+
+/// Percent-encode all characters of the given value except the unreserved characters of
+/// [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986#section-2.3).
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+#[async_trait]
+impl Source for PlainUrl {
+    async fn get_keys_by_username(&self, username: &str) -> Result<Vec<PublicKey>> {
+        let request = self
+            .client
+            .get(self.url(username)?)
+            .header("User-Agent", USER_AGENT)
+            .headers(self.auth_headers.clone())
+            .build()
+            .unwrap();
+        trace!(?request, "Sending request for plain keys");
+        let response = dump::execute(&self.client, request).await?;
+        trace!(?response, "Received response for plain keys");
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(Error::UserNotFound);
+        }
+
+        Ok(response
+            .error_for_status()?
+            .text()
+            .await?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.parse().expect("parsing a public key is infallible"))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+    use rstest::*;
+
+    const KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS";
+
+    /// The username is substituted within the path as well as the query of the URL.
+    #[rstest]
+    #[case("https://keys.corp/{username}.keys", "https://keys.corp/octocat.keys")]
+    #[case(
+        "https://keys.corp/keys?user={username}",
+        "https://keys.corp/keys?user=octocat"
+    )]
+    fn username_substituted_in_url(#[case] template: &str, #[case] expected: &str) {
+        let source = PlainUrl::new(template.parse().unwrap());
+
+        assert!(PlainUrl::is_template(&source.template));
+        assert_eq!(source.url("octocat").unwrap().as_str(), expected);
+    }
+
+    /// Characters of the username that would change the path or query of the URL are
+    /// percent-encoded.
+    #[rstest]
+    #[case(
+        "https://keys.corp/{username}.keys",
+        "../admin",
+        "https://keys.corp/..%2Fadmin.keys"
+    )]
+    #[case(
+        "https://keys.corp/keys?user={username}",
+        "octo cat&admin=1#",
+        "https://keys.corp/keys?user=octo%20cat%26admin%3D1%23"
+    )]
+    #[case(
+        "https://keys.corp/{username}.keys",
+        "octo?cat",
+        "https://keys.corp/octo%3Fcat.keys"
+    )]
+    fn username_percent_encoded_in_url(
+        #[case] template: &str,
+        #[case] username: &str,
+        #[case] expected: &str,
+    ) {
+        let source = PlainUrl::new(template.parse().unwrap());
+
+        assert_eq!(source.url(username).unwrap().as_str(), expected);
+    }
+
+    /// Keys contained in the response are returned, skipping blank and comment lines, while a
+    /// missing user is reported as such.
+    #[rstest]
+    #[tokio::test]
+    async fn keys_read_from_response() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/octocat.keys");
+            then.status(200)
+                .body(format!("# Keys of octocat\n\n{KEY} octocat@laptop\n"));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/octocta.keys");
+            then.status(404);
+        });
+        let source = PlainUrl::new(server.url("/{username}.keys").parse().unwrap());
+
+        let keys = source.get_keys_by_username("octocat").await.unwrap();
+
+        assert_eq!(keys, vec![format!("{KEY} octocat@laptop").parse().unwrap()]);
+        assert_eq!(
+            source.get_keys_by_username("octocta").await,
+            Err(Error::UserNotFound)
+        );
+    }
+}
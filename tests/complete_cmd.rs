@@ -0,0 +1,65 @@
+//! Ensure correct behavior of the hidden dynamic completion subcommand.
+use assert_cmd::Command;
+use indoc::indoc;
+use rstest::*;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+/// Completing names prints the configured names of the given kind, one per line.
+#[rstest]
+#[case("signers", "torvalds\ncwoods\n")]
+#[case(
+    "sources",
+    if cfg!(feature = "public-providers") {
+        "acme-corp\ngithub\ngitlab\n"
+    } else {
+        "acme-corp\n"
+    }
+)]
+fn completing_names_prints_configured_names(#[case] kind: &str, #[case] expected: &str) {
+    let mut config = NamedTempFile::new().unwrap();
+    config
+        .write_all(
+            indoc! {r#"
+                signers = [
+                    { name = "torvalds", principals = ["torvalds@linux-foundation.org"], sources = ["acme-corp"] },
+                    { name = "cwoods", principals = ["cwoods@acme.corp"], sources = ["acme-corp"] },
+                    { name = "cwoods", principals = ["cwoods@universal.exports"], sources = ["acme-corp"] },
+                ]
+
+                [[sources]]
+                name = "acme-corp"
+                provider = "gitlab"
+                url = "https://git.acme.corp"
+            "#}
+            .as_bytes(),
+        )
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("__complete")
+        .arg(kind);
+
+    cmd.assert().success().stdout(expected.to_string());
+}
+
+/// Completing names without a configuration file completes the default sources, if any.
+#[test]
+fn completing_names_without_configuration_prints_defaults() {
+    let dir = tempfile::TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(dir.path().join("config.toml"))
+        .arg("__complete")
+        .arg("sources");
+
+    let expected = if cfg!(feature = "public-providers") {
+        "github\ngitlab\n"
+    } else {
+        ""
+    };
+    cmd.assert().success().stdout(expected);
+}
@@ -0,0 +1,53 @@
+//! Ensure correct behavior of the config subcommand.
+use assert_cmd::Command;
+use indoc::indoc;
+use predicates::prelude::*;
+use rstest::*;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+/// Testing a configuration reports that saving it preserves it's formatting, including comments
+/// and the layout of inline tables.
+#[rstest]
+fn config_test_reports_no_formatting_changes() {
+    let mut config = NamedTempFile::new().unwrap();
+    config
+        .write_all(
+            indoc! {r#"
+                # Signers of the release team.
+                signers = [
+                    { name = "jsnow",   principals = ["j.snow@wall.com"], sources = ["acme-gitlab"] },  # Lord Commander
+                    { name = "imalcom", principals = ["ian.malcom@acme.corp"], sources = ["acme-gitlab"] },
+                ]
+
+                [[sources]]
+                name    = "acme-gitlab"
+                provider = "gitlab"
+                url = "https://git.acme.corp"
+            "#}
+            .as_bytes(),
+        )
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("config")
+        .arg("test")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("No formatting changes"));
+}
+
+/// Testing a configuration that does not exist fails.
+#[rstest]
+fn config_test_requires_existing_configuration() {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg("/nonexistent/config.toml")
+        .arg("config")
+        .arg("test")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("does not exist"));
+}
@@ -0,0 +1,25 @@
+//! Ensure correct behavior of the environment variable reference subcommand.
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+/// Listing environment variables prints those setting arguments as well as those consulted otherwise.
+#[test]
+fn listing_environment_variables_prints_all_variables() {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("env");
+
+    cmd.assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(
+                r"(?m)^HANKO_CONFIG\s+The configuration file, same as --config\.$",
+            )
+            .unwrap(),
+        )
+        .stdout(predicate::str::is_match(r"(?m)^HANKO_ALLOWED_SIGNERS\s").unwrap())
+        .stdout(predicate::str::is_match(r"(?m)^HANKO_SOURCE_<NAME>_TOKEN\s").unwrap())
+        .stdout(predicate::str::is_match(r"(?m)^<VARIABLE>\s+Read by tokens").unwrap())
+        .stdout(predicate::str::is_match(r"(?m)^XDG_CONFIG_HOME\s").unwrap())
+        .stdout(predicate::str::is_match(r"(?m)^USER, USERNAME\s").unwrap())
+        .stdout(predicate::str::is_match(r"(?m)^HTTPS_PROXY, ").unwrap());
+}
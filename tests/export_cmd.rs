@@ -0,0 +1,70 @@
+//! Ensure correct behavior of the export subcommand.
+use assert_cmd::Command;
+use httpmock::prelude::*;
+use indoc::formatdoc;
+use rstest::*;
+use serde_json::{json, Value};
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+/// Exporting prints the entries of all signers as JSON, including the source of their keys.
+#[rstest]
+fn export_prints_entries_as_json() {
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(GET).path("/users/jsnow/ssh_signing_keys");
+        then.status(200).json_body(json!([
+            {
+                "id": 773_452,
+                "key": "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS",
+                "title": "key-1",
+                "created_at": "2023-05-23T09:35:15.638Z"
+            }
+        ]));
+    });
+    let mut config = NamedTempFile::new().unwrap();
+    config
+        .write_all(
+            formatdoc! {r#"
+                signers = [
+                    {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["acme-github"] }},
+                ]
+
+                [[sources]]
+                name = "acme-github"
+                provider = "github"
+                url = "{url}"
+            "#, url = server.base_url()}
+            .as_bytes(),
+        )
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    let output = cmd
+        .arg("--config")
+        .arg(config.path())
+        .arg("export")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let export: Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(export["version"], 1);
+    assert_eq!(
+        export["entries"],
+        json!([{
+            "principals": ["j.snow@wall.com"],
+            "algorithm": "ssh-ed25519",
+            "key": "AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS",
+            "fingerprint": "SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM",
+            "valid_after": null,
+            "valid_before": null,
+            "source": "acme-github"
+        }])
+    );
+    mock.assert();
+}
@@ -0,0 +1,107 @@
+//! Ensure correct behavior of the fetch subcommand.
+use assert_cmd::Command;
+use httpmock::prelude::*;
+use indoc::formatdoc;
+use predicates::prelude::*;
+use rstest::*;
+use serde_json::json;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+/// Fetching prints the entries of a configured signer using only the given source, without
+/// querying the sources configured for the signer.
+#[rstest]
+fn fetch_uses_only_given_source() {
+    let configured = MockServer::start();
+    let configured_mock = configured.mock(|when, then| {
+        when.method(GET).path("/users/jsnow/ssh_signing_keys");
+        then.status(200).json_body(json!([]));
+    });
+    let forced = MockServer::start();
+    let forced_mock = forced.mock(|when, then| {
+        when.method(GET).path("/users/jsnow/ssh_signing_keys");
+        then.status(200).json_body(json!([
+            {
+                "id": 773_452,
+                "key": "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS",
+                "title": "key-1",
+                "created_at": "2023-05-23T09:35:15.638Z"
+            }
+        ]));
+    });
+    let mut config = NamedTempFile::new().unwrap();
+    config
+        .write_all(
+            formatdoc! {r#"
+                signers = [
+                    {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["configured"] }},
+                ]
+
+                [[sources]]
+                name = "configured"
+                provider = "github"
+                url = "{configured_url}"
+
+                [[sources]]
+                name = "forced"
+                provider = "github"
+                url = "{forced_url}"
+            "#, configured_url = configured.base_url(), forced_url = forced.base_url()}
+            .as_bytes(),
+        )
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("fetch")
+        .arg("--signer")
+        .arg("jsnow")
+        .arg("--source")
+        .arg("forced")
+        .assert()
+        .success()
+        .stdout("j.snow@wall.com ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS\n");
+
+    configured_mock.assert_hits(0);
+    forced_mock.assert();
+}
+
+/// Fetching fails if the signer or source is not configured.
+#[rstest]
+#[case("napplic", "acme-github", "Signer napplic is not configured")]
+#[case("jsnow", "mock-gitlab", "Source mock-gitlab is not configured")]
+fn fetch_requires_configured_signer_and_source(
+    #[case] signer: &str,
+    #[case] source: &str,
+    #[case] expected: &str,
+) {
+    let mut config = NamedTempFile::new().unwrap();
+    config
+        .write_all(
+            formatdoc! {r#"
+                signers = [
+                    {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["acme-github"] }},
+                ]
+
+                [[sources]]
+                name = "acme-github"
+                provider = "github"
+                url = "https://github.acme.corp/api/v3"
+            "#}
+            .as_bytes(),
+        )
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("fetch")
+        .arg("--signer")
+        .arg(signer)
+        .arg("--source")
+        .arg(source)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(expected));
+}
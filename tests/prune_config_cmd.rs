@@ -0,0 +1,121 @@
+//! Ensure correct behavior of the prune-config subcommand.
+use assert_cmd::Command;
+use httpmock::prelude::*;
+use indoc::formatdoc;
+use predicates::prelude::*;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+/// Signers that no longer exist on their sources are only listed without `--yes`, and removed
+/// from the configuration with it.
+#[test]
+fn prune_config_removes_vanished_signers_only_with_yes() {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users/octocat/ssh_signing_keys");
+        then.status(200).json_body(serde_json::json!([]));
+    });
+    server.mock(|when, then| {
+        when.method(GET).path("/users/octocta/ssh_signing_keys");
+        then.status(404)
+            .json_body(serde_json::json!({"message": "Not Found"}));
+    });
+    let toml = formatdoc! {r#"
+        [[signers]]
+        name = "octocat"
+        principals = ["octocat@github.com"]
+        sources = ["mock-github"]
+
+        [[signers]]
+        name = "octocta"
+        principals = ["octocta@github.com"]
+        sources = ["mock-github"]
+
+        [[sources]]
+        name = "mock-github"
+        provider = "github"
+        url = "{url}"
+    "#, url = server.base_url()};
+    let config = {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let prune = |yes: bool| {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+        cmd.arg("--config").arg(config.path()).arg("prune-config");
+        if yes {
+            cmd.arg("--yes");
+        }
+        cmd.assert().success()
+    };
+
+    prune(false).stdout(predicate::str::contains(
+        "Would remove signer octocta since it does not exist on any of it's sources",
+    ));
+    assert_eq!(std::fs::read_to_string(config.path()).unwrap(), toml);
+
+    prune(true).stdout(predicate::str::contains("Removed signer octocta"));
+    let result = std::fs::read_to_string(config.path()).unwrap();
+    assert!(result.contains("name = \"octocat\""));
+    assert!(!result.contains("octocta"));
+}
+
+/// Vanished signers configured within drop-in files are skipped with a warning, while the others
+/// are still removed.
+#[test]
+fn prune_config_skips_signers_of_drop_ins() {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET);
+        then.status(404)
+            .json_body(serde_json::json!({"message": "Not Found"}));
+    });
+    let dir = tempfile::TempDir::new().unwrap();
+    let config = dir.path().join("config.toml");
+    std::fs::write(
+        &config,
+        formatdoc! {r#"
+            [[signers]]
+            name = "octocta"
+            principals = ["octocta@github.com"]
+            sources = ["mock-github"]
+
+            [[sources]]
+            name = "mock-github"
+            provider = "github"
+            url = "{url}"
+        "#, url = server.base_url()},
+    )
+    .unwrap();
+    let drop_ins = dir.path().join("config.d");
+    std::fs::create_dir(&drop_ins).unwrap();
+    std::fs::write(
+        drop_ins.join("team.toml"),
+        formatdoc! {r#"
+            [[signers]]
+            name = "tanuki"
+            principals = ["tanuki@github.com"]
+            sources = ["mock-github"]
+        "#},
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("-v")
+        .arg("--config")
+        .arg(&config)
+        .arg("--config-dir")
+        .arg(&drop_ins)
+        .arg("prune-config")
+        .arg("--yes")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed signer octocta"))
+        .stdout(predicate::str::contains(
+            "HANKO-W032: Skipping signer tanuki",
+        ));
+    assert!(!std::fs::read_to_string(&config)
+        .unwrap()
+        .contains("octocta"));
+}
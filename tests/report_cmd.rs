@@ -0,0 +1,87 @@
+//! Ensure correct behavior of the report subcommand.
+use assert_cmd::Command;
+use httpmock::prelude::*;
+use indoc::formatdoc;
+use rstest::*;
+use serde_json::{json, Value as JsonValue};
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+/// Reporting prints the keys of every signer on each of it's sources as JSON, including errors
+/// of sources the keys could not be retrieved from.
+#[rstest]
+fn report_prints_keys_of_signers_as_json() {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users/jsnow/ssh_signing_keys");
+        then.status(200).json_body(json!([
+            {
+                "id": 773_452,
+                "key": "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS",
+                "title": "key-1",
+                "created_at": "2023-05-23T09:35:15.638Z"
+            }
+        ]));
+    });
+    server.mock(|when, then| {
+        when.method(GET).path("/users/napplic/ssh_signing_keys");
+        then.status(404).json_body(json!({"message": "Not Found"}));
+    });
+    let mut config = NamedTempFile::new().unwrap();
+    config
+        .write_all(
+            formatdoc! {r#"
+                signers = [
+                    {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["mock-github"] }},
+                    {{ name = "napplic", principals = ["not@applicable.com"], sources = ["mock-github"] }},
+                ]
+
+                [[sources]]
+                name = "mock-github"
+                provider = "github"
+                url = "{url}"
+            "#, url = server.base_url()}
+            .as_bytes(),
+        )
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    let output = cmd
+        .arg("--config")
+        .arg(config.path())
+        .arg("report")
+        .arg("--format")
+        .arg("json")
+        .output()
+        .unwrap();
+    let report: JsonValue = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        report,
+        json!([
+            {
+                "name": "jsnow",
+                "principals": ["j.snow@wall.com"],
+                "sources": [{
+                    "source": "mock-github",
+                    "keys": [{
+                        "key": "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS",
+                        "fingerprint": "SHA256:/J6X1jpcrEUcdeYMST6EtcQYvlmKB06JbnT14+KubfM",
+                        "created_at": "2023-05-23T09:35:15.638+00:00",
+                        "accepted": true
+                    }]
+                }]
+            },
+            {
+                "name": "napplic",
+                "principals": ["not@applicable.com"],
+                "sources": [{
+                    "source": "mock-github",
+                    "error": "requested user could not be found",
+                    "keys": []
+                }]
+            }
+        ])
+    );
+}
@@ -1,12 +1,14 @@
 //! Ensure correct behavior of the signer management subcommand.
 use assert_cmd::Command;
-use indoc::indoc;
+use httpmock::prelude::*;
+use indoc::{formatdoc, indoc};
 use predicates::prelude::*;
 use rstest::*;
 use std::io::Write;
 use tempfile::{NamedTempFile, TempDir};
 
 /// When adding a signer, the configuration file is updated accordingly.
+#[cfg(feature = "public-providers")]
 #[rstest]
 #[case(
     indoc!{r#"
@@ -42,7 +44,8 @@ fn adding_signer_updates_configuration(
         .arg(NamedTempFile::new().unwrap().path())
         .arg("signer")
         .arg("add")
-        .arg("--no-update");
+        .arg("--no-update")
+        .arg("--no-verify");
     for arg in args {
         cmd.arg(arg);
     }
@@ -54,6 +57,7 @@ fn adding_signer_updates_configuration(
 }
 
 /// Adding a signer creates a configuration file with the corresponding signer if no file exists yet.
+/// Without the `public-providers` feature, there is no default source and it must be specified.
 #[rstest]
 #[case(
     vec!["octocat", "octocat@github.com"],
@@ -77,11 +81,18 @@ fn adding_signer_creates_configuration_if_none_exists(
         .arg(NamedTempFile::new().unwrap().path())
         .arg("signer")
         .arg("add")
-        .arg("--no-update");
+        .arg("--no-update")
+        .arg("--no-verify");
     for arg in args {
         cmd.arg(arg);
     }
 
+    if !cfg!(feature = "public-providers") {
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("--source <SOURCE>"));
+        return;
+    }
     cmd.assert().success();
     let result = std::fs::read_to_string(path).unwrap();
 
@@ -105,3 +116,56 @@ fn adding_signer_requires_principal() {
     ));
     cmd.assert().stderr(predicate::str::contains("PRINCIPALS"));
 }
+
+/// Adding a signer that does not exist on it's source warns about it, while refusing to add it
+/// in strict mode.
+#[rstest]
+#[case(false)]
+#[case(true)]
+fn adding_nonexistent_signer_is_verified(#[case] strict: bool) {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users/octocta/ssh_signing_keys");
+        then.status(404)
+            .json_body(serde_json::json!({"message": "Not Found"}));
+    });
+    let toml = formatdoc! {r#"
+        [[sources]]
+        name = "mock-github"
+        provider = "github"
+        url = "{url}"
+    "#, url = server.base_url()};
+    let config = {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("-v")
+        .arg("--config")
+        .arg(config.path())
+        .arg("signer")
+        .arg("add")
+        .arg("--no-update")
+        .args(["--source", "mock-github"])
+        .args(["octocta", "octocat@github.com"]);
+    if strict {
+        cmd.arg("--strict");
+    }
+
+    let assert = if strict {
+        cmd.assert().failure()
+    } else {
+        cmd.assert().success()
+    };
+    let result = std::fs::read_to_string(config.path()).unwrap();
+
+    assert.stdout(predicate::str::contains(
+        "HANKO-W034: Signer octocta does not exist on source mock-github",
+    ));
+    if strict {
+        assert_eq!(result, toml);
+    } else {
+        assert!(result.contains(r#"name = "octocta""#));
+    }
+}
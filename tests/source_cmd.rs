@@ -0,0 +1,125 @@
+//! Ensure correct behavior of the source management subcommand.
+use assert_cmd::Command;
+use indoc::indoc;
+use predicates::prelude::*;
+use rstest::*;
+use tempfile::NamedTempFile;
+
+/// Listing providers prints all supported providers along with their default URLs, which public
+/// providers only have with the `public-providers` feature, and whether they support
+/// authentication and pagination.
+#[test]
+fn listing_providers_prints_providers_and_default_urls() {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("source").arg("providers");
+
+    let (github_url, gitlab_url) = if cfg!(feature = "public-providers") {
+        (r"https://api\.github\.com/", r"https://gitlab\.com/")
+    } else {
+        ("-", "-")
+    };
+    cmd.assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(format!(r"(?m)^github\s+{github_url}\s+yes\s+yes\s")).unwrap(),
+        )
+        .stdout(
+            predicate::str::is_match(format!(r"(?m)^gitlab\s+{gitlab_url}\s+yes\s+yes\s")).unwrap(),
+        )
+        .stdout(predicate::str::is_match(r"(?m)^url\s+-\s+no\s+no\s").unwrap());
+}
+
+/// When adding a source without specifying a provider, it is detected by the host of it's URL.
+/// The web URL of GitHub is replaced by the URL of it's API.
+#[rstest]
+#[case(
+    vec!["acme-github", "--url", "https://api.github.com"],
+    indoc!{r#"
+        [[sources]]
+        name = "acme-github"
+        provider = "github"
+        url = "https://api.github.com/"
+    "#}
+)]
+#[case(
+    vec!["public-github", "--url", "https://github.com"],
+    indoc!{r#"
+        [[sources]]
+        name = "public-github"
+        provider = "github"
+        url = "https://api.github.com/"
+    "#}
+)]
+#[case(
+    vec!["acme-corp", "--url", "https://git.acme.corp", "--provider", "gitlab"],
+    indoc!{r#"
+        [[sources]]
+        name = "acme-corp"
+        provider = "gitlab"
+        url = "https://git.acme.corp/"
+    "#}
+)]
+fn adding_source_updates_configuration(#[case] args: Vec<&str>, #[case] expected: &str) {
+    let config = NamedTempFile::new().unwrap();
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("source")
+        .arg("add")
+        .args(args);
+
+    cmd.assert().success();
+    let result = std::fs::read_to_string(config.path()).unwrap();
+
+    assert_eq!(result, expected);
+}
+
+/// Without a URL, the default URL of the provider is used, which public providers only have with
+/// the `public-providers` feature.
+#[test]
+fn adding_source_without_url_uses_default_url() {
+    let config = NamedTempFile::new().unwrap();
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("source")
+        .arg("add")
+        .arg("public-gitlab")
+        .arg("--provider")
+        .arg("gitlab");
+
+    if cfg!(feature = "public-providers") {
+        cmd.assert().success();
+        assert_eq!(
+            std::fs::read_to_string(config.path()).unwrap(),
+            indoc! {r#"
+                [[sources]]
+                name = "public-gitlab"
+                provider = "gitlab"
+                url = "https://gitlab.com/"
+            "#}
+        );
+    } else {
+        cmd.assert().failure().stderr(predicate::str::contains(
+            "Provider gitlab does not have a default URL",
+        ));
+    }
+}
+
+/// Adding a source with an unknown host and no provider returns an error asking to specify it.
+#[test]
+fn adding_source_with_unknown_host_requires_provider() {
+    let config = NamedTempFile::new().unwrap();
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("source")
+        .arg("add")
+        .arg("acme-corp")
+        .arg("--url")
+        .arg("https://git.acme.corp");
+
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "please specify it using --provider",
+    ));
+}
@@ -2,7 +2,6 @@
 use assert_cmd::Command;
 use httpmock::prelude::*;
 use indoc::{formatdoc, indoc};
-#[cfg(not(feature = "detect-allowed-signers"))]
 use predicates::prelude::*;
 use rstest::*;
 use serde_json::json;
@@ -132,6 +131,7 @@ fn update_writes_expected_allowed_signers(
     };
     let allowed_signers = NamedTempFile::new().unwrap();
     let expected_content = indoc! {"
+        # Managed by hanko, manual changes will be overwritten.
         cwoods@universal.exports ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS John Doe (gitlab.com)
         ernie@muppets.com ssh-rsa AAAAB3NzaC1yc2EAAAADAQABAAABgQDDTdEeUFjUX76aMptdG63itqcINvu/tnV5l9RXy/1TS25Ui2r+C2pRjG0vr9lzfz8TGncQt1yKmaZDAAe6mYGFiQlrkh9RJ/MPssRw4uS4slvMTDWhNufO1M3QGkek81lGaZq55uazCcaM5xSOhLBdrWIMROeLgKZ9YkHNqJXTt9V+xNE5ZkB/65i2tCkGdXnQsGJbYFbkuUTvYBuMW9lwmryLTeWwFLWGBP1moZI9etk3snh2hCLTV8+gvmhCTE8sAGBMcJq+TGxnfFoCtnA9Bdy7t+ZMLh1kV7oneUA9YT7qNeUFy55D287DAltB02ntT7CtuG6SBAQ4CQMcCoAX3Os4aVfdILOEC8ghrAj3uTEQuE3nYta0SmqqXcVAxmXUQCawf8n5CJ7QN5aIhCH73MKr6k5puk9dnkAcAFLRM6stvQhnpIqrI3YEbjqs1FGHfbc4+nfEWorxRrd7ur1ckEhuvmAXRKrLzYp9gYWU6TxfRqSxsXh3he0G6i+kC6k= John Doe (gitlab.com)
         ian.malcom@acme.corp ecdsa-sha2-nistp256 AAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAAAIbmlzdHAyNTYAAABBBCoObGvI0R2SfxLypsqi25QOgiI1lcsAhtL7AqUeVD+4mS0CQ2Nu/C8h+RHtX6tHpd+GhfGjtDXjW598Vr2j9+w=
@@ -152,6 +152,734 @@ fn update_writes_expected_allowed_signers(
     assert_eq!(content, expected_content);
 }
 
+/// When the same key is returned by a GitHub source without it's comment and by a GitLab source
+/// with it, a single entry using the key without comment is written.
+#[rstest]
+fn update_collapses_keys_differing_in_comment() {
+    const KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS";
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users/jsnow/ssh_signing_keys");
+        then.status(200).json_body(json!([{
+            "id": 1,
+            "key": KEY,
+            "title": "key-1",
+            "created_at": "2023-05-23T09:35:15.638Z"
+        }]));
+    });
+    server.mock(|when, then| {
+        when.method(GET).path("/api/v4/users/jsnow/keys");
+        then.status(200).json_body(json!([{
+            "id": 2,
+            "title": "key-1",
+            "created_at": "2020-08-21T19:43:06.816Z",
+            "expires_at": null,
+            "key": format!("{KEY} John Doe (gitlab.com)"),
+            "usage_type": "auth_and_signing"
+        }]));
+    });
+    let config = {
+        let toml = formatdoc! {r#"
+            signers = [
+                {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["mock-github", "mock-gitlab"]}},
+            ]
+
+            [[sources]]
+            name = "mock-github"
+            provider = "github"
+            url = "{url}"
+
+            [[sources]]
+            name = "mock-gitlab"
+            provider = "gitlab"
+            url = "{url}"
+        "#, url = server.base_url()};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let allowed_signers = NamedTempFile::new().unwrap();
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("--file")
+        .arg(allowed_signers.path())
+        .arg("update")
+        .assert()
+        .success();
+    let content = std::fs::read_to_string(allowed_signers.path()).unwrap();
+
+    assert_eq!(
+        content,
+        formatdoc! {"
+            # Managed by hanko, manual changes will be overwritten.
+            j.snow@wall.com {KEY}
+
+        "}
+    );
+}
+
+/// Invalid keys returned by a source are dropped with a warning naming the key when validating
+/// keys, or fail the update in strict mode.
+#[rstest]
+fn update_validates_keys() {
+    const KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS";
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users/jsnow/ssh_signing_keys");
+        then.status(200).json_body(json!([
+            {
+                "id": 1,
+                "key": KEY,
+                "title": "laptop",
+                "created_at": "2023-05-23T09:35:15.638Z"
+            },
+            {
+                "id": 2,
+                "key": "ssh-ed25519 <html>Service Unavailable</html>",
+                "title": "glitch",
+                "created_at": "2023-05-23T09:35:15.638Z"
+            }
+        ]));
+    });
+    let config = {
+        let toml = formatdoc! {r#"
+            signers = [
+                {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["mock-github"]}},
+            ]
+
+            [[sources]]
+            name = "mock-github"
+            provider = "github"
+            url = "{url}"
+        "#, url = server.base_url()};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let allowed_signers = NamedTempFile::new().unwrap();
+    let update = |mode: &str| {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+        cmd.arg("--config")
+            .arg(config.path())
+            .arg("--file")
+            .arg(allowed_signers.path())
+            .arg("-v")
+            .arg("update")
+            .arg("--validate-keys")
+            .arg(mode)
+            .assert()
+    };
+
+    update("strict").failure().stderr(predicate::str::contains(
+        "invalid key glitch: key material is not valid base64",
+    ));
+    assert_eq!(std::fs::read_to_string(allowed_signers.path()).unwrap(), "");
+
+    update("warn").success().stdout(predicate::str::contains(
+        "Dropping invalid key glitch of signer jsnow: key material is not valid base64",
+    ));
+    assert_eq!(
+        std::fs::read_to_string(allowed_signers.path()).unwrap(),
+        formatdoc! {"
+            # Managed by hanko, manual changes will be overwritten.
+            j.snow@wall.com {KEY}
+
+        "}
+    );
+}
+
+/// When running the update command using the authorized keys output format, only the
+/// deduplicated keys are written.
+#[rstest]
+fn update_writes_authorized_keys(mock_github_server: MockServer) {
+    let config = {
+        let toml = formatdoc! {r#"
+            signers = [
+                {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["mock-github"]}},
+                {{ name = "jsnow", principals = ["jon.snow@winterfell.com"], sources = ["mock-github"]}},
+                {{ name = "imalcom", principals = ["ian.malcom@acme.corp"], sources = ["mock-github"]}},
+            ]
+
+            [[sources]]
+            name = "mock-github"
+            provider = "github"
+            url = "{github_url}"
+        "#, github_url = mock_github_server.base_url()};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let authorized_keys = NamedTempFile::new().unwrap();
+    let expected_content = indoc! {"
+        # Managed by hanko, manual changes will be overwritten.
+        ecdsa-sha2-nistp256 AAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAAAIbmlzdHAyNTYAAABBBCoObGvI0R2SfxLypsqi25QOgiI1lcsAhtL7AqUeVD+4mS0CQ2Nu/C8h+RHtX6tHpd+GhfGjtDXjW598Vr2j9+w=
+        ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS
+    "};
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("--file")
+        .arg(authorized_keys.path())
+        .arg("update")
+        .arg("--output-format")
+        .arg("authorized-keys")
+        .assert()
+        .success();
+    let content = std::fs::read_to_string(authorized_keys.path()).unwrap();
+
+    assert_eq!(content, expected_content);
+}
+
+/// When running the update command for only some signers, only their entries are written to
+/// the allowed signers file.
+#[rstest]
+fn update_only_writes_selected_signers(mock_github_server: MockServer) {
+    let config = {
+        let toml = formatdoc! {r#"
+            signers = [
+                {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["mock-github"]}},
+                {{ name = "imalcom", principals = ["ian.malcom@acme.corp"], sources = ["mock-github"]}},
+            ]
+
+            [[sources]]
+            name = "mock-github"
+            provider = "github"
+            url = "{github_url}"
+        "#, github_url = mock_github_server.base_url()};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let allowed_signers = NamedTempFile::new().unwrap();
+    let expected_content = indoc! {"
+        # Managed by hanko, manual changes will be overwritten.
+        j.snow@wall.com ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS
+
+    "};
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("--file")
+        .arg(allowed_signers.path())
+        .arg("update")
+        .arg("--only")
+        .arg("jsnow")
+        .assert()
+        .success();
+    let content = std::fs::read_to_string(allowed_signers.path()).unwrap();
+
+    assert_eq!(content, expected_content);
+}
+
+/// Selecting a signer that is not configured fails the update without writing the allowed
+/// signers file, instead of removing all entries from it.
+#[rstest]
+fn update_only_unknown_signer_fails(mock_github_server: MockServer) {
+    let config = {
+        let toml = formatdoc! {r#"
+            signers = [
+                {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["mock-github"]}},
+            ]
+
+            [[sources]]
+            name = "mock-github"
+            provider = "github"
+            url = "{github_url}"
+        "#, github_url = mock_github_server.base_url()};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let mut allowed_signers = NamedTempFile::new().unwrap();
+    allowed_signers.write_all(b"existing entry\n").unwrap();
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("--file")
+        .arg(allowed_signers.path())
+        .arg("update")
+        .arg("--only")
+        .arg("jsonw")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Selected signer(s) jsonw not configured",
+        ));
+    let content = std::fs::read_to_string(allowed_signers.path()).unwrap();
+
+    assert_eq!(content, "existing entry\n");
+}
+
+/// A dry run prints the estimated number of requests without making any requests or writing the
+/// allowed signers file.
+#[rstest]
+fn update_dry_run_estimates_requests() {
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.any_request();
+        then.status(200).json_body(json!([]));
+    });
+    let config = {
+        let toml = formatdoc! {r#"
+            signers = [
+                {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["mock-github", "mock-gitlab"]}},
+                {{ name = "imalcom", principals = ["ian.malcom@acme.corp"], sources = ["mock-github"]}},
+            ]
+
+            [[sources]]
+            name = "mock-github"
+            provider = "github"
+            url = "{url}"
+
+            [[sources]]
+            name = "mock-gitlab"
+            provider = "gitlab"
+            url = "{url}"
+        "#, url = server.base_url()};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let allowed_signers = NamedTempFile::new().unwrap();
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("--file")
+        .arg(allowed_signers.path())
+        .arg("update")
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(indoc! {"
+            Would make ~3 request(s) across 2 source(s)
+              mock-github: ~2 request(s)
+              mock-gitlab: ~1 request(s)
+        "});
+
+    mock.assert_hits(0);
+    assert_eq!(std::fs::read_to_string(allowed_signers.path()).unwrap(), "");
+}
+
+/// When running the update command with a trace file, traces are written to the file without
+/// being written to the terminal.
+#[rstest]
+fn update_writes_traces_to_trace_file(mock_github_server: MockServer) {
+    let config = {
+        let toml = formatdoc! {r#"
+            signers = [
+                {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["mock-github"]}},
+            ]
+
+            [[sources]]
+            name = "mock-github"
+            provider = "github"
+            url = "{github_url}"
+        "#, github_url = mock_github_server.base_url()};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let allowed_signers = NamedTempFile::new().unwrap();
+    let trace_file = NamedTempFile::new().unwrap();
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("--file")
+        .arg(allowed_signers.path())
+        .arg("--trace-file")
+        .arg(trace_file.path())
+        .arg("update")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Loading TOML configuration file").not())
+        .stderr(predicate::str::contains("Loading TOML configuration file").not());
+    let traces = std::fs::read_to_string(trace_file.path()).unwrap();
+
+    assert!(traces.contains("INFO"));
+    assert!(traces.contains("Loading TOML configuration file"));
+}
+
+/// When running the update command with requests dumped, requests and responses are written to
+/// stderr without exposing tokens.
+#[rstest]
+fn update_dumps_requests_with_secrets_redacted(mock_gitlab_server: MockServer) {
+    let config = {
+        let toml = formatdoc! {r#"
+            signers = [
+                {{ name = "cwoods", principals = ["cwoods@universal.exports"], sources = ["mock-gitlab"]}},
+            ]
+
+            [[sources]]
+            name = "mock-gitlab"
+            provider = "gitlab"
+            url = "{gitlab_url}"
+            token = "glpat-supersecret"
+        "#, gitlab_url=mock_gitlab_server.base_url()};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let allowed_signers = NamedTempFile::new().unwrap();
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("--file")
+        .arg(allowed_signers.path())
+        .arg("--dump-requests")
+        .arg("update")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(format!(
+            "> GET {}",
+            mock_gitlab_server.url("/api/v4/users/cwoods/keys")
+        )))
+        .stderr(predicate::str::contains("> private-token: <redacted>"))
+        .stderr(predicate::str::contains("< 200 OK"))
+        .stderr(predicate::str::contains("supersecret").not());
+}
+
+/// The post-update hook only runs if the allowed signers file changed, unless hooks are always run.
+#[rstest]
+#[cfg(unix)]
+fn post_update_hook_runs_only_if_file_changed(mock_github_server: MockServer) {
+    let marker = NamedTempFile::new().unwrap();
+    let config = {
+        let toml = formatdoc! {r#"
+            signers = [
+                {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["mock-github"]}},
+            ]
+
+            [[sources]]
+            name = "mock-github"
+            provider = "github"
+            url = "{github_url}"
+
+            [hooks]
+            post_update = "echo run >> '{marker}'"
+        "#, github_url = mock_github_server.base_url(), marker = marker.path().display()};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let allowed_signers = NamedTempFile::new().unwrap();
+    let update = |extra_args: &[&str]| {
+        Command::cargo_bin(env!("CARGO_PKG_NAME"))
+            .unwrap()
+            .arg("--config")
+            .arg(config.path())
+            .arg("--file")
+            .arg(allowed_signers.path())
+            .arg("update")
+            .args(extra_args)
+            .assert()
+            .success();
+        std::fs::read_to_string(marker.path())
+            .unwrap()
+            .lines()
+            .count()
+    };
+
+    assert_eq!(update(&[]), 1);
+    assert_eq!(update(&[]), 1);
+    assert_eq!(update(&["--always-run-hooks"]), 2);
+}
+
+/// The status line is the only output of an update, counting signers skipped due to tolerated
+/// connection errors as errors, and summarizes a failing post-update hook as a failed update
+/// instead of a successful one.
+#[rstest]
+#[cfg(unix)]
+fn update_status_line_is_only_output(mock_github_server: MockServer) {
+    let config = |hook: &str| {
+        let toml = formatdoc! {r#"
+            signers = [
+                {{ name = "jsnow", principals = ["j.snow@wall.com", "jsnow@acme.corp"], sources = ["mock-github"]}},
+                {{ name = "cwoods", principals = ["cwoods@muppets.com"], sources = ["unreachable"]}},
+            ]
+
+            [[routes]]
+            principal_suffix = "@acme.corp"
+            sources = ["mock-github-internal"]
+
+            [[sources]]
+            name = "mock-github"
+            provider = "github"
+            url = "{github_url}"
+
+            [[sources]]
+            name = "mock-github-internal"
+            provider = "github"
+            url = "{github_url}"
+
+            [[sources]]
+            name = "unreachable"
+            provider = "gitlab"
+            url = "http://127.0.0.1:1"
+
+            [retry]
+            max_attempts = 1
+
+            [hooks]
+            post_update = "{hook}"
+        "#, github_url = mock_github_server.base_url()};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let allowed_signers = NamedTempFile::new().unwrap();
+    let update = |config: &NamedTempFile| {
+        Command::cargo_bin(env!("CARGO_PKG_NAME"))
+            .unwrap()
+            .arg("--config")
+            .arg(config.path())
+            .arg("--file")
+            .arg(allowed_signers.path())
+            .arg("-v")
+            .arg("update")
+            .arg("--tolerate-connection-errors")
+            .arg("--always-run-hooks")
+            .arg("--status-line")
+            .assert()
+    };
+
+    update(&config("true"))
+        .success()
+        .stdout(
+            predicate::str::is_match(r"^hanko: 2 signers, 2 keys, 1 error \([^)]+\)\n$").unwrap(),
+        )
+        .stderr(predicate::str::is_empty());
+    update(&config("exit 1"))
+        .failure()
+        .stdout(predicate::str::is_match(r"^hanko: update failed, 2 errors \([^)]+\)\n$").unwrap());
+}
+
+/// A failed update is re-run up to the given number of times, retrieving the keys of all signers
+/// again and failing only due to the signer failing in every attempt.
+#[rstest]
+fn update_retried_on_signer_failure(mock_github_server: MockServer) {
+    let config = {
+        let toml = formatdoc! {r#"
+            signers = [
+                {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["mock-github"]}},
+                {{ name = "cwoods", principals = ["cwoods@muppets.com"], sources = ["unreachable"]}},
+            ]
+
+            [[sources]]
+            name = "mock-github"
+            provider = "github"
+            url = "{github_url}"
+
+            [[sources]]
+            name = "unreachable"
+            provider = "gitlab"
+            url = "http://127.0.0.1:1"
+
+            [retry]
+            max_attempts = 1
+        "#, github_url = mock_github_server.base_url()};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let allowed_signers = NamedTempFile::new().unwrap();
+
+    Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("--config")
+        .arg(config.path())
+        .arg("--file")
+        .arg(allowed_signers.path())
+        .arg("-v")
+        .arg("update")
+        .arg("--retries")
+        .arg("2")
+        .arg("--retry-delay")
+        .arg("0s")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("HANKO-W029").count(2))
+        .stderr(predicate::str::contains("cwoods"))
+        .stderr(predicate::str::contains("jsnow").not());
+}
+
+/// The allowed signers file is piped to the given command in addition to being written, failing
+/// the update if the command fails.
+#[rstest]
+#[cfg(unix)]
+fn update_pipes_allowed_signers_to_command(mock_github_server: MockServer) {
+    let piped = NamedTempFile::new().unwrap();
+    let config = {
+        let toml = formatdoc! {r#"
+            signers = [
+                {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["mock-github"]}},
+            ]
+
+            [[sources]]
+            name = "mock-github"
+            provider = "github"
+            url = "{github_url}"
+        "#, github_url = mock_github_server.base_url()};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let allowed_signers = NamedTempFile::new().unwrap();
+    let update = |command: &str| {
+        Command::cargo_bin(env!("CARGO_PKG_NAME"))
+            .unwrap()
+            .arg("--config")
+            .arg(config.path())
+            .arg("--file")
+            .arg(allowed_signers.path())
+            .arg("update")
+            .arg("--pipe-to")
+            .arg(command)
+            .assert()
+    };
+
+    update(&format!("cat > '{}'", piped.path().display())).success();
+    assert_eq!(
+        std::fs::read_to_string(piped.path()).unwrap(),
+        std::fs::read_to_string(allowed_signers.path()).unwrap()
+    );
+
+    update("exit 3").failure().stderr(predicate::str::contains(
+        "Command exited with exit status: 3",
+    ));
+}
+
+/// When running the update command with a plan output, the plan is written without modifying the
+/// allowed signers file, which is only updated once the plan is applied.
+#[rstest]
+fn update_plan_applied_later(mock_github_server: MockServer) {
+    let config = {
+        let toml = formatdoc! {r#"
+            signers = [
+                {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["mock-github"]}},
+            ]
+
+            [[sources]]
+            name = "mock-github"
+            provider = "github"
+            url = "{github_url}"
+        "#, github_url = mock_github_server.base_url()};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let dir = tempfile::TempDir::new().unwrap();
+    let allowed_signers = dir.path().join("allowed_signers");
+    let plan = dir.path().join("plan.json");
+    let expected_content = indoc! {"
+        # Managed by hanko, manual changes will be overwritten.
+        j.snow@wall.com ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGtQUDZWhs8k/cZcykMkaoX7ZE7DXld8TP79HyddMVTS
+
+    "};
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("--file")
+        .arg(&allowed_signers)
+        .arg("update")
+        .arg("--plan-out")
+        .arg(&plan)
+        .assert()
+        .success();
+    let planned: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&plan).unwrap()).unwrap();
+
+    assert!(!allowed_signers.exists());
+    assert_eq!(planned["add"][0]["reason"], "new_principals");
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("apply")
+        .arg("--plan")
+        .arg(&plan)
+        .assert()
+        .success();
+
+    assert_eq!(
+        std::fs::read_to_string(&allowed_signers).unwrap(),
+        expected_content
+    );
+}
+
+/// With a signing key configured, the allowed signers file is signed when updated and the
+/// signature is verified by the verify-file command until the file is modified.
+#[rstest]
+fn update_signs_allowed_signers_file(mock_github_server: MockServer) {
+    let dir = tempfile::TempDir::new().unwrap();
+    let key = dir.path().join("signing_key");
+    let keygen = std::process::Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+        .arg(&key)
+        .status();
+    // The test is skipped if ssh-keygen is not available.
+    if !keygen.is_ok_and(|status| status.success()) {
+        return;
+    }
+    let config = {
+        let toml = formatdoc! {r#"
+            signers = [
+                {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["mock-github"]}},
+            ]
+
+            [[sources]]
+            name = "mock-github"
+            provider = "github"
+            url = "{github_url}"
+
+            [signing]
+            key = '{key}'
+        "#, github_url = mock_github_server.base_url(), key = key.display()};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let allowed_signers = dir.path().join("allowed_signers");
+    let verify_file = || {
+        Command::cargo_bin(env!("CARGO_PKG_NAME"))
+            .unwrap()
+            .arg("--file")
+            .arg(&allowed_signers)
+            .arg("verify-file")
+            .arg("--public-key")
+            .arg(key.with_extension("pub"))
+            .assert()
+    };
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("--config")
+        .arg(config.path())
+        .arg("--file")
+        .arg(&allowed_signers)
+        .arg("update")
+        .assert()
+        .success();
+
+    assert!(dir.path().join("allowed_signers.sig").exists());
+    verify_file().success();
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&allowed_signers)
+        .unwrap();
+    writeln!(file, "mallory@evil.com ssh-ed25519 AAAA").unwrap();
+    verify_file().failure();
+}
+
 /// When running the update command with the `detect-allowed-signers` feature enabled and
 /// an allowed signers file configured within git, the file argument is not required.
 #[test]
@@ -161,6 +889,116 @@ fn file_arg_not_required_with_detect_feature_and_git_allowed_signers_config() {
     todo!()
 }
 
+/// When running the update command in first run safe mode, a hand-written allowed signers file is
+/// only overwritten if forced, while files written by hanko are updated normally.
+#[rstest]
+fn update_first_run_safe_refuses_unmanaged_file(mock_github_server: MockServer) {
+    let config = {
+        let toml = formatdoc! {r#"
+            signers = [
+                {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["mock-github"]}},
+            ]
+
+            [[sources]]
+            name = "mock-github"
+            provider = "github"
+            url = "{github_url}"
+        "#, github_url = mock_github_server.base_url()};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        file
+    };
+    let hand_written = "ernie@muppets.com ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIDw32w3ciofX3/gFoyCtPWxSsWYmylwdKZ9Q/BmoBR/g\n";
+    let mut allowed_signers = NamedTempFile::new().unwrap();
+    allowed_signers.write_all(hand_written.as_bytes()).unwrap();
+    let update = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+        cmd.arg("--config")
+            .arg(config.path())
+            .arg("--file")
+            .arg(allowed_signers.path())
+            .arg("update")
+            .arg("--first-run-safe")
+            .args(args)
+            .assert()
+    };
+
+    update(&[])
+        .failure()
+        .stderr(predicate::str::contains("--force"));
+    assert_eq!(
+        std::fs::read_to_string(allowed_signers.path()).unwrap(),
+        hand_written
+    );
+
+    update(&["--force"]).success();
+    update(&[]).success();
+    assert!(std::fs::read_to_string(allowed_signers.path())
+        .unwrap()
+        .contains("j.snow@wall.com"));
+}
+
+/// Updating each of multiple configurations writes the allowed signers file of every
+/// configuration, printing the output of each update at once followed by a summary, and fails if
+/// any of the updates failed.
+#[rstest]
+fn update_each_config_prints_summary(mock_github_server: MockServer) {
+    let dir = tempfile::TempDir::new().unwrap();
+    let config = |file: &std::path::Path| {
+        formatdoc! {r#"
+            allowed_signers_file = '{file}'
+            signers = [
+                {{ name = "jsnow", principals = ["j.snow@wall.com"], sources = ["mock-github"] }},
+            ]
+
+            [[sources]]
+            name = "mock-github"
+            provider = "github"
+            url = "{url}"
+        "#, file = file.display(), url = mock_github_server.base_url()}
+    };
+    let allowed_signers = [
+        dir.path().join("allowed_signers_a"),
+        dir.path().join("allowed_signers_c"),
+    ];
+    std::fs::write(dir.path().join("a.toml"), config(&allowed_signers[0])).unwrap();
+    std::fs::write(dir.path().join("b.toml"), "signers = []\n").unwrap();
+    std::fs::write(dir.path().join("c.toml"), config(&allowed_signers[1])).unwrap();
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    let mut assert = cmd
+        .arg("update")
+        .arg("--each-config")
+        .arg(dir.path())
+        .arg("--jobs")
+        .arg("2")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Summary of 3 update(s):"))
+        .stdout(predicate::str::contains(
+            "b.toml: Configuration does not configure an allowed_signers_file",
+        ))
+        .stderr(predicate::str::contains("1 of 3 update(s) failed"));
+    for (name, file) in [
+        ("a.toml", &allowed_signers[0]),
+        ("c.toml", &allowed_signers[1]),
+    ] {
+        assert = assert
+            .stdout(predicate::str::contains(format!(
+                "Updated allowed signers file {} in",
+                file.display()
+            )))
+            .stdout(predicate::str::contains(format!(
+                "✓ {}: {}",
+                dir.path().join(name).display(),
+                file.display()
+            )));
+        assert!(std::fs::read_to_string(file)
+            .unwrap()
+            .contains("j.snow@wall.com"));
+    }
+}
+
 /// When running the update command with the `detect-allowed-signers` feature enabled but
 /// without an allowed signers file configured within git, the file argument is required.
 #[test]
@@ -183,3 +1021,21 @@ fn file_arg_required_without_detect_feature() {
             "error: The following required argument was not provided: file",
         ));
 }
+
+/// If neither `HOME` nor `XDG_CONFIG_HOME` are set and no configuration file is given, the error
+/// explains why the default location could not be determined.
+#[test]
+fn config_location_undetermined_without_home() {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env_remove("HOME")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("HANKO_CONFIG")
+        .arg("--file")
+        .arg(NamedTempFile::new().unwrap().path())
+        .arg("update")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "neither HOME nor XDG_CONFIG_HOME are set",
+        ));
+}
@@ -1,9 +1,22 @@
-use clap::{self, CommandFactory, ValueEnum};
+use clap::{self, Arg, Command, CommandFactory, ValueEnum};
+use clap_complete::Shell;
 use hanko::cli::Cli;
-use std::{env, error::Error, path::PathBuf};
+use std::{env, error::Error, fs, path::PathBuf};
 
 const MAN_DEFAULT_CONFIG: &str = "~/.config/hanko/config.toml";
 
+/// Arguments whose values are the names of configured signers or sources, completed dynamically
+/// using the hidden `hanko __complete` subcommand. Each is given by the path of it's subcommand,
+/// it's ID and the kind of names it is completed with.
+const DYNAMIC_ARGS: &[(&[&str], &str, &str)] = &[
+    (&["update"], "only", "signers"),
+    (&["fetch"], "signer", "signers"),
+    (&["fetch"], "source", "sources"),
+    (&["signer", "add"], "source", "sources"),
+    (&["signer", "enable"], "name", "signers"),
+    (&["signer", "disable"], "name", "signers"),
+];
+
 fn main() -> Result<(), Box<dyn Error>> {
     let task = env::args().nth(1);
     match task.as_deref() {
@@ -22,15 +35,165 @@ fn main() -> Result<(), Box<dyn Error>> {
 }
 
 /// Create command completions for all shells supported by clap_complete.
+/// The completions of bash, zsh and fish additionally complete the names of configured signers
+/// and sources, while the other shells only complete static values.
 fn create_completions(dir: clap::builder::OsStr) -> Result<(), Box<dyn Error>> {
     let mut cmd = Cli::command();
-    for &shell in clap_complete::Shell::value_variants() {
-        clap_complete::generate_to(shell, &mut cmd, "hanko", &dir)?;
+    cmd.build();
+    let dynamic_args: Vec<(&[&str], &Arg, &str)> = DYNAMIC_ARGS
+        .iter()
+        .map(|&(path, id, kind)| (path, find_arg(&cmd, path, id), kind))
+        .collect();
+    for &shell in Shell::value_variants() {
+        let path = clap_complete::generate_to(shell, &mut cmd.clone(), "hanko", &dir)?;
+        let script = fs::read_to_string(&path)?;
+        let script = match shell {
+            Shell::Bash => complete_bash_names(&script, &dynamic_args),
+            Shell::Zsh => complete_zsh_names(&script, &dynamic_args),
+            Shell::Fish => complete_fish_names(&script, &dynamic_args),
+            _ => continue,
+        };
+        fs::write(&path, script)?;
     }
 
     Ok(())
 }
 
+/// The argument with the given ID of the subcommand at the given path.
+fn find_arg<'a>(cmd: &'a Command, path: &[&str], id: &str) -> &'a Arg {
+    let subcommand = path.iter().fold(cmd, |cmd, name| {
+        cmd.find_subcommand(name)
+            .unwrap_or_else(|| panic!("unknown subcommand {name}"))
+    });
+    subcommand
+        .get_arguments()
+        .find(|arg| arg.get_id() == id)
+        .unwrap_or_else(|| panic!("unknown argument {id} of {}", path.join(" ")))
+}
+
+/// Wrap the generated bash completion function, completing the names of configured signers and
+/// sources for dynamically completed arguments and falling back to the generated function
+/// otherwise.
+fn complete_bash_names(script: &str, args: &[(&[&str], &Arg, &str)]) -> String {
+    let mut conditions = String::new();
+    for (path, arg, kind) in args {
+        let subcommand: String = path.iter().map(|name| format!("*\" {name} \"")).collect();
+        let flags: Vec<String> = arg
+            .get_short()
+            .map(|short| format!("-{short}"))
+            .into_iter()
+            .chain(arg.get_long().map(|long| format!("--{long}")))
+            .collect();
+        // Positional arguments directly follow their subcommand.
+        let previous = if flags.is_empty() {
+            vec![path.last().unwrap().to_string()]
+        } else {
+            flags
+        };
+        let previous: Vec<String> = previous
+            .iter()
+            .map(|p| format!("${{prev}} == \"{p}\""))
+            .collect();
+        conditions.push_str(&format!(
+            "    if [[ -z ${{kind}} && ${{words}} == {subcommand}* && ( {} ) ]]; then\n        kind={kind}\n    fi\n",
+            previous.join(" || ")
+        ));
+    }
+    let function = format!(
+        r#"# Complete the names of configured signers and sources using `hanko __complete`.
+_hanko_names() {{
+    local cur prev words kind
+    cur="${{COMP_WORDS[COMP_CWORD]}}"
+    prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    words="$(printf ' %s ' "${{COMP_WORDS[@]:1:COMP_CWORD-1}}")"
+{conditions}    if [[ -n ${{kind}} ]]; then
+        COMPREPLY=( $(compgen -W "$(hanko __complete "${{kind}}" 2>/dev/null)" -- "${{cur}}") )
+        return 0
+    fi
+    _hanko "$@"
+}}
+
+"#
+    );
+    let registration = "\nif [[ \"${BASH_VERSINFO[0]}\"";
+    let (functions, registrations) = script
+        .rsplit_once(registration)
+        .expect("bash completion registration not found");
+    format!(
+        "{functions}\n{function}{}{}",
+        registration.trim_start(),
+        registrations.replace("complete -F _hanko ", "complete -F _hanko_names ")
+    )
+}
+
+/// Replace the actions of dynamically completed arguments within the generated zsh completion
+/// function by functions completing the names of configured signers and sources.
+fn complete_zsh_names(script: &str, args: &[(&[&str], &Arg, &str)]) -> String {
+    let mut lines: Vec<String> = script.lines().map(ToString::to_string).collect();
+    for (path, arg, kind) in args {
+        let help = arg
+            .get_help()
+            .expect("dynamic argument without help")
+            .to_string();
+        let help = help.trim_end_matches('.');
+        let mut replaced = false;
+        for line in lines.iter_mut().filter(|line| line.contains(help)) {
+            let action = line.rfind(':').expect("zsh argument spec without action");
+            *line = format!("{}:_hanko_{kind}' \\", &line[..action]);
+            replaced = true;
+        }
+        assert!(replaced, "zsh spec of {} not found", path.join(" "));
+    }
+    let functions = r#"(( $+functions[_hanko_signers] )) ||
+_hanko_signers() {
+    local names; names=(${(f)"$(hanko __complete signers 2>/dev/null)"})
+    _wanted signers expl 'configured signer' compadd -a names
+}
+(( $+functions[_hanko_sources] )) ||
+_hanko_sources() {
+    local names; names=(${(f)"$(hanko __complete sources 2>/dev/null)"})
+    _wanted sources expl 'configured source' compadd -a names
+}
+
+"#;
+    let script = lines.join("\n") + "\n";
+    let registration = "if [ \"$funcstack[1]\" = \"_hanko\" ]; then";
+    let (functions_before, rest) = script
+        .split_once(registration)
+        .expect("zsh completion registration not found");
+    format!("{functions_before}{functions}{registration}{rest}")
+}
+
+/// Add completions of the names of configured signers and sources to the generated fish
+/// completions, which fish merges with the static completions of the same arguments.
+fn complete_fish_names(script: &str, args: &[(&[&str], &Arg, &str)]) -> String {
+    let mut script = script.to_string();
+    script.push_str(
+        "\n# Complete the names of configured signers and sources using `hanko __complete`.\n",
+    );
+    for (path, arg, kind) in args {
+        let condition = match path {
+            [subcommand] => format!("__fish_hanko_using_subcommand {subcommand}"),
+            [subcommand, nested] => format!(
+                "__fish_hanko_using_subcommand {subcommand}; and __fish_seen_subcommand_from {nested}"
+            ),
+            _ => panic!("unsupported subcommand depth of {}", path.join(" ")),
+        };
+        let mut line = format!("complete -c hanko -n \"{condition}\"");
+        if let Some(short) = arg.get_short() {
+            line.push_str(&format!(" -s {short}"));
+        }
+        if let Some(long) = arg.get_long() {
+            line.push_str(&format!(" -l {long}"));
+        }
+        line.push_str(&format!(
+            " -f -a \"(hanko __complete {kind} 2>/dev/null)\"\n"
+        ));
+        script.push_str(&line);
+    }
+    script
+}
+
 /// Create manpages for all commands and subcommands.
 fn create_manpages(dir: PathBuf) -> Result<(), Box<dyn Error>> {
     let cmd = Cli::command()